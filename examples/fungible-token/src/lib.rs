@@ -250,7 +250,8 @@ impl FungibleToken {
         let attached_deposit = env::attached_deposit();
         let refund_amount = if current_storage > initial_storage {
             let required_deposit =
-                Balance::from(current_storage - initial_storage) * STORAGE_PRICE_PER_BYTE;
+                Balance::from(current_storage.saturating_sub(initial_storage).as_bytes())
+                    * STORAGE_PRICE_PER_BYTE;
             assert!(
                 required_deposit <= attached_deposit,
                 "The required attached deposit is {}, but the given attached deposit is is {}",
@@ -260,7 +261,8 @@ impl FungibleToken {
             attached_deposit - required_deposit
         } else {
             attached_deposit
-                + Balance::from(initial_storage - current_storage) * STORAGE_PRICE_PER_BYTE
+                + Balance::from(initial_storage.saturating_sub(current_storage).as_bytes())
+                    * STORAGE_PRICE_PER_BYTE
         };
         if refund_amount > 0 {
             env::log(format!("Refunding {} tokens for storage", refund_amount).as_bytes());
@@ -336,13 +338,13 @@ mod tests {
         testing_env!(context.clone());
         let total_supply = 1_000_000_000_000_000u128;
         let mut contract = FungibleToken::new(carol(), total_supply.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
 
         context.attached_deposit = 1000 * STORAGE_PRICE_PER_BYTE;
         testing_env!(context.clone());
         let transfer_amount = total_supply / 3;
         contract.transfer(bob(), transfer_amount.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
 
         context.is_view = true;
@@ -359,7 +361,7 @@ mod tests {
         testing_env!(context.clone());
         let total_supply = 1_000_000_000_000_000u128;
         let mut contract = FungibleToken::new(carol(), total_supply.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
 
         context.attached_deposit = 1000 * STORAGE_PRICE_PER_BYTE;
         testing_env!(context.clone());
@@ -437,7 +439,7 @@ mod tests {
         testing_env!(context.clone());
         let total_supply = 1_000_000_000_000_000u128;
         let mut contract = FungibleToken::new(carol(), total_supply.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
 
         context.is_view = true;
         testing_env!(context.clone());
@@ -449,7 +451,7 @@ mod tests {
         context.attached_deposit = STORAGE_PRICE_PER_BYTE * 1000;
         testing_env!(context.clone());
         contract.inc_allowance(bob(), allowance.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
 
         context.is_view = true;
@@ -463,7 +465,7 @@ mod tests {
         context.predecessor_account_id = bob();
         testing_env!(context.clone());
         contract.transfer_from(carol(), alice(), transfer_amount.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
 
         context.is_view = true;
@@ -481,7 +483,7 @@ mod tests {
         testing_env!(context.clone());
         let total_supply = 1_000_000_000_000_000u128;
         let mut contract = FungibleToken::new(carol(), total_supply.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
 
         context.is_view = true;
         testing_env!(context.clone());
@@ -493,7 +495,7 @@ mod tests {
         context.attached_deposit = STORAGE_PRICE_PER_BYTE * 1000;
         testing_env!(context.clone());
         contract.inc_allowance(bob(), allowance.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
 
         context.is_view = true;
@@ -508,7 +510,7 @@ mod tests {
         context.predecessor_account_id = bob();
         testing_env!(context.clone());
         contract.transfer_from(carol(), alice(), transfer_amount.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
 
         context.is_view = true;
@@ -525,14 +527,14 @@ mod tests {
         testing_env!(context.clone());
         let total_supply = 1_000_000_000_000_000u128;
         let mut contract = FungibleToken::new(carol(), total_supply.into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
 
         let initial_balance = context.account_balance;
         let initial_storage = context.storage_usage;
         context.attached_deposit = STORAGE_PRICE_PER_BYTE * 1000;
         testing_env!(context.clone());
         contract.inc_allowance(bob(), (total_supply / 2).into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
         assert_eq!(
             context.account_balance,
@@ -546,7 +548,7 @@ mod tests {
         context.attached_deposit = 0;
         testing_env!(context.clone());
         contract.dec_allowance(bob(), (total_supply / 2).into());
-        context.storage_usage = env::storage_usage();
+        context.storage_usage = env::storage_usage().as_bytes();
         context.account_balance = env::account_balance();
         assert!(context.storage_usage < initial_storage);
         assert!(context.account_balance < initial_balance);
@@ -518,6 +518,17 @@ def test_vec_string():
     print("Average diff", mean(diffs[2:]))
 
 
+def test_storage_reads():
+    print(" -> STORAGE READS <-")
+
+    # The values are written in the same call, so the cost of the writes is subtracted.
+    for n in [1, 10, 100]:
+        input = json_input({"n": n})
+        gas_write = gas_of("storage_write_n", input)
+        gas_read = gas_of("storage_read_n", input) - gas_write
+        gas_read_into = gas_of("storage_read_into_n", input) - gas_write
+        print("Cost of reading %d values of 100 bytes with env::storage_read is %s, with env::storage_read_into is %s, saved %s"
+              % (n, f(gas_read), f(gas_read_into), f(gas_read - gas_read_into)))
 
 
 test_integers()
@@ -527,3 +538,4 @@ test_vec_u32()
 test_vec_vec_u8()
 test_vec_string()
 test_simple_struct()
+test_storage_reads()
@@ -1,6 +1,6 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::near_bindgen;
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen};
 
 #[global_allocator]
 static ALLOC: near_sdk::wee_alloc::WeeAlloc<'_> = near_sdk::wee_alloc::WeeAlloc::INIT;
@@ -134,4 +134,32 @@ impl GasFeeTester {
     pub fn output_borsh_vec_string_v(#[serializer(borsh)] v: Vec<String>) -> Vec<String> {
         v
     }
+
+    // Storage reads
+
+    pub fn storage_write_n(n: u32) {
+        write_values(n);
+    }
+
+    pub fn storage_read_n(n: u32) {
+        write_values(n);
+        for i in 0..n {
+            env::storage_read(&i.to_le_bytes());
+        }
+    }
+
+    pub fn storage_read_into_n(n: u32) {
+        write_values(n);
+        let mut buf = vec![];
+        for i in 0..n {
+            env::storage_read_into(&i.to_le_bytes(), &mut buf);
+        }
+    }
+}
+
+/// Writes `n` values of 100 bytes, which the storage benchmarks read back.
+fn write_values(n: u32) {
+    for i in 0..n {
+        env::storage_write(&i.to_le_bytes(), &[1u8; 100]);
+    }
 }
@@ -0,0 +1,214 @@
+use crate::access_control::AccessControllable;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedMap, UnorderedSet};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, AccountId};
+
+/// The name of a role.
+pub type Role = String;
+
+/// The role that administers the roles that don't have another admin role.
+pub const DEFAULT_ADMIN_ROLE: &str = "DEFAULT_ADMIN";
+
+/// The members of the roles and the hierarchy of the roles.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AccessControl {
+    /// Role -> The accounts that have the role.
+    pub members: UnorderedMap<Role, UnorderedSet<AccountId>>,
+    /// Role -> The admin role of the role, if it's not `DEFAULT_ADMIN`.
+    pub admin_roles: UnorderedMap<Role, Role>,
+    /// The prefix of the sets of members.
+    members_prefix: Vec<u8>,
+}
+
+impl AccessControl {
+    /// Creates the roles with `admin_id` as the only member of `DEFAULT_ADMIN`. Use `prefix` as a
+    /// unique identifier of the roles on the trie.
+    pub fn new(prefix: Vec<u8>, admin_id: ValidAccountId) -> Self {
+        let mut this = Self {
+            members: UnorderedMap::new([prefix.as_slice(), b"m"].concat()),
+            admin_roles: UnorderedMap::new([prefix.as_slice(), b"a"].concat()),
+            members_prefix: [prefix.as_slice(), b"s"].concat(),
+        };
+        this.internal_grant_role(DEFAULT_ADMIN_ROLE, admin_id.as_ref());
+        this
+    }
+
+    /// Returns `true` if the account has the role.
+    pub fn internal_has_role(&self, role: &str, account_id: &AccountId) -> bool {
+        match self.members.get(&role.to_string()) {
+            Some(members) => members.contains(account_id),
+            None => false,
+        }
+    }
+
+    /// Returns the admin role of the role.
+    pub fn internal_role_admin(&self, role: &str) -> Role {
+        self.admin_roles.get(&role.to_string()).unwrap_or_else(|| DEFAULT_ADMIN_ROLE.to_string())
+    }
+
+    /// Panics unless the predecessor has the role.
+    pub fn assert_role(&self, role: &str) {
+        self.assert_any_role(&[role]);
+    }
+
+    /// Panics unless the predecessor has at least one of the roles.
+    pub fn assert_any_role(&self, roles: &[&str]) {
+        let account_id = env::predecessor_account_id();
+        if !roles.iter().any(|role| self.internal_has_role(role, &account_id)) {
+            env::panic(format!("Requires one of the roles: {}", roles.join(", ")).as_bytes());
+        }
+    }
+
+    /// Grants the role to the account without checking the predecessor. Returns `false` if the
+    /// account already had the role.
+    pub fn internal_grant_role(&mut self, role: &str, account_id: &AccountId) -> bool {
+        let role = role.to_string();
+        let mut members = self.members.get(&role).unwrap_or_else(|| {
+            UnorderedSet::new(
+                [self.members_prefix.as_slice(), &env::sha256(role.as_bytes())].concat(),
+            )
+        });
+        let granted = members.insert(account_id);
+        if granted {
+            self.members.insert(&role, &members);
+            env::log(format!("Granted the role {} to {}", role, account_id).as_bytes());
+        }
+        granted
+    }
+
+    /// Revokes the role from the account without checking the predecessor. Returns `false` if the
+    /// account didn't have the role.
+    pub fn internal_revoke_role(&mut self, role: &str, account_id: &AccountId) -> bool {
+        let role = role.to_string();
+        let mut members = match self.members.get(&role) {
+            Some(members) => members,
+            None => return false,
+        };
+        let revoked = members.remove(account_id);
+        if revoked {
+            if members.is_empty() {
+                self.members.remove(&role);
+            } else {
+                self.members.insert(&role, &members);
+            }
+            env::log(format!("Revoked the role {} from {}", role, account_id).as_bytes());
+        }
+        revoked
+    }
+
+    fn assert_role_admin(&self, role: &str) {
+        self.assert_role(&self.internal_role_admin(role));
+    }
+}
+
+impl AccessControllable for AccessControl {
+    fn acl_has_role(&self, role: Role, account_id: ValidAccountId) -> bool {
+        self.internal_has_role(&role, account_id.as_ref())
+    }
+
+    fn acl_role_members(&self, role: Role) -> Vec<AccountId> {
+        self.members.get(&role).map(|members| members.to_vec()).unwrap_or_default()
+    }
+
+    fn acl_role_admin(&self, role: Role) -> Role {
+        self.internal_role_admin(&role)
+    }
+
+    fn acl_grant_role(&mut self, role: Role, account_id: ValidAccountId) -> bool {
+        assert_one_yocto();
+        self.assert_role_admin(&role);
+        self.internal_grant_role(&role, account_id.as_ref())
+    }
+
+    fn acl_revoke_role(&mut self, role: Role, account_id: ValidAccountId) -> bool {
+        assert_one_yocto();
+        self.assert_role_admin(&role);
+        self.internal_revoke_role(&role, account_id.as_ref())
+    }
+
+    fn acl_renounce_role(&mut self, role: Role) -> bool {
+        assert_one_yocto();
+        self.internal_revoke_role(&role, &env::predecessor_account_id())
+    }
+
+    fn acl_set_role_admin(&mut self, role: Role, admin_role: Role) {
+        assert_one_yocto();
+        self.assert_role_admin(&role);
+        if admin_role == DEFAULT_ADMIN_ROLE {
+            self.admin_roles.remove(&role);
+        } else {
+            self.admin_roles.insert(&role, &admin_role);
+        }
+        env::log(format!("Set the admin role of {} to {}", role, admin_role).as_bytes());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+
+    fn valid(account_id: AccountId) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    fn setup_acl() -> AccessControl {
+        set_env(get_context(alice(), 1), vec![]);
+        AccessControl::new(b"r".to_vec(), valid(alice()))
+    }
+
+    #[test]
+    fn test_grant_revoke() {
+        let mut acl = setup_acl();
+        assert!(acl.acl_grant_role("minter".to_string(), valid(bob())));
+        assert!(!acl.acl_grant_role("minter".to_string(), valid(bob())));
+        assert!(acl.acl_has_role("minter".to_string(), valid(bob())));
+        assert_eq!(acl.acl_role_members("minter".to_string()), vec![bob()]);
+        set_env(get_context(bob(), 1), vec![]);
+        acl.assert_any_role(&["burner", "minter"]);
+        set_env(get_context(alice(), 1), vec![]);
+        assert!(acl.acl_revoke_role("minter".to_string(), valid(bob())));
+        assert!(!acl.acl_has_role("minter".to_string(), valid(bob())));
+        assert!(acl.acl_role_members("minter".to_string()).is_empty());
+    }
+
+    #[test]
+    fn test_role_admin() {
+        let mut acl = setup_acl();
+        acl.acl_set_role_admin("minter".to_string(), "minter_admin".to_string());
+        assert_eq!(acl.acl_role_admin("minter".to_string()), "minter_admin".to_string());
+        acl.acl_grant_role("minter_admin".to_string(), valid(bob()));
+        set_env(get_context(bob(), 1), vec![]);
+        acl.acl_grant_role("minter".to_string(), valid(contract()));
+        assert!(acl.acl_has_role("minter".to_string(), valid(contract())));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires one of the roles: minter_admin")]
+    fn test_default_admin_lost_role() {
+        let mut acl = setup_acl();
+        acl.acl_set_role_admin("minter".to_string(), "minter_admin".to_string());
+        acl.acl_grant_role("minter".to_string(), valid(bob()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires one of the roles: DEFAULT_ADMIN")]
+    fn test_grant_not_admin() {
+        let mut acl = setup_acl();
+        set_env(get_context(bob(), 1), vec![]);
+        acl.acl_grant_role("minter".to_string(), valid(bob()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires one of the roles: DEFAULT_ADMIN")]
+    fn test_renounce() {
+        let mut acl = setup_acl();
+        assert!(acl.acl_renounce_role(DEFAULT_ADMIN_ROLE.to_string()));
+        acl.assert_role(DEFAULT_ADMIN_ROLE);
+    }
+}
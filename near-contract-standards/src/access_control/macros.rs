@@ -0,0 +1,91 @@
+/// Exposes the role methods of the `AccessControl` stored in the field `$acl`, and adds the
+/// `assert_any_role` method that `#[access_control(roles = "...")]` relies on:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     acl: AccessControl,
+///     counter: u64,
+/// }
+///
+/// near_contract_standards::impl_access_control!(Contract, acl);
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     #[access_control(roles = "resetter")]
+///     pub fn reset(&mut self) {
+///         self.counter = 0;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_access_control {
+    ($contract: ident, $acl: ident) => {
+        use $crate::access_control::AccessControllable;
+
+        #[near_sdk::near_bindgen]
+        impl AccessControllable for $contract {
+            fn acl_has_role(
+                &self,
+                role: $crate::access_control::Role,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> bool {
+                self.$acl.acl_has_role(role, account_id)
+            }
+
+            fn acl_role_members(
+                &self,
+                role: $crate::access_control::Role,
+            ) -> Vec<near_sdk::AccountId> {
+                self.$acl.acl_role_members(role)
+            }
+
+            fn acl_role_admin(
+                &self,
+                role: $crate::access_control::Role,
+            ) -> $crate::access_control::Role {
+                self.$acl.acl_role_admin(role)
+            }
+
+            #[payable]
+            fn acl_grant_role(
+                &mut self,
+                role: $crate::access_control::Role,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> bool {
+                self.$acl.acl_grant_role(role, account_id)
+            }
+
+            #[payable]
+            fn acl_revoke_role(
+                &mut self,
+                role: $crate::access_control::Role,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> bool {
+                self.$acl.acl_revoke_role(role, account_id)
+            }
+
+            #[payable]
+            fn acl_renounce_role(&mut self, role: $crate::access_control::Role) -> bool {
+                self.$acl.acl_renounce_role(role)
+            }
+
+            #[payable]
+            fn acl_set_role_admin(
+                &mut self,
+                role: $crate::access_control::Role,
+                admin_role: $crate::access_control::Role,
+            ) {
+                self.$acl.acl_set_role_admin(role, admin_role)
+            }
+        }
+
+        impl $contract {
+            /// Panics unless the predecessor has at least one of the roles.
+            pub fn assert_any_role(&self, roles: &[&str]) {
+                self.$acl.assert_any_role(roles)
+            }
+        }
+    };
+}
@@ -0,0 +1,45 @@
+//! Role-based access control.
+//!
+//! `AccessControl` keeps the members of every role and the admin role of every role, and
+//! implements the `AccessControllable` trait. The members of the admin role of a role grant and
+//! revoke the role; the admin role of every role is `DEFAULT_ADMIN` unless it's changed with
+//! `acl_set_role_admin`. A contract embeds `AccessControl` into its state and exposes the methods
+//! with `impl_access_control!`, which also gives the contract an `assert_any_role` method.
+//!
+//! The methods of the contract marked with `#[access_control(roles = "minter, burner")]` inside
+//! `#[near_bindgen]` panic unless the predecessor has at least one of the roles.
+
+mod access_control_impl;
+mod macros;
+
+pub use access_control_impl::{AccessControl, Role, DEFAULT_ADMIN_ROLE};
+
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::AccountId;
+
+pub trait AccessControllable {
+    /// Returns `true` if the account has the role.
+    fn acl_has_role(&self, role: Role, account_id: ValidAccountId) -> bool;
+
+    /// Returns the members of the role.
+    fn acl_role_members(&self, role: Role) -> Vec<AccountId>;
+
+    /// Returns the role whose members grant and revoke the role.
+    fn acl_role_admin(&self, role: Role) -> Role;
+
+    /// Grants the role to the account. Returns `false` if the account already had it. Can only be
+    /// called by a member of the admin role of the role with 1 yoctoNEAR attached.
+    fn acl_grant_role(&mut self, role: Role, account_id: ValidAccountId) -> bool;
+
+    /// Revokes the role from the account. Returns `false` if the account didn't have it. Can only
+    /// be called by a member of the admin role of the role with 1 yoctoNEAR attached.
+    fn acl_revoke_role(&mut self, role: Role, account_id: ValidAccountId) -> bool;
+
+    /// Revokes the role from the predecessor. Returns `false` if it didn't have the role.
+    /// Requires 1 yoctoNEAR attached.
+    fn acl_renounce_role(&mut self, role: Role) -> bool;
+
+    /// Makes `admin_role` the admin role of `role`. Can only be called by a member of the current
+    /// admin role of `role` with 1 yoctoNEAR attached.
+    fn acl_set_role_admin(&mut self, role: Role, admin_role: Role);
+}
@@ -0,0 +1,148 @@
+use crate::access_list::AccessListManagement;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupSet;
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, AccountId};
+
+/// The whitelisted and the blacklisted accounts.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AccessList {
+    pub whitelist: LookupSet<AccountId>,
+    pub blacklist: LookupSet<AccountId>,
+}
+
+impl AccessList {
+    /// Creates empty lists. Use `prefix` as a unique identifier of the lists on the trie.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            whitelist: LookupSet::new([prefix.as_slice(), b"w"].concat()),
+            blacklist: LookupSet::new([prefix.as_slice(), b"b"].concat()),
+        }
+    }
+
+    /// Panics unless the account is whitelisted.
+    pub fn assert_account_whitelisted(&self, account_id: &AccountId) {
+        if !self.whitelist.contains(account_id) {
+            env::panic(format!("The account {} is not whitelisted", account_id).as_bytes());
+        }
+    }
+
+    /// Panics if the account is blacklisted.
+    pub fn assert_account_not_blacklisted(&self, account_id: &AccountId) {
+        if self.blacklist.contains(account_id) {
+            env::panic(format!("The account {} is blacklisted", account_id).as_bytes());
+        }
+    }
+
+    /// Panics unless the predecessor is whitelisted.
+    pub fn assert_whitelisted(&self) {
+        self.assert_account_whitelisted(&env::predecessor_account_id());
+    }
+
+    /// Panics if the predecessor is blacklisted.
+    pub fn assert_not_blacklisted(&self) {
+        self.assert_account_not_blacklisted(&env::predecessor_account_id());
+    }
+}
+
+impl AccessListManagement for AccessList {
+    fn is_whitelisted(&self, account_id: ValidAccountId) -> bool {
+        self.whitelist.contains(account_id.as_ref())
+    }
+
+    fn is_blacklisted(&self, account_id: ValidAccountId) -> bool {
+        self.blacklist.contains(account_id.as_ref())
+    }
+
+    fn add_to_whitelist(&mut self, account_ids: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        for account_id in account_ids {
+            if self.whitelist.insert(account_id.as_ref()) {
+                env::log(format!("Whitelisted {}", account_id.as_ref()).as_bytes());
+            }
+        }
+    }
+
+    fn remove_from_whitelist(&mut self, account_ids: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        for account_id in account_ids {
+            if self.whitelist.remove(account_id.as_ref()) {
+                env::log(format!("Removed {} from the whitelist", account_id.as_ref()).as_bytes());
+            }
+        }
+    }
+
+    fn add_to_blacklist(&mut self, account_ids: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        for account_id in account_ids {
+            if self.blacklist.insert(account_id.as_ref()) {
+                env::log(format!("Blacklisted {}", account_id.as_ref()).as_bytes());
+            }
+        }
+    }
+
+    fn remove_from_blacklist(&mut self, account_ids: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        for account_id in account_ids {
+            if self.blacklist.remove(account_id.as_ref()) {
+                env::log(format!("Removed {} from the blacklist", account_id.as_ref()).as_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, get_context, set_env};
+
+    fn valid(account_id: AccountId) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    fn setup_access_list() -> AccessList {
+        set_env(get_context(alice(), 1), vec![]);
+        AccessList::new(b"l".to_vec())
+    }
+
+    #[test]
+    fn test_whitelist() {
+        let mut list = setup_access_list();
+        list.add_to_whitelist(vec![valid(alice()), valid(bob())]);
+        assert!(list.is_whitelisted(valid(bob())));
+        list.assert_whitelisted();
+        list.remove_from_whitelist(vec![valid(bob())]);
+        assert!(!list.is_whitelisted(valid(bob())));
+        assert!(list.is_whitelisted(valid(alice())));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob.near is not whitelisted")]
+    fn test_not_whitelisted() {
+        let mut list = setup_access_list();
+        list.add_to_whitelist(vec![valid(alice())]);
+        set_env(get_context(bob(), 0), vec![]);
+        list.assert_whitelisted();
+    }
+
+    #[test]
+    #[should_panic(expected = "The account alice.near is blacklisted")]
+    fn test_blacklist() {
+        let mut list = setup_access_list();
+        list.add_to_blacklist(vec![valid(alice())]);
+        assert!(list.is_blacklisted(valid(alice())));
+        list.assert_not_blacklisted();
+    }
+
+    #[test]
+    fn test_remove_from_blacklist() {
+        let mut list = setup_access_list();
+        list.add_to_blacklist(vec![valid(alice())]);
+        list.remove_from_blacklist(vec![valid(alice())]);
+        list.assert_not_blacklisted();
+    }
+}
@@ -0,0 +1,81 @@
+/// Exposes the list methods of the `AccessList` stored in the field `$list`, and adds the
+/// `assert_whitelisted` method that `#[only_whitelisted]` relies on, and `assert_not_blacklisted`.
+/// Only the owner can change the lists, so the contract should implement the ownership with
+/// `impl_ownable!`:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     owner: Owner,
+///     access_list: AccessList,
+/// }
+///
+/// near_contract_standards::impl_ownable!(Contract, owner);
+/// near_contract_standards::impl_access_list!(Contract, access_list);
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     #[only_whitelisted]
+///     pub fn create_child(&mut self, name: String) { ... }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_access_list {
+    ($contract: ident, $list: ident) => {
+        use $crate::access_list::AccessListManagement;
+
+        #[near_sdk::near_bindgen]
+        impl AccessListManagement for $contract {
+            fn is_whitelisted(&self, account_id: near_sdk::json_types::ValidAccountId) -> bool {
+                self.$list.is_whitelisted(account_id)
+            }
+
+            fn is_blacklisted(&self, account_id: near_sdk::json_types::ValidAccountId) -> bool {
+                self.$list.is_blacklisted(account_id)
+            }
+
+            #[only_owner]
+            #[payable]
+            fn add_to_whitelist(&mut self, account_ids: Vec<near_sdk::json_types::ValidAccountId>) {
+                self.$list.add_to_whitelist(account_ids)
+            }
+
+            #[only_owner]
+            #[payable]
+            fn remove_from_whitelist(
+                &mut self,
+                account_ids: Vec<near_sdk::json_types::ValidAccountId>,
+            ) {
+                self.$list.remove_from_whitelist(account_ids)
+            }
+
+            #[only_owner]
+            #[payable]
+            fn add_to_blacklist(&mut self, account_ids: Vec<near_sdk::json_types::ValidAccountId>) {
+                self.$list.add_to_blacklist(account_ids)
+            }
+
+            #[only_owner]
+            #[payable]
+            fn remove_from_blacklist(
+                &mut self,
+                account_ids: Vec<near_sdk::json_types::ValidAccountId>,
+            ) {
+                self.$list.remove_from_blacklist(account_ids)
+            }
+        }
+
+        impl $contract {
+            /// Panics unless the predecessor is whitelisted.
+            pub fn assert_whitelisted(&self) {
+                self.$list.assert_whitelisted()
+            }
+
+            /// Panics if the predecessor is blacklisted.
+            pub fn assert_not_blacklisted(&self) {
+                self.$list.assert_not_blacklisted()
+            }
+        }
+    };
+}
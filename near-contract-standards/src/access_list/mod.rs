@@ -0,0 +1,38 @@
+//! Allow and deny lists of accounts.
+//!
+//! `AccessList` keeps the whitelisted and the blacklisted accounts, and implements the
+//! `AccessListManagement` trait. A contract embeds it into its state and exposes the methods with
+//! `impl_access_list!`, which also gives the contract the `assert_whitelisted` and
+//! `assert_not_blacklisted` methods. Only the owner of the contract can change the lists, so the
+//! contract also needs `impl_ownable!`.
+//!
+//! The methods of the contract marked with `#[only_whitelisted]` inside `#[near_bindgen]` panic
+//! unless the predecessor is whitelisted, e.g. to allow only the accounts that passed KYC to
+//! transfer a token. The lists are stored in `LookupSet`s, so they can't be enumerated.
+
+mod access_list_impl;
+mod macros;
+
+pub use access_list_impl::AccessList;
+
+use near_sdk::json_types::ValidAccountId;
+
+pub trait AccessListManagement {
+    /// Returns `true` if the account is whitelisted.
+    fn is_whitelisted(&self, account_id: ValidAccountId) -> bool;
+
+    /// Returns `true` if the account is blacklisted.
+    fn is_blacklisted(&self, account_id: ValidAccountId) -> bool;
+
+    /// Adds the accounts to the whitelist. Requires 1 yoctoNEAR attached.
+    fn add_to_whitelist(&mut self, account_ids: Vec<ValidAccountId>);
+
+    /// Removes the accounts from the whitelist. Requires 1 yoctoNEAR attached.
+    fn remove_from_whitelist(&mut self, account_ids: Vec<ValidAccountId>);
+
+    /// Adds the accounts to the blacklist. Requires 1 yoctoNEAR attached.
+    fn add_to_blacklist(&mut self, account_ids: Vec<ValidAccountId>);
+
+    /// Removes the accounts from the blacklist. Requires 1 yoctoNEAR attached.
+    fn remove_from_blacklist(&mut self, account_ids: Vec<ValidAccountId>);
+}
@@ -0,0 +1,20 @@
+/// Exposes `self_destruct` to the owner of the contract, which implements `Decommissionable`. The
+/// contract should implement the ownership with `impl_ownable!`.
+#[macro_export]
+macro_rules! impl_decommission {
+    ($contract: ident) => {
+        #[near_sdk::near_bindgen]
+        impl $contract {
+            /// Clears a batch of the state per call, and deletes the account in favor of
+            /// `beneficiary_id` once the state is cleared. Requires 1 yoctoNEAR attached.
+            #[only_owner]
+            #[payable]
+            pub fn self_destruct(
+                &mut self,
+                beneficiary_id: near_sdk::json_types::ValidAccountId,
+            ) -> near_sdk::PromiseOrValue<bool> {
+                $crate::decommission::self_destruct(self, beneficiary_id)
+            }
+        }
+    };
+}
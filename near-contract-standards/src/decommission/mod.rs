@@ -0,0 +1,111 @@
+//! Decommissioning of a contract.
+//!
+//! The contract implements `Decommissionable`: it tells whether it still holds the funds of its
+//! users, and clears its state in batches that fit into the gas limit of a call. `self_destruct`
+//! refuses to run while any user funds are held, clears a batch of the state per call, and deletes
+//! the account once the state is cleared, sending the remaining balance to the beneficiary. A
+//! contract exposes it to its owner with `impl_decommission!`, so it also needs `impl_ownable!`.
+
+mod macros;
+
+use crate::utils::assert_one_yocto;
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, Promise, PromiseOrValue};
+
+pub trait Decommissionable {
+    /// Returns `true` while the contract holds funds that belong to its users.
+    fn has_user_funds(&self) -> bool;
+
+    /// Removes a batch of the state, e.g. with `clear_partial` of the collections. Returns `true`
+    /// once all the state besides the contract struct is removed.
+    fn clear_state_batch(&mut self) -> bool;
+}
+
+/// Clears a batch of the state of the contract, and deletes the account in favor of
+/// `beneficiary_id` once the state is cleared. Returns `PromiseOrValue::Value(false)` while more
+/// batches remain, so the caller should call it again. Requires 1 yoctoNEAR attached. Panics while
+/// the contract holds user funds.
+pub fn self_destruct<C: Decommissionable>(
+    contract: &mut C,
+    beneficiary_id: ValidAccountId,
+) -> PromiseOrValue<bool> {
+    assert_one_yocto();
+    let beneficiary_id: String = beneficiary_id.into();
+    if beneficiary_id == env::current_account_id() {
+        env::panic(b"The beneficiary should be another account");
+    }
+    if contract.has_user_funds() {
+        env::panic(b"All user funds should be withdrawn before decommissioning the contract");
+    }
+    if !contract.clear_state_batch() {
+        env::log(b"Cleared a batch of the state, call again to continue");
+        return PromiseOrValue::Value(false);
+    }
+    env::log(format!("Deleting the account in favor of {}", beneficiary_id).as_bytes());
+    Promise::new(env::current_account_id()).delete_account(beneficiary_id).into()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+    use near_sdk::collections::UnorderedMap;
+    use near_sdk::AccountId;
+
+    struct Bank {
+        deposits: UnorderedMap<AccountId, u128>,
+        records: UnorderedMap<u64, u64>,
+    }
+
+    impl Decommissionable for Bank {
+        fn has_user_funds(&self) -> bool {
+            self.deposits.values().any(|amount| amount > 0)
+        }
+
+        fn clear_state_batch(&mut self) -> bool {
+            self.deposits.clear_partial(2) && self.records.clear_partial(2)
+        }
+    }
+
+    fn setup_bank() -> Bank {
+        set_env(get_context(alice(), 1), vec![]);
+        let mut bank = Bank {
+            deposits: UnorderedMap::new(b"d".to_vec()),
+            records: UnorderedMap::new(b"r".to_vec()),
+        };
+        bank.deposits.insert(&alice(), &0);
+        bank.records.extend((0..3).map(|i| (i, i)));
+        bank
+    }
+
+    #[test]
+    fn test_self_destruct() {
+        let mut bank = setup_bank();
+        let mut calls = 0;
+        while let PromiseOrValue::Value(false) = self_destruct(&mut bank, bob().try_into().unwrap())
+        {
+            calls += 1;
+        }
+        assert_eq!(calls, 1);
+        assert!(bank.deposits.is_empty());
+        assert!(bank.records.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "All user funds should be withdrawn")]
+    fn test_self_destruct_with_funds() {
+        let mut bank = setup_bank();
+        bank.deposits.insert(&bob(), &10);
+        self_destruct(&mut bank, bob().try_into().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "The beneficiary should be another account")]
+    fn test_self_destruct_to_itself() {
+        let mut bank = setup_bank();
+        self_destruct(&mut bank, contract().try_into().unwrap());
+    }
+}
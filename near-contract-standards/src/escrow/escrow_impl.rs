@@ -0,0 +1,595 @@
+use crate::escrow::{Asset, Deal, DealId, DealStatus, DealTerms, EscrowCore, EscrowResolver};
+use crate::fungible_token::receiver::FungibleTokenReceiver;
+use crate::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::{
+    env, ext_contract, AccountId, Balance, Gas, Promise, PromiseOrValue, PromiseResult,
+    StorageUsage,
+};
+
+const GAS_FOR_FT_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_RESOLVE_PAYOUT: Gas = 10_000_000_000_000;
+
+const ONE_YOCTO: Balance = 1;
+const NO_DEPOSIT: Balance = 0;
+
+/// The longest deal id, in bytes.
+pub const MAX_DEAL_ID_LEN: usize = 64;
+
+#[ext_contract(ext_fungible_token)]
+trait FungibleTokenCore {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+#[ext_contract(ext_self)]
+trait EscrowResolver {
+    fn escrow_resolve_payout(&mut self, depositor_id: AccountId, deal_id: DealId) -> bool;
+}
+
+/// The storage deposit of an account and the storage taken by its registration and its deals.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub struct StorageAccount {
+    pub total: Balance,
+    pub used: StorageUsage,
+}
+
+impl StorageAccount {
+    fn available(&self) -> Balance {
+        self.total.saturating_sub(self.used.cost())
+    }
+}
+
+/// The deals held by the escrow, by their depositors and ids, and the storage deposits of the
+/// depositors. The storage of a deal is paid from the storage deposit of its depositor, so the
+/// depositors register with `storage_deposit` before they create deals.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Escrow {
+    pub deals: UnorderedMap<(AccountId, DealId), Deal>,
+    pub storage_accounts: LookupMap<AccountId, StorageAccount>,
+    /// The storage taken by the registration of one account.
+    pub account_storage_usage: StorageUsage,
+}
+
+impl Escrow {
+    /// Use `prefix` as a unique identifier of the deals and the storage deposits on the trie.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        let mut this = Self {
+            deals: UnorderedMap::new([prefix.as_slice(), b"d"].concat()),
+            storage_accounts: LookupMap::new([prefix.as_slice(), b"s"].concat()),
+            account_storage_usage: StorageUsage::default(),
+        };
+        this.measure_account_storage_usage();
+        this
+    }
+
+    /// Measures the storage taken by one account by registering and removing the account with the
+    /// longest possible ID.
+    fn measure_account_storage_usage(&mut self) {
+        let initial_storage_usage = env::storage_usage();
+        let tmp_account_id = "a".repeat(64);
+        let tmp_account = StorageAccount { total: 0, used: StorageUsage::default() };
+        self.storage_accounts.insert(&tmp_account_id, &tmp_account);
+        self.account_storage_usage = env::storage_usage().saturating_sub(initial_storage_usage);
+        self.storage_accounts.remove(&tmp_account_id);
+    }
+
+    fn internal_storage_account(&self, account_id: &AccountId) -> StorageAccount {
+        match self.storage_accounts.get(account_id) {
+            Some(account) => account,
+            None => env::panic(
+                format!(
+                    "The account {} is not registered, register it with storage_deposit",
+                    account_id
+                )
+                .as_bytes(),
+            ),
+        }
+    }
+
+    /// Charges the account for the storage it started to use since `initial_storage_usage`. Panics
+    /// if its storage deposit doesn't cover it.
+    fn internal_charge_storage(
+        &mut self,
+        account_id: &AccountId,
+        initial_storage_usage: StorageUsage,
+    ) {
+        let mut account = self.internal_storage_account(account_id);
+        let used = env::storage_usage().saturating_sub(initial_storage_usage);
+        account.used = account.used.checked_add(used).unwrap();
+        if account.used.cost() > account.total {
+            env::panic(
+                format!(
+                    "The storage deposit of {} doesn't cover the deal, add {} yoctoNEAR with storage_deposit",
+                    account_id,
+                    account.used.cost() - account.total
+                )
+                .as_bytes(),
+            );
+        }
+        self.storage_accounts.insert(account_id, &account);
+    }
+
+    /// Removes the deal and returns its storage to the storage deposit of its depositor.
+    fn internal_remove_deal(&mut self, key: &(AccountId, DealId)) {
+        let initial_storage_usage = env::storage_usage();
+        self.deals.remove(key);
+        let freed = initial_storage_usage.saturating_sub(env::storage_usage());
+        if let Some(mut account) = self.storage_accounts.get(&key.0) {
+            account.used = account.used.saturating_sub(freed);
+            self.storage_accounts.insert(&key.0, &account);
+        }
+    }
+
+    /// Creates a funded deal of the depositor and charges the depositor for its storage. Panics if
+    /// the depositor already has a deal with the same id.
+    pub fn internal_create_deal(
+        &mut self,
+        depositor_id: AccountId,
+        terms: DealTerms,
+        asset: Asset,
+        amount: Balance,
+    ) {
+        if amount == 0 {
+            env::panic(b"The deal should be funded");
+        }
+        if terms.deal_id.is_empty() || terms.deal_id.len() > MAX_DEAL_ID_LEN {
+            env::panic(
+                format!("The deal id should have 1 to {} bytes", MAX_DEAL_ID_LEN).as_bytes(),
+            );
+        }
+        if terms.beneficiary_id.as_ref() == &depositor_id {
+            env::panic(b"The beneficiary should be different from the depositor");
+        }
+        let key = (depositor_id.clone(), terms.deal_id.clone());
+        if self.deals.get(&key).is_some() {
+            env::panic(format!("The deal {} already exists", terms.deal_id).as_bytes());
+        }
+        let deal = Deal {
+            depositor_id: depositor_id.clone(),
+            beneficiary_id: terms.beneficiary_id.into(),
+            arbiter_id: terms.arbiter_id.map(|arbiter_id| arbiter_id.into()),
+            asset,
+            amount: amount.into(),
+            deadline: terms.deadline,
+            status: DealStatus::Funded,
+        };
+        let initial_storage_usage = env::storage_usage();
+        self.deals.insert(&key, &deal);
+        self.internal_charge_storage(&depositor_id, initial_storage_usage);
+        env::log(format!("Created the deal {} of {}", terms.deal_id, depositor_id).as_bytes());
+    }
+
+    fn internal_funded_deal(&self, key: &(AccountId, DealId)) -> Deal {
+        match self.deals.get(key) {
+            Some(deal) if deal.status == DealStatus::Funded => deal,
+            Some(_) => env::panic(format!("The deal {} is being paid out", key.1).as_bytes()),
+            None => env::panic(format!("The deal {} doesn't exist", key.1).as_bytes()),
+        }
+    }
+
+    /// Transfers the funds of the deal to the receiver. NEAR deals are removed right away, while
+    /// fungible token deals wait for `escrow_resolve_payout`.
+    fn internal_payout(
+        &mut self,
+        key: (AccountId, DealId),
+        mut deal: Deal,
+        receiver_id: AccountId,
+        status: DealStatus,
+    ) -> Promise {
+        match deal.asset.clone() {
+            Asset::Near => {
+                self.internal_remove_deal(&key);
+                Promise::new(receiver_id).transfer(deal.amount.0)
+            }
+            Asset::FungibleToken(token_id) => {
+                deal.status = status;
+                self.deals.insert(&key, &deal);
+                let (depositor_id, deal_id) = key;
+                ext_fungible_token::ft_transfer(
+                    receiver_id,
+                    deal.amount,
+                    Some(format!("Escrow deal {}", deal_id)),
+                    &token_id,
+                    ONE_YOCTO,
+                    GAS_FOR_FT_TRANSFER,
+                )
+                .then(ext_self::escrow_resolve_payout(
+                    depositor_id,
+                    deal_id,
+                    &env::current_account_id(),
+                    NO_DEPOSIT,
+                    GAS_FOR_RESOLVE_PAYOUT,
+                ))
+            }
+        }
+    }
+
+    fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        self.storage_accounts.get(account_id).map(|account| StorageBalance {
+            total: account.total.into(),
+            available: account.available().into(),
+        })
+    }
+}
+
+impl EscrowCore for Escrow {
+    fn escrow_deposit(&mut self, terms: DealTerms) {
+        self.internal_create_deal(
+            env::predecessor_account_id(),
+            terms,
+            Asset::Near,
+            env::attached_deposit(),
+        );
+    }
+
+    fn escrow_release(&mut self, depositor_id: ValidAccountId, deal_id: DealId) -> Promise {
+        assert_one_yocto();
+        let key = (depositor_id.into(), deal_id);
+        let deal = self.internal_funded_deal(&key);
+        let predecessor_id = env::predecessor_account_id();
+        if predecessor_id != deal.depositor_id && Some(&predecessor_id) != deal.arbiter_id.as_ref()
+        {
+            env::panic(b"Only the depositor or the arbiter can release the deal");
+        }
+        env::log(format!("Releasing the deal {}", key.1).as_bytes());
+        let beneficiary_id = deal.beneficiary_id.clone();
+        self.internal_payout(key, deal, beneficiary_id, DealStatus::Releasing)
+    }
+
+    fn escrow_refund(&mut self, depositor_id: ValidAccountId, deal_id: DealId) -> Promise {
+        assert_one_yocto();
+        let key = (depositor_id.into(), deal_id);
+        let deal = self.internal_funded_deal(&key);
+        let predecessor_id = env::predecessor_account_id();
+        let is_expired = env::block_timestamp() > deal.deadline.0;
+        if predecessor_id != deal.beneficiary_id
+            && Some(&predecessor_id) != deal.arbiter_id.as_ref()
+            && !(predecessor_id == deal.depositor_id && is_expired)
+        {
+            env::panic(
+                b"Only the beneficiary or the arbiter can refund the deal before the deadline",
+            );
+        }
+        env::log(format!("Refunding the deal {}", key.1).as_bytes());
+        let depositor_id = deal.depositor_id.clone();
+        self.internal_payout(key, deal, depositor_id, DealStatus::Refunding)
+    }
+
+    fn escrow_deal(&self, depositor_id: ValidAccountId, deal_id: DealId) -> Option<Deal> {
+        self.deals.get(&(depositor_id.into(), deal_id))
+    }
+}
+
+impl EscrowResolver for Escrow {
+    fn escrow_resolve_payout(&mut self, depositor_id: AccountId, deal_id: DealId) -> bool {
+        let key = (depositor_id, deal_id);
+        let mut deal = match self.deals.get(&key) {
+            Some(deal) => deal,
+            None => env::panic(format!("The deal {} doesn't exist", key.1).as_bytes()),
+        };
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                self.internal_remove_deal(&key);
+                true
+            }
+            PromiseResult::Failed => {
+                env::log(format!("The payout of the deal {} failed", key.1).as_bytes());
+                deal.status = DealStatus::Funded;
+                self.deals.insert(&key, &deal);
+                false
+            }
+        }
+    }
+}
+
+impl FungibleTokenReceiver for Escrow {
+    /// Creates a deal funded with the transferred tokens. `msg` is the JSON of `DealTerms`.
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let terms: DealTerms = match near_sdk::serde_json::from_str(&msg) {
+            Ok(terms) => terms,
+            Err(_) => env::panic(b"The message should be the terms of the deal"),
+        };
+        let token_id = env::predecessor_account_id();
+        self.internal_create_deal(
+            sender_id.into(),
+            terms,
+            Asset::FungibleToken(token_id),
+            amount.into(),
+        );
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
+impl StorageManagement for Escrow {
+    /// Adds the attached deposit to the storage deposit of the account. With `registration_only`,
+    /// only registers the account and refunds the rest.
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.map(|a| a.into()).unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        let min_balance = self.storage_balance_bounds().min.0;
+        let (account, refund) = match self.storage_accounts.get(&account_id) {
+            Some(_) if registration_only => {
+                env::log(b"The account is already registered, refunding the deposit");
+                (None, amount)
+            }
+            Some(mut account) => {
+                account.total += amount;
+                (Some(account), 0)
+            }
+            None => {
+                if amount < min_balance {
+                    env::panic(b"The attached deposit is less than the minimum storage balance");
+                }
+                let total = if registration_only { min_balance } else { amount };
+                (Some(StorageAccount { total, used: self.account_storage_usage }), amount - total)
+            }
+        };
+        if let Some(account) = account {
+            self.storage_accounts.insert(&account_id, &account);
+        }
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let mut account = self.internal_storage_account(&account_id);
+        let available = account.available();
+        let amount = amount.map(|amount| amount.0).unwrap_or(available);
+        if amount > available {
+            env::panic(b"The amount is greater than the available storage balance");
+        }
+        account.total -= amount;
+        self.storage_accounts.insert(&account_id, &account);
+        if amount > 0 {
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    /// The deals hold the funds of other accounts, so an account with deals can't be unregistered
+    /// even with `force`.
+    fn storage_unregister(&mut self, _force: Option<bool>) -> bool {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        match self.storage_accounts.get(&account_id) {
+            Some(account) => {
+                if account.used != self.account_storage_usage {
+                    env::panic(b"Can't unregister the account with deals");
+                }
+                self.storage_accounts.remove(&account_id);
+                Promise::new(account_id).transfer(account.total);
+                true
+            }
+            None => {
+                env::log(format!("The account {} is not registered", account_id).as_bytes());
+                false
+            }
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds { min: self.account_storage_usage.cost().into(), max: None }
+    }
+
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(account_id.as_ref())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+    use near_sdk::json_types::U64;
+
+    fn carol() -> AccountId {
+        "carol.near".to_string()
+    }
+
+    fn token() -> AccountId {
+        "token.near".to_string()
+    }
+
+    fn id(account_id: AccountId) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    fn terms(deal_id: &str) -> DealTerms {
+        DealTerms {
+            deal_id: deal_id.to_string(),
+            beneficiary_id: id(bob()),
+            arbiter_id: Some(id(carol())),
+            deadline: U64(100),
+        }
+    }
+
+    fn set_time(account_id: AccountId, attached_deposit: Balance, timestamp: u64) {
+        let mut context = get_context(account_id, attached_deposit);
+        context.block_timestamp = timestamp;
+        set_env(context, vec![]);
+    }
+
+    /// Registers the account with a storage deposit that covers a few deals, and returns it.
+    fn register(escrow: &mut Escrow, account_id: AccountId) -> Balance {
+        let storage_deposit = escrow.storage_balance_bounds().min.0 * 10;
+        set_time(account_id, storage_deposit, 0);
+        escrow.storage_deposit(None, None);
+        storage_deposit
+    }
+
+    fn setup_escrow() -> Escrow {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut escrow = Escrow::new(b"e".to_vec());
+        register(&mut escrow, alice());
+        set_time(alice(), 1000, 0);
+        escrow.escrow_deposit(terms("near"));
+        set_env(get_context(token(), 0), vec![]);
+        escrow.ft_on_transfer(
+            id(alice()),
+            U128(500),
+            near_sdk::serde_json::to_string(&terms("ft")).unwrap(),
+        );
+        escrow
+    }
+
+    fn deal(escrow: &Escrow, deal_id: &str) -> Option<Deal> {
+        escrow.escrow_deal(id(alice()), deal_id.to_string())
+    }
+
+    #[test]
+    fn test_deposit() {
+        let escrow = setup_escrow();
+        let deal_near = deal(&escrow, "near").unwrap();
+        assert_eq!(deal_near.amount, U128(1000));
+        assert_eq!(deal_near.asset, Asset::Near);
+        let deal_ft = deal(&escrow, "ft").unwrap();
+        assert_eq!(deal_ft.amount, U128(500));
+        assert_eq!(deal_ft.asset, Asset::FungibleToken(token()));
+        // The deals are paid from the storage deposit of the depositor.
+        let min_balance = escrow.storage_balance_bounds().min.0;
+        let storage_balance = escrow.storage_balance_of(id(alice())).unwrap();
+        assert_eq!(storage_balance.total, U128(min_balance * 10));
+        assert!(storage_balance.available.0 < min_balance * 9);
+    }
+
+    #[test]
+    fn test_deal_ids_of_depositors() {
+        let mut escrow = setup_escrow();
+        register(&mut escrow, carol());
+        set_time(carol(), 2000, 0);
+        escrow.escrow_deposit(terms("near"));
+        assert_eq!(deal(&escrow, "near").unwrap().amount, U128(1000));
+        assert_eq!(escrow.escrow_deal(id(carol()), "near".to_string()).unwrap().amount, U128(2000));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account carol.near is not registered")]
+    fn test_deposit_unregistered() {
+        let mut escrow = setup_escrow();
+        set_time(carol(), 1000, 0);
+        escrow.escrow_deposit(terms("near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't cover the deal")]
+    fn test_deposit_without_storage() {
+        let mut escrow = setup_escrow();
+        let min_balance = escrow.storage_balance_bounds().min.0;
+        set_time(carol(), min_balance, 0);
+        escrow.storage_deposit(None, Some(true));
+        set_time(carol(), 1000, 0);
+        escrow.escrow_deposit(terms("near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The deal id should have 1 to 64 bytes")]
+    fn test_long_deal_id() {
+        let mut escrow = setup_escrow();
+        set_time(alice(), 1000, 0);
+        escrow.escrow_deposit(terms(&"a".repeat(MAX_DEAL_ID_LEN + 1)));
+    }
+
+    #[test]
+    fn test_release_near() {
+        let mut escrow = setup_escrow();
+        let available = escrow.storage_balance_of(id(alice())).unwrap().available;
+        set_time(alice(), 1, 0);
+        escrow.escrow_release(id(alice()), "near".to_string());
+        assert_eq!(deal(&escrow, "near"), None);
+        // The storage of the deal is returned to the depositor.
+        assert!(escrow.storage_balance_of(id(alice())).unwrap().available.0 > available.0);
+    }
+
+    #[test]
+    fn test_release_ft() {
+        let mut escrow = setup_escrow();
+        set_time(carol(), 1, 0);
+        escrow.escrow_release(id(alice()), "ft".to_string());
+        assert_eq!(deal(&escrow, "ft").unwrap().status, DealStatus::Releasing);
+        set_env(get_context(contract(), 0), vec![PromiseResult::Failed]);
+        assert!(!escrow.escrow_resolve_payout(alice(), "ft".to_string()));
+        assert_eq!(deal(&escrow, "ft").unwrap().status, DealStatus::Funded);
+        set_time(carol(), 1, 0);
+        escrow.escrow_release(id(alice()), "ft".to_string());
+        set_env(get_context(contract(), 0), vec![PromiseResult::Successful(vec![])]);
+        assert!(escrow.escrow_resolve_payout(alice(), "ft".to_string()));
+        assert_eq!(deal(&escrow, "ft"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the depositor or the arbiter can release the deal")]
+    fn test_release_by_beneficiary() {
+        let mut escrow = setup_escrow();
+        set_time(bob(), 1, 0);
+        escrow.escrow_release(id(alice()), "near".to_string());
+    }
+
+    #[test]
+    fn test_refund_after_deadline() {
+        let mut escrow = setup_escrow();
+        set_time(alice(), 1, 101);
+        escrow.escrow_refund(id(alice()), "near".to_string());
+        assert_eq!(deal(&escrow, "near"), None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Only the beneficiary or the arbiter can refund the deal before the deadline"
+    )]
+    fn test_refund_before_deadline() {
+        let mut escrow = setup_escrow();
+        set_time(alice(), 1, 100);
+        escrow.escrow_refund(id(alice()), "near".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "The deal near already exists")]
+    fn test_deposit_twice() {
+        let mut escrow = setup_escrow();
+        set_time(alice(), 1000, 0);
+        escrow.escrow_deposit(terms("near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't unregister the account with deals")]
+    fn test_unregister_with_deals() {
+        let mut escrow = setup_escrow();
+        set_time(alice(), 1, 0);
+        escrow.storage_unregister(Some(true));
+    }
+
+    #[test]
+    fn test_storage_withdraw_and_unregister() {
+        let mut escrow = setup_escrow();
+        set_time(alice(), 1, 101);
+        escrow.escrow_refund(id(alice()), "near".to_string());
+        set_env(get_context(contract(), 0), vec![]);
+        escrow.internal_remove_deal(&(alice(), "ft".to_string()));
+        set_time(alice(), 1, 0);
+        let storage_balance = escrow.storage_withdraw(Some(U128(1)));
+        assert_eq!(storage_balance.total, U128(escrow.storage_balance_bounds().min.0 * 10 - 1));
+        assert!(escrow.storage_unregister(None));
+        assert_eq!(escrow.storage_balance_of(id(alice())), None);
+    }
+}
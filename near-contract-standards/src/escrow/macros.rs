@@ -0,0 +1,105 @@
+/// Exposes the escrow methods of the `Escrow` stored in the field `$escrow`, including
+/// `ft_on_transfer` that funds the deals with fungible tokens and the storage management (NEP-145)
+/// that pays for the deals.
+#[macro_export]
+macro_rules! impl_escrow {
+    ($contract: ident, $escrow: ident) => {
+        use $crate::escrow::{EscrowCore, EscrowResolver};
+        use $crate::fungible_token::receiver::FungibleTokenReceiver;
+        use $crate::storage_management::StorageManagement;
+
+        #[near_sdk::near_bindgen]
+        impl EscrowCore for $contract {
+            #[payable]
+            fn escrow_deposit(&mut self, terms: $crate::escrow::DealTerms) {
+                self.$escrow.escrow_deposit(terms)
+            }
+
+            #[payable]
+            fn escrow_release(
+                &mut self,
+                depositor_id: near_sdk::json_types::ValidAccountId,
+                deal_id: $crate::escrow::DealId,
+            ) -> near_sdk::Promise {
+                self.$escrow.escrow_release(depositor_id, deal_id)
+            }
+
+            #[payable]
+            fn escrow_refund(
+                &mut self,
+                depositor_id: near_sdk::json_types::ValidAccountId,
+                deal_id: $crate::escrow::DealId,
+            ) -> near_sdk::Promise {
+                self.$escrow.escrow_refund(depositor_id, deal_id)
+            }
+
+            fn escrow_deal(
+                &self,
+                depositor_id: near_sdk::json_types::ValidAccountId,
+                deal_id: $crate::escrow::DealId,
+            ) -> Option<$crate::escrow::Deal> {
+                self.$escrow.escrow_deal(depositor_id, deal_id)
+            }
+        }
+
+        #[near_sdk::near_bindgen]
+        impl EscrowResolver for $contract {
+            fn escrow_resolve_payout(
+                &mut self,
+                depositor_id: near_sdk::AccountId,
+                deal_id: $crate::escrow::DealId,
+            ) -> bool {
+                near_sdk::wrapper::assert_private("escrow_resolve_payout");
+                self.$escrow.escrow_resolve_payout(depositor_id, deal_id)
+            }
+        }
+
+        #[near_sdk::near_bindgen]
+        impl FungibleTokenReceiver for $contract {
+            fn ft_on_transfer(
+                &mut self,
+                sender_id: near_sdk::json_types::ValidAccountId,
+                amount: near_sdk::json_types::U128,
+                msg: String,
+            ) -> near_sdk::PromiseOrValue<near_sdk::json_types::U128> {
+                self.$escrow.ft_on_transfer(sender_id, amount, msg)
+            }
+        }
+
+        #[near_sdk::near_bindgen]
+        impl StorageManagement for $contract {
+            #[payable]
+            fn storage_deposit(
+                &mut self,
+                account_id: Option<near_sdk::json_types::ValidAccountId>,
+                registration_only: Option<bool>,
+            ) -> $crate::storage_management::StorageBalance {
+                self.$escrow.storage_deposit(account_id, registration_only)
+            }
+
+            #[payable]
+            fn storage_withdraw(
+                &mut self,
+                amount: Option<near_sdk::json_types::U128>,
+            ) -> $crate::storage_management::StorageBalance {
+                self.$escrow.storage_withdraw(amount)
+            }
+
+            #[payable]
+            fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+                self.$escrow.storage_unregister(force)
+            }
+
+            fn storage_balance_bounds(&self) -> $crate::storage_management::StorageBalanceBounds {
+                self.$escrow.storage_balance_bounds()
+            }
+
+            fn storage_balance_of(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> Option<$crate::storage_management::StorageBalance> {
+                self.$escrow.storage_balance_of(account_id)
+            }
+        }
+    };
+}
@@ -0,0 +1,102 @@
+//! Escrow of NEAR and fungible tokens.
+//!
+//! `Escrow` holds the deposits of the deals, and implements the `EscrowCore` and `EscrowResolver`
+//! traits. The depositor funds a deal with NEAR through `escrow_deposit`, or with fungible tokens
+//! through `ft_transfer_call` to the contract with the deal in the message. The funds are then
+//! either released to the beneficiary or refunded to the depositor:
+//!
+//! * the depositor or the arbiter releases the deal;
+//! * the beneficiary or the arbiter refunds the deal, and so can the depositor after the deadline.
+//!
+//! The deals are identified by their depositors and ids, so a depositor can't take the id of the
+//! deal of another. The depositors pay for the storage of their deals with the NEP-145 storage
+//! management: they register with `storage_deposit` before they create deals, and the storage of
+//! a deal is returned once it's paid out.
+//!
+//! The payouts of fungible tokens are resolved by `escrow_resolve_payout`, which restores the deal
+//! if the transfer failed. A contract embeds `Escrow` into its state and exposes the methods with
+//! `impl_escrow!`.
+
+mod escrow_impl;
+mod macros;
+
+pub use escrow_impl::{Escrow, StorageAccount, MAX_DEAL_ID_LEN};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{ValidAccountId, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Promise};
+
+/// The identifier of a deal among the deals of its depositor, chosen by the depositor. At most
+/// `MAX_DEAL_ID_LEN` bytes.
+pub type DealId = String;
+
+/// What is held in the escrow.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Asset {
+    Near,
+    /// Fungible tokens of the given token contract.
+    FungibleToken(AccountId),
+}
+
+/// The stage of a deal.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DealStatus {
+    /// The funds are held.
+    Funded,
+    /// The funds are being transferred to the beneficiary.
+    Releasing,
+    /// The funds are being transferred back to the depositor.
+    Refunding,
+}
+
+/// A deal with the funds held by the escrow.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Deal {
+    pub depositor_id: AccountId,
+    pub beneficiary_id: AccountId,
+    /// The account that can release or refund the deal in a dispute.
+    pub arbiter_id: Option<AccountId>,
+    pub asset: Asset,
+    pub amount: U128,
+    /// After this timestamp in nanoseconds the depositor can refund the deal.
+    pub deadline: U64,
+    pub status: DealStatus,
+}
+
+/// The terms of a new deal. Passed as the message of `ft_transfer_call` to fund a deal with
+/// fungible tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DealTerms {
+    pub deal_id: DealId,
+    pub beneficiary_id: ValidAccountId,
+    pub arbiter_id: Option<ValidAccountId>,
+    pub deadline: U64,
+}
+
+pub trait EscrowCore {
+    /// Creates a deal funded with the attached NEAR. The storage of the deal is paid from the
+    /// storage deposit of the caller.
+    fn escrow_deposit(&mut self, terms: DealTerms);
+
+    /// Transfers the funds of the deal to the beneficiary. Can be called by the depositor or the
+    /// arbiter with 1 yoctoNEAR attached.
+    fn escrow_release(&mut self, depositor_id: ValidAccountId, deal_id: DealId) -> Promise;
+
+    /// Transfers the funds of the deal back to the depositor. Can be called by the beneficiary or
+    /// the arbiter, or by the depositor after the deadline, with 1 yoctoNEAR attached.
+    fn escrow_refund(&mut self, depositor_id: ValidAccountId, deal_id: DealId) -> Promise;
+
+    /// Returns the deal of the depositor.
+    fn escrow_deal(&self, depositor_id: ValidAccountId, deal_id: DealId) -> Option<Deal>;
+}
+
+pub trait EscrowResolver {
+    /// Private callback of the payout of fungible tokens. Removes the deal if the transfer
+    /// succeeded, and restores it otherwise. Returns `true` if the transfer succeeded.
+    fn escrow_resolve_payout(&mut self, depositor_id: AccountId, deal_id: DealId) -> bool;
+}
@@ -0,0 +1,39 @@
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::PromiseOrValue;
+
+/// The core methods of the fungible token standard (NEP-141).
+pub trait FungibleTokenCore {
+    /// Transfers positive `amount` of tokens from the `env::predecessor_account_id` to
+    /// `receiver_id`. Both accounts must be registered with the contract for the transfer to
+    /// succeed. The caller must attach exactly 1 yoctoNEAR to confirm the transaction with a full
+    /// access key.
+    ///
+    /// Arguments:
+    /// - `receiver_id` - the account ID of the receiver.
+    /// - `amount` - the amount of tokens to transfer. Must be a positive number in decimal string
+    ///   representation.
+    /// - `memo` - an optional string field in a free form to associate a memo with this transfer.
+    fn ft_transfer(&mut self, receiver_id: ValidAccountId, amount: U128, memo: Option<String>);
+
+    /// Transfers positive `amount` of tokens from the `env::predecessor_account_id` to
+    /// `receiver_id` account, then calls `ft_on_transfer` on the receiver contract and
+    /// `ft_resolve_transfer` on this contract after that.
+    ///
+    /// The receiver returns the amount of tokens it didn't use, and these tokens are refunded to
+    /// the sender by `ft_resolve_transfer`. The caller must attach exactly 1 yoctoNEAR.
+    ///
+    /// Returns a promise which will result in the amount of tokens withdrawn from the sender.
+    fn ft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+
+    /// Returns the total supply of the token in a decimal string representation.
+    fn ft_total_supply(&self) -> U128;
+
+    /// Returns the balance of the account. If the account doesn't exist, returns `"0"`.
+    fn ft_balance_of(&self, account_id: ValidAccountId) -> U128;
+}
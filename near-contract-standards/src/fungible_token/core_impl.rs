@@ -0,0 +1,336 @@
+use crate::fungible_token::core::FungibleTokenCore;
+use crate::fungible_token::resolver::FungibleTokenResolver;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::math::CheckedOrPanic;
+use near_sdk::{
+    env, ext_contract, AccountId, Balance, Gas, PromiseOrValue, PromiseResult, StorageUsage,
+};
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 5_000_000_000_000;
+const GAS_FOR_FT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
+
+const NO_DEPOSIT: Balance = 0;
+
+#[ext_contract(ext_self)]
+trait FungibleTokenResolver {
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128;
+}
+
+#[ext_contract(ext_fungible_token_receiver)]
+pub trait FungibleTokenReceiver {
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+}
+
+/// Implementation of the fungible token standard (NEP-141).
+/// The contract keeps it as a field of its state and implements `FungibleTokenCore` and
+/// `FungibleTokenResolver` by delegating to it, see `impl_fungible_token_core!`.
+///
+/// An account has to be registered before it can hold tokens, either by the contract with
+/// `internal_register_account` or by paying for its storage with `storage_deposit`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FungibleToken {
+    /// AccountID -> Account balance.
+    pub accounts: UnorderedMap<AccountId, Balance>,
+    /// Total supply of the all token.
+    pub total_supply: Balance,
+    /// The storage size in bytes for one account.
+    pub account_storage_usage: StorageUsage,
+}
+
+impl FungibleToken {
+    /// Creates a token with zero supply. Use `prefix` as a unique identifier of the accounts map
+    /// on the trie.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        let mut this = Self {
+            accounts: UnorderedMap::new(prefix),
+            total_supply: 0,
+            account_storage_usage: StorageUsage::default(),
+        };
+        this.measure_account_storage_usage();
+        this
+    }
+
+    /// Measures the storage taken by one account by registering and removing the account with the
+    /// longest possible ID.
+    fn measure_account_storage_usage(&mut self) {
+        let initial_storage_usage = env::storage_usage();
+        let tmp_account_id = "a".repeat(64);
+        self.accounts.insert(&tmp_account_id, &0u128);
+        self.account_storage_usage = env::storage_usage().saturating_sub(initial_storage_usage);
+        self.accounts.remove(&tmp_account_id);
+    }
+
+    /// Returns the balance of a registered account, panics if the account is not registered.
+    pub fn internal_unwrap_balance_of(&self, account_id: &AccountId) -> Balance {
+        match self.accounts.get(account_id) {
+            Some(balance) => balance,
+            None => env::panic(format!("The account {} is not registered", account_id).as_bytes()),
+        }
+    }
+
+    /// Mints `amount` of tokens on a registered account.
+    pub fn internal_deposit(&mut self, account_id: &AccountId, amount: Balance) {
+        let balance = self.internal_unwrap_balance_of(account_id);
+        let new_balance = balance.checked_add_or_panic(amount, "Balance overflow");
+        self.accounts.insert(account_id, &new_balance);
+        self.total_supply = self.total_supply.checked_add_or_panic(amount, "Total supply overflow");
+    }
+
+    /// Burns `amount` of tokens from a registered account.
+    pub fn internal_withdraw(&mut self, account_id: &AccountId, amount: Balance) {
+        let balance = self.internal_unwrap_balance_of(account_id);
+        let new_balance =
+            balance.checked_sub_or_panic(amount, "The account doesn't have enough balance");
+        self.accounts.insert(account_id, &new_balance);
+        self.total_supply = self.total_supply.checked_sub_or_panic(amount, "Total supply overflow");
+    }
+
+    /// Moves `amount` of tokens between two registered accounts and logs the transfer.
+    pub fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+        memo: Option<String>,
+    ) {
+        if sender_id == receiver_id {
+            env::panic(b"Sender and receiver should be different");
+        }
+        if amount == 0 {
+            env::panic(b"The amount should be a positive number");
+        }
+        self.internal_withdraw(sender_id, amount);
+        self.internal_deposit(receiver_id, amount);
+        env::log(format!("Transfer {} from {} to {}", amount, sender_id, receiver_id).as_bytes());
+        if let Some(memo) = memo {
+            env::log(format!("Memo: {}", memo).as_bytes());
+        }
+    }
+
+    /// Registers an account with zero balance, panics if it is already registered.
+    pub fn internal_register_account(&mut self, account_id: &AccountId) {
+        if self.accounts.insert(account_id, &0).is_some() {
+            env::panic(b"The account is already registered");
+        }
+    }
+
+    /// Resolves `ft_transfer_call` by refunding the unused tokens to the sender.
+    /// If the sender was unregistered while the transfer was in flight, the refunded tokens are
+    /// burned instead.
+    ///
+    /// Returns `(used amount, burned amount)`.
+    pub fn internal_ft_resolve_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: ValidAccountId,
+        amount: U128,
+    ) -> (Balance, Balance) {
+        let receiver_id: AccountId = receiver_id.into();
+        let amount: Balance = amount.into();
+
+        // Get the unused amount from the `ft_on_transfer` call result.
+        let unused_amount = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<U128>(&value) {
+                    Ok(unused_amount) => std::cmp::min(amount, unused_amount.0),
+                    Err(_) => amount,
+                }
+            }
+            PromiseResult::Failed => amount,
+        };
+
+        if unused_amount > 0 {
+            let receiver_balance = self.accounts.get(&receiver_id).unwrap_or(0);
+            if receiver_balance > 0 {
+                let refund_amount = std::cmp::min(receiver_balance, unused_amount);
+                self.accounts.insert(&receiver_id, &(receiver_balance - refund_amount));
+
+                if let Some(sender_balance) = self.accounts.get(sender_id) {
+                    self.accounts.insert(sender_id, &(sender_balance + refund_amount));
+                    env::log(
+                        format!("Refund {} from {} to {}", refund_amount, receiver_id, sender_id)
+                            .as_bytes(),
+                    );
+                    return (amount - refund_amount, 0);
+                } else {
+                    // The sender's account was deleted, so the refunded tokens are burned.
+                    self.total_supply -= refund_amount;
+                    env::log(b"The account of the sender was deleted");
+                    return (amount, refund_amount);
+                }
+            }
+        }
+        (amount, 0)
+    }
+}
+
+impl FungibleTokenCore for FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: ValidAccountId, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let amount: Balance = amount.into();
+        self.internal_transfer(&sender_id, receiver_id.as_ref(), amount, memo);
+    }
+
+    fn ft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_one_yocto();
+        if env::prepaid_gas() <= GAS_FOR_FT_TRANSFER_CALL {
+            env::panic(b"More gas is required");
+        }
+        let sender_id = env::predecessor_account_id();
+        let amount: Balance = amount.into();
+        self.internal_transfer(&sender_id, receiver_id.as_ref(), amount, memo);
+        // Initiating receiver's call and the callback
+        ext_fungible_token_receiver::ft_on_transfer(
+            sender_id.clone(),
+            amount.into(),
+            msg,
+            receiver_id.as_ref(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_FT_TRANSFER_CALL,
+        )
+        .then(ext_self::ft_resolve_transfer(
+            sender_id,
+            receiver_id.into(),
+            amount.into(),
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+        .into()
+    }
+
+    fn ft_total_supply(&self) -> U128 {
+        self.total_supply.into()
+    }
+
+    fn ft_balance_of(&self, account_id: ValidAccountId) -> U128 {
+        self.accounts.get(account_id.as_ref()).unwrap_or(0).into()
+    }
+}
+
+impl FungibleTokenResolver for FungibleToken {
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        receiver_id: ValidAccountId,
+        amount: U128,
+    ) -> U128 {
+        self.internal_ft_resolve_transfer(sender_id.as_ref(), receiver_id, amount).0.into()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use near_sdk::PromiseResult;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+
+    fn setup_token() -> FungibleToken {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut ft = FungibleToken::new(b"t".to_vec());
+        ft.internal_register_account(&alice());
+        ft.internal_register_account(&bob());
+        ft.internal_deposit(&alice(), 1_000);
+        ft
+    }
+
+    #[test]
+    fn test_deposit_withdraw() {
+        let mut ft = setup_token();
+        assert_eq!(ft.ft_total_supply().0, 1_000);
+        ft.internal_withdraw(&alice(), 400);
+        assert_eq!(ft.ft_balance_of(alice().try_into().unwrap()).0, 600);
+        assert_eq!(ft.ft_total_supply().0, 600);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account carol.near is not registered")]
+    fn test_deposit_unregistered() {
+        let mut ft = setup_token();
+        ft.internal_deposit(&"carol.near".to_string(), 1);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut ft = setup_token();
+        set_env(get_context(alice(), 1), vec![]);
+        ft.ft_transfer(bob().try_into().unwrap(), 300.into(), None);
+        assert_eq!(ft.ft_balance_of(alice().try_into().unwrap()).0, 700);
+        assert_eq!(ft.ft_balance_of(bob().try_into().unwrap()).0, 300);
+        assert_eq!(ft.ft_total_supply().0, 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_transfer_without_yocto() {
+        let mut ft = setup_token();
+        set_env(get_context(alice(), 0), vec![]);
+        ft.ft_transfer(bob().try_into().unwrap(), 300.into(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn test_transfer_not_enough_balance() {
+        let mut ft = setup_token();
+        set_env(get_context(alice(), 1), vec![]);
+        ft.ft_transfer(bob().try_into().unwrap(), 1_001.into(), None);
+    }
+
+    #[test]
+    fn test_resolve_transfer_refunds_unused() {
+        let mut ft = setup_token();
+        set_env(get_context(alice(), 1), vec![]);
+        ft.ft_transfer(bob().try_into().unwrap(), 300.into(), None);
+        let unused = near_sdk::serde_json::to_vec(&U128(100)).unwrap();
+        set_env(get_context(contract(), 0), vec![PromiseResult::Successful(unused)]);
+        let used = ft.ft_resolve_transfer(
+            alice().try_into().unwrap(),
+            bob().try_into().unwrap(),
+            300.into(),
+        );
+        assert_eq!(used.0, 200);
+        assert_eq!(ft.ft_balance_of(alice().try_into().unwrap()).0, 800);
+        assert_eq!(ft.ft_balance_of(bob().try_into().unwrap()).0, 200);
+    }
+
+    #[test]
+    fn test_resolve_transfer_failed_refunds_all() {
+        let mut ft = setup_token();
+        set_env(get_context(alice(), 1), vec![]);
+        ft.ft_transfer(bob().try_into().unwrap(), 300.into(), None);
+        set_env(get_context(contract(), 0), vec![PromiseResult::Failed]);
+        let used = ft.ft_resolve_transfer(
+            alice().try_into().unwrap(),
+            bob().try_into().unwrap(),
+            300.into(),
+        );
+        assert_eq!(used.0, 0);
+        assert_eq!(ft.ft_balance_of(alice().try_into().unwrap()).0, 1_000);
+        assert_eq!(ft.ft_balance_of(bob().try_into().unwrap()).0, 0);
+    }
+}
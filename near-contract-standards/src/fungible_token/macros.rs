@@ -0,0 +1,142 @@
+/// The core methods for a basic fungible token. Extension standards may be
+/// added in addition to this macro.
+///
+/// Expects the contract to store `FungibleToken` in the field `$token`:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     token: FungibleToken,
+/// }
+///
+/// near_contract_standards::impl_fungible_token_core!(Contract, token);
+/// ```
+#[macro_export]
+macro_rules! impl_fungible_token_core {
+    ($contract: ident, $token: ident) => {
+        use $crate::fungible_token::core::FungibleTokenCore;
+        use $crate::fungible_token::resolver::FungibleTokenResolver;
+
+        #[near_sdk::near_bindgen]
+        impl FungibleTokenCore for $contract {
+            #[payable]
+            fn ft_transfer(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                amount: near_sdk::json_types::U128,
+                memo: Option<String>,
+            ) {
+                self.$token.ft_transfer(receiver_id, amount, memo)
+            }
+
+            #[payable]
+            fn ft_transfer_call(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                amount: near_sdk::json_types::U128,
+                memo: Option<String>,
+                msg: String,
+            ) -> near_sdk::PromiseOrValue<near_sdk::json_types::U128> {
+                self.$token.ft_transfer_call(receiver_id, amount, memo, msg)
+            }
+
+            fn ft_total_supply(&self) -> near_sdk::json_types::U128 {
+                self.$token.ft_total_supply()
+            }
+
+            fn ft_balance_of(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> near_sdk::json_types::U128 {
+                self.$token.ft_balance_of(account_id)
+            }
+        }
+
+        #[near_sdk::near_bindgen]
+        impl FungibleTokenResolver for $contract {
+            fn ft_resolve_transfer(
+                &mut self,
+                sender_id: near_sdk::json_types::ValidAccountId,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                amount: near_sdk::json_types::U128,
+            ) -> near_sdk::json_types::U128 {
+                near_sdk::wrapper::assert_private("ft_resolve_transfer");
+                self.$token.ft_resolve_transfer(sender_id, receiver_id, amount)
+            }
+        }
+    };
+}
+
+/// Exposes the token metadata stored in `LazyOption<FungibleTokenMetadata>` field `$metadata`
+/// through the `ft_metadata` view method.
+#[macro_export]
+macro_rules! impl_fungible_token_metadata {
+    ($contract: ident, $metadata: ident) => {
+        use $crate::fungible_token::metadata::FungibleTokenMetadataProvider;
+
+        #[near_sdk::near_bindgen]
+        impl FungibleTokenMetadataProvider for $contract {
+            fn ft_metadata(&self) -> $crate::fungible_token::metadata::FungibleTokenMetadata {
+                match self.$metadata.get() {
+                    Some(metadata) => metadata,
+                    None => near_sdk::env::panic(b"The token metadata is not set"),
+                }
+            }
+        }
+    };
+}
+
+/// Exposes the storage management (NEP-145) of the token stored in the field `$token`.
+///
+/// `$on_account_closed` is a method of the contract with the signature
+/// `fn(&mut self, account_id: AccountId, balance: Balance)`. It is called when an account is
+/// force-unregistered with a positive balance, after the tokens were burned.
+#[macro_export]
+macro_rules! impl_fungible_token_storage {
+    ($contract: ident, $token: ident, $on_account_closed: ident) => {
+        use $crate::storage_management::StorageManagement;
+
+        #[near_sdk::near_bindgen]
+        impl StorageManagement for $contract {
+            #[payable]
+            fn storage_deposit(
+                &mut self,
+                account_id: Option<near_sdk::json_types::ValidAccountId>,
+                registration_only: Option<bool>,
+            ) -> $crate::storage_management::StorageBalance {
+                self.$token.storage_deposit(account_id, registration_only)
+            }
+
+            #[payable]
+            fn storage_withdraw(
+                &mut self,
+                amount: Option<near_sdk::json_types::U128>,
+            ) -> $crate::storage_management::StorageBalance {
+                self.$token.storage_withdraw(amount)
+            }
+
+            #[payable]
+            fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+                if let Some((account_id, balance)) = self.$token.internal_storage_unregister(force)
+                {
+                    self.$on_account_closed(account_id, balance);
+                    true
+                } else {
+                    false
+                }
+            }
+
+            fn storage_balance_bounds(&self) -> $crate::storage_management::StorageBalanceBounds {
+                self.$token.storage_balance_bounds()
+            }
+
+            fn storage_balance_of(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> Option<$crate::storage_management::StorageBalance> {
+                self.$token.storage_balance_of(account_id)
+            }
+        }
+    };
+}
@@ -0,0 +1,131 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Version of the fungible token metadata standard (NEP-148).
+pub const FT_METADATA_SPEC: &str = "ft-1.0.0";
+
+/// The largest number of decimals that can still represent one whole token in `u128`.
+const MAX_DECIMALS: u8 = 38;
+/// Length of the sha256 hash of the reference JSON.
+const REFERENCE_HASH_LEN: usize = 32;
+
+/// Metadata of a fungible token as defined by NEP-148.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Deserialize, Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FungibleTokenMetadata {
+    /// Version of the metadata standard, should be `FT_METADATA_SPEC`.
+    pub spec: String,
+    /// Human-readable name of the token.
+    pub name: String,
+    /// Abbreviated ticker of the token, e.g. `wETH`.
+    pub symbol: String,
+    /// Small image of the token as a data URL, e.g. `data:image/svg+xml,...`.
+    pub icon: Option<String>,
+    /// Link to a JSON file with additional information.
+    pub reference: Option<String>,
+    /// Sha256 hash of the JSON file from `reference`. Required when `reference` is set.
+    pub reference_hash: Option<Base64VecU8>,
+    /// Number of decimals used to display the balances.
+    pub decimals: u8,
+}
+
+impl FungibleTokenMetadata {
+    /// Panics if the metadata doesn't conform to the standard.
+    pub fn assert_valid(&self) {
+        if self.spec != FT_METADATA_SPEC {
+            near_sdk::env::panic(
+                format!("Unsupported metadata spec {}, expected {}", self.spec, FT_METADATA_SPEC)
+                    .as_bytes(),
+            );
+        }
+        if self.decimals > MAX_DECIMALS {
+            near_sdk::env::panic(
+                format!("The number of decimals cannot exceed {}", MAX_DECIMALS).as_bytes(),
+            );
+        }
+        if let Some(icon) = &self.icon {
+            if !icon.starts_with("data:") {
+                near_sdk::env::panic(b"The icon should be a data URL");
+            }
+        }
+        if self.reference.is_some() != self.reference_hash.is_some() {
+            near_sdk::env::panic(b"The reference and the reference hash should be set together");
+        }
+        if let Some(reference_hash) = &self.reference_hash {
+            if reference_hash.0.len() != REFERENCE_HASH_LEN {
+                near_sdk::env::panic(b"The reference hash has to be 32 bytes");
+            }
+        }
+    }
+}
+
+/// Exposes the metadata of the token through `ft_metadata` view method.
+pub trait FungibleTokenMetadataProvider {
+    fn ft_metadata(&self) -> FungibleTokenMetadata;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, get_context, set_env};
+
+    fn metadata() -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "Example Token".to_string(),
+            symbol: "EXT".to_string(),
+            icon: Some("data:image/svg+xml,%3Csvg%3E%3C/svg%3E".to_string()),
+            reference: None,
+            reference_hash: None,
+            decimals: 24,
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        set_env(get_context(alice(), 0), vec![]);
+        metadata().assert_valid();
+    }
+
+    #[test]
+    fn test_json() {
+        let actual = near_sdk::serde_json::to_string(&metadata()).unwrap();
+        let deser: FungibleTokenMetadata = near_sdk::serde_json::from_str(&actual).unwrap();
+        assert_eq!(deser, metadata());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported metadata spec")]
+    fn test_wrong_spec() {
+        set_env(get_context(alice(), 0), vec![]);
+        FungibleTokenMetadata { spec: "ft-0.1.0".to_string(), ..metadata() }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of decimals cannot exceed 38")]
+    fn test_too_many_decimals() {
+        set_env(get_context(alice(), 0), vec![]);
+        FungibleTokenMetadata { decimals: 39, ..metadata() }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "The icon should be a data URL")]
+    fn test_icon_not_data_url() {
+        set_env(get_context(alice(), 0), vec![]);
+        FungibleTokenMetadata {
+            icon: Some("https://example.com/icon.svg".to_string()),
+            ..metadata()
+        }
+        .assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "The reference and the reference hash should be set together")]
+    fn test_reference_without_hash() {
+        set_env(get_context(alice(), 0), vec![]);
+        FungibleTokenMetadata { reference: Some("https://example.com".to_string()), ..metadata() }
+            .assert_valid();
+    }
+}
@@ -0,0 +1,22 @@
+//! Fungible token standard (NEP-141).
+//!
+//! `FungibleToken` keeps the balances and the total supply of the token, and implements the
+//! `FungibleTokenCore` and `FungibleTokenResolver` traits. A contract embeds it into its state and
+//! exposes the methods with `impl_fungible_token_core!`.
+//!
+//! The token metadata (NEP-148) is described by `FungibleTokenMetadata`. It is usually stored in a
+//! `LazyOption`, so that it is not deserialized on every call, and exposed with
+//! `impl_fungible_token_metadata!`.
+//!
+//! Accounts pay for their registration through the storage management standard (NEP-145), which
+//! `FungibleToken` implements and `impl_fungible_token_storage!` exposes.
+
+pub mod core;
+pub mod core_impl;
+mod macros;
+pub mod metadata;
+pub mod receiver;
+pub mod resolver;
+mod storage_impl;
+
+pub use core_impl::FungibleToken;
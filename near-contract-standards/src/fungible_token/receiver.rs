@@ -0,0 +1,17 @@
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::PromiseOrValue;
+
+/// Interface of a contract that accepts fungible tokens through `ft_transfer_call`.
+pub trait FungibleTokenReceiver {
+    /// Called by the fungible token contract after `amount` of tokens were transferred to this
+    /// contract by `sender_id`.
+    ///
+    /// Returns the number of unused tokens that should be refunded to the sender. The value may be
+    /// returned directly or through a promise.
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+}
@@ -0,0 +1,15 @@
+use near_sdk::json_types::{ValidAccountId, U128};
+
+/// Callback that finishes `ft_transfer_call` on the fungible token contract.
+pub trait FungibleTokenResolver {
+    /// Resolves the result of `ft_on_transfer` on the receiver and refunds the unused tokens back
+    /// to the sender. Must only be called by the contract itself.
+    ///
+    /// Returns the amount of tokens that were spent by the receiver.
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        receiver_id: ValidAccountId,
+        amount: U128,
+    ) -> U128;
+}
@@ -0,0 +1,205 @@
+use crate::fungible_token::FungibleToken;
+use crate::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+use crate::utils::assert_one_yocto;
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::{env, AccountId, Balance, Promise};
+
+impl FungibleToken {
+    /// Unregisters the predecessor and refunds its storage deposit.
+    /// If the account still holds tokens, panics unless `force` is `true`, in which case the
+    /// tokens are burned.
+    ///
+    /// Returns the account ID and the burned balance, or `None` if the account was not registered.
+    pub fn internal_storage_unregister(
+        &mut self,
+        force: Option<bool>,
+    ) -> Option<(AccountId, Balance)> {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let force = force.unwrap_or(false);
+        match self.accounts.get(&account_id) {
+            Some(balance) => {
+                if balance != 0 && !force {
+                    env::panic(
+                        b"Can't unregister the account with the positive balance without force",
+                    );
+                }
+                self.accounts.remove(&account_id);
+                self.total_supply -= balance;
+                Promise::new(account_id.clone()).transfer(self.storage_balance_bounds().min.0 + 1);
+                Some((account_id, balance))
+            }
+            None => {
+                env::log(format!("The account {} is not registered", account_id).as_bytes());
+                None
+            }
+        }
+    }
+
+    fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        if self.accounts.get(account_id).is_some() {
+            Some(StorageBalance { total: self.storage_balance_bounds().min, available: 0.into() })
+        } else {
+            None
+        }
+    }
+}
+
+impl StorageManagement for FungibleToken {
+    /// The storage balance of an account is fixed, so `registration_only` doesn't change anything:
+    /// the contract takes exactly the cost of one account and refunds the rest.
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        _registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount: Balance = env::attached_deposit();
+        let account_id = account_id.map(|a| a.into()).unwrap_or_else(env::predecessor_account_id);
+        if self.accounts.get(&account_id).is_some() {
+            env::log(b"The account is already registered, refunding the deposit");
+            if amount > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(amount);
+            }
+        } else {
+            let min_balance = self.storage_balance_bounds().min.0;
+            if amount < min_balance {
+                env::panic(b"The attached deposit is less than the minimum storage balance");
+            }
+            self.internal_register_account(&account_id);
+            let refund = amount - min_balance;
+            if refund > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    /// Nothing can be withdrawn, since the whole deposit of an account pays for its storage.
+    /// Panics if `amount` is positive.
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let predecessor_account_id = env::predecessor_account_id();
+        match self.internal_storage_balance_of(&predecessor_account_id) {
+            Some(storage_balance) => {
+                if amount.map(|amount| amount.0).unwrap_or(0) > 0 {
+                    env::panic(b"The amount is greater than the available storage balance");
+                }
+                storage_balance
+            }
+            None => env::panic(
+                format!("The account {} is not registered", predecessor_account_id).as_bytes(),
+            ),
+        }
+    }
+
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        self.internal_storage_unregister(force).is_some()
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        let required_storage_balance = self.account_storage_usage.cost();
+        StorageBalanceBounds {
+            min: required_storage_balance.into(),
+            max: Some(required_storage_balance.into()),
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(account_id.as_ref())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::fungible_token::core::FungibleTokenCore;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+
+    fn setup_token() -> (FungibleToken, Balance) {
+        set_env(get_context(contract(), 0), vec![]);
+        let ft = FungibleToken::new(b"t".to_vec());
+        let min_balance = ft.storage_balance_bounds().min.0;
+        (ft, min_balance)
+    }
+
+    #[test]
+    fn test_account_storage_usage() {
+        let (ft, min_balance) = setup_token();
+        assert!(ft.account_storage_usage.as_bytes() > 64);
+        assert_eq!(min_balance, ft.account_storage_usage.cost());
+        assert_eq!(ft.storage_balance_bounds().max, Some(min_balance.into()));
+    }
+
+    #[test]
+    fn test_storage_deposit() {
+        let (mut ft, min_balance) = setup_token();
+        assert_eq!(ft.storage_balance_of(alice().try_into().unwrap()), None);
+        set_env(get_context(alice(), min_balance * 2), vec![]);
+        let storage_balance = ft.storage_deposit(None, None);
+        assert_eq!(
+            storage_balance,
+            StorageBalance { total: min_balance.into(), available: 0.into() }
+        );
+        assert_eq!(ft.storage_balance_of(alice().try_into().unwrap()), Some(storage_balance));
+        assert_eq!(ft.ft_balance_of(alice().try_into().unwrap()).0, 0);
+        // The excess is refunded.
+        assert_eq!(env::created_receipts().len(), 1);
+    }
+
+    #[test]
+    fn test_storage_deposit_for_other_account() {
+        let (mut ft, min_balance) = setup_token();
+        set_env(get_context(alice(), min_balance), vec![]);
+        ft.storage_deposit(Some(bob().try_into().unwrap()), Some(true));
+        assert!(ft.storage_balance_of(bob().try_into().unwrap()).is_some());
+        assert!(ft.storage_balance_of(alice().try_into().unwrap()).is_none());
+        assert!(env::created_receipts().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The attached deposit is less than the minimum storage balance")]
+    fn test_storage_deposit_not_enough() {
+        let (mut ft, min_balance) = setup_token();
+        set_env(get_context(alice(), min_balance - 1), vec![]);
+        ft.storage_deposit(None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The amount is greater than the available storage balance")]
+    fn test_storage_withdraw_positive_amount() {
+        let (mut ft, min_balance) = setup_token();
+        set_env(get_context(alice(), min_balance), vec![]);
+        ft.storage_deposit(None, None);
+        set_env(get_context(alice(), 1), vec![]);
+        ft.storage_withdraw(Some(1.into()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Can't unregister the account with the positive balance without force"
+    )]
+    fn test_storage_unregister_positive_balance() {
+        let (mut ft, min_balance) = setup_token();
+        set_env(get_context(alice(), min_balance), vec![]);
+        ft.storage_deposit(None, None);
+        ft.internal_deposit(&alice(), 100);
+        set_env(get_context(alice(), 1), vec![]);
+        ft.storage_unregister(None);
+    }
+
+    #[test]
+    fn test_storage_unregister_force() {
+        let (mut ft, min_balance) = setup_token();
+        set_env(get_context(alice(), min_balance), vec![]);
+        ft.storage_deposit(None, None);
+        ft.internal_deposit(&alice(), 100);
+        set_env(get_context(alice(), 1), vec![]);
+        assert_eq!(ft.internal_storage_unregister(Some(true)), Some((alice(), 100)));
+        assert_eq!(ft.ft_total_supply().0, 0);
+        assert!(ft.storage_balance_of(alice().try_into().unwrap()).is_none());
+        assert!(!ft.storage_unregister(None));
+    }
+}
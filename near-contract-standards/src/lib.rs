@@ -0,0 +1,42 @@
+//! Implementations of the NEAR contract standards that can be embedded into contracts.
+//!
+//! Each component keeps its state in a struct that is stored as a field of the contract, and
+//! exposes the standard interface through traits that the contract implements with
+//! `#[near_bindgen]`.
+
+/// Role-based access control.
+pub mod access_control;
+/// Allow and deny lists of accounts.
+pub mod access_list;
+/// Decommissioning of a contract.
+pub mod decommission;
+/// Escrow of NEAR and fungible tokens.
+pub mod escrow;
+/// Fungible Token Standard.
+pub mod fungible_token;
+/// Multi Token Standard.
+pub mod multi_token;
+/// K-of-n confirmations of the actions of a contract.
+pub mod multisig;
+/// Non-Fungible Token Standard.
+pub mod non_fungible_token;
+/// Ownership of a contract.
+pub mod owner;
+/// Emergency stops of a contract.
+pub mod pause;
+/// Client of the staking pool contracts.
+pub mod staking_pool;
+/// Moves of the raw contract state between accounts.
+pub mod state_transfer;
+/// Storage Management Standard.
+pub mod storage_management;
+/// Upgrades of the contract code by the contract itself.
+pub mod upgrade;
+/// Vesting and lockup schedules.
+pub mod vesting;
+
+mod utils;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,37 @@
+use crate::multi_token::TokenId;
+use near_sdk::json_types::{ValidAccountId, U128};
+
+pub trait MultiTokenCore {
+    /// Transfers `amount` of the token `token_id` from the predecessor to `receiver_id`.
+    /// Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn mt_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        amount: U128,
+        memo: Option<String>,
+    );
+
+    /// Transfers `amounts[i]` of the token `token_ids[i]` from the predecessor to `receiver_id`
+    /// for every `i`. The batch is applied atomically: if any transfer fails, none is applied.
+    /// Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        memo: Option<String>,
+    );
+
+    /// Returns the balance of the token `token_id` of `account_id`.
+    fn mt_balance_of(&self, account_id: ValidAccountId, token_id: TokenId) -> U128;
+
+    /// Returns the balances of the tokens `token_ids` of `account_id`, in the same order.
+    fn mt_batch_balance_of(&self, account_id: ValidAccountId, token_ids: Vec<TokenId>)
+        -> Vec<U128>;
+
+    /// Returns the total supply of the token, or `None` if it was never minted.
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128>;
+}
@@ -0,0 +1,298 @@
+use crate::multi_token::core::MultiTokenCore;
+use crate::multi_token::TokenId;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::math::CheckedOrPanic;
+use near_sdk::{env, AccountId, Balance};
+
+/// Implementation of the multi token standard (NEP-245).
+/// The contract keeps it as a field of its state and implements `MultiTokenCore` by delegating to
+/// it, see `impl_multi_token_core!`.
+///
+/// Tokens are created with `mint`, which can only be called by `owner_id`. The accounts don't need
+/// to be registered: a balance is stored when an account receives a token and removed when it
+/// drops to zero.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MultiToken {
+    /// The account that is allowed to mint tokens.
+    pub owner_id: AccountId,
+    /// (TokenId, AccountId) -> Positive balance of the account.
+    pub balances: LookupMap<(TokenId, AccountId), Balance>,
+    /// TokenId -> Total supply of the token.
+    pub supply: LookupMap<TokenId, Balance>,
+}
+
+impl MultiToken {
+    /// Creates an empty collection of tokens that can be minted by `owner_id`. Use `prefix` as a
+    /// unique identifier of the collection on the trie.
+    pub fn new(prefix: Vec<u8>, owner_id: ValidAccountId) -> Self {
+        Self {
+            owner_id: owner_id.into(),
+            balances: LookupMap::new([prefix.as_slice(), b"b"].concat()),
+            supply: LookupMap::new([prefix.as_slice(), b"s"].concat()),
+        }
+    }
+
+    /// Mints `amount` of the token `token_id` to `receiver_id`, creating the token if it doesn't
+    /// exist yet. Panics if the predecessor is not `owner_id`.
+    pub fn mint(&mut self, token_id: TokenId, receiver_id: ValidAccountId, amount: Balance) {
+        if env::predecessor_account_id() != self.owner_id {
+            env::panic(b"Only the owner of the contract can mint tokens");
+        }
+        if amount == 0 {
+            env::panic(b"The amount should be a positive number");
+        }
+        self.internal_deposit(&token_id, receiver_id.as_ref(), amount);
+        env::log(format!("Mint {} of {} to {}", amount, token_id, receiver_id.as_ref()).as_bytes());
+    }
+
+    /// Returns the balance of the token of the account, which is 0 for unknown tokens.
+    pub fn internal_balance_of(&self, token_id: &TokenId, account_id: &AccountId) -> Balance {
+        self.balances.get(&(token_id.clone(), account_id.clone())).unwrap_or(0)
+    }
+
+    /// Adds `amount` of the token to the balance of the account and to the supply of the token.
+    pub fn internal_deposit(
+        &mut self,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        let balance = self.internal_balance_of(token_id, account_id);
+        let new_balance = balance.checked_add_or_panic(amount, "Balance overflow");
+        self.balances.insert(&(token_id.clone(), account_id.clone()), &new_balance);
+        let supply = self.supply.get(token_id).unwrap_or(0);
+        let new_supply = supply.checked_add_or_panic(amount, "Total supply overflow");
+        self.supply.insert(token_id, &new_supply);
+    }
+
+    /// Subtracts `amount` of the token from the balance of the account and from the supply of the
+    /// token.
+    pub fn internal_withdraw(
+        &mut self,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        let key = (token_id.clone(), account_id.clone());
+        let balance = self.balances.get(&key).unwrap_or(0);
+        match balance.checked_sub(amount) {
+            Some(0) => {
+                self.balances.remove(&key);
+            }
+            Some(new_balance) => {
+                self.balances.insert(&key, &new_balance);
+            }
+            None => env::panic(
+                format!("The account doesn't have enough balance of {}", token_id).as_bytes(),
+            ),
+        }
+        let supply = self.supply.get(token_id).unwrap_or(0);
+        let new_supply = supply.checked_sub_or_panic(amount, "Total supply overflow");
+        self.supply.insert(token_id, &new_supply);
+    }
+
+    /// Moves `amount` of the token between two accounts and logs the transfer.
+    pub fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+        memo: Option<String>,
+    ) {
+        self.internal_batch_transfer(
+            sender_id,
+            receiver_id,
+            std::slice::from_ref(token_id),
+            &[amount],
+            memo,
+        )
+    }
+
+    /// Moves `amounts[i]` of the token `token_ids[i]` between two accounts for every `i`, and logs
+    /// the transfers.
+    pub fn internal_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) {
+        if sender_id == receiver_id {
+            env::panic(b"Sender and receiver should be different");
+        }
+        if token_ids.is_empty() {
+            env::panic(b"At least one token should be transferred");
+        }
+        if token_ids.len() != amounts.len() {
+            env::panic(b"The number of token_ids and amounts should be equal");
+        }
+        for (token_id, amount) in token_ids.iter().zip(amounts) {
+            if *amount == 0 {
+                env::panic(b"The amount should be a positive number");
+            }
+            self.internal_withdraw(token_id, sender_id, *amount);
+            self.internal_deposit(token_id, receiver_id, *amount);
+            env::log(
+                format!(
+                    "Transfer {} of {} from {} to {}",
+                    amount, token_id, sender_id, receiver_id
+                )
+                .as_bytes(),
+            );
+        }
+        if let Some(memo) = memo {
+            env::log(format!("Memo: {}", memo).as_bytes());
+        }
+    }
+}
+
+impl MultiTokenCore for MultiToken {
+    fn mt_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        amount: U128,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, receiver_id.as_ref(), &token_id, amount.into(), memo);
+    }
+
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(|amount| amount.0).collect();
+        self.internal_batch_transfer(&sender_id, receiver_id.as_ref(), &token_ids, &amounts, memo);
+    }
+
+    fn mt_balance_of(&self, account_id: ValidAccountId, token_id: TokenId) -> U128 {
+        self.internal_balance_of(&token_id, account_id.as_ref()).into()
+    }
+
+    fn mt_batch_balance_of(
+        &self,
+        account_id: ValidAccountId,
+        token_ids: Vec<TokenId>,
+    ) -> Vec<U128> {
+        token_ids
+            .iter()
+            .map(|token_id| self.internal_balance_of(token_id, account_id.as_ref()).into())
+            .collect()
+    }
+
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128> {
+        self.supply.get(&token_id).map(|supply| supply.into())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+
+    fn valid(account_id: AccountId) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    fn setup_tokens() -> MultiToken {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut mt = MultiToken::new(b"m".to_vec(), valid(contract()));
+        mt.mint("gold".to_string(), valid(alice()), 1_000);
+        mt.mint("sword".to_string(), valid(alice()), 1);
+        mt
+    }
+
+    #[test]
+    fn test_mint() {
+        let mut mt = setup_tokens();
+        mt.mint("gold".to_string(), valid(bob()), 500);
+        assert_eq!(mt.mt_balance_of(valid(alice()), "gold".to_string()).0, 1_000);
+        assert_eq!(mt.mt_balance_of(valid(bob()), "gold".to_string()).0, 500);
+        assert_eq!(mt.mt_supply("gold".to_string()), Some(U128(1_500)));
+        assert_eq!(mt.mt_supply("shield".to_string()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner of the contract can mint tokens")]
+    fn test_mint_not_owner() {
+        let mut mt = setup_tokens();
+        set_env(get_context(alice(), 0), vec![]);
+        mt.mint("gold".to_string(), valid(alice()), 1);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut mt = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        mt.mt_transfer(valid(bob()), "sword".to_string(), U128(1), None);
+        assert_eq!(mt.mt_balance_of(valid(alice()), "sword".to_string()).0, 0);
+        assert_eq!(mt.mt_balance_of(valid(bob()), "sword".to_string()).0, 1);
+        assert_eq!(mt.mt_supply("sword".to_string()), Some(U128(1)));
+        // The zero balance is removed from the storage.
+        assert!(!mt.balances.contains_key(&("sword".to_string(), alice())));
+    }
+
+    #[test]
+    fn test_batch_transfer() {
+        let mut mt = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        mt.mt_batch_transfer(
+            valid(bob()),
+            vec!["gold".to_string(), "sword".to_string()],
+            vec![U128(300), U128(1)],
+            Some("loot".to_string()),
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(valid(alice()), vec!["gold".to_string(), "sword".to_string()]),
+            vec![U128(700), U128(0)]
+        );
+        assert_eq!(
+            mt.mt_batch_balance_of(valid(bob()), vec!["gold".to_string(), "sword".to_string()]),
+            vec![U128(300), U128(1)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance of sword")]
+    fn test_batch_transfer_not_enough_balance() {
+        let mut mt = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        mt.mt_batch_transfer(
+            valid(bob()),
+            vec!["gold".to_string(), "sword".to_string()],
+            vec![U128(300), U128(2)],
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of token_ids and amounts should be equal")]
+    fn test_batch_transfer_length_mismatch() {
+        let mut mt = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        mt.mt_batch_transfer(valid(bob()), vec!["gold".to_string()], vec![], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_transfer_without_yocto() {
+        let mut mt = setup_tokens();
+        set_env(get_context(alice(), 0), vec![]);
+        mt.mt_transfer(valid(bob()), "gold".to_string(), U128(1), None);
+    }
+}
@@ -0,0 +1,67 @@
+/// The core methods of a multi token contract.
+///
+/// Expects the contract to store `MultiToken` in the field `$tokens`:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     tokens: MultiToken,
+/// }
+///
+/// near_contract_standards::impl_multi_token_core!(Contract, tokens);
+/// ```
+#[macro_export]
+macro_rules! impl_multi_token_core {
+    ($contract: ident, $tokens: ident) => {
+        use $crate::multi_token::core::MultiTokenCore;
+
+        #[near_sdk::near_bindgen]
+        impl MultiTokenCore for $contract {
+            #[payable]
+            fn mt_transfer(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                token_id: $crate::multi_token::TokenId,
+                amount: near_sdk::json_types::U128,
+                memo: Option<String>,
+            ) {
+                self.$tokens.mt_transfer(receiver_id, token_id, amount, memo)
+            }
+
+            #[payable]
+            fn mt_batch_transfer(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                token_ids: Vec<$crate::multi_token::TokenId>,
+                amounts: Vec<near_sdk::json_types::U128>,
+                memo: Option<String>,
+            ) {
+                self.$tokens.mt_batch_transfer(receiver_id, token_ids, amounts, memo)
+            }
+
+            fn mt_balance_of(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+                token_id: $crate::multi_token::TokenId,
+            ) -> near_sdk::json_types::U128 {
+                self.$tokens.mt_balance_of(account_id, token_id)
+            }
+
+            fn mt_batch_balance_of(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+                token_ids: Vec<$crate::multi_token::TokenId>,
+            ) -> Vec<near_sdk::json_types::U128> {
+                self.$tokens.mt_batch_balance_of(account_id, token_ids)
+            }
+
+            fn mt_supply(
+                &self,
+                token_id: $crate::multi_token::TokenId,
+            ) -> Option<near_sdk::json_types::U128> {
+                self.$tokens.mt_supply(token_id)
+            }
+        }
+    };
+}
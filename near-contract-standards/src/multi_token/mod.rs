@@ -0,0 +1,15 @@
+//! Multi token standard (NEP-245).
+//!
+//! `MultiToken` keeps the balances of many tokens in one contract, e.g. the items of a game, where
+//! every token can be fungible or have a supply of one. The balances are keyed by the token ID and
+//! the account, and can be transferred one token at a time or in batches. A contract embeds it into
+//! its state and exposes the methods with `impl_multi_token_core!`.
+
+pub mod core;
+pub mod core_impl;
+mod macros;
+
+pub use core_impl::MultiToken;
+
+/// Identifier of a token, unique within the contract.
+pub type TokenId = String;
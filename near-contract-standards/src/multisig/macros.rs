@@ -0,0 +1,64 @@
+/// Exposes the methods of the `MultiSig` stored in the field `$multisig`.
+#[macro_export]
+macro_rules! impl_multisig {
+    ($contract: ident, $multisig: ident) => {
+        use $crate::multisig::MultiSigManagement;
+
+        #[near_sdk::near_bindgen]
+        impl MultiSigManagement for $contract {
+            fn add_request(
+                &mut self,
+                request: $crate::multisig::MultiSigRequest,
+            ) -> $crate::multisig::RequestId {
+                self.$multisig.add_request(request)
+            }
+
+            fn add_request_and_confirm(
+                &mut self,
+                request: $crate::multisig::MultiSigRequest,
+            ) -> near_sdk::PromiseOrValue<bool> {
+                self.$multisig.add_request_and_confirm(request)
+            }
+
+            fn confirm(
+                &mut self,
+                request_id: $crate::multisig::RequestId,
+            ) -> near_sdk::PromiseOrValue<bool> {
+                self.$multisig.confirm(request_id)
+            }
+
+            fn delete_request(
+                &mut self,
+                request_id: $crate::multisig::RequestId,
+            ) -> $crate::multisig::MultiSigRequest {
+                self.$multisig.delete_request(request_id)
+            }
+
+            fn get_request(
+                &self,
+                request_id: $crate::multisig::RequestId,
+            ) -> Option<$crate::multisig::MultiSigRequest> {
+                self.$multisig.get_request(request_id)
+            }
+
+            fn get_confirmations(
+                &self,
+                request_id: $crate::multisig::RequestId,
+            ) -> Vec<near_sdk::AccountId> {
+                self.$multisig.get_confirmations(request_id)
+            }
+
+            fn list_request_ids(&self) -> Vec<$crate::multisig::RequestId> {
+                self.$multisig.list_request_ids()
+            }
+
+            fn get_members(&self) -> Vec<near_sdk::AccountId> {
+                self.$multisig.get_members()
+            }
+
+            fn get_num_confirmations(&self) -> u32 {
+                self.$multisig.get_num_confirmations()
+            }
+        }
+    };
+}
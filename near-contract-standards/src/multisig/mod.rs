@@ -0,0 +1,77 @@
+//! K-of-n confirmations of the actions of a contract.
+//!
+//! `MultiSig` keeps the members, the number of confirmations that a request needs, and the pending
+//! requests with their confirmations, and implements the `MultiSigManagement` trait. A member adds
+//! a request of actions on some account, the members confirm it, and the request is executed by the
+//! confirmation that reaches the threshold. A member can have at most `ACTIVE_REQUESTS_LIMIT`
+//! pending requests. The requests on the contract itself can also change the members and the
+//! threshold. A contract embeds `MultiSig` into its state and exposes the methods
+//! with `impl_multisig!`.
+
+mod macros;
+mod multisig_impl;
+
+pub use multisig_impl::{MultiSig, PendingRequest, ACTIVE_REQUESTS_LIMIT};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, PromiseOrValue};
+
+/// The identifier of a request.
+pub type RequestId = u32;
+
+/// An action of a request.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", tag = "type")]
+pub enum MultiSigRequestAction {
+    /// Transfers NEAR from the contract to the receiver.
+    Transfer { amount: U128 },
+    /// Calls a method of the receiver.
+    FunctionCall { method_name: String, args: Base64VecU8, deposit: U128, gas: U64 },
+    /// Adds a member. The receiver should be the contract.
+    AddMember { member: AccountId },
+    /// Removes a member. The receiver should be the contract.
+    DeleteMember { member: AccountId },
+    /// Changes the number of confirmations. The receiver should be the contract.
+    SetNumConfirmations { num_confirmations: u32 },
+}
+
+/// Actions on the receiver that are executed together once the request is confirmed.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MultiSigRequest {
+    pub receiver_id: AccountId,
+    pub actions: Vec<MultiSigRequestAction>,
+}
+
+pub trait MultiSigManagement {
+    /// Adds a request and returns its id. Can only be called by a member.
+    fn add_request(&mut self, request: MultiSigRequest) -> RequestId;
+
+    /// Adds a request and confirms it by the predecessor. Can only be called by a member.
+    fn add_request_and_confirm(&mut self, request: MultiSigRequest) -> PromiseOrValue<bool>;
+
+    /// Confirms the request by the predecessor. The confirmation that reaches the threshold removes
+    /// the request and executes it. Returns `false` if the request is not executed yet. Can only be
+    /// called by a member.
+    fn confirm(&mut self, request_id: RequestId) -> PromiseOrValue<bool>;
+
+    /// Removes the request. Can only be called by the member that added it.
+    fn delete_request(&mut self, request_id: RequestId) -> MultiSigRequest;
+
+    /// Returns the request.
+    fn get_request(&self, request_id: RequestId) -> Option<MultiSigRequest>;
+
+    /// Returns the members that confirmed the request.
+    fn get_confirmations(&self, request_id: RequestId) -> Vec<AccountId>;
+
+    /// Returns the ids of the pending requests.
+    fn list_request_ids(&self) -> Vec<RequestId>;
+
+    /// Returns the members.
+    fn get_members(&self) -> Vec<AccountId>;
+
+    /// Returns the number of confirmations that a request needs.
+    fn get_num_confirmations(&self) -> u32;
+}
@@ -0,0 +1,335 @@
+use std::collections::HashSet;
+
+use crate::multisig::{MultiSigManagement, MultiSigRequest, MultiSigRequestAction, RequestId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, AccountId, Promise, PromiseOrValue};
+
+/// The number of the pending requests that a member can have at once, so that a member can't grow
+/// the storage of the requests without a bound.
+pub const ACTIVE_REQUESTS_LIMIT: u32 = 12;
+
+/// A pending request with the member that added it and the members that confirmed it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingRequest {
+    pub request: MultiSigRequest,
+    pub requester_id: AccountId,
+    pub confirmations: HashSet<AccountId>,
+}
+
+/// The members, the threshold, and the pending requests.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MultiSig {
+    pub members: UnorderedSet<AccountId>,
+    pub num_confirmations: u32,
+    pub requests: UnorderedMap<RequestId, PendingRequest>,
+    /// The number of the pending requests of each member.
+    pub num_requests: LookupMap<AccountId, u32>,
+    /// The id of the next request.
+    pub request_nonce: RequestId,
+}
+
+impl MultiSig {
+    /// Creates the multisig of `members` where a request needs `num_confirmations`. Use `prefix` as
+    /// a unique identifier of the multisig on the trie.
+    pub fn new(prefix: Vec<u8>, members: Vec<ValidAccountId>, num_confirmations: u32) -> Self {
+        let mut this = Self {
+            members: UnorderedSet::new([prefix.as_slice(), b"m"].concat()),
+            num_confirmations,
+            requests: UnorderedMap::new([prefix.as_slice(), b"r"].concat()),
+            num_requests: LookupMap::new([prefix.as_slice(), b"n"].concat()),
+            request_nonce: 0,
+        };
+        for member in members {
+            this.members.insert(member.as_ref());
+        }
+        this.assert_valid_num_confirmations();
+        this
+    }
+
+    /// Panics unless the predecessor is a member.
+    pub fn assert_member(&self) {
+        if !self.members.contains(&env::predecessor_account_id()) {
+            env::panic(b"Method is only available to the members");
+        }
+    }
+
+    fn assert_valid_num_confirmations(&self) {
+        if self.num_confirmations == 0 || u64::from(self.num_confirmations) > self.members.len() {
+            env::panic(
+                b"The number of confirmations should be between 1 and the number of members",
+            );
+        }
+    }
+
+    fn internal_request(&self, request_id: RequestId) -> PendingRequest {
+        match self.requests.get(&request_id) {
+            Some(request) => request,
+            None => env::panic(format!("The request {} doesn't exist", request_id).as_bytes()),
+        }
+    }
+
+    /// Removes the pending request and frees its place in the limit of its requester.
+    fn internal_remove_request(&mut self, request_id: RequestId, pending: &PendingRequest) {
+        self.requests.remove(&request_id);
+        let num_requests = self.num_requests.get(&pending.requester_id).unwrap_or(0);
+        if num_requests <= 1 {
+            self.num_requests.remove(&pending.requester_id);
+        } else {
+            self.num_requests.insert(&pending.requester_id, &(num_requests - 1));
+        }
+    }
+
+    /// Executes the request: the actions on the contract itself change the multisig, and the other
+    /// actions are batched into a promise.
+    fn internal_execute(&mut self, request: MultiSigRequest) -> PromiseOrValue<bool> {
+        let is_self = request.receiver_id == env::current_account_id();
+        let mut promise = Promise::new(request.receiver_id.clone());
+        let mut has_promise = false;
+        for action in request.actions {
+            match action {
+                MultiSigRequestAction::Transfer { amount } => {
+                    promise = promise.transfer(amount.into());
+                    has_promise = true;
+                }
+                MultiSigRequestAction::FunctionCall { method_name, args, deposit, gas } => {
+                    promise = promise.function_call(
+                        method_name.into_bytes(),
+                        args.into(),
+                        deposit.into(),
+                        gas.into(),
+                    );
+                    has_promise = true;
+                }
+                MultiSigRequestAction::AddMember { member } => {
+                    assert_self_request(is_self);
+                    self.members.insert(&member);
+                }
+                MultiSigRequestAction::DeleteMember { member } => {
+                    assert_self_request(is_self);
+                    self.members.remove(&member);
+                    self.assert_valid_num_confirmations();
+                }
+                MultiSigRequestAction::SetNumConfirmations { num_confirmations } => {
+                    assert_self_request(is_self);
+                    self.num_confirmations = num_confirmations;
+                    self.assert_valid_num_confirmations();
+                }
+            }
+        }
+        if has_promise {
+            PromiseOrValue::Promise(promise)
+        } else {
+            PromiseOrValue::Value(true)
+        }
+    }
+}
+
+fn assert_self_request(is_self: bool) {
+    if !is_self {
+        env::panic(b"Only the requests on the contract can change the members");
+    }
+}
+
+impl MultiSigManagement for MultiSig {
+    fn add_request(&mut self, request: MultiSigRequest) -> RequestId {
+        self.assert_member();
+        if request.actions.is_empty() {
+            env::panic(b"The request should have at least one action");
+        }
+        let requester_id = env::predecessor_account_id();
+        let num_requests = self.num_requests.get(&requester_id).unwrap_or(0) + 1;
+        if num_requests > ACTIVE_REQUESTS_LIMIT {
+            env::panic(b"The member has too many pending requests");
+        }
+        self.num_requests.insert(&requester_id, &num_requests);
+        let request_id = self.request_nonce;
+        self.request_nonce += 1;
+        let pending = PendingRequest { request, requester_id, confirmations: HashSet::new() };
+        self.requests.insert(&request_id, &pending);
+        env::log(format!("Added the request {}", request_id).as_bytes());
+        request_id
+    }
+
+    fn add_request_and_confirm(&mut self, request: MultiSigRequest) -> PromiseOrValue<bool> {
+        let request_id = self.add_request(request);
+        self.confirm(request_id)
+    }
+
+    fn confirm(&mut self, request_id: RequestId) -> PromiseOrValue<bool> {
+        self.assert_member();
+        let mut pending = self.internal_request(request_id);
+        if !pending.confirmations.insert(env::predecessor_account_id()) {
+            env::panic(b"The request is already confirmed by the member");
+        }
+        // The members that were removed after confirming don't count.
+        let confirmations =
+            pending.confirmations.iter().filter(|member| self.members.contains(member)).count();
+        if (confirmations as u64) < u64::from(self.num_confirmations) {
+            self.requests.insert(&request_id, &pending);
+            return PromiseOrValue::Value(false);
+        }
+        self.internal_remove_request(request_id, &pending);
+        env::log(format!("Executing the request {}", request_id).as_bytes());
+        self.internal_execute(pending.request)
+    }
+
+    fn delete_request(&mut self, request_id: RequestId) -> MultiSigRequest {
+        let pending = self.internal_request(request_id);
+        if pending.requester_id != env::predecessor_account_id() {
+            env::panic(b"Only the member that added the request can delete it");
+        }
+        self.internal_remove_request(request_id, &pending);
+        env::log(format!("Deleted the request {}", request_id).as_bytes());
+        pending.request
+    }
+
+    fn get_request(&self, request_id: RequestId) -> Option<MultiSigRequest> {
+        self.requests.get(&request_id).map(|pending| pending.request)
+    }
+
+    fn get_confirmations(&self, request_id: RequestId) -> Vec<AccountId> {
+        self.requests
+            .get(&request_id)
+            .map(|pending| pending.confirmations.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn list_request_ids(&self) -> Vec<RequestId> {
+        self.requests.keys().collect()
+    }
+
+    fn get_members(&self) -> Vec<AccountId> {
+        self.members.to_vec()
+    }
+
+    fn get_num_confirmations(&self) -> u32 {
+        self.num_confirmations
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+    use near_sdk::json_types::U128;
+
+    fn carol() -> AccountId {
+        "carol.near".to_string()
+    }
+
+    fn setup_multisig() -> MultiSig {
+        set_env(get_context(alice(), 0), vec![]);
+        let members = vec![alice(), bob(), carol()];
+        MultiSig::new(
+            b"m".to_vec(),
+            members.into_iter().map(|m| m.try_into().unwrap()).collect(),
+            2,
+        )
+    }
+
+    fn transfer_request() -> MultiSigRequest {
+        MultiSigRequest {
+            receiver_id: carol(),
+            actions: vec![MultiSigRequestAction::Transfer { amount: U128(100) }],
+        }
+    }
+
+    fn as_value(result: PromiseOrValue<bool>) -> Option<bool> {
+        match result {
+            PromiseOrValue::Value(value) => Some(value),
+            PromiseOrValue::Promise(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_confirm_and_execute() {
+        let mut multisig = setup_multisig();
+        assert_eq!(as_value(multisig.add_request_and_confirm(transfer_request())), Some(false));
+        assert_eq!(multisig.list_request_ids(), vec![0]);
+        assert_eq!(multisig.get_confirmations(0), vec![alice()]);
+        set_env(get_context(bob(), 0), vec![]);
+        assert_eq!(as_value(multisig.confirm(0)), None);
+        assert_eq!(multisig.get_request(0), None);
+    }
+
+    #[test]
+    fn test_change_members() {
+        let mut multisig = setup_multisig();
+        let request = MultiSigRequest {
+            receiver_id: contract(),
+            actions: vec![
+                MultiSigRequestAction::DeleteMember { member: carol() },
+                MultiSigRequestAction::SetNumConfirmations { num_confirmations: 1 },
+            ],
+        };
+        multisig.add_request_and_confirm(request);
+        set_env(get_context(carol(), 0), vec![]);
+        assert_eq!(as_value(multisig.confirm(0)), Some(true));
+        assert_eq!(multisig.get_members(), vec![alice(), bob()]);
+        assert_eq!(multisig.get_num_confirmations(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the requests on the contract can change the members")]
+    fn test_change_members_of_other_account() {
+        let mut multisig = setup_multisig();
+        let request = MultiSigRequest {
+            receiver_id: carol(),
+            actions: vec![MultiSigRequestAction::AddMember { member: carol() }],
+        };
+        multisig.add_request_and_confirm(request);
+        set_env(get_context(bob(), 0), vec![]);
+        multisig.confirm(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The request is already confirmed by the member")]
+    fn test_confirm_twice() {
+        let mut multisig = setup_multisig();
+        multisig.add_request_and_confirm(transfer_request());
+        multisig.confirm(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the members")]
+    fn test_not_member() {
+        let mut multisig = setup_multisig();
+        set_env(get_context(contract(), 0), vec![]);
+        multisig.add_request(transfer_request());
+    }
+
+    #[test]
+    #[should_panic(expected = "The member has too many pending requests")]
+    fn test_active_requests_limit() {
+        let mut multisig = setup_multisig();
+        for _ in 0..ACTIVE_REQUESTS_LIMIT {
+            multisig.add_request(transfer_request());
+        }
+        // The deleted and the executed requests free their places.
+        multisig.delete_request(0);
+        multisig.add_request(transfer_request());
+        multisig.confirm(1);
+        set_env(get_context(bob(), 0), vec![]);
+        multisig.confirm(1);
+        set_env(get_context(alice(), 0), vec![]);
+        multisig.add_request(transfer_request());
+        assert_eq!(multisig.list_request_ids().len() as u32, ACTIVE_REQUESTS_LIMIT);
+        multisig.add_request(transfer_request());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the member that added the request can delete it")]
+    fn test_delete_request() {
+        let mut multisig = setup_multisig();
+        multisig.add_request(transfer_request());
+        assert_eq!(multisig.delete_request(0), transfer_request());
+        multisig.add_request(transfer_request());
+        set_env(get_context(bob(), 0), vec![]);
+        multisig.delete_request(1);
+    }
+}
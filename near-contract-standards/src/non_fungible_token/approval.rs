@@ -0,0 +1,47 @@
+use crate::non_fungible_token::token::TokenId;
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{Promise, PromiseOrValue};
+
+/// Approval management extension of the non-fungible token standard (NEP-178).
+pub trait NonFungibleTokenApproval {
+    /// Approves `account_id` to transfer the token on behalf of its owner. Only the owner of the
+    /// token can call it, and the attached deposit has to cover the storage of the approval.
+    ///
+    /// If `msg` is given, calls `nft_on_approve` on `account_id` and returns the promise.
+    // `#[payable]` is required on the implementation.
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: ValidAccountId,
+        msg: Option<String>,
+    ) -> Option<Promise>;
+
+    /// Revokes the approval of `account_id`. Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: ValidAccountId);
+
+    /// Revokes all the approvals of the token. Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn nft_revoke_all(&mut self, token_id: TokenId);
+
+    /// Returns `true` if `approved_account_id` is approved to transfer the token. If `approval_id`
+    /// is given, it has to match the current approval of the account.
+    fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: ValidAccountId,
+        approval_id: Option<u64>,
+    ) -> bool;
+}
+
+/// Implemented by the contracts that are notified about the approvals with `nft_approve`, e.g.
+/// marketplaces.
+pub trait NonFungibleTokenApprovalReceiver {
+    fn nft_on_approve(
+        &mut self,
+        token_id: TokenId,
+        owner_id: ValidAccountId,
+        approval_id: u64,
+        msg: String,
+    ) -> PromiseOrValue<String>;
+}
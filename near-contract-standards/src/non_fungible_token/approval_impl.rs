@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use crate::non_fungible_token::approval::NonFungibleTokenApproval;
+use crate::non_fungible_token::token::TokenId;
+use crate::non_fungible_token::NonFungibleToken;
+use crate::utils::{assert_at_least_one_yocto, assert_one_yocto, refund_deposit};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, ext_contract, AccountId, Balance, Gas, Promise, StorageUsage};
+
+const GAS_FOR_NFT_APPROVE: Gas = 10_000_000_000_000;
+
+const NO_DEPOSIT: Balance = 0;
+
+#[ext_contract(ext_approval_receiver)]
+pub trait NonFungibleTokenApprovalReceiver {
+    fn nft_on_approve(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        approval_id: u64,
+        msg: String,
+    ) -> near_sdk::PromiseOrValue<String>;
+}
+
+/// The storage taken by one approval: the Borsh-serialized account ID and the approval ID.
+fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
+    account_id.len() as u64 + 4 + 8
+}
+
+/// Refunds the storage of the approvals to `account_id`.
+pub(crate) fn refund_approved_account_ids_iter<'a, I>(
+    account_id: AccountId,
+    approved_account_ids: I,
+) where
+    I: Iterator<Item = &'a AccountId>,
+{
+    let storage_released: u64 = approved_account_ids.map(bytes_for_approved_account_id).sum();
+    if storage_released > 0 {
+        Promise::new(account_id).transfer(StorageUsage::from_bytes(storage_released).cost());
+    }
+}
+
+/// Refunds the storage of the approvals to `account_id`.
+pub(crate) fn refund_approved_account_ids(
+    account_id: AccountId,
+    approved_account_ids: &HashMap<AccountId, u64>,
+) {
+    refund_approved_account_ids_iter(account_id, approved_account_ids.keys());
+}
+
+impl NonFungibleToken {
+    /// Panics unless the predecessor owns the token, returns the owner.
+    fn assert_token_owner(&self, token_id: &TokenId) -> AccountId {
+        let owner_id = self.internal_unwrap_owner_of(token_id);
+        if env::predecessor_account_id() != owner_id {
+            env::panic(b"Only the owner of the token can manage its approvals");
+        }
+        owner_id
+    }
+}
+
+impl NonFungibleTokenApproval for NonFungibleToken {
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: ValidAccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        assert_at_least_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        let account_id: AccountId = account_id.into();
+
+        let mut approved_account_ids = self.approvals_by_id.get(&token_id).unwrap_or_default();
+        let approval_id = self.next_approval_id_by_id.get(&token_id).unwrap_or(1);
+        let old_approval_id = approved_account_ids.insert(account_id.clone(), approval_id);
+        self.approvals_by_id.insert(&token_id, &approved_account_ids);
+        self.next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
+
+        // Re-approving an account doesn't take more storage.
+        let storage_used =
+            if old_approval_id.is_none() { bytes_for_approved_account_id(&account_id) } else { 0 };
+        refund_deposit(StorageUsage::from_bytes(storage_used));
+
+        msg.map(|msg| {
+            ext_approval_receiver::nft_on_approve(
+                token_id,
+                owner_id,
+                approval_id,
+                msg,
+                &account_id,
+                NO_DEPOSIT,
+                env::prepaid_gas() - GAS_FOR_NFT_APPROVE,
+            )
+        })
+    }
+
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: ValidAccountId) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        if let Some(mut approved_account_ids) = self.approvals_by_id.get(&token_id) {
+            if approved_account_ids.remove(account_id.as_ref()).is_some() {
+                refund_approved_account_ids_iter(owner_id, std::iter::once(account_id.as_ref()));
+                if approved_account_ids.is_empty() {
+                    self.approvals_by_id.remove(&token_id);
+                } else {
+                    self.approvals_by_id.insert(&token_id, &approved_account_ids);
+                }
+            }
+        }
+    }
+
+    fn nft_revoke_all(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        let owner_id = self.assert_token_owner(&token_id);
+        if let Some(approved_account_ids) = self.approvals_by_id.remove(&token_id) {
+            refund_approved_account_ids(owner_id, &approved_account_ids);
+        }
+    }
+
+    fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: ValidAccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        self.internal_unwrap_owner_of(&token_id);
+        let approved_account_ids = match self.approvals_by_id.get(&token_id) {
+            Some(approved_account_ids) => approved_account_ids,
+            None => return false,
+        };
+        match approved_account_ids.get(approved_account_id.as_ref()) {
+            Some(actual_approval_id) => {
+                approval_id.is_none() || approval_id == Some(*actual_approval_id)
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::non_fungible_token::core::NonFungibleTokenCore;
+    use crate::non_fungible_token::resolver::NonFungibleTokenResolver;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+    use near_sdk::PromiseResult;
+
+    fn valid(account_id: AccountId) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    fn approval_cost(account_id: &AccountId) -> Balance {
+        Balance::from(bytes_for_approved_account_id(account_id)) * env::storage_byte_cost()
+    }
+
+    /// Mints token "0" to alice and approves bob.
+    fn setup_approved() -> NonFungibleToken {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut nft = NonFungibleToken::new(b"n".to_vec(), valid(contract()));
+        nft.mint("0".to_string(), valid(alice()));
+        set_env(get_context(alice(), approval_cost(&bob())), vec![]);
+        assert!(nft.nft_approve("0".to_string(), valid(bob()), None).is_none());
+        nft
+    }
+
+    #[test]
+    fn test_approve() {
+        let mut nft = setup_approved();
+        assert!(nft.nft_is_approved("0".to_string(), valid(bob()), None));
+        assert!(nft.nft_is_approved("0".to_string(), valid(bob()), Some(1)));
+        assert!(!nft.nft_is_approved("0".to_string(), valid(bob()), Some(2)));
+        assert!(!nft.nft_is_approved("0".to_string(), valid(contract()), None));
+        let token = nft.nft_token("0".to_string()).unwrap();
+        assert_eq!(token.approved_account_ids.get(&bob()), Some(&1));
+
+        // Approving again issues a new approval ID and doesn't charge for the storage.
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_approve("0".to_string(), valid(bob()), None);
+        assert!(nft.nft_is_approved("0".to_string(), valid(bob()), Some(2)));
+    }
+
+    #[test]
+    fn test_approve_with_msg() {
+        let mut nft = setup_approved();
+        set_env(get_context(alice(), approval_cost(&contract())), vec![]);
+        let promise = nft.nft_approve("0".to_string(), valid(contract()), Some("sell".to_string()));
+        assert!(promise.is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn test_approve_not_enough_deposit() {
+        let mut nft = setup_approved();
+        set_env(get_context(alice(), approval_cost(&contract()) - 1), vec![]);
+        nft.nft_approve("0".to_string(), valid(contract()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner of the token can manage its approvals")]
+    fn test_approve_not_owner() {
+        let mut nft = setup_approved();
+        set_env(get_context(bob(), approval_cost(&bob())), vec![]);
+        nft.nft_approve("0".to_string(), valid(bob()), None);
+    }
+
+    #[test]
+    fn test_revoke() {
+        let mut nft = setup_approved();
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_revoke("0".to_string(), valid(bob()));
+        assert!(!nft.nft_is_approved("0".to_string(), valid(bob()), None));
+        assert!(nft.approvals_by_id.get(&"0".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_revoke_all() {
+        let mut nft = setup_approved();
+        set_env(get_context(alice(), approval_cost(&contract())), vec![]);
+        nft.nft_approve("0".to_string(), valid(contract()), None);
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_revoke_all("0".to_string());
+        assert!(!nft.nft_is_approved("0".to_string(), valid(bob()), None));
+        assert!(!nft.nft_is_approved("0".to_string(), valid(contract()), None));
+    }
+
+    #[test]
+    fn test_transfer_by_approved_account() {
+        let mut nft = setup_approved();
+        set_env(get_context(bob(), 1), vec![]);
+        nft.nft_transfer(valid(bob()), "0".to_string(), Some(1), None);
+        let token = nft.nft_token("0".to_string()).unwrap();
+        assert_eq!(token.owner_id, bob());
+        assert!(token.approved_account_ids.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The actual approval_id 1 is different from the given approval_id 2")]
+    fn test_transfer_wrong_approval_id() {
+        let mut nft = setup_approved();
+        set_env(get_context(bob(), 1), vec![]);
+        nft.nft_transfer(valid(bob()), "0".to_string(), Some(2), None);
+    }
+
+    #[test]
+    fn test_resolve_transfer_restores_approvals() {
+        let mut nft = setup_approved();
+        set_env(get_context(bob(), 1), vec![]);
+        let (previous_owner_id, approved_account_ids) =
+            nft.internal_transfer(&bob(), &contract(), &"0".to_string(), None, None);
+        assert!(nft.approvals_by_id.get(&"0".to_string()).is_none());
+        set_env(get_context(contract(), 0), vec![PromiseResult::Failed]);
+        let kept = nft.nft_resolve_transfer(
+            valid(previous_owner_id),
+            valid(contract()),
+            "0".to_string(),
+            approved_account_ids,
+        );
+        assert!(!kept);
+        assert_eq!(nft.nft_token("0".to_string()).unwrap().owner_id, alice());
+        assert!(nft.nft_is_approved("0".to_string(), valid(bob()), Some(1)));
+    }
+}
@@ -0,0 +1,36 @@
+use crate::non_fungible_token::token::{Token, TokenId};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::PromiseOrValue;
+
+pub trait NonFungibleTokenCore {
+    /// Transfers the token `token_id` from the predecessor to `receiver_id`.
+    /// Requires exactly 1 yoctoNEAR to be attached.
+    ///
+    /// `approval_id` is the approval ID of the predecessor when it transfers the token on behalf of
+    /// the owner, see the approval management extension.
+    // `#[payable]` is required on the implementation.
+    fn nft_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+
+    /// Transfers the token and calls `nft_on_transfer` on the receiver with `msg`.
+    /// The token is returned to the previous owner if `nft_on_transfer` returns `true` or fails.
+    /// Returns `true` if the token was successfully transferred to the receiver.
+    /// Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+
+    /// Returns the token with the given `token_id`, or `None` if it doesn't exist.
+    fn nft_token(&self, token_id: TokenId) -> Option<Token>;
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use crate::non_fungible_token::approval_impl::refund_approved_account_ids;
+use crate::non_fungible_token::core::NonFungibleTokenCore;
+use crate::non_fungible_token::resolver::NonFungibleTokenResolver;
+use crate::non_fungible_token::token::{Token, TokenId};
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{TreeMap, UnorderedMap, UnorderedSet};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, ext_contract, AccountId, Balance, Gas, PromiseOrValue, PromiseResult};
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_NFT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
+
+const NO_DEPOSIT: Balance = 0;
+
+#[ext_contract(ext_self)]
+trait NonFungibleTokenResolver {
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool;
+}
+
+#[ext_contract(ext_non_fungible_token_receiver)]
+pub trait NonFungibleTokenReceiver {
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
+
+/// Implementation of the non-fungible token standard (NEP-171).
+/// The contract keeps it as a field of its state and implements `NonFungibleTokenCore` and
+/// `NonFungibleTokenResolver` by delegating to it, see `impl_non_fungible_token_core!`.
+///
+/// Tokens are created with `mint`, which can only be called by `owner_id`. The owner of a token
+/// can approve other accounts to transfer it, see `NonFungibleTokenApproval`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct NonFungibleToken {
+    /// The account that is allowed to mint tokens.
+    pub owner_id: AccountId,
+    /// TokenId -> Owner of the token.
+    pub owner_by_id: TreeMap<TokenId, AccountId>,
+    /// Owner -> Tokens of the owner. The owners without tokens are removed.
+    pub tokens_per_owner: UnorderedMap<AccountId, UnorderedSet<TokenId>>,
+    /// Prefix of the storage keys of the sets in `tokens_per_owner`.
+    tokens_per_owner_prefix: Vec<u8>,
+    /// TokenId -> Accounts approved to transfer the token and their approval IDs.
+    pub approvals_by_id: UnorderedMap<TokenId, HashMap<AccountId, u64>>,
+    /// TokenId -> The approval ID of the next approval of the token.
+    pub next_approval_id_by_id: UnorderedMap<TokenId, u64>,
+}
+
+impl NonFungibleToken {
+    /// Creates an empty collection of tokens that can be minted by `owner_id`. Use `prefix` as a
+    /// unique identifier of the collection on the trie.
+    pub fn new(prefix: Vec<u8>, owner_id: ValidAccountId) -> Self {
+        Self {
+            owner_id: owner_id.into(),
+            owner_by_id: TreeMap::new([prefix.as_slice(), b"o"].concat()),
+            tokens_per_owner: UnorderedMap::new([prefix.as_slice(), b"t"].concat()),
+            tokens_per_owner_prefix: [prefix.as_slice(), b"s"].concat(),
+            approvals_by_id: UnorderedMap::new([prefix.as_slice(), b"a"].concat()),
+            next_approval_id_by_id: UnorderedMap::new([prefix.as_slice(), b"n"].concat()),
+        }
+    }
+
+    /// Returns the owner of the token, panics if the token doesn't exist.
+    pub fn internal_unwrap_owner_of(&self, token_id: &TokenId) -> AccountId {
+        match self.owner_by_id.get(token_id) {
+            Some(owner_id) => owner_id,
+            None => env::panic(format!("The token {} doesn't exist", token_id).as_bytes()),
+        }
+    }
+
+    /// Creates a new token owned by `token_owner_id`. Panics if the predecessor is not `owner_id`.
+    pub fn mint(&mut self, token_id: TokenId, token_owner_id: ValidAccountId) -> Token {
+        if env::predecessor_account_id() != self.owner_id {
+            env::panic(b"Only the owner of the contract can mint tokens");
+        }
+        self.internal_mint(token_id, token_owner_id.into())
+    }
+
+    /// Creates a new token owned by `owner_id` without checking the predecessor.
+    pub fn internal_mint(&mut self, token_id: TokenId, owner_id: AccountId) -> Token {
+        if self.owner_by_id.insert(&token_id, &owner_id).is_some() {
+            env::panic(format!("The token {} already exists", token_id).as_bytes());
+        }
+        self.internal_add_token_to_owner(&owner_id, &token_id);
+        env::log(format!("Mint {} to {}", token_id, owner_id).as_bytes());
+        Token { token_id, owner_id, approved_account_ids: HashMap::new() }
+    }
+
+    /// Returns the token as it is shown by the view methods.
+    pub(crate) fn internal_token(&self, token_id: TokenId, owner_id: AccountId) -> Token {
+        let approved_account_ids = self.approvals_by_id.get(&token_id).unwrap_or_default();
+        Token { token_id, owner_id, approved_account_ids }
+    }
+
+    fn internal_add_token_to_owner(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        let mut tokens = self.tokens_per_owner.get(owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                [self.tokens_per_owner_prefix.as_slice(), &env::sha256(owner_id.as_bytes())]
+                    .concat(),
+            )
+        });
+        tokens.insert(token_id);
+        self.tokens_per_owner.insert(owner_id, &tokens);
+    }
+
+    fn internal_remove_token_from_owner(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        let mut tokens = match self.tokens_per_owner.get(owner_id) {
+            Some(tokens) => tokens,
+            None => env::panic(b"The token should be owned by the sender"),
+        };
+        tokens.remove(token_id);
+        if tokens.is_empty() {
+            self.tokens_per_owner.remove(owner_id);
+        } else {
+            self.tokens_per_owner.insert(owner_id, &tokens);
+        }
+    }
+
+    /// Moves the token from `from` to `to` without any checks.
+    pub fn internal_transfer_unguarded(
+        &mut self,
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+    ) {
+        self.owner_by_id.insert(token_id, to);
+        self.internal_remove_token_from_owner(from, token_id);
+        self.internal_add_token_to_owner(to, token_id);
+    }
+
+    /// Transfers the token from its owner to `receiver_id` on behalf of `sender_id`, who has to be
+    /// either the owner or an account approved by the owner. If `approval_id` is given, it has to
+    /// match the approval of `sender_id`.
+    ///
+    /// The approvals of the token are cleared. Logs the transfer and returns the previous owner
+    /// together with the cleared approvals.
+    pub fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> (AccountId, Option<HashMap<AccountId, u64>>) {
+        let owner_id = self.internal_unwrap_owner_of(token_id);
+        let approved_account_ids = self.approvals_by_id.remove(token_id);
+        if sender_id != &owner_id {
+            let actual_approval_id = match approved_account_ids
+                .as_ref()
+                .and_then(|approved_account_ids| approved_account_ids.get(sender_id))
+            {
+                Some(actual_approval_id) => *actual_approval_id,
+                None => env::panic(b"The sender is not approved to transfer the token"),
+            };
+            if let Some(approval_id) = approval_id {
+                if approval_id != actual_approval_id {
+                    env::panic(
+                        format!(
+                            "The actual approval_id {} is different from the given approval_id {}",
+                            actual_approval_id, approval_id
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+        }
+        if &owner_id == receiver_id {
+            env::panic(b"The token owner and the receiver should be different");
+        }
+        self.internal_transfer_unguarded(token_id, &owner_id, receiver_id);
+        env::log(format!("Transfer {} from {} to {}", token_id, owner_id, receiver_id).as_bytes());
+        if let Some(memo) = memo {
+            env::log(format!("Memo: {}", memo).as_bytes());
+        }
+        (owner_id, approved_account_ids)
+    }
+}
+
+impl NonFungibleTokenCore for NonFungibleToken {
+    fn nft_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) =
+            self.internal_transfer(&sender_id, receiver_id.as_ref(), &token_id, approval_id, memo);
+        if let Some(approved_account_ids) = approved_account_ids {
+            refund_approved_account_ids(previous_owner_id, &approved_account_ids);
+        }
+    }
+
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        assert_one_yocto();
+        if env::prepaid_gas() <= GAS_FOR_NFT_TRANSFER_CALL {
+            env::panic(b"More gas is required");
+        }
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) =
+            self.internal_transfer(&sender_id, receiver_id.as_ref(), &token_id, approval_id, memo);
+        // Initiating receiver's call and the callback
+        ext_non_fungible_token_receiver::nft_on_transfer(
+            sender_id,
+            previous_owner_id.clone(),
+            token_id.clone(),
+            msg,
+            receiver_id.as_ref(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL,
+        )
+        .then(ext_self::nft_resolve_transfer(
+            previous_owner_id,
+            receiver_id.into(),
+            token_id,
+            approved_account_ids,
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+        .into()
+    }
+
+    fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        self.owner_by_id.get(&token_id).map(|owner_id| self.internal_token(token_id, owner_id))
+    }
+}
+
+impl NonFungibleTokenResolver for NonFungibleToken {
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: ValidAccountId,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        // Whether the receiver asked to return the token.
+        let must_return = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(true)
+            }
+            PromiseResult::Failed => true,
+        };
+        // The receiver could have transferred the token further while `nft_on_transfer` was
+        // running, in which case it can't be returned.
+        let owner_id = match self.owner_by_id.get(&token_id) {
+            Some(owner_id) if must_return && &owner_id == receiver_id.as_ref() => owner_id,
+            _ => {
+                // The token stays with its new owner, so the storage of the cleared approvals is
+                // refunded to the previous owner.
+                if let Some(approved_account_ids) = approved_account_ids {
+                    refund_approved_account_ids(previous_owner_id.into(), &approved_account_ids);
+                }
+                return true;
+            }
+        };
+        self.internal_transfer_unguarded(&token_id, &owner_id, previous_owner_id.as_ref());
+        // Restore the approvals of the previous owner, dropping the ones added by the receiver.
+        match approved_account_ids {
+            Some(approved_account_ids) => {
+                self.approvals_by_id.insert(&token_id, &approved_account_ids);
+            }
+            None => {
+                self.approvals_by_id.remove(&token_id);
+            }
+        }
+        env::log(
+            format!("Return {} from {} to {}", token_id, owner_id, previous_owner_id.as_ref())
+                .as_bytes(),
+        );
+        false
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use near_sdk::PromiseResult;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+
+    fn setup_tokens() -> NonFungibleToken {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut nft = NonFungibleToken::new(b"n".to_vec(), contract().try_into().unwrap());
+        nft.mint("0".to_string(), alice().try_into().unwrap());
+        nft.mint("1".to_string(), alice().try_into().unwrap());
+        nft
+    }
+
+    fn tokens_of(nft: &NonFungibleToken, account_id: &AccountId) -> Vec<TokenId> {
+        let mut tokens =
+            nft.tokens_per_owner.get(account_id).map(|tokens| tokens.to_vec()).unwrap_or_default();
+        tokens.sort();
+        tokens
+    }
+
+    #[test]
+    fn test_mint() {
+        let nft = setup_tokens();
+        assert_eq!(
+            nft.nft_token("0".to_string()),
+            Some(Token {
+                token_id: "0".to_string(),
+                owner_id: alice(),
+                approved_account_ids: HashMap::new()
+            })
+        );
+        assert_eq!(nft.nft_token("2".to_string()), None);
+        assert_eq!(tokens_of(&nft, &alice()), vec!["0".to_string(), "1".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner of the contract can mint tokens")]
+    fn test_mint_not_owner() {
+        let mut nft = setup_tokens();
+        set_env(get_context(alice(), 0), vec![]);
+        nft.mint("2".to_string(), alice().try_into().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "The token 0 already exists")]
+    fn test_mint_duplicate() {
+        let mut nft = setup_tokens();
+        nft.mint("0".to_string(), bob().try_into().unwrap());
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut nft = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_transfer(bob().try_into().unwrap(), "0".to_string(), None, None);
+        assert_eq!(nft.nft_token("0".to_string()).unwrap().owner_id, bob());
+        assert_eq!(tokens_of(&nft, &alice()), vec!["1".to_string()]);
+        assert_eq!(tokens_of(&nft, &bob()), vec!["0".to_string()]);
+
+        nft.nft_transfer(bob().try_into().unwrap(), "1".to_string(), None, None);
+        assert!(nft.tokens_per_owner.get(&alice()).is_none());
+        assert_eq!(tokens_of(&nft, &bob()), vec!["0".to_string(), "1".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The sender is not approved to transfer the token")]
+    fn test_transfer_not_owner() {
+        let mut nft = setup_tokens();
+        set_env(get_context(bob(), 1), vec![]);
+        nft.nft_transfer(bob().try_into().unwrap(), "0".to_string(), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_transfer_without_yocto() {
+        let mut nft = setup_tokens();
+        set_env(get_context(alice(), 0), vec![]);
+        nft.nft_transfer(bob().try_into().unwrap(), "0".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_resolve_transfer_returns_token() {
+        let mut nft = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_transfer(bob().try_into().unwrap(), "0".to_string(), None, None);
+        let result = near_sdk::serde_json::to_vec(&true).unwrap();
+        set_env(get_context(contract(), 0), vec![PromiseResult::Successful(result)]);
+        let kept = nft.nft_resolve_transfer(
+            alice().try_into().unwrap(),
+            bob().try_into().unwrap(),
+            "0".to_string(),
+            None,
+        );
+        assert!(!kept);
+        assert_eq!(nft.nft_token("0".to_string()).unwrap().owner_id, alice());
+        assert!(nft.tokens_per_owner.get(&bob()).is_none());
+    }
+
+    #[test]
+    fn test_resolve_transfer_keeps_token() {
+        let mut nft = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_transfer(bob().try_into().unwrap(), "0".to_string(), None, None);
+        let result = near_sdk::serde_json::to_vec(&false).unwrap();
+        set_env(get_context(contract(), 0), vec![PromiseResult::Successful(result)]);
+        let kept = nft.nft_resolve_transfer(
+            alice().try_into().unwrap(),
+            bob().try_into().unwrap(),
+            "0".to_string(),
+            None,
+        );
+        assert!(kept);
+        assert_eq!(nft.nft_token("0".to_string()).unwrap().owner_id, bob());
+    }
+
+    #[test]
+    fn test_resolve_transfer_failed_after_further_transfer() {
+        let mut nft = setup_tokens();
+        set_env(get_context(alice(), 1), vec![]);
+        nft.nft_transfer(bob().try_into().unwrap(), "0".to_string(), None, None);
+        set_env(get_context(bob(), 1), vec![]);
+        nft.nft_transfer(contract().try_into().unwrap(), "0".to_string(), None, None);
+        set_env(get_context(contract(), 0), vec![PromiseResult::Failed]);
+        let kept = nft.nft_resolve_transfer(
+            alice().try_into().unwrap(),
+            bob().try_into().unwrap(),
+            "0".to_string(),
+            None,
+        );
+        assert!(kept);
+        assert_eq!(nft.nft_token("0".to_string()).unwrap().owner_id, contract());
+    }
+}
@@ -0,0 +1,24 @@
+use crate::non_fungible_token::token::Token;
+use near_sdk::json_types::{ValidAccountId, U128};
+
+/// Enumeration extension of the non-fungible token standard (NEP-181).
+pub trait NonFungibleTokenEnumeration {
+    /// Returns the total number of tokens.
+    fn nft_total_supply(&self) -> U128;
+
+    /// Returns up to `limit` tokens ordered by token ID, starting at the `from_index`-th token.
+    /// `from_index` defaults to 0, and `limit` to all the remaining tokens.
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token>;
+
+    /// Returns the number of tokens owned by `account_id`.
+    fn nft_supply_for_owner(&self, account_id: ValidAccountId) -> U128;
+
+    /// Returns up to `limit` tokens of `account_id`, starting at the `from_index`-th token.
+    /// The order of the tokens of an owner changes when a token is transferred.
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token>;
+}
@@ -0,0 +1,125 @@
+use crate::non_fungible_token::enumeration::NonFungibleTokenEnumeration;
+use crate::non_fungible_token::token::Token;
+use crate::non_fungible_token::NonFungibleToken;
+use near_sdk::env;
+use near_sdk::json_types::{ValidAccountId, U128};
+
+/// Converts the pagination arguments into the number of tokens to skip and to take.
+fn pagination(from_index: Option<U128>, limit: Option<u64>) -> (usize, usize) {
+    let from_index = from_index.map(|from_index| from_index.0).unwrap_or(0);
+    if from_index > usize::MAX as u128 {
+        env::panic(b"from_index is out of bounds");
+    }
+    let limit = limit.map(|limit| limit as usize).unwrap_or(usize::MAX);
+    if limit == 0 {
+        env::panic(b"Cannot provide limit of 0");
+    }
+    (from_index as usize, limit)
+}
+
+impl NonFungibleTokenEnumeration for NonFungibleToken {
+    fn nft_total_supply(&self) -> U128 {
+        U128(self.owner_by_id.len() as u128)
+    }
+
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        let (skip, take) = pagination(from_index, limit);
+        self.owner_by_id
+            .iter()
+            .skip(skip)
+            .take(take)
+            .map(|(token_id, owner_id)| self.internal_token(token_id, owner_id))
+            .collect()
+    }
+
+    fn nft_supply_for_owner(&self, account_id: ValidAccountId) -> U128 {
+        self.tokens_per_owner
+            .get(account_id.as_ref())
+            .map(|tokens| U128(tokens.len() as u128))
+            .unwrap_or(U128(0))
+    }
+
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        let (skip, take) = pagination(from_index, limit);
+        let tokens = match self.tokens_per_owner.get(account_id.as_ref()) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+        tokens
+            .iter()
+            .skip(skip)
+            .take(take)
+            .map(|token_id| self.internal_token(token_id, account_id.clone().into()))
+            .collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+    use near_sdk::AccountId;
+
+    fn setup_tokens() -> NonFungibleToken {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut nft = NonFungibleToken::new(b"n".to_vec(), contract().try_into().unwrap());
+        for i in 0..5 {
+            nft.mint(i.to_string(), alice().try_into().unwrap());
+        }
+        for i in 5..7 {
+            nft.mint(i.to_string(), bob().try_into().unwrap());
+        }
+        nft
+    }
+
+    fn token_ids(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|token| token.token_id).collect()
+    }
+
+    fn valid(account_id: AccountId) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_nft_tokens() {
+        let nft = setup_tokens();
+        assert_eq!(nft.nft_total_supply().0, 7);
+        assert_eq!(token_ids(nft.nft_tokens(None, None)).len(), 7);
+        assert_eq!(token_ids(nft.nft_tokens(Some(U128(2)), Some(3))), vec!["2", "3", "4"]);
+        assert_eq!(token_ids(nft.nft_tokens(Some(U128(6)), Some(3))), vec!["6"]);
+        assert!(nft.nft_tokens(Some(U128(7)), None).is_empty());
+    }
+
+    #[test]
+    fn test_nft_tokens_for_owner() {
+        let nft = setup_tokens();
+        assert_eq!(nft.nft_supply_for_owner(valid(alice())).0, 5);
+        assert_eq!(nft.nft_supply_for_owner(valid(contract())).0, 0);
+        assert_eq!(
+            token_ids(nft.nft_tokens_for_owner(valid(alice()), None, Some(2))),
+            vec!["0", "1"]
+        );
+        assert_eq!(
+            token_ids(nft.nft_tokens_for_owner(valid(bob()), Some(U128(1)), None)),
+            vec!["6"]
+        );
+        assert!(nft.nft_tokens_for_owner(valid(contract()), None, None).is_empty());
+        let tokens = nft.nft_tokens_for_owner(valid(bob()), None, None);
+        assert!(tokens.iter().all(|token| token.owner_id == bob()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot provide limit of 0")]
+    fn test_zero_limit() {
+        let nft = setup_tokens();
+        nft.nft_tokens(None, Some(0));
+    }
+}
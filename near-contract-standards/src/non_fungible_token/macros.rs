@@ -0,0 +1,199 @@
+/// The core methods for a basic non-fungible token. Extension standards may be
+/// added in addition to this macro.
+///
+/// Expects the contract to store `NonFungibleToken` in the field `$tokens`:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     tokens: NonFungibleToken,
+/// }
+///
+/// near_contract_standards::impl_non_fungible_token_core!(Contract, tokens);
+/// ```
+#[macro_export]
+macro_rules! impl_non_fungible_token_core {
+    ($contract: ident, $tokens: ident) => {
+        use $crate::non_fungible_token::core::NonFungibleTokenCore;
+        use $crate::non_fungible_token::resolver::NonFungibleTokenResolver;
+
+        #[near_sdk::near_bindgen]
+        impl NonFungibleTokenCore for $contract {
+            #[payable]
+            fn nft_transfer(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                token_id: $crate::non_fungible_token::TokenId,
+                approval_id: Option<u64>,
+                memo: Option<String>,
+            ) {
+                self.$tokens.nft_transfer(receiver_id, token_id, approval_id, memo)
+            }
+
+            #[payable]
+            fn nft_transfer_call(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                token_id: $crate::non_fungible_token::TokenId,
+                approval_id: Option<u64>,
+                memo: Option<String>,
+                msg: String,
+            ) -> near_sdk::PromiseOrValue<bool> {
+                self.$tokens.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
+            }
+
+            fn nft_token(
+                &self,
+                token_id: $crate::non_fungible_token::TokenId,
+            ) -> Option<$crate::non_fungible_token::Token> {
+                self.$tokens.nft_token(token_id)
+            }
+        }
+
+        #[near_sdk::near_bindgen]
+        impl NonFungibleTokenResolver for $contract {
+            fn nft_resolve_transfer(
+                &mut self,
+                previous_owner_id: near_sdk::json_types::ValidAccountId,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                token_id: $crate::non_fungible_token::TokenId,
+                approved_account_ids: Option<std::collections::HashMap<near_sdk::AccountId, u64>>,
+            ) -> bool {
+                near_sdk::wrapper::assert_private("nft_resolve_transfer");
+                self.$tokens.nft_resolve_transfer(
+                    previous_owner_id,
+                    receiver_id,
+                    token_id,
+                    approved_account_ids,
+                )
+            }
+        }
+    };
+}
+
+/// Exposes the enumeration extension (NEP-181) of the tokens stored in the field `$tokens`.
+#[macro_export]
+macro_rules! impl_non_fungible_token_enumeration {
+    ($contract: ident, $tokens: ident) => {
+        use $crate::non_fungible_token::enumeration::NonFungibleTokenEnumeration;
+
+        #[near_sdk::near_bindgen]
+        impl NonFungibleTokenEnumeration for $contract {
+            fn nft_total_supply(&self) -> near_sdk::json_types::U128 {
+                self.$tokens.nft_total_supply()
+            }
+
+            fn nft_tokens(
+                &self,
+                from_index: Option<near_sdk::json_types::U128>,
+                limit: Option<u64>,
+            ) -> Vec<$crate::non_fungible_token::Token> {
+                self.$tokens.nft_tokens(from_index, limit)
+            }
+
+            fn nft_supply_for_owner(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) -> near_sdk::json_types::U128 {
+                self.$tokens.nft_supply_for_owner(account_id)
+            }
+
+            fn nft_tokens_for_owner(
+                &self,
+                account_id: near_sdk::json_types::ValidAccountId,
+                from_index: Option<near_sdk::json_types::U128>,
+                limit: Option<u64>,
+            ) -> Vec<$crate::non_fungible_token::Token> {
+                self.$tokens.nft_tokens_for_owner(account_id, from_index, limit)
+            }
+        }
+    };
+}
+
+/// Exposes the approval management extension (NEP-178) of the tokens stored in the field
+/// `$tokens`.
+#[macro_export]
+macro_rules! impl_non_fungible_token_approval {
+    ($contract: ident, $tokens: ident) => {
+        use $crate::non_fungible_token::approval::NonFungibleTokenApproval;
+
+        #[near_sdk::near_bindgen]
+        impl NonFungibleTokenApproval for $contract {
+            #[payable]
+            fn nft_approve(
+                &mut self,
+                token_id: $crate::non_fungible_token::TokenId,
+                account_id: near_sdk::json_types::ValidAccountId,
+                msg: Option<String>,
+            ) -> Option<near_sdk::Promise> {
+                self.$tokens.nft_approve(token_id, account_id, msg)
+            }
+
+            #[payable]
+            fn nft_revoke(
+                &mut self,
+                token_id: $crate::non_fungible_token::TokenId,
+                account_id: near_sdk::json_types::ValidAccountId,
+            ) {
+                self.$tokens.nft_revoke(token_id, account_id)
+            }
+
+            #[payable]
+            fn nft_revoke_all(&mut self, token_id: $crate::non_fungible_token::TokenId) {
+                self.$tokens.nft_revoke_all(token_id)
+            }
+
+            fn nft_is_approved(
+                &self,
+                token_id: $crate::non_fungible_token::TokenId,
+                approved_account_id: near_sdk::json_types::ValidAccountId,
+                approval_id: Option<u64>,
+            ) -> bool {
+                self.$tokens.nft_is_approved(token_id, approved_account_id, approval_id)
+            }
+        }
+    };
+}
+
+/// Exposes the payouts (NEP-199) of the tokens stored in the field `$tokens`, using the
+/// `Royalties` stored in the field `$royalties`.
+#[macro_export]
+macro_rules! impl_non_fungible_token_payout {
+    ($contract: ident, $tokens: ident, $royalties: ident) => {
+        use $crate::non_fungible_token::payout::NonFungibleTokenPayout;
+
+        #[near_sdk::near_bindgen]
+        impl NonFungibleTokenPayout for $contract {
+            fn nft_payout(
+                &self,
+                token_id: $crate::non_fungible_token::TokenId,
+                balance: near_sdk::json_types::U128,
+                max_len_payout: Option<u32>,
+            ) -> $crate::non_fungible_token::payout::Payout {
+                self.$royalties.nft_payout(&self.$tokens, token_id, balance, max_len_payout)
+            }
+
+            #[payable]
+            fn nft_transfer_payout(
+                &mut self,
+                receiver_id: near_sdk::json_types::ValidAccountId,
+                token_id: $crate::non_fungible_token::TokenId,
+                approval_id: Option<u64>,
+                memo: Option<String>,
+                balance: near_sdk::json_types::U128,
+                max_len_payout: Option<u32>,
+            ) -> $crate::non_fungible_token::payout::Payout {
+                self.$royalties.nft_transfer_payout(
+                    &mut self.$tokens,
+                    receiver_id,
+                    token_id,
+                    approval_id,
+                    memo,
+                    balance,
+                    max_len_payout,
+                )
+            }
+        }
+    };
+}
@@ -0,0 +1,29 @@
+//! Non-fungible token standard (NEP-171).
+//!
+//! `NonFungibleToken` keeps the owner of every token and the set of tokens of every owner, and
+//! implements the `NonFungibleTokenCore` and `NonFungibleTokenResolver` traits. A contract embeds it
+//! into its state and exposes the methods with `impl_non_fungible_token_core!`.
+//!
+//! The tokens can be listed with the enumeration extension (NEP-181), exposed with
+//! `impl_non_fungible_token_enumeration!`. The owner of a token can approve other accounts, e.g.
+//! marketplaces, to transfer it with the approval management extension (NEP-178), exposed with
+//! `impl_non_fungible_token_approval!`. Marketplaces learn how to split the price of a sold token
+//! between its owner and the royalty holders from the payout extension (NEP-199), which is
+//! configured with `Royalties` and exposed with `impl_non_fungible_token_payout!`.
+
+pub mod approval;
+mod approval_impl;
+pub mod core;
+pub mod core_impl;
+pub mod enumeration;
+mod enumeration_impl;
+mod macros;
+pub mod payout;
+mod payout_impl;
+pub mod receiver;
+pub mod resolver;
+pub mod token;
+
+pub use core_impl::NonFungibleToken;
+pub use payout_impl::{apportion, Royalties, ROYALTY_DENOMINATOR};
+pub use token::{Token, TokenId};
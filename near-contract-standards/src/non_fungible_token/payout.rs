@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use crate::non_fungible_token::token::TokenId;
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// How the price of a token is split between the accounts when it is sold.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
+/// Royalties extension of the non-fungible token standard (NEP-199).
+pub trait NonFungibleTokenPayout {
+    /// Returns how `balance` paid for the token should be split between its owner and the
+    /// royalty holders. Panics if the payout has more than `max_len_payout` receivers.
+    fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout;
+
+    /// Transfers the token like `nft_transfer` and returns the payout of `balance` to the previous
+    /// owner and the royalty holders. Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn nft_transfer_payout(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout;
+}
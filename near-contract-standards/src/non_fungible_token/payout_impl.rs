@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use crate::non_fungible_token::approval_impl::refund_approved_account_ids;
+use crate::non_fungible_token::payout::Payout;
+use crate::non_fungible_token::token::TokenId;
+use crate::non_fungible_token::NonFungibleToken;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::math::{Fraction, Rounding};
+use near_sdk::{env, AccountId, Balance};
+
+/// The royalty of an account is expressed in basis points: 1/10000 of the price.
+pub const ROYALTY_DENOMINATOR: u16 = 10_000;
+
+/// Returns `balance * numerator / ROYALTY_DENOMINATOR` rounded down, without overflowing `u128`.
+pub fn apportion(balance: Balance, numerator: u16) -> Balance {
+    Fraction::new(numerator.into(), ROYALTY_DENOMINATOR.into()).mul_int(balance, Rounding::Down)
+}
+
+/// Royalty splits applied to every token of the contract: the royalty holders receive their share
+/// of the price whenever a token is sold, and the owner of the token gets the rest.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct Royalties {
+    /// Account -> Royalty of the account in basis points.
+    pub accounts: HashMap<AccountId, u16>,
+}
+
+impl Royalties {
+    /// Creates the royalty splits, panics if they add up to more than 100%.
+    pub fn new(accounts: HashMap<AccountId, u16>) -> Self {
+        let this = Self { accounts };
+        this.assert_valid();
+        this
+    }
+
+    /// Panics if the royalties add up to more than 100%.
+    pub fn assert_valid(&self) {
+        let total = self.accounts.values().fold(0u32, |total, royalty| total + u32::from(*royalty));
+        if total > u32::from(ROYALTY_DENOMINATOR) {
+            env::panic(b"The royalties cannot exceed 100%");
+        }
+    }
+
+    /// Splits `balance` between the royalty holders and `owner_id`.
+    pub fn create_payout(
+        &self,
+        balance: Balance,
+        owner_id: &AccountId,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        let max_len_payout = max_len_payout.unwrap_or(u32::MAX);
+        let len_payout = self.accounts.len() + usize::from(!self.accounts.contains_key(owner_id));
+        if len_payout as u64 > u64::from(max_len_payout) {
+            env::panic(b"Cannot payout to so many receivers");
+        }
+        let mut payout = HashMap::new();
+        let mut paid: Balance = 0;
+        for (account_id, royalty) in self.accounts.iter() {
+            let amount = apportion(balance, *royalty);
+            paid += amount;
+            payout.insert(account_id.clone(), amount);
+        }
+        // The royalties add up to at most 100%, so the rounded down amounts can't exceed `balance`.
+        *payout.entry(owner_id.clone()).or_insert(0) += balance - paid;
+        Payout {
+            payout: payout
+                .into_iter()
+                .map(|(account_id, amount)| (account_id, U128(amount)))
+                .collect(),
+        }
+    }
+
+    /// Implementation of `nft_payout` for the tokens stored in `tokens`.
+    pub fn nft_payout(
+        &self,
+        tokens: &NonFungibleToken,
+        token_id: TokenId,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        let owner_id = tokens.internal_unwrap_owner_of(&token_id);
+        self.create_payout(balance.0, &owner_id, max_len_payout)
+    }
+
+    /// Implementation of `nft_transfer_payout` for the tokens stored in `tokens`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn nft_transfer_payout(
+        &self,
+        tokens: &mut NonFungibleToken,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) = tokens.internal_transfer(
+            &sender_id,
+            receiver_id.as_ref(),
+            &token_id,
+            approval_id,
+            memo,
+        );
+        if let Some(approved_account_ids) = approved_account_ids {
+            refund_approved_account_ids(previous_owner_id.clone(), &approved_account_ids);
+        }
+        self.create_payout(balance.0, &previous_owner_id, max_len_payout)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, contract, get_context, set_env};
+
+    fn royalties() -> Royalties {
+        let mut accounts = HashMap::new();
+        accounts.insert(contract(), 1_000);
+        accounts.insert(bob(), 250);
+        Royalties::new(accounts)
+    }
+
+    fn amount_of(payout: &Payout, account_id: &AccountId) -> Balance {
+        payout.payout.get(account_id).map(|amount| amount.0).unwrap_or(0)
+    }
+
+    #[test]
+    fn test_apportion() {
+        assert_eq!(apportion(10_000, 250), 250);
+        assert_eq!(apportion(99, 5_000), 49);
+        assert_eq!(apportion(Balance::MAX, ROYALTY_DENOMINATOR), Balance::MAX);
+        assert_eq!(apportion(Balance::MAX, 5_000), Balance::MAX / 2);
+        assert_eq!(apportion(Balance::MAX, 0), 0);
+    }
+
+    #[test]
+    fn test_create_payout() {
+        set_env(get_context(alice(), 0), vec![]);
+        let payout = royalties().create_payout(1_000_001, &alice(), Some(3));
+        assert_eq!(amount_of(&payout, &contract()), 100_000);
+        assert_eq!(amount_of(&payout, &bob()), 25_000);
+        assert_eq!(amount_of(&payout, &alice()), 875_001);
+    }
+
+    #[test]
+    fn test_create_payout_owner_with_royalty() {
+        set_env(get_context(alice(), 0), vec![]);
+        let payout = royalties().create_payout(1_000, &bob(), Some(2));
+        assert_eq!(payout.payout.len(), 2);
+        assert_eq!(amount_of(&payout, &contract()), 100);
+        assert_eq!(amount_of(&payout, &bob()), 900);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot payout to so many receivers")]
+    fn test_create_payout_too_many_receivers() {
+        set_env(get_context(alice(), 0), vec![]);
+        royalties().create_payout(1_000, &alice(), Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "The royalties cannot exceed 100%")]
+    fn test_royalties_exceed_total() {
+        set_env(get_context(alice(), 0), vec![]);
+        let mut accounts = HashMap::new();
+        accounts.insert(contract(), 6_000);
+        accounts.insert(bob(), 4_001);
+        Royalties::new(accounts);
+    }
+
+    #[test]
+    fn test_nft_transfer_payout() {
+        set_env(get_context(contract(), 0), vec![]);
+        let mut tokens = NonFungibleToken::new(b"n".to_vec(), contract().try_into().unwrap());
+        tokens.mint("0".to_string(), alice().try_into().unwrap());
+        set_env(get_context(alice(), 1), vec![]);
+        let royalties = royalties();
+        let payout = royalties.nft_transfer_payout(
+            &mut tokens,
+            bob().try_into().unwrap(),
+            "0".to_string(),
+            None,
+            None,
+            U128(2_000),
+            None,
+        );
+        assert_eq!(amount_of(&payout, &alice()), 1_750);
+        assert_eq!(tokens.internal_unwrap_owner_of(&"0".to_string()), bob());
+        let payout = royalties.nft_payout(&tokens, "0".to_string(), U128(2_000), None);
+        assert_eq!(amount_of(&payout, &bob()), 1_800);
+    }
+}
@@ -0,0 +1,16 @@
+use crate::non_fungible_token::token::TokenId;
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::PromiseOrValue;
+
+/// Implemented by the contracts that accept tokens through `nft_transfer_call`.
+pub trait NonFungibleTokenReceiver {
+    /// Called by the token contract after the token was transferred to the receiver.
+    /// Returns `true` if the token should be returned to `previous_owner_id`.
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        previous_owner_id: ValidAccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
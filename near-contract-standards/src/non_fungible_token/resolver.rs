@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+
+use crate::non_fungible_token::token::TokenId;
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::AccountId;
+
+pub trait NonFungibleTokenResolver {
+    /// Callback of `nft_transfer_call` that returns the token to `previous_owner_id` if the
+    /// receiver asked for it or the `nft_on_transfer` call failed.
+    /// `approved_account_ids` are the approvals the token had before the transfer. They are
+    /// restored if the token is returned.
+    ///
+    /// Returns `true` if the token stays with the receiver.
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: ValidAccountId,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool;
+}
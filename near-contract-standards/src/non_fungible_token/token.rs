@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// Identifier of a token, unique within the contract.
+pub type TokenId = String;
+
+/// The token as returned by `nft_token`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Token {
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    /// Accounts approved to transfer the token and their approval IDs.
+    pub approved_account_ids: HashMap<AccountId, u64>,
+}
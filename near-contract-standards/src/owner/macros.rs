@@ -0,0 +1,58 @@
+/// Exposes the ownership methods of the `Owner` stored in the field `$owner`, and adds the
+/// `assert_owner` method that `#[only_owner]` relies on:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     owner: Owner,
+///     counter: u64,
+/// }
+///
+/// near_contract_standards::impl_ownable!(Contract, owner);
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     #[only_owner]
+///     pub fn reset(&mut self) {
+///         self.counter = 0;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_ownable {
+    ($contract: ident, $owner: ident) => {
+        use $crate::owner::Ownable;
+
+        #[near_sdk::near_bindgen]
+        impl Ownable for $contract {
+            fn owner(&self) -> near_sdk::AccountId {
+                self.$owner.owner()
+            }
+
+            fn proposed_owner(&self) -> Option<near_sdk::AccountId> {
+                self.$owner.proposed_owner()
+            }
+
+            #[payable]
+            fn propose_owner(
+                &mut self,
+                new_owner_id: Option<near_sdk::json_types::ValidAccountId>,
+            ) {
+                self.$owner.propose_owner(new_owner_id)
+            }
+
+            #[payable]
+            fn accept_owner(&mut self) {
+                self.$owner.accept_owner()
+            }
+        }
+
+        impl $contract {
+            /// Panics unless the predecessor is the owner of the contract.
+            pub fn assert_owner(&self) {
+                self.$owner.assert_owner()
+            }
+        }
+    };
+}
@@ -0,0 +1,35 @@
+//! Ownership of a contract.
+//!
+//! `Owner` keeps the account that administers the contract, and implements the `Ownable` trait. A
+//! contract embeds it into its state and exposes the methods with `impl_ownable!`, which also gives
+//! the contract an `assert_owner` method. The methods of the contract marked with `#[only_owner]`
+//! inside `#[near_bindgen]` start with a call to `assert_owner`, so they panic unless called by the
+//! owner.
+//!
+//! The ownership is transferred in two steps, so that it can't be lost to a mistyped account: the
+//! owner proposes the new owner with `propose_owner`, and the proposed account becomes the owner by
+//! calling `accept_owner`.
+
+mod macros;
+mod owner_impl;
+
+pub use owner_impl::Owner;
+
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::AccountId;
+
+pub trait Ownable {
+    /// Returns the current owner of the contract.
+    fn owner(&self) -> AccountId;
+
+    /// Returns the account that was proposed as the new owner and hasn't accepted the ownership yet.
+    fn proposed_owner(&self) -> Option<AccountId>;
+
+    /// Proposes `new_owner_id` as the new owner of the contract, replacing the previous proposal.
+    /// `None` cancels the proposal. Can only be called by the owner with 1 yoctoNEAR attached.
+    fn propose_owner(&mut self, new_owner_id: Option<ValidAccountId>);
+
+    /// Makes the proposed account the owner of the contract. Can only be called by the proposed
+    /// account with 1 yoctoNEAR attached.
+    fn accept_owner(&mut self);
+}
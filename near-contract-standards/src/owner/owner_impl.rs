@@ -0,0 +1,128 @@
+use crate::owner::Ownable;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, AccountId};
+
+/// The owner of the contract and the account proposed to replace it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Owner {
+    /// The current owner.
+    pub owner_id: AccountId,
+    /// The account that can accept the ownership.
+    pub proposed_owner_id: Option<AccountId>,
+}
+
+impl Owner {
+    pub fn new(owner_id: ValidAccountId) -> Self {
+        Self { owner_id: owner_id.into(), proposed_owner_id: None }
+    }
+
+    /// Returns `true` if the predecessor is the owner.
+    pub fn is_owner(&self) -> bool {
+        env::predecessor_account_id() == self.owner_id
+    }
+
+    /// Panics unless the predecessor is the owner.
+    pub fn assert_owner(&self) {
+        if !self.is_owner() {
+            env::panic(b"Method is only available to the owner");
+        }
+    }
+}
+
+impl Ownable for Owner {
+    fn owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    fn proposed_owner(&self) -> Option<AccountId> {
+        self.proposed_owner_id.clone()
+    }
+
+    fn propose_owner(&mut self, new_owner_id: Option<ValidAccountId>) {
+        assert_one_yocto();
+        self.assert_owner();
+        self.proposed_owner_id = new_owner_id.map(|account_id| account_id.into());
+        match &self.proposed_owner_id {
+            Some(account_id) => {
+                env::log(format!("Proposed {} as the new owner", account_id).as_bytes())
+            }
+            None => env::log(b"Cancelled the proposal of the new owner"),
+        }
+    }
+
+    fn accept_owner(&mut self) {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        if self.proposed_owner_id.as_ref() != Some(&account_id) {
+            env::panic(b"Only the proposed owner can accept the ownership");
+        }
+        env::log(
+            format!("Transferred the ownership from {} to {}", self.owner_id, account_id)
+                .as_bytes(),
+        );
+        self.owner_id = account_id;
+        self.proposed_owner_id = None;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, get_context, set_env};
+
+    fn setup_owner() -> Owner {
+        set_env(get_context(alice(), 1), vec![]);
+        Owner::new(alice().try_into().unwrap())
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut owner = setup_owner();
+        owner.assert_owner();
+        owner.propose_owner(Some(bob().try_into().unwrap()));
+        assert_eq!(owner.owner(), alice());
+        assert_eq!(owner.proposed_owner(), Some(bob()));
+        set_env(get_context(bob(), 1), vec![]);
+        owner.accept_owner();
+        assert_eq!(owner.owner(), bob());
+        assert_eq!(owner.proposed_owner(), None);
+        owner.assert_owner();
+    }
+
+    #[test]
+    fn test_cancel_proposal() {
+        let mut owner = setup_owner();
+        owner.propose_owner(Some(bob().try_into().unwrap()));
+        owner.propose_owner(None);
+        assert_eq!(owner.proposed_owner(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the owner")]
+    fn test_assert_owner() {
+        let owner = setup_owner();
+        set_env(get_context(bob(), 0), vec![]);
+        owner.assert_owner();
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the owner")]
+    fn test_propose_not_owner() {
+        let mut owner = setup_owner();
+        set_env(get_context(bob(), 1), vec![]);
+        owner.propose_owner(Some(bob().try_into().unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed owner can accept the ownership")]
+    fn test_accept_not_proposed() {
+        let mut owner = setup_owner();
+        set_env(get_context(bob(), 1), vec![]);
+        owner.accept_owner();
+    }
+}
@@ -0,0 +1,60 @@
+/// Exposes the pause methods of the `Pausable` stored in the field `$pausable`, and adds the
+/// `assert_not_paused` method that `#[if_paused(panic)]` relies on. The contract should implement
+/// the ownership with `impl_ownable!`, since only the owner can pause and unpause the features:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     owner: Owner,
+///     pausable: Pausable,
+///     counter: u64,
+/// }
+///
+/// near_contract_standards::impl_ownable!(Contract, owner);
+/// near_contract_standards::impl_pausable!(Contract, pausable);
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     #[if_paused(panic)]
+///     pub fn increment(&mut self) {
+///         self.counter += 1;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_pausable {
+    ($contract: ident, $pausable: ident) => {
+        use $crate::pause::Pause;
+
+        #[near_sdk::near_bindgen]
+        impl Pause for $contract {
+            fn is_paused(&self, feature: String) -> bool {
+                self.$pausable.is_paused(feature)
+            }
+
+            fn paused_features(&self) -> Vec<String> {
+                self.$pausable.paused_features()
+            }
+
+            #[only_owner]
+            #[payable]
+            fn pause_feature(&mut self, feature: String) -> bool {
+                self.$pausable.pause_feature(feature)
+            }
+
+            #[only_owner]
+            #[payable]
+            fn unpause_feature(&mut self, feature: String) -> bool {
+                self.$pausable.unpause_feature(feature)
+            }
+        }
+
+        impl $contract {
+            /// Panics if the feature of the contract is paused.
+            pub fn assert_not_paused(&self, feature: &str) {
+                self.$pausable.assert_not_paused(feature)
+            }
+        }
+    };
+}
@@ -0,0 +1,30 @@
+//! Emergency stops of a contract.
+//!
+//! `Pausable` keeps the set of paused features, and implements the `Pause` trait. A feature is any
+//! name chosen by the contract, e.g. `transfers`; pausing `ALL` pauses every feature. A contract
+//! embeds `Pausable` into its state and exposes the methods with `impl_pausable!`, which also gives
+//! the contract an `assert_not_paused` method. Only the owner of the contract can pause and unpause
+//! the features, so the contract also needs `impl_ownable!`.
+//!
+//! The methods of the contract marked with `#[if_paused(panic)]` inside `#[near_bindgen]` panic
+//! while their feature is paused. The feature is the name of the method, unless it's given with
+//! `#[if_paused(panic, feature = "...")]`, which lets several methods share a feature.
+
+mod macros;
+mod pausable_impl;
+
+pub use pausable_impl::{Pausable, PAUSE_ALL};
+
+pub trait Pause {
+    /// Returns `true` if the feature is paused, directly or with `ALL`.
+    fn is_paused(&self, feature: String) -> bool;
+
+    /// Returns the paused features.
+    fn paused_features(&self) -> Vec<String>;
+
+    /// Pauses the feature. Returns `false` if it was already paused. Requires 1 yoctoNEAR attached.
+    fn pause_feature(&mut self, feature: String) -> bool;
+
+    /// Unpauses the feature. Returns `false` if it wasn't paused. Requires 1 yoctoNEAR attached.
+    fn unpause_feature(&mut self, feature: String) -> bool;
+}
@@ -0,0 +1,106 @@
+use crate::pause::Pause;
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::env;
+
+/// The feature that pauses all the features.
+pub const PAUSE_ALL: &str = "ALL";
+
+/// The set of paused features.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Pausable {
+    pub paused_features: UnorderedSet<String>,
+}
+
+impl Pausable {
+    /// Creates a contract with no paused features. Use `prefix` as a unique identifier of the set
+    /// on the trie.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self { paused_features: UnorderedSet::new(prefix) }
+    }
+
+    /// Returns `true` if the feature is paused, directly or with `ALL`.
+    pub fn internal_is_paused(&self, feature: &str) -> bool {
+        self.paused_features.contains(&feature.to_string())
+            || self.paused_features.contains(&PAUSE_ALL.to_string())
+    }
+
+    /// Panics if the feature is paused.
+    pub fn assert_not_paused(&self, feature: &str) {
+        if self.internal_is_paused(feature) {
+            env::panic(format!("The feature {} is paused", feature).as_bytes());
+        }
+    }
+}
+
+impl Pause for Pausable {
+    fn is_paused(&self, feature: String) -> bool {
+        self.internal_is_paused(&feature)
+    }
+
+    fn paused_features(&self) -> Vec<String> {
+        self.paused_features.to_vec()
+    }
+
+    fn pause_feature(&mut self, feature: String) -> bool {
+        assert_one_yocto();
+        let paused = self.paused_features.insert(&feature);
+        if paused {
+            env::log(format!("Paused the feature {}", feature).as_bytes());
+        }
+        paused
+    }
+
+    fn unpause_feature(&mut self, feature: String) -> bool {
+        assert_one_yocto();
+        let unpaused = self.paused_features.remove(&feature);
+        if unpaused {
+            env::log(format!("Unpaused the feature {}", feature).as_bytes());
+        }
+        unpaused
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, get_context, set_env};
+
+    fn setup_pausable() -> Pausable {
+        set_env(get_context(alice(), 1), vec![]);
+        Pausable::new(b"p".to_vec())
+    }
+
+    #[test]
+    fn test_pause_feature() {
+        let mut pausable = setup_pausable();
+        assert!(pausable.pause_feature("transfers".to_string()));
+        assert!(!pausable.pause_feature("transfers".to_string()));
+        assert!(pausable.is_paused("transfers".to_string()));
+        assert!(!pausable.is_paused("mint".to_string()));
+        pausable.assert_not_paused("mint");
+        assert_eq!(pausable.paused_features(), vec!["transfers".to_string()]);
+        assert!(pausable.unpause_feature("transfers".to_string()));
+        assert!(!pausable.unpause_feature("transfers".to_string()));
+        pausable.assert_not_paused("transfers");
+    }
+
+    #[test]
+    #[should_panic(expected = "The feature mint is paused")]
+    fn test_pause_all() {
+        let mut pausable = setup_pausable();
+        pausable.pause_feature(PAUSE_ALL.to_string());
+        assert!(pausable.is_paused("transfers".to_string()));
+        pausable.assert_not_paused("mint");
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_pause_without_yocto() {
+        let mut pausable = setup_pausable();
+        set_env(get_context(alice(), 0), vec![]);
+        pausable.pause_feature("transfers".to_string());
+    }
+}
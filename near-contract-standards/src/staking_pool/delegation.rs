@@ -0,0 +1,230 @@
+use crate::staking_pool::{ext_staking_pool, StakingPoolAccount, NUM_EPOCHS_TO_UNLOCK};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, AccountId, Balance, EpochHeight, Gas, Promise, PromiseResult};
+
+const GAS_FOR_STAKING_POOL_CALL: Gas = 75_000_000_000_000;
+const GAS_FOR_VIEW_CALL: Gas = 5_000_000_000_000;
+
+const NO_DEPOSIT: Balance = 0;
+
+/// The NEAR delegated to a staking pool, as known by the contract.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct Delegation {
+    pub staking_pool_id: AccountId,
+    pub staked_balance: Balance,
+    pub unstaked_balance: Balance,
+    /// The epoch of the last unstake. The unstaked balance is locked for `NUM_EPOCHS_TO_UNLOCK`
+    /// epochs after it.
+    pub unstaked_epoch_height: Option<EpochHeight>,
+}
+
+impl Delegation {
+    pub fn new(staking_pool_id: AccountId) -> Self {
+        if !env::is_valid_account_id(staking_pool_id.as_bytes()) {
+            env::panic(b"The staking pool account ID is invalid");
+        }
+        Self {
+            staking_pool_id,
+            staked_balance: 0,
+            unstaked_balance: 0,
+            unstaked_epoch_height: None,
+        }
+    }
+
+    /// The first epoch when the unstaked balance can be withdrawn.
+    pub fn unlock_epoch_height(&self) -> Option<EpochHeight> {
+        self.unstaked_epoch_height.map(|epoch_height| epoch_height + NUM_EPOCHS_TO_UNLOCK)
+    }
+
+    /// Whether the unstaked balance can be withdrawn in the current epoch.
+    pub fn is_unstaked_balance_available(&self) -> bool {
+        match self.unlock_epoch_height() {
+            Some(epoch_height) => env::epoch_height() >= epoch_height,
+            None => true,
+        }
+    }
+
+    /// Deposits `amount` of the contract balance to the pool and stakes it.
+    pub fn deposit_and_stake(&self, amount: Balance) -> Promise {
+        if amount == 0 {
+            env::panic(b"The amount should be positive");
+        }
+        ext_staking_pool::deposit_and_stake(
+            &self.staking_pool_id,
+            amount,
+            GAS_FOR_STAKING_POOL_CALL,
+        )
+    }
+
+    /// Unstakes `amount` of the staked balance.
+    pub fn unstake(&self, amount: Balance) -> Promise {
+        if amount == 0 || amount > self.staked_balance {
+            env::panic(b"The amount should be positive and at most the staked balance");
+        }
+        ext_staking_pool::unstake(
+            amount.into(),
+            &self.staking_pool_id,
+            NO_DEPOSIT,
+            GAS_FOR_STAKING_POOL_CALL,
+        )
+    }
+
+    /// Withdraws the whole unstaked balance from the pool. Panics if it is still locked.
+    pub fn withdraw_all(&self) -> Promise {
+        if !self.is_unstaked_balance_available() {
+            env::panic(
+                format!(
+                    "The unstaked balance is locked until the epoch {}",
+                    self.unlock_epoch_height().unwrap()
+                )
+                .as_bytes(),
+            );
+        }
+        ext_staking_pool::withdraw_all(&self.staking_pool_id, NO_DEPOSIT, GAS_FOR_STAKING_POOL_CALL)
+    }
+
+    /// Fetches the account of the contract from the pool, to be passed to `resolve_refresh`.
+    pub fn refresh(&self) -> Promise {
+        ext_staking_pool::get_account(
+            env::current_account_id(),
+            &self.staking_pool_id,
+            NO_DEPOSIT,
+            GAS_FOR_VIEW_CALL,
+        )
+    }
+
+    /// Resolves `deposit_and_stake`. Returns whether the call succeeded.
+    pub fn resolve_deposit_and_stake(&mut self, amount: Balance) -> bool {
+        let is_success = is_promise_success();
+        if is_success {
+            self.staked_balance += amount;
+        }
+        is_success
+    }
+
+    /// Resolves `unstake` and starts the unbonding period. Returns whether the call succeeded.
+    pub fn resolve_unstake(&mut self, amount: Balance) -> bool {
+        let is_success = is_promise_success();
+        if is_success {
+            self.staked_balance = self.staked_balance.saturating_sub(amount);
+            self.unstaked_balance += amount;
+            self.unstaked_epoch_height = Some(env::epoch_height());
+        }
+        is_success
+    }
+
+    /// Resolves `withdraw_all`. Returns the withdrawn amount, or `None` if the call failed.
+    pub fn resolve_withdraw_all(&mut self) -> Option<Balance> {
+        if !is_promise_success() {
+            return None;
+        }
+        let amount = self.unstaked_balance;
+        self.unstaked_balance = 0;
+        self.unstaked_epoch_height = None;
+        Some(amount)
+    }
+
+    /// Resolves `refresh` and replaces the balances with the ones reported by the pool, which
+    /// include the rewards. Returns whether the call succeeded.
+    pub fn resolve_refresh(&mut self) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                let account: StakingPoolAccount = match near_sdk::serde_json::from_slice(&value) {
+                    Ok(account) => account,
+                    Err(_) => env::panic(b"Cannot parse the account of the staking pool"),
+                };
+                let U128(staked_balance) = account.staked_balance;
+                let U128(unstaked_balance) = account.unstaked_balance;
+                self.staked_balance = staked_balance;
+                self.unstaked_balance = unstaked_balance;
+                true
+            }
+            PromiseResult::Failed => false,
+        }
+    }
+}
+
+fn is_promise_success() -> bool {
+    match env::promise_result(0) {
+        PromiseResult::NotReady => unreachable!(),
+        PromiseResult::Successful(_) => true,
+        PromiseResult::Failed => false,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{contract, get_context, set_env};
+
+    fn pool() -> AccountId {
+        "pool.near".to_string()
+    }
+
+    fn set_epoch(epoch_height: EpochHeight, promise_results: Vec<PromiseResult>) {
+        let mut context = get_context(contract(), 0);
+        context.epoch_height = epoch_height;
+        set_env(context, promise_results);
+    }
+
+    fn success() -> Vec<PromiseResult> {
+        vec![PromiseResult::Successful(vec![])]
+    }
+
+    #[test]
+    fn test_stake_unstake_withdraw() {
+        set_epoch(10, success());
+        let mut delegation = Delegation::new(pool());
+        assert!(delegation.resolve_deposit_and_stake(100));
+        assert!(delegation.resolve_unstake(40));
+        assert_eq!(delegation.staked_balance, 60);
+        assert_eq!(delegation.unstaked_balance, 40);
+        assert_eq!(delegation.unlock_epoch_height(), Some(10 + NUM_EPOCHS_TO_UNLOCK));
+        assert!(!delegation.is_unstaked_balance_available());
+        set_epoch(10 + NUM_EPOCHS_TO_UNLOCK, success());
+        assert!(delegation.is_unstaked_balance_available());
+        assert_eq!(delegation.resolve_withdraw_all(), Some(40));
+        assert_eq!(delegation.unstaked_balance, 0);
+        assert_eq!(delegation.unstaked_epoch_height, None);
+    }
+
+    #[test]
+    fn test_failed_calls() {
+        set_epoch(0, vec![PromiseResult::Failed]);
+        let mut delegation = Delegation::new(pool());
+        assert!(!delegation.resolve_deposit_and_stake(100));
+        assert_eq!(delegation.staked_balance, 0);
+        assert_eq!(delegation.resolve_withdraw_all(), None);
+    }
+
+    #[test]
+    fn test_refresh() {
+        let account = StakingPoolAccount {
+            account_id: contract(),
+            unstaked_balance: U128(5),
+            staked_balance: U128(110),
+            can_withdraw: true,
+        };
+        set_epoch(
+            0,
+            vec![PromiseResult::Successful(near_sdk::serde_json::to_vec(&account).unwrap())],
+        );
+        let mut delegation = Delegation::new(pool());
+        assert!(delegation.resolve_refresh());
+        assert_eq!(delegation.staked_balance, 110);
+        assert_eq!(delegation.unstaked_balance, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "The unstaked balance is locked until the epoch 4")]
+    fn test_withdraw_locked() {
+        set_epoch(0, success());
+        let mut delegation = Delegation::new(pool());
+        delegation.resolve_deposit_and_stake(100);
+        delegation.resolve_unstake(100);
+        delegation.withdraw_all();
+    }
+}
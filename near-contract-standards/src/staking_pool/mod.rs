@@ -0,0 +1,70 @@
+//! Client of the staking pool contracts.
+//!
+//! `ext_staking_pool` is the interface of the reference staking pool, and `Delegation` tracks the
+//! NEAR that a contract delegated to one pool. `Delegation` builds the calls to the pool, and its
+//! `resolve_*` methods update the balances from the results of the calls. The contract calls them
+//! from its own callbacks, for example:
+//!
+//! ```ignore
+//! pub fn stake(&mut self, amount: U128) -> Promise {
+//!     self.delegation.deposit_and_stake(amount.0).then(ext_self::on_stake(
+//!         amount,
+//!         &env::current_account_id(),
+//!         0,
+//!         GAS_FOR_CALLBACK,
+//!     ))
+//! }
+//!
+//! pub fn on_stake(&mut self, amount: U128) -> bool {
+//!     self.delegation.resolve_deposit_and_stake(amount.0)
+//! }
+//! ```
+
+mod delegation;
+
+pub use delegation::Delegation;
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{ext_contract, AccountId, EpochHeight};
+
+/// The number of epochs after which the unstaked balance can be withdrawn from the pool.
+pub const NUM_EPOCHS_TO_UNLOCK: EpochHeight = 4;
+
+/// The account of a delegator as returned by the staking pool.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPoolAccount {
+    pub account_id: AccountId,
+    pub unstaked_balance: U128,
+    pub staked_balance: U128,
+    pub can_withdraw: bool,
+}
+
+/// The interface of the staking pool contract.
+#[ext_contract(ext_staking_pool)]
+pub trait StakingPool {
+    fn deposit(&mut self);
+
+    fn deposit_and_stake(&mut self);
+
+    fn withdraw(&mut self, amount: U128);
+
+    fn withdraw_all(&mut self);
+
+    fn stake(&mut self, amount: U128);
+
+    fn unstake(&mut self, amount: U128);
+
+    fn unstake_all(&mut self);
+
+    fn get_account_staked_balance(&self, account_id: AccountId) -> U128;
+
+    fn get_account_unstaked_balance(&self, account_id: AccountId) -> U128;
+
+    fn get_account_total_balance(&self, account_id: AccountId) -> U128;
+
+    fn is_account_unstaked_balance_available(&self, account_id: AccountId) -> bool;
+
+    fn get_account(&self, account_id: AccountId) -> StakingPoolAccount;
+}
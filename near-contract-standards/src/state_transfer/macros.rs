@@ -0,0 +1,20 @@
+/// Exposes the state transfer method. Only the owner can call it, so the contract should
+/// implement the ownership with `impl_ownable!`.
+#[macro_export]
+macro_rules! impl_state_transfer {
+    ($contract: ident) => {
+        use $crate::state_transfer::StateTransfer;
+
+        #[near_sdk::near_bindgen]
+        impl StateTransfer for $contract {
+            #[only_owner]
+            fn import_state(&mut self, chunk: Vec<$crate::state_transfer::StateEntry>) {
+                $crate::state_transfer::import_entries(chunk);
+                // The wrapper writes the contract struct at the end of the call.
+                if let Some(state) = near_sdk::wrapper::state_read() {
+                    *self = state;
+                }
+            }
+        }
+    };
+}
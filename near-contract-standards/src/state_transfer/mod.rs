@@ -0,0 +1,68 @@
+//! Moves of the raw contract state between accounts.
+//!
+//! The owner reads the entries of the storage of the contract with the `view_state` RPC of the
+//! contract account, which needs no method of the contract, and imports them into the contract
+//! deployed to another account with `import_state`, in chunks that fit into the gas limit of a
+//! call. The same method restores a contract from a backup.
+//!
+//! The contract exposes the method with `impl_state_transfer!`. Only the owner of the contract can
+//! call it, so the contract also needs `impl_ownable!`. The new contract is initialized with the
+//! same owner, and the import of the `STATE` key then replaces its whole state, including the owner.
+
+mod macros;
+
+use near_sdk::env;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// A raw key/value pair of the storage.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateEntry {
+    pub key: Base64VecU8,
+    pub value: Base64VecU8,
+}
+
+pub trait StateTransfer {
+    /// Writes the entries into the storage, replacing the existing values. The contract struct is
+    /// then reloaded from the imported `STATE` entry, so it's not written back over it.
+    fn import_state(&mut self, chunk: Vec<StateEntry>);
+}
+
+/// Writes the entries into the storage.
+pub fn import_entries(chunk: Vec<StateEntry>) {
+    if chunk.is_empty() {
+        env::panic(b"The chunk should not be empty");
+    }
+    let count = chunk.len();
+    for StateEntry { key, value } in chunk {
+        env::storage_write(&key.0, &value.0);
+    }
+    env::log(format!("Imported {} entries", count).as_bytes());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, get_context, set_env};
+
+    #[test]
+    fn test_import() {
+        set_env(get_context(alice(), 0), vec![]);
+        let entries = vec![
+            StateEntry { key: b"STATE".to_vec().into(), value: b"state".to_vec().into() },
+            StateEntry { key: b"a\x00".to_vec().into(), value: b"value".to_vec().into() },
+        ];
+        import_entries(entries);
+        assert_eq!(env::storage_read(b"STATE"), Some(b"state".to_vec()));
+        assert_eq!(env::storage_read(b"a\x00"), Some(b"value".to_vec()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The chunk should not be empty")]
+    fn test_import_empty_chunk() {
+        set_env(get_context(alice(), 0), vec![]);
+        import_entries(vec![]);
+    }
+}
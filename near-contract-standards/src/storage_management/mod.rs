@@ -0,0 +1,53 @@
+//! Storage management standard (NEP-145).
+//!
+//! Accounts pay for the storage they occupy in the contract by depositing NEAR with
+//! `storage_deposit`. The deposit can be withdrawn when it is no longer needed, and
+//! `storage_unregister` removes the account and refunds the whole deposit.
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Storage balance of an account.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    /// The total amount deposited for the storage of the account.
+    pub total: U128,
+    /// The part of `total` that is not used for the storage and can be withdrawn.
+    pub available: U128,
+}
+
+/// The minimum deposit required to register an account, and the maximum deposit the contract
+/// accepts for one account. `max` is `None` if the contract accepts any amount.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+pub trait StorageManagement {
+    /// Deposits the attached amount for the storage of `account_id`, or of the predecessor if it
+    /// is not given. If `registration_only` is `true` the contract only takes the minimum deposit
+    /// needed to register the account and refunds the rest.
+    // `#[payable]` is required on the implementation.
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance;
+
+    /// Withdraws `amount` of the available storage balance of the predecessor, or all of it if
+    /// `amount` is not given. Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance;
+
+    /// Unregisters the predecessor and refunds its storage deposit. Returns `true` if the account
+    /// was registered. Unless `force` is `true`, panics if the account still holds any assets.
+    /// Requires exactly 1 yoctoNEAR to be attached.
+    // `#[payable]` is required on the implementation.
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool;
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
+
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance>;
+}
@@ -0,0 +1,60 @@
+//! Helpers for the unit tests of the components.
+use near_sdk::{env, AccountId, Balance, MockedBlockchain, PromiseResult, VMContext};
+
+pub fn alice() -> AccountId {
+    "alice.near".to_string()
+}
+
+pub fn bob() -> AccountId {
+    "bob.near".to_string()
+}
+
+/// The account of the contract under test.
+pub fn contract() -> AccountId {
+    "contract.near".to_string()
+}
+
+/// An ED25519 key of the signer: the curve byte and the 32 bytes of the key.
+pub fn signer_pk() -> Vec<u8> {
+    let mut pk = vec![0];
+    pk.extend_from_slice(&[1; 32]);
+    pk
+}
+
+pub fn get_context(predecessor_account_id: AccountId, attached_deposit: Balance) -> VMContext {
+    VMContext {
+        current_account_id: contract(),
+        signer_account_id: predecessor_account_id.clone(),
+        signer_account_pk: signer_pk(),
+        predecessor_account_id,
+        input: vec![],
+        block_index: 0,
+        block_timestamp: 0,
+        account_balance: 1_000_000_000_000_000_000_000_000_000u128,
+        account_locked_balance: 0,
+        storage_usage: 10u64.pow(6),
+        attached_deposit,
+        prepaid_gas: 10u64.pow(18),
+        random_seed: vec![0, 1, 2],
+        is_view: false,
+        output_data_receivers: vec![],
+        epoch_height: 0,
+    }
+}
+
+/// Replaces the blockchain with a new one that uses the given context, keeping the storage of the
+/// previous blockchain.
+pub fn set_env(context: VMContext, promise_results: Vec<PromiseResult>) {
+    let storage = match env::take_blockchain_interface() {
+        Some(mut bi) => bi.as_mut_mocked_blockchain().unwrap().take_storage(),
+        None => Default::default(),
+    };
+    env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+        context,
+        Default::default(),
+        Default::default(),
+        promise_results,
+        storage,
+        Default::default(),
+    )));
+}
@@ -0,0 +1,64 @@
+/// Exposes the upgrade methods of the `Upgradable` stored in the field `$upgradable`. Only the
+/// owner can call them, so the contract should implement the ownership with `impl_ownable!`:
+///
+/// ```ignore
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {
+///     owner: Owner,
+///     upgradable: Upgradable,
+/// }
+///
+/// near_contract_standards::impl_ownable!(Contract, owner);
+/// near_contract_standards::impl_upgradable!(Contract, upgradable);
+/// ```
+///
+/// `stage_code` and `upgrade` take the code as the raw input of the call, e.g. with
+/// `near call <contract> upgrade --base64 $(base64 -w0 contract.wasm) --depositYocto 1`.
+#[macro_export]
+macro_rules! impl_upgradable {
+    ($contract: ident, $upgradable: ident) => {
+        use $crate::upgrade::{Upgrade, UpgradeResolver};
+
+        #[near_sdk::near_bindgen]
+        impl Upgrade for $contract {
+            #[only_owner]
+            #[payable]
+            fn stage_code(&mut self) {
+                self.$upgradable.stage_code()
+            }
+
+            fn staged_code_hash(&self) -> Option<String> {
+                self.$upgradable.staged_code_hash()
+            }
+
+            #[only_owner]
+            #[payable]
+            fn deploy_code(&mut self, migrate_method_name: Option<String>) -> near_sdk::Promise {
+                self.$upgradable.deploy_code(migrate_method_name)
+            }
+
+            #[only_owner]
+            #[payable]
+            fn upgrade(&mut self) -> near_sdk::Promise {
+                self.$upgradable.upgrade()
+            }
+
+            fn code_hash(&self) -> Option<String> {
+                self.$upgradable.code_hash()
+            }
+
+            fn code_hash_matches(&self, expected: String) -> bool {
+                self.$upgradable.code_hash_matches(expected)
+            }
+        }
+
+        #[near_sdk::near_bindgen]
+        impl UpgradeResolver for $contract {
+            fn upgrade_resolve_deploy(&mut self, code_hash: String) -> bool {
+                near_sdk::wrapper::assert_private("upgrade_resolve_deploy");
+                self.$upgradable.upgrade_resolve_deploy(code_hash)
+            }
+        }
+    };
+}
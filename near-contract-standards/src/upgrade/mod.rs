@@ -0,0 +1,56 @@
+//! Upgrades of the contract code by the contract itself.
+//!
+//! `Upgradable` deploys new code to the account of the contract, and implements the `Upgrade`
+//! trait. The code is passed as the raw input of the call instead of a JSON argument, so that
+//! hundreds of kilobytes of code are not encoded, copied and parsed. The code is either deployed
+//! right away with `upgrade`, or staged with `stage_code` and deployed later with `deploy_code`, so
+//! that the staged code can be checked by its hash before it's deployed.
+//!
+//! The deployment is batched with a call of the migration method of the new code, `migrate` by
+//! default, so that a failed migration reverts the deployment. A contract embeds `Upgradable` into
+//! its state and exposes the methods with `impl_upgradable!`. Only the owner of the contract can
+//! upgrade it, so the contract also needs `impl_ownable!`.
+//!
+//! A contract can't read the hash of its own code, so `Upgradable` records the hash of the code it
+//! deploys once the deployment and the migration succeed. `code_hash_matches` lets anyone check
+//! that the deployed code is the one built from the published source, e.g. with the toolchain in
+//! the contract source metadata. The hash is unknown until the first upgrade through the contract,
+//! as the code deployed by the account itself doesn't go through `Upgradable`. The new code has to
+//! expose `upgrade_resolve_deploy` with `Upgradable` under the same prefix to record its hash.
+
+mod macros;
+mod upgradable_impl;
+
+pub use upgradable_impl::{Upgradable, MIGRATE_METHOD_NAME};
+
+use near_sdk::Promise;
+
+pub trait Upgrade {
+    /// Stages the code given as the raw input of the call, replacing the previously staged code.
+    /// Requires 1 yoctoNEAR attached.
+    fn stage_code(&mut self);
+
+    /// Returns the base58 SHA-256 hash of the staged code.
+    fn staged_code_hash(&self) -> Option<String>;
+
+    /// Deploys the staged code and calls its migration method, `migrate` unless
+    /// `migrate_method_name` is given, in the same batch. The staged code is removed once the
+    /// batch succeeds. Requires 1 yoctoNEAR attached.
+    fn deploy_code(&mut self, migrate_method_name: Option<String>) -> Promise;
+
+    /// Deploys the code given as the raw input of the call and calls its `migrate` method in the
+    /// same batch. Requires 1 yoctoNEAR attached.
+    fn upgrade(&mut self) -> Promise;
+
+    /// Returns the base58 SHA-256 hash of the code deployed by `deploy_code` or `upgrade`.
+    fn code_hash(&self) -> Option<String>;
+
+    /// Returns `true` if the deployed code has the base58 SHA-256 hash `expected`.
+    fn code_hash_matches(&self, expected: String) -> bool;
+}
+
+pub trait UpgradeResolver {
+    /// Private callback of the deployment. Records the hash of the deployed code if the deployment
+    /// and the migration succeeded. Returns `true` if they succeeded.
+    fn upgrade_resolve_deploy(&mut self, code_hash: String) -> bool;
+}
@@ -0,0 +1,212 @@
+use crate::upgrade::{Upgrade, UpgradeResolver};
+use crate::utils::assert_one_yocto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, ext_contract, Balance, Gas, Promise, PromiseResult};
+
+/// The method of the new code that is called after the deployment.
+pub const MIGRATE_METHOD_NAME: &str = "migrate";
+
+const GAS_FOR_DEPLOY_CODE: Gas = 20_000_000_000_000;
+const GAS_FOR_RESOLVE_DEPLOY: Gas = 10_000_000_000_000;
+const NO_DEPOSIT: Balance = 0;
+
+#[ext_contract(ext_self)]
+trait UpgradeResolver {
+    fn upgrade_resolve_deploy(&mut self, code_hash: String) -> bool;
+}
+
+/// Keeps the staged code and the hash of the deployed code under their own storage keys, outside
+/// of the contract state.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Upgradable {
+    /// The storage key of the staged code.
+    staged_code_key: Vec<u8>,
+    /// The storage key of the hash of the code deployed by `deploy_code` or `upgrade`.
+    code_hash_key: Vec<u8>,
+}
+
+impl Upgradable {
+    /// Use `prefix` as a unique identifier of the staged code and the code hash on the trie.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            staged_code_key: [prefix.as_slice(), b"c"].concat(),
+            code_hash_key: [prefix.as_slice(), b"h"].concat(),
+        }
+    }
+
+    /// Returns the staged code.
+    pub fn staged_code(&self) -> Option<Vec<u8>> {
+        env::storage_read(&self.staged_code_key)
+    }
+
+    /// Deploys the code to the current account and calls `migrate_method_name` in the same batch,
+    /// with all the gas that this call and the resolution of the deployment don't need. The hash
+    /// of the code is recorded by `upgrade_resolve_deploy` once the batch succeeds.
+    pub fn internal_deploy(&self, code: Vec<u8>, migrate_method_name: &str) -> Promise {
+        if env::prepaid_gas() <= GAS_FOR_DEPLOY_CODE + GAS_FOR_RESOLVE_DEPLOY + env::used_gas() {
+            env::panic(b"More gas is required");
+        }
+        let code_hash = code_hash(&code);
+        env::log(format!("Deploying the code {}", code_hash).as_bytes());
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                migrate_method_name.as_bytes().to_vec(),
+                vec![],
+                NO_DEPOSIT,
+                env::prepaid_gas() - env::used_gas() - GAS_FOR_DEPLOY_CODE - GAS_FOR_RESOLVE_DEPLOY,
+            )
+            .then(ext_self::upgrade_resolve_deploy(
+                code_hash,
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_RESOLVE_DEPLOY,
+            ))
+    }
+}
+
+/// Reads the code from the raw input of the call.
+fn input_code() -> Vec<u8> {
+    match env::input() {
+        Some(code) if !code.is_empty() => code,
+        _ => env::panic(b"The code should be passed as the input"),
+    }
+}
+
+fn code_hash(code: &[u8]) -> String {
+    env::sha256_hash(code).to_string()
+}
+
+impl Upgrade for Upgradable {
+    fn stage_code(&mut self) {
+        assert_one_yocto();
+        let code = input_code();
+        env::log(format!("Staged the code {}", code_hash(&code)).as_bytes());
+        env::storage_write(&self.staged_code_key, &code);
+    }
+
+    fn staged_code_hash(&self) -> Option<String> {
+        self.staged_code().map(|code| code_hash(&code))
+    }
+
+    fn deploy_code(&mut self, migrate_method_name: Option<String>) -> Promise {
+        assert_one_yocto();
+        let code = match self.staged_code() {
+            Some(code) => code,
+            None => env::panic(b"No code is staged"),
+        };
+        let migrate_method_name =
+            migrate_method_name.unwrap_or_else(|| MIGRATE_METHOD_NAME.to_string());
+        self.internal_deploy(code, &migrate_method_name)
+    }
+
+    fn upgrade(&mut self) -> Promise {
+        assert_one_yocto();
+        self.internal_deploy(input_code(), MIGRATE_METHOD_NAME)
+    }
+
+    fn code_hash(&self) -> Option<String> {
+        env::storage_read(&self.code_hash_key).map(|hash| String::from_utf8(hash).unwrap())
+    }
+
+    fn code_hash_matches(&self, expected: String) -> bool {
+        self.code_hash().as_ref() == Some(&expected)
+    }
+}
+
+impl UpgradeResolver for Upgradable {
+    fn upgrade_resolve_deploy(&mut self, code_hash: String) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                env::storage_write(&self.code_hash_key, code_hash.as_bytes());
+                // The staged code is kept until it's deployed, so a failed deployment can be
+                // retried with `deploy_code`.
+                if self.staged_code_hash().as_ref() == Some(&code_hash) {
+                    env::storage_remove(&self.staged_code_key);
+                }
+                true
+            }
+            PromiseResult::Failed => {
+                env::log(format!("The deployment of the code {} failed", code_hash).as_bytes());
+                false
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, contract, get_context, set_env};
+
+    const CODE: &[u8] = b"\0asm new code";
+
+    fn set_input(input: &[u8], attached_deposit: u128) {
+        let mut context = get_context(alice(), attached_deposit);
+        context.input = input.to_vec();
+        set_env(context, vec![]);
+    }
+
+    fn set_deploy_result(result: PromiseResult) {
+        set_env(get_context(contract(), 0), vec![result]);
+    }
+
+    #[test]
+    fn test_stage_and_deploy() {
+        set_input(CODE, 1);
+        let mut upgradable = Upgradable::new(b"u".to_vec());
+        assert_eq!(upgradable.staged_code_hash(), None);
+        upgradable.stage_code();
+        assert_eq!(upgradable.staged_code(), Some(CODE.to_vec()));
+        assert_eq!(upgradable.staged_code_hash(), Some(code_hash(CODE)));
+        set_input(b"", 1);
+        upgradable.deploy_code(None);
+        assert_eq!(upgradable.staged_code(), Some(CODE.to_vec()));
+        set_deploy_result(PromiseResult::Failed);
+        assert!(!upgradable.upgrade_resolve_deploy(code_hash(CODE)));
+        assert_eq!(upgradable.staged_code(), Some(CODE.to_vec()));
+        set_deploy_result(PromiseResult::Successful(vec![]));
+        assert!(upgradable.upgrade_resolve_deploy(code_hash(CODE)));
+        assert_eq!(upgradable.staged_code(), None);
+    }
+
+    #[test]
+    fn test_upgrade() {
+        set_input(CODE, 1);
+        let mut upgradable = Upgradable::new(b"u".to_vec());
+        upgradable.upgrade();
+        assert_eq!(upgradable.staged_code(), None);
+    }
+
+    #[test]
+    fn test_code_hash() {
+        set_input(CODE, 1);
+        let mut upgradable = Upgradable::new(b"u".to_vec());
+        upgradable.upgrade();
+        assert_eq!(upgradable.code_hash(), None);
+        set_deploy_result(PromiseResult::Failed);
+        assert!(!upgradable.upgrade_resolve_deploy(code_hash(CODE)));
+        assert_eq!(upgradable.code_hash(), None);
+        set_deploy_result(PromiseResult::Successful(vec![]));
+        assert!(upgradable.upgrade_resolve_deploy(code_hash(CODE)));
+        assert_eq!(upgradable.code_hash(), Some(code_hash(CODE)));
+        assert!(upgradable.code_hash_matches(code_hash(CODE)));
+        assert!(!upgradable.code_hash_matches(code_hash(b"\0asm old code")));
+    }
+
+    #[test]
+    #[should_panic(expected = "No code is staged")]
+    fn test_deploy_without_staged_code() {
+        set_input(b"", 1);
+        Upgradable::new(b"u".to_vec()).deploy_code(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The code should be passed as the input")]
+    fn test_stage_without_code() {
+        set_input(b"", 1);
+        Upgradable::new(b"u".to_vec()).stage_code();
+    }
+}
@@ -0,0 +1,26 @@
+use near_sdk::{diagnostics, env, ContractError, Promise, StorageUsage};
+
+pub(crate) use near_sdk::assert_one_yocto;
+
+/// Panics unless at least 1 yoctoNEAR is attached.
+pub(crate) fn assert_at_least_one_yocto() {
+    near_sdk::assert_at_least_attached(1);
+}
+
+/// Charges the predecessor for `storage_used` bytes from the attached deposit and refunds the rest.
+/// Panics if the deposit doesn't cover the storage.
+pub(crate) fn refund_deposit(storage_used: StorageUsage) {
+    let required_cost = storage_used.cost();
+    let attached_deposit = env::attached_deposit();
+    if required_cost > attached_deposit {
+        env::panic_err_with(
+            ContractError::INVALID_DEPOSIT,
+            &format!("Must attach {} yoctoNEAR to cover the storage", required_cost),
+            diagnostics::call_context,
+        );
+    }
+    let refund = attached_deposit - required_cost;
+    if refund > 1 {
+        Promise::new(env::predecessor_account_id()).transfer(refund);
+    }
+}
@@ -0,0 +1,16 @@
+//! Vesting and lockup schedules.
+//!
+//! `VestingSchedule` describes when an amount is released: nothing before the cliff, then linearly
+//! from the start until the end. `Vesting` applies a schedule to the amount of a beneficiary,
+//! tracks how much was already claimed, and can be terminated, which stops the release at the
+//! termination time. `Vestings` keeps the vestings of many beneficiaries, and pays out what they
+//! claim in NEAR.
+//!
+//! The contract decides who creates and terminates the vestings, e.g. with `#[only_owner]`
+//! methods that call `Vestings::internal_add` and `Vestings::internal_terminate`.
+
+mod schedule;
+mod vestings;
+
+pub use schedule::{Vesting, VestingSchedule};
+pub use vestings::Vestings;
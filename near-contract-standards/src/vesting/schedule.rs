@@ -0,0 +1,136 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::math::{Fraction, Rounding};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, Balance};
+
+/// The release of an amount over time. The timestamps are in nanoseconds, like
+/// `env::block_timestamp`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingSchedule {
+    /// The release starts at this time.
+    pub start_timestamp: U64,
+    /// Nothing is released before this time. At this time the amount that was released linearly
+    /// since the start becomes available at once.
+    pub cliff_timestamp: U64,
+    /// The whole amount is released at this time.
+    pub end_timestamp: U64,
+}
+
+impl VestingSchedule {
+    /// Panics unless `start <= cliff <= end`.
+    pub fn assert_valid(&self) {
+        if self.start_timestamp.0 > self.cliff_timestamp.0
+            || self.cliff_timestamp.0 > self.end_timestamp.0
+        {
+            env::panic(b"The schedule should satisfy start <= cliff <= end");
+        }
+    }
+
+    /// Returns the part of `total_amount` that is released at `timestamp`.
+    pub fn unlocked_amount(&self, total_amount: Balance, timestamp: u64) -> Balance {
+        if timestamp < self.cliff_timestamp.0 {
+            return 0;
+        }
+        if timestamp >= self.end_timestamp.0 {
+            return total_amount;
+        }
+        // `start < end` here, since `start <= cliff <= timestamp < end`.
+        let duration = self.end_timestamp.0 - self.start_timestamp.0;
+        let elapsed = timestamp - self.start_timestamp.0;
+        Fraction::new(elapsed.into(), duration.into()).mul_int(total_amount, Rounding::Down)
+    }
+}
+
+/// The vesting of an amount to a beneficiary.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Vesting {
+    pub schedule: VestingSchedule,
+    /// The amount that is released by the end of the schedule.
+    pub total_amount: U128,
+    /// The amount that the beneficiary already claimed.
+    pub claimed_amount: U128,
+    /// The time when the vesting was terminated, after which nothing more is released.
+    pub termination_timestamp: Option<U64>,
+}
+
+impl Vesting {
+    pub fn new(schedule: VestingSchedule, total_amount: Balance) -> Self {
+        schedule.assert_valid();
+        Self {
+            schedule,
+            total_amount: total_amount.into(),
+            claimed_amount: 0.into(),
+            termination_timestamp: None,
+        }
+    }
+
+    /// Returns the amount that is released at `timestamp`, including the claimed amount.
+    pub fn unlocked_amount(&self, timestamp: u64) -> Balance {
+        let timestamp = match &self.termination_timestamp {
+            Some(termination_timestamp) => timestamp.min(termination_timestamp.0),
+            None => timestamp,
+        };
+        self.schedule.unlocked_amount(self.total_amount.0, timestamp)
+    }
+
+    /// Returns the amount that the beneficiary can claim now.
+    pub fn claimable_amount(&self) -> Balance {
+        self.unlocked_amount(env::block_timestamp()) - self.claimed_amount.0
+    }
+
+    /// Stops the release at the current time. Returns the amount that will never be released.
+    /// Panics if the vesting is already terminated.
+    pub fn terminate(&mut self) -> Balance {
+        if self.termination_timestamp.is_some() {
+            env::panic(b"The vesting is already terminated");
+        }
+        let timestamp = env::block_timestamp();
+        let unvested_amount = self.total_amount.0 - self.unlocked_amount(timestamp);
+        self.termination_timestamp = Some(timestamp.into());
+        unvested_amount
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alice, get_context, set_env};
+
+    fn schedule(start: u64, cliff: u64, end: u64) -> VestingSchedule {
+        VestingSchedule {
+            start_timestamp: start.into(),
+            cliff_timestamp: cliff.into(),
+            end_timestamp: end.into(),
+        }
+    }
+
+    #[test]
+    fn test_unlocked_amount() {
+        let schedule = schedule(100, 150, 200);
+        assert_eq!(schedule.unlocked_amount(1000, 0), 0);
+        assert_eq!(schedule.unlocked_amount(1000, 149), 0);
+        assert_eq!(schedule.unlocked_amount(1000, 150), 500);
+        assert_eq!(schedule.unlocked_amount(1000, 175), 750);
+        assert_eq!(schedule.unlocked_amount(1000, 200), 1000);
+        assert_eq!(schedule.unlocked_amount(1000, 300), 1000);
+        assert_eq!(schedule.unlocked_amount(u128::MAX, 150), u128::MAX / 2);
+    }
+
+    #[test]
+    fn test_instant_schedule() {
+        let schedule = schedule(100, 100, 100);
+        assert_eq!(schedule.unlocked_amount(1000, 99), 0);
+        assert_eq!(schedule.unlocked_amount(1000, 100), 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "The schedule should satisfy start <= cliff <= end")]
+    fn test_invalid_schedule() {
+        set_env(get_context(alice(), 0), vec![]);
+        Vesting::new(schedule(100, 250, 200), 1000);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::vesting::Vesting;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, AccountId, Balance, Promise};
+
+/// The vestings of the beneficiaries, paid out in NEAR. The contract should hold the NEAR for the
+/// amounts that are not claimed yet.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Vestings {
+    pub vestings: LookupMap<AccountId, Vesting>,
+}
+
+impl Vestings {
+    /// Use `prefix` as a unique identifier of the vestings on the trie.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self { vestings: LookupMap::new(prefix) }
+    }
+
+    /// Returns the vesting of the beneficiary.
+    pub fn get_vesting(&self, account_id: &AccountId) -> Option<Vesting> {
+        self.vestings.get(account_id)
+    }
+
+    fn internal_vesting(&self, account_id: &AccountId) -> Vesting {
+        match self.vestings.get(account_id) {
+            Some(vesting) => vesting,
+            None => env::panic(format!("The account {} has no vesting", account_id).as_bytes()),
+        }
+    }
+
+    /// Adds the vesting of the beneficiary. Panics if the beneficiary already has one.
+    pub fn internal_add(&mut self, account_id: ValidAccountId, vesting: Vesting) {
+        if self.vestings.insert(account_id.as_ref(), &vesting).is_some() {
+            env::panic(
+                format!("The account {} already has a vesting", account_id.as_ref()).as_bytes(),
+            );
+        }
+        env::log(
+            format!("Added the vesting of {} to {}", vesting.total_amount.0, account_id.as_ref())
+                .as_bytes(),
+        );
+    }
+
+    /// Terminates the vesting of the beneficiary. Returns the amount that will never be released,
+    /// which the contract can use again.
+    pub fn internal_terminate(&mut self, account_id: &AccountId) -> Balance {
+        let mut vesting = self.internal_vesting(account_id);
+        let unvested_amount = vesting.terminate();
+        self.vestings.insert(account_id, &vesting);
+        env::log(
+            format!("Terminated the vesting of {}, unvested {}", account_id, unvested_amount)
+                .as_bytes(),
+        );
+        unvested_amount
+    }
+
+    /// Transfers the claimable amount to the predecessor. The vesting is removed once it's fully
+    /// claimed.
+    pub fn claim(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let mut vesting = self.internal_vesting(&account_id);
+        let amount = vesting.claimable_amount();
+        if amount == 0 {
+            env::panic(b"Nothing to claim");
+        }
+        vesting.claimed_amount = (vesting.claimed_amount.0 + amount).into();
+        if vesting.claimed_amount.0 == vesting.unlocked_amount(u64::MAX) {
+            self.vestings.remove(&account_id);
+        } else {
+            self.vestings.insert(&account_id, &vesting);
+        }
+        env::log(format!("{} claimed {}", account_id, amount).as_bytes());
+        Promise::new(account_id).transfer(amount)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::test_utils::{alice, bob, get_context, set_env};
+    use crate::vesting::VestingSchedule;
+
+    fn set_time(account_id: AccountId, timestamp: u64) {
+        let mut context = get_context(account_id, 0);
+        context.block_timestamp = timestamp;
+        set_env(context, vec![]);
+    }
+
+    fn setup_vestings() -> Vestings {
+        set_time(alice(), 0);
+        let mut vestings = Vestings::new(b"v".to_vec());
+        let schedule = VestingSchedule {
+            start_timestamp: 100.into(),
+            cliff_timestamp: 150.into(),
+            end_timestamp: 200.into(),
+        };
+        vestings.internal_add(bob().try_into().unwrap(), Vesting::new(schedule, 1000));
+        vestings
+    }
+
+    #[test]
+    fn test_claim() {
+        let mut vestings = setup_vestings();
+        set_time(bob(), 175);
+        vestings.claim();
+        assert_eq!(vestings.get_vesting(&bob()).unwrap().claimed_amount.0, 750);
+        set_time(bob(), 250);
+        vestings.claim();
+        assert_eq!(vestings.get_vesting(&bob()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to claim")]
+    fn test_claim_before_cliff() {
+        let mut vestings = setup_vestings();
+        set_time(bob(), 149);
+        vestings.claim();
+    }
+
+    #[test]
+    fn test_terminate() {
+        let mut vestings = setup_vestings();
+        set_time(alice(), 150);
+        assert_eq!(vestings.internal_terminate(&bob()), 500);
+        set_time(bob(), 300);
+        vestings.claim();
+        assert_eq!(vestings.get_vesting(&bob()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob.near already has a vesting")]
+    fn test_add_twice() {
+        let mut vestings = setup_vestings();
+        let vesting = vestings.get_vesting(&bob()).unwrap();
+        vestings.internal_add(bob().try_into().unwrap(), vesting);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::info_extractor::{AsyncMethodInfo, AsyncVariable};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_quote, Error, FnArg, ImplItem, ImplItemMethod, ItemImpl};
+
+impl AsyncMethodInfo {
+    /// The methods that replace the `async` method: the method itself runs the first segment, and
+    /// each other segment runs in a private callback of the promise awaited before it. The contract
+    /// state is written at the end of each segment, and the variables that the later segments use
+    /// are passed to the callbacks as JSON. They are serialized after the statements of the
+    /// segment, before the promise is created, so the callbacks get the values that the statements
+    /// assigned.
+    pub fn methods(&self) -> Vec<ImplItemMethod> {
+        let last = self.segments.len() - 1;
+        self.segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let mut method = self.method.clone();
+                method.sig.asyncness = None;
+                method.sig.ident = self.segment_ident(index);
+                if index != last {
+                    method.sig.output = parse_quote! { -> near_sdk::Promise };
+                }
+                // The result serializer applies to the result of the last segment, and the other
+                // attributes, like `#[payable]`, to the method itself.
+                if index == 0 {
+                    if index != last {
+                        method.attrs.retain(|attr| !attr.path.is_ident("result_serializer"));
+                    }
+                } else {
+                    method.vis = parse_quote! { pub };
+                    method
+                        .attrs
+                        .retain(|attr| index == last && attr.path.is_ident("result_serializer"));
+                    method.attrs.push(parse_quote! { #[doc(hidden)] });
+                    method.sig.inputs = method
+                        .sig
+                        .inputs
+                        .into_iter()
+                        .filter(|arg| matches!(arg, FnArg::Receiver(_)))
+                        .collect();
+                    for AsyncVariable { pat, ty, .. } in self.variables_used_from(index) {
+                        method.sig.inputs.push(parse_quote! { #pat: #ty });
+                    }
+                    if let Some((_, ty)) = &segment.binding {
+                        method.sig.inputs.push(parse_quote! { #[callback] async_result: #ty });
+                    }
+                }
+
+                let callback_checks = if segment.is_continuation {
+                    let name = method.sig.ident.to_string();
+                    let result_check = if segment.binding.is_none() {
+                        quote! { near_sdk::wrapper::assert_callback_success(); }
+                    } else {
+                        quote! {}
+                    };
+                    quote! {
+                        near_sdk::wrapper::assert_private(#name);
+                        #result_check
+                    }
+                } else {
+                    quote! {}
+                };
+                let binding = match &segment.binding {
+                    Some((pat, ty)) => quote! { let #pat: #ty = async_result; },
+                    None => quote! {},
+                };
+                let stmts = &segment.stmts;
+                let block = match &segment.promise {
+                    Some(promise) => {
+                        let next = self.segment_ident(index + 1).to_string();
+                        let args: Vec<_> = self
+                            .variables_used_from(index + 1)
+                            .into_iter()
+                            .map(|variable| &variable.ident)
+                            .collect();
+                        let continuation_args = if args.is_empty() {
+                            quote! { let continuation_args = Vec::new(); }
+                        } else {
+                            let types = self
+                                .variables_used_from(index + 1)
+                                .into_iter()
+                                .map(|variable| &variable.ty);
+                            quote! {
+                                #[derive(near_sdk::serde::Serialize)]
+                                #[serde(crate = "near_sdk::serde")]
+                                struct ContinuationArgs<'a> {
+                                    #(#args: &'a #types,)*
+                                }
+                                let continuation_args = near_sdk::wrapper::continuation_args(
+                                    &ContinuationArgs { #(#args: &#args,)* }
+                                );
+                            }
+                        };
+                        let gas = match self.continuation_gas {
+                            Some(gas) => quote! { #gas },
+                            None => quote! { near_sdk::wrapper::CONTINUATION_GAS },
+                        };
+                        quote! {{
+                            #callback_checks
+                            #binding
+                            #(#stmts)*
+                            #continuation_args
+                            near_sdk::wrapper::then_continue(#promise, #next, continuation_args, #gas)
+                        }}
+                    }
+                    None => quote! {{
+                        #callback_checks
+                        #binding
+                        #(#stmts)*
+                    }},
+                };
+                method.block = parse_quote! { #block };
+                method
+            })
+            .collect()
+    }
+}
+
+/// Replaces the `async` methods of the impl section with the methods of their segments, before
+/// the impl section is processed like any other. The experimental `async-methods` feature of
+/// `near-sdk` enables it.
+pub fn desugar_async_methods(item_impl: &mut ItemImpl) -> syn::Result<()> {
+    if item_impl.trait_.is_some() {
+        return Ok(());
+    }
+    let mut items = vec![];
+    for item in item_impl.items.drain(..) {
+        match item {
+            ImplItem::Method(method) if method.sig.asyncness.is_some() => {
+                if let Some(attr) = method.attrs.iter().find(|attr| attr.path.is_ident("init")) {
+                    return Err(Error::new(attr.span(), "Initializers cannot be async."));
+                }
+                let info = AsyncMethodInfo::new(&method)?;
+                items.extend(info.methods().into_iter().map(ImplItem::Method));
+            }
+            item => items.push(item),
+        }
+    }
+    item_impl.items = items;
+    Ok(())
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{parse_quote, ItemImpl};
+    use crate::desugar_async_methods;
+
+    #[test]
+    fn async_method() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Exchange {
+                /// Swaps the tokens.
+                #[payable]
+                #[result_serializer(borsh)]
+                pub async fn swap(&mut self, token_id: String, amount: U128, memo: String) -> U128 {
+                    self.swaps += 1;
+                    let balance: U128 = ext_token::balance_of(token_id.clone()).await;
+                    ext_token::transfer(amount, &token_id).await;
+                    log!("{}", memo);
+                    balance
+                }
+            }
+        };
+        desugar_async_methods(&mut input).unwrap();
+        let expected = quote! {
+            impl Exchange {
+                /// Swaps the tokens.
+                #[payable]
+                pub fn swap(&mut self, token_id: String, amount: U128, memo: String) -> near_sdk::Promise {
+                    self.swaps += 1;
+                    #[derive(near_sdk::serde::Serialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                    struct ContinuationArgs<'a> {
+                        token_id: &'a String,
+                        amount: &'a U128,
+                        memo: &'a String,
+                    }
+                    let continuation_args = near_sdk::wrapper::continuation_args(
+                        &ContinuationArgs { token_id: &token_id, amount: &amount, memo: &memo, }
+                    );
+                    near_sdk::wrapper::then_continue(
+                        ext_token::balance_of(token_id.clone()),
+                        "__swap_then_1",
+                        continuation_args,
+                        near_sdk::wrapper::CONTINUATION_GAS
+                    )
+                }
+                #[doc(hidden)]
+                pub fn __swap_then_1(&mut self, token_id: String, amount: U128, memo: String, #[callback] async_result: U128) -> near_sdk::Promise {
+                    near_sdk::wrapper::assert_private("__swap_then_1");
+                    let balance: U128 = async_result;
+                    #[derive(near_sdk::serde::Serialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                    struct ContinuationArgs<'a> {
+                        memo: &'a String,
+                        balance: &'a U128,
+                    }
+                    let continuation_args = near_sdk::wrapper::continuation_args(
+                        &ContinuationArgs { memo: &memo, balance: &balance, }
+                    );
+                    near_sdk::wrapper::then_continue(
+                        ext_token::transfer(amount, &token_id),
+                        "__swap_then_2",
+                        continuation_args,
+                        near_sdk::wrapper::CONTINUATION_GAS
+                    )
+                }
+                #[result_serializer(borsh)]
+                #[doc(hidden)]
+                pub fn __swap_then_2(&mut self, memo: String, balance: U128) -> U128 {
+                    near_sdk::wrapper::assert_private("__swap_then_2");
+                    near_sdk::wrapper::assert_callback_success();
+                    log!("{}", memo);
+                    balance
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), quote!(#input).to_string());
+    }
+
+    #[test]
+    fn async_method_mutation() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Counter {
+                pub async fn add(&mut self, mut total: u64, label: String) -> u64 {
+                    total += self.step;
+                    let label = label.to_uppercase();
+                    let x: u64 = ext::get(label.clone()).await;
+                    total + x
+                }
+            }
+        };
+        desugar_async_methods(&mut input).unwrap();
+        let expected = quote! {
+            impl Counter {
+                pub fn add(&mut self, mut total: u64, label: String) -> near_sdk::Promise {
+                    total += self.step;
+                    let label = label.to_uppercase();
+                    #[derive(near_sdk::serde::Serialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                    struct ContinuationArgs<'a> {
+                        total: &'a u64,
+                    }
+                    let continuation_args = near_sdk::wrapper::continuation_args(
+                        &ContinuationArgs { total: &total, }
+                    );
+                    near_sdk::wrapper::then_continue(
+                        ext::get(label.clone()),
+                        "__add_then_1",
+                        continuation_args,
+                        near_sdk::wrapper::CONTINUATION_GAS
+                    )
+                }
+                #[doc(hidden)]
+                pub fn __add_then_1(&mut self, mut total: u64, #[callback] async_result: u64) -> u64 {
+                    near_sdk::wrapper::assert_private("__add_then_1");
+                    let x: u64 = async_result;
+                    total + x
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), quote!(#input).to_string());
+    }
+
+    #[test]
+    fn async_method_local_variables() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Vault {
+                #[continuation_gas(30_000_000_000_000)]
+                pub async fn withdraw(&mut self, amount: U128) {
+                    let amount: u128 = amount.0;
+                    ext::burn(amount.into()).await;
+                    self.total -= amount;
+                }
+            }
+        };
+        desugar_async_methods(&mut input).unwrap();
+        let expected = quote! {
+            impl Vault {
+                pub fn withdraw(&mut self, amount: U128) -> near_sdk::Promise {
+                    let amount: u128 = amount.0;
+                    #[derive(near_sdk::serde::Serialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                    struct ContinuationArgs<'a> {
+                        amount: &'a u128,
+                    }
+                    let continuation_args = near_sdk::wrapper::continuation_args(
+                        &ContinuationArgs { amount: &amount, }
+                    );
+                    near_sdk::wrapper::then_continue(
+                        ext::burn(amount.into()),
+                        "__withdraw_then_1",
+                        continuation_args,
+                        30000000000000u64
+                    )
+                }
+                #[doc(hidden)]
+                pub fn __withdraw_then_1(&mut self, amount: u128) {
+                    near_sdk::wrapper::assert_private("__withdraw_then_1");
+                    near_sdk::wrapper::assert_callback_success();
+                    self.total -= amount;
+                }
+            }
+        };
+        assert_eq!(expected.to_string(), quote!(#input).to_string());
+    }
+
+    #[test]
+    fn invalid_async_method() {
+        let error = |method: &str| {
+            let mut input: ItemImpl = syn::parse_str(&format!("impl Hello {{ {} }}", method)).unwrap();
+            desugar_async_methods(&mut input).unwrap_err().to_string()
+        };
+        assert_eq!(
+            error("pub async fn get(&mut self) -> u64 { let x = ext::get().await; x }"),
+            "The result of `.await` needs a type to be deserialized, e.g. `let x: u64 = promise.await;`."
+        );
+        assert_eq!(
+            error("pub async fn get(&mut self) -> u64 { ext::get().await }"),
+            "Only the statements like `let x: T = promise.await;` or `promise.await;` can await in the async methods of the contract."
+        );
+        assert_eq!(
+            error("pub async fn get(&mut self, #[serializer(borsh)] k: u64) { }"),
+            "Arguments of async methods cannot have attributes, they are passed to the callbacks as JSON."
+        );
+        assert_eq!(error("#[init] pub async fn new() -> Self { }"), "Initializers cannot be async.");
+        assert_eq!(
+            error("pub async fn get(&self) { ext::get().await; }"),
+            "Async methods of the contract should take `&mut self`, since they create promises and write the contract state."
+        );
+        assert_eq!(
+            error("pub async fn withdraw(&mut self, amount: U128) { let amount = amount.0; ext::burn().await; log(amount); }"),
+            "`amount` is used after `.await`, so it's passed to the callback and should be bound with a type, e.g. `let amount: u64 = ...;`."
+        );
+        assert_eq!(
+            error("pub async fn get(&mut self) { let (a, b) = pair(); ext::get(a).await; log(b); }"),
+            "`b` is used after `.await`, so it's passed to the callback and should be bound with a type, e.g. `let b: u64 = ...;`."
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use syn::export::TokenStream2;
 
-use crate::info_extractor::{ArgInfo, AttrSigInfo, BindgenArgType, SerializerType};
+use crate::info_extractor::{ArgInfo, AttrSigInfo, BindgenArgType, SerializerType, ValidationRule};
 use quote::quote;
 
 impl AttrSigInfo {
@@ -143,45 +143,40 @@ impl AttrSigInfo {
         result
     }
 
-    /// Create code that deserializes arguments that were decorated with `#[callback]`
+    /// Create code that deserializes arguments that were decorated with `#[callback]` or
+    /// `#[callback_result]`.
     pub fn callback_deserialization(&self) -> TokenStream2 {
-        self
-            .args
+        self.args
             .iter()
             .filter(|arg| match arg.bindgen_ty {
-                BindgenArgType::CallbackArg => true,
+                BindgenArgType::CallbackArg | BindgenArgType::CallbackResultArg => true,
                 _ => false,
             })
             .enumerate()
             .fold(TokenStream2::new(), |acc, (idx, arg)| {
                 let idx = idx as u64;
                 let ArgInfo { mutability, ident, ty, .. } = arg;
-                let read_data = quote! {
-                let data: Vec<u8> = match near_sdk::env::promise_result(#idx) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", #idx)
-                };
-            };
+                let is_result = matches!(arg.bindgen_ty, BindgenArgType::CallbackResultArg);
                 let invocation = match arg.serializer_ty {
-                    SerializerType::JSON => quote! {
-                        near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON")
-                    },
-                    SerializerType::Borsh => quote! {
-                        near_sdk::borsh::BorshDeserialize::try_from_slice(&data).expect("Failed to deserialize callback using Borsh")
-                    },
+                    SerializerType::JSON if is_result => {
+                        quote! { near_sdk::wrapper::callback_result_json(#idx) }
+                    }
+                    SerializerType::Borsh if is_result => {
+                        quote! { near_sdk::wrapper::callback_result_borsh(#idx) }
+                    }
+                    SerializerType::JSON => quote! { near_sdk::wrapper::callback_json(#idx) },
+                    SerializerType::Borsh => quote! { near_sdk::wrapper::callback_borsh(#idx) },
                 };
                 quote! {
-                #acc
-                #read_data
-                let #mutability #ident: #ty = #invocation;
-            }
+                    #acc
+                    let #mutability #ident: #ty = #invocation;
+                }
             })
     }
 
     /// Create code that deserializes arguments that were decorated with `#[callback_vec]`.
     pub fn callback_vec_deserialization(&self) -> TokenStream2 {
-        self
-            .args
+        self.args
             .iter()
             .filter(|arg| match arg.bindgen_ty {
                 BindgenArgType::CallbackArgVec => true,
@@ -190,24 +185,80 @@ impl AttrSigInfo {
             .fold(TokenStream2::new(), |acc, arg| {
                 let ArgInfo { mutability, ident, ty, .. } = arg;
                 let invocation = match arg.serializer_ty {
-                    SerializerType::JSON => quote! {
-                        near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON")
-                    },
-                    SerializerType::Borsh => quote! {
-                        near_sdk::borsh::BorshDeserialize::try_from_slice(&data).expect("Failed to deserialize callback using Borsh")
-                    },
+                    SerializerType::JSON => quote! { near_sdk::wrapper::callback_vec_json() },
+                    SerializerType::Borsh => quote! { near_sdk::wrapper::callback_vec_borsh() },
                 };
                 quote! {
-                #acc
-                let #mutability #ident: #ty = (0..near_sdk::env::promise_results_count())
-                .map(|i| {
-                    let data: Vec<u8> = match near_sdk::env::promise_result(i) {
-                        near_sdk::PromiseResult::Successful(x) => x,
-                        _ => panic!("Callback computation {} was not successful", i)
-                    };
-                    #invocation
-                }).collect();
-            }
+                    #acc
+                    let #mutability #ident: #ty = #invocation;
+                }
             })
     }
+
+    /// Create the deserialization of the JSON input that names the failing argument.
+    /// # Example:
+    /// ```ignore
+    /// near_sdk::wrapper::input_json_detailed(&[
+    ///     ("arg0", near_sdk::wrapper::check_json_arg::<u64>),
+    ///     ("arg1", near_sdk::wrapper::check_json_arg::<Vec<String>>),
+    /// ])
+    /// ```
+    pub fn detailed_input_json(&self) -> TokenStream2 {
+        let mut checks = TokenStream2::new();
+        for arg in self.input_args() {
+            let name = arg.ident.to_string();
+            let ty = &arg.ty;
+            checks.extend(quote! {
+                (#name, near_sdk::wrapper::check_json_arg::<#ty>),
+            });
+        }
+        quote! {
+            near_sdk::wrapper::input_json_detailed(&[#checks])
+        }
+    }
+
+    /// Create the checks of `#[validate(...)]` on the arguments, which run after the arguments are
+    /// deserialized.
+    /// # Example:
+    /// ```ignore
+    /// near_sdk::validation::check_range("amount", &amount, Some(1), None);
+    /// near_sdk::validation::check_len("memo", &memo, None, Some(64usize));
+    /// ```
+    pub fn argument_validation(&self) -> TokenStream2 {
+        let mut result = TokenStream2::new();
+        for arg in &self.args {
+            let ident = &arg.ident;
+            let name = ident.to_string();
+            for rule in &arg.validations {
+                result.extend(match rule {
+                    ValidationRule::Range { min, max } => {
+                        let min = optional_bound(min);
+                        let max = optional_bound(max);
+                        quote! {
+                            near_sdk::validation::check_range(#name, &#ident, #min, #max);
+                        }
+                    }
+                    ValidationRule::Len { min, max } => {
+                        let min = optional_bound(min);
+                        let max = optional_bound(max);
+                        quote! {
+                            near_sdk::validation::check_len(#name, &#ident, #min, #max);
+                        }
+                    }
+                    ValidationRule::Regex(regex) => quote! {
+                        near_sdk::validation::check_regex(#name, &#ident, #regex);
+                    },
+                });
+            }
+        }
+        result
+    }
+}
+
+/// `Some(bound)` or `None`.
+fn optional_bound<T: quote::ToTokens>(bound: &Option<T>) -> TokenStream2 {
+    match bound {
+        Some(bound) => quote! { Some(#bound) },
+        None => quote! { None },
+    }
 }
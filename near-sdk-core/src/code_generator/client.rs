@@ -0,0 +1,225 @@
+use crate::info_extractor::{ArgInfo, ImplItemMethodInfo, ItemImplInfo, SerializerType};
+use quote::{format_ident, quote};
+use syn::export::TokenStream2;
+use syn::spanned::Spanned;
+use syn::{Error, GenericArgument, Ident, ItemStruct, PathArguments, Type};
+
+/// The name of the client of the contract struct, e.g. `StatusMessageClient`.
+fn client_ident(contract: &Ident) -> Ident {
+    format_ident!("{}Client", contract)
+}
+
+/// Generate the client struct of the contract struct. The methods are added by the impl sections.
+pub fn client_struct(input: &ItemStruct) -> TokenStream2 {
+    let vis = &input.vis;
+    let ident = client_ident(&input.ident);
+    let doc = format!(" The off-chain client of `{}`, see `near_sdk::client`.", input.ident);
+    quote! {
+        #[doc = #doc]
+        #[cfg(not(target_arch = "wasm32"))]
+        #vis struct #ident<T> {
+            pub transport: T,
+            pub contract_id: near_sdk::AccountId,
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        impl<T: near_sdk::client::Transport> #ident<T> {
+            pub fn with_transport(transport: T, contract_id: near_sdk::AccountId) -> Self {
+                Self { transport, contract_id }
+            }
+        }
+    }
+}
+
+impl ItemImplInfo {
+    /// Generate the functions of the client for the methods of the impl section.
+    pub fn client_code(&self) -> syn::Result<TokenStream2> {
+        let contract = match &self.ty {
+            Type::Path(path) => &path.path.segments.last().unwrap().ident,
+            ty => return Err(Error::new(ty.span(), "The client requires a named contract type.")),
+        };
+        let ident = client_ident(contract);
+        let mut methods = TokenStream2::new();
+        for method in &self.methods {
+            if method.is_public || self.is_trait_impl {
+                methods.extend(method.client_method());
+            }
+        }
+        Ok(quote! {
+            #[cfg(not(target_arch = "wasm32"))]
+            impl<T: near_sdk::client::Transport> #ident<T> {
+                #methods
+            }
+        })
+    }
+}
+
+/// The type of the value the client returns for the result of the method. The value of
+/// `PromiseOrValue<T>` is `T`, and the result of a `Promise` is returned as raw bytes.
+fn client_result_type(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return Some(ty),
+    };
+    if segment.ident == "Promise" {
+        return None;
+    }
+    if segment.ident == "PromiseOrValue" {
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                return Some(ty);
+            }
+        }
+    }
+    Some(ty)
+}
+
+impl ImplItemMethodInfo {
+    /// Generate the function of the client that calls the method.
+    pub fn client_method(&self) -> TokenStream2 {
+        let info = &self.attr_signature_info;
+        let ident = &info.ident;
+        let method_name = ident.to_string();
+        let mut params = TokenStream2::new();
+        for arg in info.input_args() {
+            let ArgInfo { ident, ty, .. } = arg;
+            params.extend(quote! { #ident: #ty, });
+        }
+        let args = if info.input_args().next().is_some() {
+            let input_struct = info.input_struct();
+            let constructor = info.constructor_expr();
+            let serialize = match info.input_serializer {
+                SerializerType::JSON => quote! { near_sdk::client::to_json },
+                SerializerType::Borsh => quote! { near_sdk::client::to_borsh },
+            };
+            quote! {
+                #input_struct
+                let args = #serialize::<_, T::Error>(&#constructor)?;
+            }
+        } else {
+            quote! { let args = vec![]; }
+        };
+        let send = if info.is_view {
+            quote! { self.transport.view(&self.contract_id, #method_name, args) }
+        } else {
+            params.extend(quote! { options: near_sdk::client::CallOptions, });
+            quote! { self.transport.call(&self.contract_id, #method_name, args, options) }
+        };
+        let (result_ty, result) = match info.owned_result_type() {
+            Some(ty) if !info.is_init => match client_result_type(&ty) {
+                Some(ty) => {
+                    let deserialize = match info.result_serializer {
+                        SerializerType::JSON => quote! { near_sdk::client::from_json },
+                        SerializerType::Borsh => quote! { near_sdk::client::from_borsh },
+                    };
+                    (
+                        quote! { #ty },
+                        quote! {
+                            let result = #send.await.map_err(near_sdk::client::Error::Transport)?;
+                            #deserialize(&result)
+                        },
+                    )
+                }
+                None => (
+                    quote! { Vec<u8> },
+                    quote! { #send.await.map_err(near_sdk::client::Error::Transport) },
+                ),
+            },
+            _ => (
+                quote! { () },
+                quote! {
+                    #send.await.map_err(near_sdk::client::Error::Transport)?;
+                    Ok(())
+                },
+            ),
+        };
+        quote! {
+            pub async fn #ident(&self, #params) -> Result<#result_ty, near_sdk::client::Error<T::Error>> {
+                #args
+                #result
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemImpl, ItemStruct};
+    use quote::quote;
+    use crate::info_extractor::ItemImplInfo;
+    use super::client_struct;
+
+    #[test]
+    fn client_struct_code() {
+        let input: ItemStruct = parse_quote! {
+            pub struct Contract { value: u64 }
+        };
+        let actual = client_struct(&input);
+        let expected = quote!(
+            #[doc = " The off-chain client of `Contract`, see `near_sdk::client`."]
+            #[cfg(not(target_arch = "wasm32"))]
+            pub struct ContractClient<T> {
+                pub transport: T,
+                pub contract_id: near_sdk::AccountId,
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            impl<T: near_sdk::client::Transport> ContractClient<T> {
+                pub fn with_transport(transport: T, contract_id: near_sdk::AccountId) -> Self {
+                    Self { transport, contract_id }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn client_methods() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Contract {
+                #[init]
+                pub fn new(owner_id: AccountId) -> Self { }
+                pub fn get(&self) -> PromiseOrValue<u64> { }
+                #[result_serializer(borsh)]
+                pub fn set(&mut self, #[serializer(borsh)] value: u64) -> u64 { }
+                pub fn forward(&mut self) -> Promise { }
+                fn private(&self) { }
+            }
+        };
+        let actual = ItemImplInfo::new(&mut input).unwrap().client_code().unwrap();
+        let expected = quote!(
+            #[cfg(not(target_arch = "wasm32"))]
+            impl<T: near_sdk::client::Transport> ContractClient<T> {
+                pub async fn new(&self, owner_id: AccountId, options: near_sdk::client::CallOptions,) -> Result<(), near_sdk::client::Error<T::Error>> {
+                    #[derive(near_sdk::serde::Deserialize, near_sdk::serde::Serialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                    struct Input {
+                        owner_id: AccountId,
+                    }
+                    let args = near_sdk::client::to_json::<_, T::Error>(&Input { owner_id, })?;
+                    self.transport.call(&self.contract_id, "new", args, options).await.map_err(near_sdk::client::Error::Transport)?;
+                    Ok(())
+                }
+                pub async fn get(&self,) -> Result<u64, near_sdk::client::Error<T::Error>> {
+                    let args = vec![];
+                    let result = self.transport.view(&self.contract_id, "get", args).await.map_err(near_sdk::client::Error::Transport)?;
+                    near_sdk::client::from_json(&result)
+                }
+                pub async fn set(&self, value: u64, options: near_sdk::client::CallOptions,) -> Result<u64, near_sdk::client::Error<T::Error>> {
+                    #[derive(near_sdk::borsh::BorshDeserialize, near_sdk::borsh::BorshSerialize)]
+                    struct Input {
+                        value: u64,
+                    }
+                    let args = near_sdk::client::to_borsh::<_, T::Error>(&Input { value, })?;
+                    let result = self.transport.call(&self.contract_id, "set", args, options).await.map_err(near_sdk::client::Error::Transport)?;
+                    near_sdk::client::from_borsh(&result)
+                }
+                pub async fn forward(&self, options: near_sdk::client::CallOptions,) -> Result<Vec<u8>, near_sdk::client::Error<T::Error>> {
+                    let args = vec![];
+                    self.transport.call(&self.contract_id, "forward", args, options).await.map_err(near_sdk::client::Error::Transport)
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::info_extractor::ContractAttrArgs;
+use quote::quote;
+use syn::export::TokenStream2;
+use syn::ItemStruct;
+
+impl ContractAttrArgs {
+    /// Generate the `EVENT_PREFIX` constant of the contract struct, the standard of the events
+    /// that derive `NearEvent` with `#[near_event(contract = "...")]`.
+    pub fn event_prefix_const(&self, input: &ItemStruct) -> TokenStream2 {
+        let prefix = match &self.event_prefix {
+            Some(prefix) => prefix,
+            None => return TokenStream2::new(),
+        };
+        let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// The standard of the events of the contract.
+                pub const EVENT_PREFIX: &'static str = #prefix;
+            }
+        }
+    }
+
+    /// Generate the `ContractState` of the contract struct, which the wrappers of all the methods
+    /// use to read and write the state. It checks the schema of the state with `state_schema`.
+    pub fn contract_state_impl(&self, input: &ItemStruct) -> TokenStream2 {
+        let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let methods = if self.state_schema {
+            quote! {
+                fn state_read() -> Option<Self> {
+                    near_sdk::migration::state_read_checked()
+                }
+                fn state_write(&self) {
+                    near_sdk::migration::state_write_checked(self)
+                }
+            }
+        } else {
+            TokenStream2::new()
+        };
+        quote! {
+            impl #impl_generics near_sdk::migration::ContractState for #ident #ty_generics #where_clause {
+                #methods
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::ItemStruct;
+    use crate::info_extractor::{ContractAttrArgs, ContractMetadataInfo, ContractVersionInfo};
+
+    #[test]
+    fn contract_attr_args() {
+        let args = quote! {
+            metadata(standard = "nep141", version = "1.0.0"),
+            contract_metadata(version = "2.0.0", standard(standard = "nep145", version = "1.0.0")),
+            metadata(standard = "nep148", version = "1.0.0"),
+            event_prefix = "my_token",
+            contract_version
+        };
+        let config = ContractAttrArgs::parse(args.clone()).unwrap();
+        assert!(config.contract_version);
+        assert!(!config.lazy_fields);
+        let metadata = config.contract_metadata.as_ref().unwrap();
+        assert_eq!(metadata.version.as_deref(), Some("2.0.0"));
+        assert_eq!(
+            metadata.standards,
+            vec![
+                ("nep141".to_string(), "1.0.0".to_string()),
+                ("nep145".to_string(), "1.0.0".to_string()),
+                ("nep148".to_string(), "1.0.0".to_string()),
+            ]
+        );
+        assert!(ContractMetadataInfo::from_attr_args(args.clone()).unwrap().is_some());
+        assert!(ContractVersionInfo::from_attr_args(args).unwrap().is_some());
+
+        let input: ItemStruct = syn::parse_str("pub struct Token { total_supply: u128 }").unwrap();
+        let actual = config.event_prefix_const(&input);
+        let expected = quote!(
+            impl Token {
+                /// The standard of the events of the contract.
+                pub const EVENT_PREFIX: &'static str = "my_token";
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn invalid_contract_attr_args() {
+        assert!(ContractAttrArgs::parse(quote! { metadata(standard = "nep141") }).is_err());
+        assert!(ContractAttrArgs::parse(quote! { event_prefix = "" }).is_err());
+        assert!(ContractAttrArgs::parse(quote! { event_prefix(token) }).is_err());
+        assert!(ContractAttrArgs::parse(quote! {
+            contract_metadata(version = "1.0.0"), contract_metadata(version = "2.0.0")
+        }).is_err());
+        let config = ContractAttrArgs::parse(quote! { lazy_fields }).unwrap();
+        assert!(config.contract_metadata.is_none());
+        let input: ItemStruct = syn::parse_str("pub struct Token {}").unwrap();
+        assert!(config.event_prefix_const(&input).is_empty());
+    }
+
+    #[test]
+    fn contract_state_impl() {
+        let input: ItemStruct = syn::parse_str("pub struct Token { total_supply: u128 }").unwrap();
+        let config = ContractAttrArgs::parse(quote! {}).unwrap();
+        let actual = config.contract_state_impl(&input);
+        let expected = quote!(
+            impl near_sdk::migration::ContractState for Token {}
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+
+        let config = ContractAttrArgs::parse(quote! { state_schema }).unwrap();
+        let actual = config.contract_state_impl(&input);
+        let expected = quote!(
+            impl near_sdk::migration::ContractState for Token {
+                fn state_read() -> Option<Self> {
+                    near_sdk::migration::state_read_checked()
+                }
+                fn state_write(&self) {
+                    near_sdk::migration::state_write_checked(self)
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::info_extractor::ContractMetadataInfo;
+use quote::quote;
+use syn::export::TokenStream2;
+
+impl ContractMetadataInfo {
+    /// Generate the `contract_source_metadata` view method of NEP-330. The values that are not set
+    /// in the attribute are taken from the `NEP330_*` and `CARGO_PKG_*` environment variables at
+    /// the build time of the contract, and the toolchain from the `NEAR_SDK_BUILD_*` ones.
+    pub fn contract_source_metadata_method(&self) -> TokenStream2 {
+        let panic_hook = quote! {
+            near_sdk::env::setup_panic_hook();
+        };
+        let env_creation = quote! {
+            near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+        };
+        let version = match &self.version {
+            Some(version) => quote! { Some(#version.to_string()) },
+            None => quote! {
+                Some(option_env!("NEP330_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")).to_string())
+            },
+        };
+        let link = match &self.link {
+            Some(link) => quote! { Some(#link.to_string()) },
+            None => quote! {
+                option_env!("NEP330_LINK")
+                    .or(option_env!("CARGO_PKG_REPOSITORY"))
+                    .filter(|link| !link.is_empty())
+                    .map(|link| link.to_string())
+            },
+        };
+        let standards = self.standards.iter().map(|(standard, version)| {
+            quote! {
+                near_sdk::contract_metadata::Standard {
+                    standard: #standard.to_string(),
+                    version: #version.to_string(),
+                }
+            }
+        });
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn contract_source_metadata() {
+                #panic_hook
+                #env_creation
+                let metadata = near_sdk::ContractSourceMetadata {
+                    version: #version,
+                    link: #link,
+                    standards: vec![#(#standards),*],
+                    build_info: near_sdk::contract_metadata::BuildInfo::from_parts(
+                        option_env!("NEP330_BUILD_INFO_BUILD_ENVIRONMENT"),
+                        option_env!("NEP330_BUILD_INFO_BUILD_COMMAND"),
+                        option_env!("NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT"),
+                        option_env!("NEP330_BUILD_INFO_CONTRACT_PATH"),
+                    ),
+                    toolchain: near_sdk::contract_metadata::Toolchain::from_parts(
+                        option_env!("NEAR_SDK_BUILD_RUSTC"),
+                        option_env!("NEAR_SDK_BUILD_TARGET"),
+                        option_env!("NEAR_SDK_BUILD_PROFILE"),
+                        option_env!("NEAR_SDK_BUILD_FEATURES"),
+                    ),
+                };
+                let data = near_sdk::serde_json::to_vec(&metadata).expect("Failed to serialize the contract source metadata using JSON.");
+                near_sdk::env::value_return(&data);
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use crate::info_extractor::ContractMetadataInfo;
+
+    #[test]
+    fn no_contract_metadata() {
+        assert!(ContractMetadataInfo::from_attr_args(quote! {}).unwrap().is_none());
+        assert!(ContractMetadataInfo::from_attr_args(quote! { unknown }).is_err());
+    }
+
+    #[test]
+    fn contract_metadata() {
+        let info = ContractMetadataInfo::from_attr_args(quote! {
+            contract_metadata(version = "1.0.0", standard(standard = "nep171", version = "1.0.0"))
+        }).unwrap().unwrap();
+        let actual = info.contract_source_metadata_method();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn contract_source_metadata() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let metadata = near_sdk::ContractSourceMetadata {
+                    version: Some("1.0.0".to_string()),
+                    link: option_env!("NEP330_LINK")
+                        .or(option_env!("CARGO_PKG_REPOSITORY"))
+                        .filter(|link| !link.is_empty())
+                        .map(|link| link.to_string()),
+                    standards: vec![near_sdk::contract_metadata::Standard {
+                        standard: "nep171".to_string(),
+                        version: "1.0.0".to_string(),
+                    }],
+                    build_info: near_sdk::contract_metadata::BuildInfo::from_parts(
+                        option_env!("NEP330_BUILD_INFO_BUILD_ENVIRONMENT"),
+                        option_env!("NEP330_BUILD_INFO_BUILD_COMMAND"),
+                        option_env!("NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT"),
+                        option_env!("NEP330_BUILD_INFO_CONTRACT_PATH"),
+                    ),
+                    toolchain: near_sdk::contract_metadata::Toolchain::from_parts(
+                        option_env!("NEAR_SDK_BUILD_RUSTC"),
+                        option_env!("NEAR_SDK_BUILD_TARGET"),
+                        option_env!("NEAR_SDK_BUILD_PROFILE"),
+                        option_env!("NEAR_SDK_BUILD_FEATURES"),
+                    ),
+                };
+                let data = near_sdk::serde_json::to_vec(&metadata).expect("Failed to serialize the contract source metadata using JSON.");
+                near_sdk::env::value_return(&data);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::info_extractor::ContractVersionInfo;
+use quote::quote;
+use syn::export::TokenStream2;
+
+impl ContractVersionInfo {
+    /// Generate the `version` view method, which returns the `ContractVersion` captured with
+    /// `near_sdk::contract_version!()` at the build time of the contract.
+    pub fn version_method(&self) -> TokenStream2 {
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn version() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let version = near_sdk::contract_version!();
+                let data = near_sdk::serde_json::to_vec(&version).expect("Failed to serialize the contract version using JSON.");
+                near_sdk::env::value_return(&data);
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use crate::info_extractor::{ContractMetadataInfo, ContractVersionInfo};
+
+    #[test]
+    fn contract_version() {
+        let args = quote! { contract_version, lazy_fields };
+        assert!(ContractMetadataInfo::from_attr_args(args.clone()).unwrap().is_none());
+        let actual = ContractVersionInfo::from_attr_args(args).unwrap().unwrap().version_method();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn version() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                let version = near_sdk::contract_version!();
+                let data = near_sdk::serde_json::to_vec(&version).expect("Failed to serialize the contract version using JSON.");
+                near_sdk::env::value_return(&data);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn no_contract_version() {
+        assert!(ContractVersionInfo::from_attr_args(quote! { lazy_fields }).unwrap().is_none());
+    }
+}
@@ -1,39 +1,56 @@
-use crate::info_extractor::{AttrSigInfo, ImplItemMethodInfo, SerializerType};
+use crate::info_extractor::{AttrSigInfo, ImplItemMethodInfo, NonReentrantAttr, SerializerType};
 use quote::quote;
 use syn::export::TokenStream2;
-use syn::ReturnType;
+use syn::{ReturnType, Type};
 
 impl ImplItemMethodInfo {
     /// Generate wrapper method for the given method of the contract.
     pub fn method_wrapper(&self) -> TokenStream2 {
-        let ImplItemMethodInfo { attr_signature_info, struct_type, .. } = self;
+        let ImplItemMethodInfo {
+            attr_signature_info,
+            struct_type,
+            failure_events,
+            max_input_len,
+            is_dispatched,
+            ..
+        } = self;
         // Args provided by `env::input()`.
         let has_input_args = attr_signature_info.input_args().next().is_some();
 
-        let panic_hook = quote! {
-            near_sdk::env::setup_panic_hook();
-        };
-        let env_creation = quote! {
-            near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+        // The entry point of `dispatch` sets up the environment before it reads the method name.
+        let mut setup = if *is_dispatched {
+            TokenStream2::new()
+        } else {
+            quote! {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+            }
         };
+        if *failure_events {
+            let method_name = attr_signature_info.ident.to_string();
+            setup.extend(quote! {
+                near_sdk::env::set_failure_event_method(#method_name);
+            });
+        }
         let arg_struct;
         let arg_parsing;
         if has_input_args {
             arg_struct = attr_signature_info.input_struct();
             let decomposition = attr_signature_info.decomposition_pattern();
             let serializer_invocation = match attr_signature_info.input_serializer {
-                SerializerType::JSON => quote! {
-                    near_sdk::serde_json::from_slice(
-                        &near_sdk::env::input().expect("Expected input since method has arguments.")
-                    ).expect("Failed to deserialize input from JSON.")
-                },
-                SerializerType::Borsh => quote! {
-                    near_sdk::borsh::BorshDeserialize::try_from_slice(
-                        &near_sdk::env::input().expect("Expected input since method has arguments.")
-                    ).expect("Failed to deserialize input from Borsh.")
+                SerializerType::JSON if cfg!(feature = "detailed-input-errors") => {
+                    attr_signature_info.detailed_input_json()
+                }
+                SerializerType::JSON => quote! { near_sdk::wrapper::input_json() },
+                SerializerType::Borsh => quote! { near_sdk::wrapper::input_borsh() },
+            };
+            let input_len_check = match max_input_len {
+                Some(max_input_len) => quote! {
+                    near_sdk::wrapper::assert_input_len(#max_input_len);
                 },
+                None => TokenStream2::new(),
             };
             arg_parsing = quote! {
+                #input_len_check
                 let #decomposition : Input = #serializer_invocation ;
             };
         } else {
@@ -41,8 +58,21 @@ impl ImplItemMethodInfo {
             arg_parsing = TokenStream2::new();
         };
 
+        // The method that releases a lock is a callback, so only the contract can call it. The lock
+        // is released before the arguments and the results of the promises are deserialized.
+        let unlock = match &attr_signature_info.non_reentrant {
+            Some(NonReentrantAttr::Unlock(operation)) => {
+                let method_name = attr_signature_info.ident.to_string();
+                quote! {
+                    near_sdk::wrapper::assert_private(#method_name);
+                    near_sdk::reentrancy::unlock(#operation);
+                }
+            }
+            _ => TokenStream2::new(),
+        };
         let callback_deser = attr_signature_info.callback_deserialization();
         let callback_vec_deser = attr_signature_info.callback_vec_deserialization();
+        let argument_validation = attr_signature_info.argument_validation();
 
         let arg_list = attr_signature_info.arg_list();
         let AttrSigInfo {
@@ -53,24 +83,52 @@ impl ImplItemMethodInfo {
             result_serializer,
             is_init,
             is_payable,
+            is_one_yocto,
             is_view,
+            is_graceful,
+            is_measure_gas,
+            ignores_state,
+            is_fallible_init,
             ..
         } = attr_signature_info;
-        let deposit_check = if *is_payable || *is_view {
+        let deposit_check = if *is_one_yocto {
+            quote! {
+                near_sdk::assert_one_yocto();
+            }
+        } else if *is_payable || *is_view {
             // No check if the method is payable or a view method
             quote! {}
         } else {
             // If method is not payable, do a check to make sure that it doesn't consume deposit
             quote! {
-                if near_sdk::env::attached_deposit() != 0 {
-                    near_sdk::env::panic(b"Method doesn't accept deposit");
-                }
+                near_sdk::wrapper::assert_no_deposit();
             }
         };
         let body = if *is_init {
+            let state_check = if *ignores_state {
+                TokenStream2::new()
+            } else {
+                quote! {
+                    near_sdk::wrapper::assert_not_initialized();
+                }
+            };
+            let contract_init = if *is_fallible_init {
+                // The state is not written when the initializer fails.
+                quote! {
+                    let contract = match #struct_type::#ident(#arg_list) {
+                        Ok(contract) => contract,
+                        Err(err) => near_sdk::wrapper::init_error(err),
+                    };
+                }
+            } else {
+                quote! {
+                    let contract = #struct_type::#ident(#arg_list);
+                }
+            };
             quote! {
-                let contract = #struct_type::#ident(#arg_list);
-                near_sdk::env::state_write(&contract);
+                #state_check
+                #contract_init
+                near_sdk::wrapper::state_write(&contract);
             }
         } else {
             let contract_deser;
@@ -79,14 +137,14 @@ impl ImplItemMethodInfo {
             if let Some(receiver) = receiver {
                 let mutability = &receiver.mutability;
                 contract_deser = quote! {
-                    let #mutability contract: #struct_type = near_sdk::env::state_read().unwrap_or_default();
+                    let #mutability contract: #struct_type = near_sdk::wrapper::state_read().unwrap_or_default();
                 };
                 method_invocation = quote! {
                     contract.#ident(#arg_list)
                 };
                 if !is_view {
                     contract_ser = quote! {
-                        near_sdk::env::state_write(&contract);
+                        near_sdk::wrapper::state_write(&contract);
                     };
                 } else {
                     contract_ser = TokenStream2::new();
@@ -104,44 +162,86 @@ impl ImplItemMethodInfo {
                     #method_invocation;
                     #contract_ser
                 },
-                ReturnType::Type(_, _) => {
-                    let value_ser = match result_serializer {
-                        SerializerType::JSON => quote! {
-                            let result = near_sdk::serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
-                        },
-                        SerializerType::Borsh => quote! {
-                            let result = near_sdk::borsh::BorshSerialize::try_to_vec(&result).expect("Failed to serialize the return value using Borsh.");
-                        },
+                ReturnType::Type(_, ty) => {
+                    let value_return = match result_serializer {
+                        SerializerType::JSON => quote! { near_sdk::wrapper::return_json(&result); },
+                        // Borsh is not implemented for the references, but it is for `str` and
+                        // `[T]`, so the borrowed result is passed as it is.
+                        SerializerType::Borsh if matches!(**ty, Type::Reference(_)) => {
+                            quote! { near_sdk::wrapper::return_borsh(result); }
+                        }
+                        SerializerType::Borsh => {
+                            quote! { near_sdk::wrapper::return_borsh(&result); }
+                        }
                     };
-                    quote! {
-                    #contract_deser
-                    let result = #method_invocation;
-                    #value_ser
-                    near_sdk::env::value_return(&result);
-                    #contract_ser
+                    if *is_graceful {
+                        // The transaction succeeds on `Err` too, so the state is written like the
+                        // collections and the promises changed before the error.
+                        quote! {
+                        #contract_deser
+                        let result = #method_invocation;
+                        #contract_ser
+                        match result {
+                            Ok(result) => {
+                                #value_return
+                            }
+                            Err(err) => near_sdk::wrapper::return_error(err),
+                        }
+                        }
+                    } else {
+                        quote! {
+                        #contract_deser
+                        let result = #method_invocation;
+                        #value_return
+                        #contract_ser
+                        }
                     }
                 }
             }
         };
+        let (gas_entry, gas_exit) = if *is_measure_gas {
+            let method_name = ident.to_string();
+            (
+                quote! {
+                    let gas_meter = near_sdk::wrapper::measure_gas_entry(#method_name);
+                },
+                quote! {
+                    near_sdk::wrapper::measure_gas_exit(gas_meter);
+                },
+            )
+        } else {
+            (TokenStream2::new(), TokenStream2::new())
+        };
         let non_bindgen_attrs = non_bindgen_attrs.iter().fold(TokenStream2::new(), |acc, value| {
             quote! {
                 #acc
                 #value
             }
         });
+        // The dispatched wrapper is a function of the entry point, which is compiled for wasm32.
+        let signature = if *is_dispatched {
+            quote! { fn #ident() }
+        } else {
+            quote! {
+                #[cfg(target_arch = "wasm32")]
+                #[no_mangle]
+                pub extern "C" fn #ident()
+            }
+        };
         quote! {
             #non_bindgen_attrs
-            #[cfg(target_arch = "wasm32")]
-            #[no_mangle]
-            pub extern "C" fn #ident() {
-                #panic_hook
-                #env_creation
+            #signature {
+                #setup
+                #gas_entry
                 #deposit_check
+                #unlock
                 #arg_struct
                 #arg_parsing
                 #callback_deser
                 #callback_vec_deser
+                #argument_validation
                 #body
+                #gas_exit
             }
         }
     }
@@ -0,0 +1,135 @@
+use crate::info_extractor::{BindgenArgType, ItemImplInfo};
+use quote::quote;
+use syn::export::TokenStream2;
+
+impl ItemImplInfo {
+    /// Generate the trait set by `#[near_bindgen(interface = "...")]` with the public methods of
+    /// the impl section, and its implementation for the contract that calls them. The callbacks,
+    /// i.e. the methods with `#[callback]` arguments, are not a part of the interface.
+    pub fn interface_trait(&self) -> TokenStream2 {
+        let interface = match &self.interface {
+            Some(interface) => interface,
+            None => return TokenStream2::new(),
+        };
+        let ty = &self.ty;
+        let mut declarations = TokenStream2::new();
+        let mut definitions = TokenStream2::new();
+        for method in &self.methods {
+            let info = &method.attr_signature_info;
+            let is_callback =
+                info.args.iter().any(|arg| !matches!(arg.bindgen_ty, BindgenArgType::Regular));
+            if !method.is_public || is_callback {
+                continue;
+            }
+            let ident = &info.ident;
+            let docs = info.non_bindgen_attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+            let receiver = match &info.receiver {
+                Some(receiver) if receiver.reference.is_some() => {
+                    let mutability = &receiver.mutability;
+                    quote! { &#mutability self, }
+                }
+                Some(_) => quote! { self, },
+                None => TokenStream2::new(),
+            };
+            let mut params = TokenStream2::new();
+            let mut args = TokenStream2::new();
+            for arg in &info.args {
+                let arg_ident = &arg.ident;
+                let arg_ty = &arg.original.ty;
+                params.extend(quote! { #arg_ident: #arg_ty, });
+                args.extend(quote! { #arg_ident, });
+            }
+            let returns = &info.returns;
+            let bound = if info.receiver.is_none() {
+                quote! { where Self: Sized }
+            } else {
+                TokenStream2::new()
+            };
+            let call = if info.receiver.is_some() {
+                quote! { <#ty>::#ident(self, #args) }
+            } else {
+                quote! { <#ty>::#ident(#args) }
+            };
+            declarations.extend(quote! {
+                #(#docs)*
+                fn #ident(#receiver #params) #returns #bound;
+            });
+            definitions.extend(quote! {
+                fn #ident(#receiver #params) #returns {
+                    #call
+                }
+            });
+        }
+        let doc = format!(" The public methods of `{}`.", quote! { #ty });
+        quote! {
+            #[doc = #doc]
+            pub trait #interface {
+                #declarations
+            }
+            impl #interface for #ty {
+                #definitions
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemImpl};
+    use quote::quote;
+    use crate::info_extractor::ItemImplInfo;
+
+    #[test]
+    fn interface_trait() {
+        let mut input: ItemImpl = parse_quote! {
+            impl StatusMessage {
+                #[init]
+                pub fn new(owner_id: AccountId) -> Self { }
+                /// Sets the status of the caller.
+                #[payable]
+                pub fn set_status(&mut self, message: String) { }
+                pub fn get_status(&self, account_id: &AccountId) -> Option<String> { }
+                pub fn on_status(&mut self, #[callback] status: String) { }
+                fn private(&self) { }
+            }
+        };
+        let mut info = ItemImplInfo::new(&mut input).unwrap();
+        info.apply_attr_args(quote! { interface = "StatusMessageApi" }).unwrap();
+        let actual = info.interface_trait();
+        let expected = quote!(
+            #[doc = " The public methods of `StatusMessage`."]
+            pub trait StatusMessageApi {
+                fn new(owner_id: AccountId,) -> Self where Self: Sized;
+                #[doc = r" Sets the status of the caller."]
+                fn set_status(&mut self, message: String,);
+                fn get_status(&self, account_id: &AccountId,) -> Option<String>;
+            }
+            impl StatusMessageApi for StatusMessage {
+                fn new(owner_id: AccountId,) -> Self {
+                    <StatusMessage>::new(owner_id,)
+                }
+                fn set_status(&mut self, message: String,) {
+                    <StatusMessage>::set_status(self, message,)
+                }
+                fn get_status(&self, account_id: &AccountId,) -> Option<String> {
+                    <StatusMessage>::get_status(self, account_id,)
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn interface_of_trait_impl() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Api for StatusMessage {
+                fn get(&self) -> u64 { }
+            }
+        };
+        let mut info = ItemImplInfo::new(&mut input).unwrap();
+        assert!(info.apply_attr_args(quote! { interface = "StatusMessageApi" }).is_err());
+        assert!(info.interface_trait().is_empty());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::ItemImplInfo;
+use proc_macro2::Span;
+use quote::quote;
 use syn::export::TokenStream2;
+use syn::LitByteStr;
 
 impl ItemImplInfo {
     /// Generate the code that wraps
@@ -10,16 +13,45 @@ impl ItemImplInfo {
                 res.extend(method.method_wrapper());
             }
         }
-        res
+        match &self.dispatch {
+            Some(entry_point) => self.dispatcher(entry_point, res),
+            None => res,
+        }
+    }
+
+    /// Generate the single export of `#[near_bindgen(dispatch = "...")]`. It reads the name of the
+    /// method from the input and calls the wrapper of the method, which is its inner function.
+    fn dispatcher(&self, entry_point: &syn::Ident, wrappers: TokenStream2) -> TokenStream2 {
+        let (names, idents): (Vec<_>, Vec<_>) = self
+            .methods
+            .iter()
+            .filter(|method| method.is_public || self.is_trait_impl)
+            .map(|method| {
+                let ident = &method.attr_signature_info.ident;
+                (LitByteStr::new(ident.to_string().as_bytes(), Span::call_site()), ident)
+            })
+            .unzip();
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn #entry_point() {
+                #wrappers
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                match near_sdk::wrapper::dispatched_method().as_slice() {
+                    #(#names => #idents(),)*
+                    method => near_sdk::wrapper::unknown_method(method),
+                }
+            }
+        }
     }
 }
 // Rustfmt removes comas.
 #[rustfmt::skip]
 #[cfg(test)]
 mod tests {
-    use syn::{Type, ImplItemMethod, parse_quote};
+    use syn::{Type, ImplItemMethod, ItemImpl, parse_quote};
     use quote::quote;
-    use crate::info_extractor::ImplItemMethodInfo;
+    use crate::info_extractor::{ImplItemMethodInfo, ItemImplInfo};
 
 
     #[test]
@@ -32,9 +64,8 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method();
             }
         );
@@ -51,9 +82,8 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method();
             }
         );
@@ -70,14 +100,11 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                if near_sdk::env::attached_deposit() != 0 {
-                    near_sdk::env::panic(b"Method doesn't accept deposit");
-                }
-                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method();
-                near_sdk::env::state_write(&contract);
+                near_sdk::wrapper::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -93,18 +120,14 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                 #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                 #[serde(crate = "near_sdk::serde")]
                 struct Input {
                     k: u64,
                 }
-                let Input { k, }: Input = near_sdk::serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from JSON.");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let Input { k, }: Input = near_sdk::wrapper::input_json();
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method(k, );
             }
         );
@@ -122,24 +145,18 @@ mod tests {
                 #[cfg(target_arch = "wasm32")]
                 #[no_mangle]
                 pub extern "C" fn method() {
-                    near_sdk::env::setup_panic_hook();
-                    near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                    if near_sdk::env::attached_deposit() != 0 {
-                        near_sdk::env::panic(b"Method doesn't accept deposit");
-                    }
+                    near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                    near_sdk::wrapper::assert_no_deposit();
                     #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                     #[serde(crate = "near_sdk::serde")]
                     struct Input {
                         k: u64,
                         m: Bar,
                     }
-                    let Input { k, m, }: Input = near_sdk::serde_json::from_slice(
-                        &near_sdk::env::input().expect("Expected input since method has arguments.")
-                    )
-                    .expect("Failed to deserialize input from JSON.");
-                    let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    let Input { k, m, }: Input = near_sdk::wrapper::input_json();
+                    let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                     contract.method(k, m, );
-                    near_sdk::env::state_write(&contract);
+                    near_sdk::wrapper::state_write(&contract);
                 }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -156,27 +173,19 @@ mod tests {
                 #[cfg(target_arch = "wasm32")]
                 #[no_mangle]
                 pub extern "C" fn method() {
-                    near_sdk::env::setup_panic_hook();
-                    near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                    if near_sdk::env::attached_deposit() != 0 {
-                        near_sdk::env::panic(b"Method doesn't accept deposit");
-                    }
+                    near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                    near_sdk::wrapper::assert_no_deposit();
                     #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                     #[serde(crate = "near_sdk::serde")]
                     struct Input {
                         k: u64,
                         m: Bar,
                     }
-                    let Input { k, m, }: Input = near_sdk::serde_json::from_slice(
-                        &near_sdk::env::input().expect("Expected input since method has arguments.")
-                    )
-                    .expect("Failed to deserialize input from JSON.");
-                    let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    let Input { k, m, }: Input = near_sdk::wrapper::input_json();
+                    let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                     let result = contract.method(k, m, );
-                    let result =
-                        near_sdk::serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
-                    near_sdk::env::value_return(&result);
-                    near_sdk::env::state_write(&contract);
+                    near_sdk::wrapper::return_json(&result);
+                    near_sdk::wrapper::state_write(&contract);
                 }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -193,13 +202,10 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 let result = contract.method();
-                let result =
-                    near_sdk::serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
-                near_sdk::env::value_return(&result);
+                near_sdk::wrapper::return_json(&result);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -215,18 +221,14 @@ mod tests {
                 #[cfg(target_arch = "wasm32")]
                 #[no_mangle]
                 pub extern "C" fn method() {
-                    near_sdk::env::setup_panic_hook();
-                    near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                    near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                     #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                     #[serde(crate = "near_sdk::serde")]
                     struct Input {
                         k: u64,
                     }
-                    let Input { k, }: Input = near_sdk::serde_json::from_slice(
-                        &near_sdk::env::input().expect("Expected input since method has arguments.")
-                    )
-                    .expect("Failed to deserialize input from JSON.");
-                    let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                    let Input { k, }: Input = near_sdk::wrapper::input_json();
+                    let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                     contract.method(&k, );
                 }
         );
@@ -244,18 +246,14 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                 #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                 #[serde(crate = "near_sdk::serde")]
                 struct Input {
                     k: u64,
                 }
-                let Input { mut k, }: Input = near_sdk::serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from JSON.");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let Input { mut k, }: Input = near_sdk::wrapper::input_json();
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method(&mut k, );
             }
         );
@@ -274,30 +272,16 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                 #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                 #[serde(crate = "near_sdk::serde")]
                 struct Input {
                     y: String,
                 }
-                let Input { y, }: Input = near_sdk::serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from JSON.");
-                let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", 0u64)
-                };
-                let mut x: u64 =
-                    near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let data: Vec<u8> = match near_sdk::env::promise_result(1u64) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", 1u64)
-                };
-                let z: Vec<u8> =
-                    near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let Input { y, }: Input = near_sdk::wrapper::input_json();
+                let mut x: u64 = near_sdk::wrapper::callback_json(0u64);
+                let z: Vec<u8> = near_sdk::wrapper::callback_json(1u64);
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method(&mut x, y, z, );
             }
         );
@@ -316,21 +300,10 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", 0u64)
-                };
-                let mut x: u64 =
-                    near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let data: Vec<u8> = match near_sdk::env::promise_result(1u64) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", 1u64)
-                };
-                let y: String =
-                    near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let mut x: u64 = near_sdk::wrapper::callback_json(0u64);
+                let y: String = near_sdk::wrapper::callback_json(1u64);
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method(&mut x, y, );
             }
         );
@@ -350,27 +323,15 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                 #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                 #[serde(crate = "near_sdk::serde")]
                 struct Input {
                     y: String,
                 }
-                let Input { y, }: Input = near_sdk::serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from JSON.");
-                let x: Vec<String> = (0..near_sdk::env::promise_results_count())
-                    .map(|i| {
-                        let data: Vec<u8> = match near_sdk::env::promise_result(i) {
-                            near_sdk::PromiseResult::Successful(x) => x,
-                            _ => panic!("Callback computation {} was not successful", i)
-                        };
-                        near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON")
-                    })
-                    .collect();
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let Input { y, }: Input = near_sdk::wrapper::input_json();
+                let x: Vec<String> = near_sdk::wrapper::callback_vec_json();
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method(x, y, );
             }
         );
@@ -390,22 +351,17 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                if near_sdk::env::attached_deposit() != 0 {
-                    near_sdk::env::panic(b"Method doesn't accept deposit");
-                }
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
                 #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                 #[serde(crate = "near_sdk::serde")]
                 struct Input {
                     k: u64,
                 }
-                let Input { mut k, }: Input = near_sdk::serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from JSON.");
+                let Input { mut k, }: Input = near_sdk::wrapper::input_json();
+                near_sdk::wrapper::assert_not_initialized();
                 let contract = Hello::method(&mut k,);
-                near_sdk::env::state_write(&contract);
+                near_sdk::wrapper::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -425,22 +381,76 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                 #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
                 #[serde(crate = "near_sdk::serde")]
                 struct Input {
                     k: u64,
                 }
-                let Input { mut k, }: Input = near_sdk::serde_json::from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from JSON.");
+                let Input { mut k, }: Input = near_sdk::wrapper::input_json();
+                near_sdk::wrapper::assert_not_initialized();
                 let contract = Hello::method(&mut k,);
-                near_sdk::env::state_write(&contract);
+                near_sdk::wrapper::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn init_ignore_state() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[init(ignore_state)]
+            pub fn migrate() -> Self { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn migrate() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                let contract = Hello::migrate();
+                near_sdk::wrapper::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn fallible_init() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[init]
+            pub fn new(fee: u32) -> Result<Self, ContractError> { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        assert!(method_info.attr_signature_info.is_fallible_init);
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn new() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    fee: u32,
+                }
+                let Input { fee, }: Input = near_sdk::wrapper::input_json();
+                near_sdk::wrapper::assert_not_initialized();
+                let contract = match Hello::new(fee,) {
+                    Ok(contract) => contract,
+                    Err(err) => near_sdk::wrapper::init_error(err),
+                };
+                near_sdk::wrapper::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
+        let owned_ty = method_info.attr_signature_info.owned_result_type().unwrap();
+        assert_eq!(quote!(#owned_ty).to_string(), quote!(Self).to_string());
     }
 
     #[test]
@@ -456,26 +466,18 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                if near_sdk::env::attached_deposit() != 0 {
-                    near_sdk::env::panic(b"Method doesn't accept deposit");
-                }
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
                 #[derive(near_sdk :: borsh :: BorshDeserialize, near_sdk :: borsh :: BorshSerialize)]
                 struct Input {
                     k: u64,
                     m: Bar,
                 }
-                let Input { k, m, }: Input = near_sdk::borsh::BorshDeserialize::try_from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from Borsh.");
-                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let Input { k, m, }: Input = near_sdk::wrapper::input_borsh();
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 let result = contract.method(k, m, );
-                let result = near_sdk::borsh::BorshSerialize::try_to_vec(&result)
-                    .expect("Failed to serialize the return value using Borsh.");
-                near_sdk::env::value_return(&result);
-                near_sdk::env::state_write(&contract);
+                near_sdk::wrapper::return_borsh(&result);
+                near_sdk::wrapper::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
@@ -493,29 +495,15 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
                 #[derive(near_sdk :: borsh :: BorshDeserialize, near_sdk :: borsh :: BorshSerialize)]
                 struct Input {
                     y: String,
                 }
-                let Input { y, }: Input = near_sdk::borsh::BorshDeserialize::try_from_slice(
-                    &near_sdk::env::input().expect("Expected input since method has arguments.")
-                )
-                .expect("Failed to deserialize input from Borsh.");
-                let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", 0u64)
-                };
-                let mut x: u64 = near_sdk::borsh::BorshDeserialize::try_from_slice(&data)
-                    .expect("Failed to deserialize callback using Borsh");
-                let data: Vec<u8> = match near_sdk::env::promise_result(1u64) {
-                    near_sdk::PromiseResult::Successful(x) => x,
-                    _ => panic!("Callback computation {} was not successful", 1u64)
-                };
-                let z: Vec<u8> =
-                    near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
-                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let Input { y, }: Input = near_sdk::wrapper::input_borsh();
+                let mut x: u64 = near_sdk::wrapper::callback_borsh(0u64);
+                let z: Vec<u8> = near_sdk::wrapper::callback_json(1u64);
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method(&mut x, y, z, );
             }
         );
@@ -532,13 +520,534 @@ mod tests {
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn method() {
-                near_sdk::env::setup_panic_hook();
-                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.method();
+                near_sdk::wrapper::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn only_owner() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[only_owner] pub fn method(&mut self) { self.x = 1; }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
                 contract.method();
-                near_sdk::env::state_write(&contract);
+                near_sdk::wrapper::state_write(&contract);
             }
         );
         assert_eq!(expected.to_string(), actual.to_string());
+        let expected = quote!(
+            pub fn method(&mut self) {
+                self.assert_owner();
+                self.x = 1;
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+    }
+
+    #[test]
+    fn only_owner_without_self() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[only_owner] pub fn method() { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn if_paused() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[only_owner] #[if_paused(panic)] pub fn method(&mut self) { }").unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let expected = quote!(
+            pub fn method(&mut self) {
+                self.assert_owner();
+                self.assert_not_paused("method");
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[if_paused(panic, feature = "transfers")] pub fn method(&mut self) { }"#).unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let expected = quote!(
+            pub fn method(&mut self) {
+                self.assert_not_paused("transfers");
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+
+        let mut method: ImplItemMethod = syn::parse_str("#[if_paused(ignore)] pub fn method(&mut self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn access_control() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[access_control(roles = "minter, admin")] #[if_paused(panic)] pub fn mint(&mut self) { }"#).unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let expected = quote!(
+            pub fn mint(&mut self) {
+                self.assert_any_role(&["minter", "admin"]);
+                self.assert_not_paused("mint");
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[access_control(roles = "")] pub fn mint(&mut self) { }"#).unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn non_reentrant() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[only_owner] #[non_reentrant(lock = "withdraw")] pub fn withdraw(&mut self) -> Promise { self.call() }"#).unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let expected = quote!(
+            pub fn withdraw(&mut self) -> Promise {
+                self.assert_owner();
+                near_sdk::reentrancy::lock("withdraw");
+                self.call()
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[non_reentrant(unlock = "withdraw")] pub fn resolve(&mut self, #[callback_result] amount: Result<u64, PromiseError>) { }"#).unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let expected = quote!(
+            pub fn resolve(&mut self, amount: Result<u64, PromiseError>) { }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn resolve() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                near_sdk::wrapper::assert_private("resolve");
+                near_sdk::reentrancy::unlock("withdraw");
+                let amount: Result<u64, PromiseError> = near_sdk::wrapper::callback_result_json(0u64);
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.resolve(amount, );
+                near_sdk::wrapper::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[non_reentrant(unlock = "withdraw")] pub fn resolve(&mut self, #[callback] amount: u64) { }"#).unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[non_reentrant(unlock = "withdraw")] pub fn resolve(&mut self, #[callback_vec] amounts: Vec<u64>) { }"#).unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = syn::parse_str(r#"pub fn resolve(&mut self, #[callback_result] amount: u64) { }"#).unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+
+        let mut method: ImplItemMethod = syn::parse_str(r#"#[non_reentrant(hold = "withdraw")] pub fn resolve() { }"#).unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn only_whitelisted() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[only_whitelisted] pub fn method(&mut self) { }").unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let expected = quote!(
+            pub fn method(&mut self) {
+                self.assert_whitelisted();
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+
+        let mut method: ImplItemMethod = syn::parse_str("#[only_whitelisted] pub fn method() { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn detailed_input_json() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("pub fn method(&mut self, k: u64, m: &Vec<Bar>) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.attr_signature_info.detailed_input_json();
+        let expected = quote!(
+            near_sdk::wrapper::input_json_detailed(&[
+                ("k", near_sdk::wrapper::check_json_arg::<u64>),
+                ("m", near_sdk::wrapper::check_json_arg::<Vec<Bar> >),
+            ])
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn validate_args() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&mut self, #[validate(range(min = 1, max = 10))] k: u64, #[validate(len(max = 64), regex = "^[a-z]+$")] name: String) { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    k: u64,
+                    name: String,
+                }
+                let Input { k, name, }: Input = near_sdk::wrapper::input_json();
+                near_sdk::validation::check_range("k", &k, Some(1), Some(10));
+                near_sdk::validation::check_len("name", &name, None, Some(64usize));
+                near_sdk::validation::check_regex("name", &name, "^[a-z]+$");
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.method(k, name, );
+                near_sdk::wrapper::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert_eq!(quote!(#method).to_string(), quote!(pub fn method(&mut self, k: u64, name: String) { }).to_string());
+
+        let mut method: ImplItemMethod = parse_quote! { pub fn method(&mut self, #[validate(range())] k: u64) { } };
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = parse_quote! { pub fn method(&mut self, #[validate(size(max = 1))] k: u64) { } };
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn one_yocto() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[one_yocto] pub fn method(&mut self) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::assert_one_yocto();
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.method();
+                near_sdk::wrapper::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert!(method.attrs.is_empty());
+
+        let mut method: ImplItemMethod = syn::parse_str("#[one_yocto] pub fn method(&self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = syn::parse_str("#[one_yocto] #[payable] pub fn method(&mut self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn measure_gas() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[measure_gas] pub fn method(&self) -> u64 { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let gas_meter = near_sdk::wrapper::measure_gas_entry("method");
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                let result = contract.method();
+                near_sdk::wrapper::return_json(&result);
+                near_sdk::wrapper::measure_gas_exit(gas_meter);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert!(method.attrs.is_empty());
+    }
+
+    #[test]
+    fn graceful() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[graceful] pub fn method(&mut self, k: u64) -> Result<u64, ContractError> { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::wrapper::assert_no_deposit();
+                #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    k: u64,
+                }
+                let Input { k, }: Input = near_sdk::wrapper::input_json();
+                let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                let result = contract.method(k, );
+                near_sdk::wrapper::state_write(&contract);
+                match result {
+                    Ok(result) => {
+                        near_sdk::wrapper::return_json(&result);
+                    }
+                    Err(err) => near_sdk::wrapper::return_error(err),
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert!(method.attrs.is_empty());
+
+        let mut method: ImplItemMethod = syn::parse_str("#[graceful] pub fn method(&mut self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = syn::parse_str("#[graceful] pub fn method(&mut self) -> u64 { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = syn::parse_str("#[graceful] #[result_serializer(borsh)] pub fn method(&self) -> Result<u64, ContractError> { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn malformed_attrs() {
+        let error = |method: &str| {
+            let impl_type: Type = syn::parse_str("Hello").unwrap();
+            let mut method: ImplItemMethod = syn::parse_str(method).unwrap();
+            match ImplItemMethodInfo::new(&mut method, impl_type) {
+                Ok(_) => panic!("The method is accepted"),
+                Err(err) => err.to_string(),
+            }
+        };
+        assert_eq!(
+            error("#[init(typo)] pub fn new() -> Self { }"),
+            "Unknown argument `typo` of `#[init]`, the only argument is `ignore_state`."
+        );
+        assert_eq!(
+            error("#[init(ignore_state, ignore_state)] pub fn new() -> Self { }"),
+            "`#[init]` takes a single `ignore_state`."
+        );
+        assert_eq!(
+            error("#[result_serializer(unknown)] pub fn method(&self) -> u64 { }"),
+            "Unsupported serializer type `unknown`, expected `json` or `borsh`."
+        );
+        assert_eq!(
+            error("#[payable(true)] pub fn method(&mut self) { }"),
+            "`#[payable]` doesn't take arguments."
+        );
+        assert_eq!(
+            error("pub fn method(&self, #[callback(json)] x: u64) { }"),
+            "`#[callback]` doesn't take arguments."
+        );
+        assert_eq!(
+            error("pub fn method(&self, #[serializer(bincode)] x: u64) { }"),
+            "Unsupported serializer type `bincode`, expected `json` or `borsh`."
+        );
+    }
+
+    #[test]
+    fn error_notes() {
+        let errors = |method: &str| {
+            let impl_type: Type = syn::parse_str("Hello").unwrap();
+            let mut method: ImplItemMethod = syn::parse_str(method).unwrap();
+            match ImplItemMethodInfo::new(&mut method, impl_type) {
+                Ok(_) => panic!("The method is accepted"),
+                Err(err) => err.into_iter().map(|err| err.to_string()).collect::<Vec<_>>(),
+            }
+        };
+        assert_eq!(
+            errors("pub fn method(&mut self, a: u64, #[serializer(borsh)] b: u64) { }"),
+            vec![
+                "Input arguments should be all of the same serialization type, `b` is serialized with Borsh and `a` with JSON.",
+                "note: the arguments of `method` are deserialized from a single input",
+            ]
+        );
+        assert_eq!(
+            errors("#[payable] pub fn method(&self) { }"),
+            vec![
+                "Payable method must be mutable (not view)",
+                "note: `method` is a view method, it takes `&self`",
+            ]
+        );
+        assert_eq!(
+            errors("#[init] #[only_owner] pub fn new() -> Self { }")[1],
+            "note: `new` is an initializer"
+        );
+        assert_eq!(
+            errors("#[graceful] #[result_serializer(borsh)] pub fn method(&self) -> Result<u64, ContractError> { }")[1],
+            "note: the result of `method` is serialized with Borsh"
+        );
+        assert_eq!(
+            errors("#[graceful] pub fn method(&self) -> u64 { }")[1],
+            "note: `method` doesn't return a `Result`"
+        );
+    }
+
+    #[test]
+    fn state_schema() {
+        let mut item_impl: ItemImpl = syn::parse_str("impl Hello { pub fn method(&mut self) { } }").unwrap();
+        let mut info = ItemImplInfo::new(&mut item_impl).unwrap();
+        assert!(info.apply_attr_args(quote!(state_schema)).is_err());
+    }
+
+    #[test]
+    fn failure_events() {
+        let mut item_impl: ItemImpl = syn::parse_str("impl Hello { pub fn method(&self) { } }").unwrap();
+        let mut info = ItemImplInfo::new(&mut item_impl).unwrap();
+        info.apply_attr_args(quote!(failure_events)).unwrap();
+        let actual = info.wrapper_code();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                near_sdk::env::set_failure_event_method("method");
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.method();
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn max_input_len() {
+        let mut item_impl: ItemImpl =
+            syn::parse_str("impl Hello { pub fn method(&self, k: u64) { } pub fn view(&self) { } }").unwrap();
+        let mut info = ItemImplInfo::new(&mut item_impl).unwrap();
+        info.apply_attr_args(quote!(max_input_len = 1024)).unwrap();
+        let actual = info.wrapper_code();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    k: u64,
+                }
+                near_sdk::wrapper::assert_input_len(1024u64);
+                let Input { k, }: Input = near_sdk::wrapper::input_json();
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.method(k, );
+            }
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn view() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                contract.view();
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert!(info.apply_attr_args(quote!(max_input_len = "1024")).is_err());
+    }
+
+    #[test]
+    fn dispatch() {
+        let mut item_impl: ItemImpl =
+            syn::parse_str("impl Hello { pub fn method(&mut self, k: u64) { } pub fn view(&self) { } fn private(&self) { } }").unwrap();
+        let mut info = ItemImplInfo::new(&mut item_impl).unwrap();
+        info.apply_attr_args(quote!(dispatch = "call")).unwrap();
+        let actual = info.wrapper_code();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn call() {
+                fn method() {
+                    near_sdk::wrapper::assert_no_deposit();
+                    #[derive(near_sdk :: serde :: Deserialize, near_sdk :: serde :: Serialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                    struct Input {
+                        k: u64,
+                    }
+                    let Input { k, }: Input = near_sdk::wrapper::input_json();
+                    let mut contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                    contract.method(k, );
+                    near_sdk::wrapper::state_write(&contract);
+                }
+                fn view() {
+                    let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                    contract.view();
+                }
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                match near_sdk::wrapper::dispatched_method().as_slice() {
+                    b"method" => method(),
+                    b"view" => view(),
+                    method => near_sdk::wrapper::unknown_method(method),
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        assert!(info.apply_attr_args(quote!(dispatch = call)).is_err());
+    }
+
+    #[test]
+    fn unsupported_impl_attr_arg() {
+        let mut item_impl: ItemImpl = syn::parse_str("impl Hello { pub fn method(&self) { } }").unwrap();
+        let mut info = ItemImplInfo::new(&mut item_impl).unwrap();
+        assert!(info.apply_attr_args(quote!(state)).is_err());
+        info.apply_attr_args(quote!(init => new)).unwrap();
+    }
+
+    #[test]
+    fn borsh_only() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[result_serializer(borsh)]
+            pub fn method(&self, #[serializer(borsh)] k: u64, #[callback] #[serializer(borsh)] m: u64) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        assert!(method_info.attr_signature_info.check_borsh_only().is_ok());
+        let mut method: ImplItemMethod = parse_quote! {
+            #[result_serializer(borsh)]
+            pub fn method(&self, k: u64) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        assert!(method_info.attr_signature_info.check_borsh_only().is_err());
+        let mut method: ImplItemMethod = parse_quote! {
+            pub fn method(&self, #[serializer(borsh)] k: u64) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        assert!(method_info.attr_signature_info.check_borsh_only().is_err());
+    }
+
+    #[test]
+    fn borrowed_result() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[result_serializer(borsh)]
+            pub fn method(&self) -> &str { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::wrapper::setup(Box::new(near_blockchain::NearBlockchain {}));
+                let contract: Hello = near_sdk::wrapper::state_read().unwrap_or_default();
+                let result = contract.method();
+                near_sdk::wrapper::return_borsh(result);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        for (returns, owned) in &[
+            ("&str", "String"),
+            ("&[u64]", "Vec<u64>"),
+            ("&Vec<u64>", "Vec<u64>"),
+            ("Cow<str>", "String"),
+            ("std::borrow::Cow<'_, [u8]>", "Vec<u8>"),
+            ("Option<&str>", "Option<&str>"),
+        ] {
+            let mut method: ImplItemMethod =
+                syn::parse_str(&format!("pub fn method(&self) -> {} {{ }}", returns)).unwrap();
+            let method_info = ImplItemMethodInfo::new(&mut method, impl_type.clone()).unwrap();
+            let owned_ty: Type = syn::parse_str(owned).unwrap();
+            let actual = method_info.attr_signature_info.owned_result_type().unwrap();
+            assert_eq!(quote!(#owned_ty).to_string(), quote!(#actual).to_string());
+        }
     }
 }
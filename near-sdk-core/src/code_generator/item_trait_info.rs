@@ -89,4 +89,47 @@ mod tests {
         };
         assert_eq!(actual.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn borsh_args() {
+        let mut t: ItemTrait = syn::parse2(
+            quote!{
+                    pub trait ExternalCrossContract {
+                        #[result_serializer(borsh)]
+                        fn merge(&self, #[serializer(borsh)] arr: Vec<u8>) -> Vec<u8>;
+                    }
+            }
+        ).unwrap();
+        let info = ItemTraitInfo::new(&mut t, None).unwrap();
+        let actual = info.wrapped_module();
+
+        let expected = quote! {
+            pub mod external_cross_contract {
+                use super::*;
+                use near_sdk::{Gas, Balance, AccountId, Promise};
+                use std::string::ToString;
+                pub fn merge<T: ToString>(
+                    arr: Vec<u8>,
+                    __account_id: &T,
+                    __balance: near_sdk::Balance,
+                    __gas: near_sdk::Gas
+                ) -> near_sdk::Promise {
+                    #[derive(near_sdk::borsh::BorshDeserialize, near_sdk::borsh::BorshSerialize)]
+                    struct Input {
+                        arr: Vec<u8>,
+                    }
+                    let args = Input { arr, };
+                    let args = near_sdk::borsh::BorshSerialize::try_to_vec(&args)
+                        .expect("Failed to serialize the cross contract args using Borsh.");
+                    near_sdk::Promise::new(__account_id.to_string()).function_call(
+                        b"merge".to_vec(),
+                        args,
+                        __balance,
+                        __gas,
+                    )
+                }
+            }
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
 }
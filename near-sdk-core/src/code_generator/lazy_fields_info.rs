@@ -0,0 +1,125 @@
+use crate::info_extractor::LazyFieldsInfo;
+use quote::{format_ident, quote};
+use syn::export::TokenStream2;
+
+impl LazyFieldsInfo {
+    /// Generate the contract struct with each field stored under its own key in a
+    /// `near_sdk::collections::LazyOption`, so that only the fields used by a call are read from the
+    /// storage. The struct gets the `new_lazy` constructor, and the `get_<field>` and `set_<field>`
+    /// accessors of each field.
+    pub fn lazy_struct(&self) -> TokenStream2 {
+        let LazyFieldsInfo { attrs, vis, ident, fields } = self;
+        let mut struct_fields = TokenStream2::new();
+        let mut constructor_args = TokenStream2::new();
+        let mut constructor_fields = TokenStream2::new();
+        let mut accessors = TokenStream2::new();
+        for field in fields {
+            let field_attrs = &field.attrs;
+            let field_vis = &field.vis;
+            let field_ident = &field.ident;
+            let ty = &field.ty;
+            let key = format!("STATE:{}", field_ident);
+            let getter = format_ident!("get_{}", field_ident);
+            let setter = format_ident!("set_{}", field_ident);
+            let error = format!("The field {} is not initialized.", field_ident);
+            struct_fields.extend(quote! {
+                #(#field_attrs)*
+                #field_vis #field_ident: near_sdk::collections::LazyOption<#ty>,
+            });
+            constructor_args.extend(quote! { #field_ident: #ty, });
+            constructor_fields.extend(quote! {
+                #field_ident: near_sdk::collections::LazyOption::new(#key.as_bytes().to_vec(), Some(&#field_ident)),
+            });
+            accessors.extend(quote! {
+                #field_vis fn #getter(&self) -> #ty {
+                    match self.#field_ident.get() {
+                        Some(value) => value,
+                        None => near_sdk::env::panic(#error.as_bytes()),
+                    }
+                }
+                #field_vis fn #setter(&mut self, value: &#ty) {
+                    self.#field_ident.set(value);
+                }
+            });
+        }
+        quote! {
+            #(#attrs)*
+            #vis struct #ident {
+                #struct_fields
+            }
+            impl #ident {
+                /// Creates the contract and writes each field under its own key.
+                pub fn new_lazy(#constructor_args) -> Self {
+                    Self {
+                        #constructor_fields
+                    }
+                }
+                #accessors
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::ItemStruct;
+    use quote::quote;
+    use crate::info_extractor::LazyFieldsInfo;
+
+    #[test]
+    fn lazy_fields() {
+        let input: ItemStruct = syn::parse2(quote! {
+            #[derive(BorshDeserialize, BorshSerialize)]
+            pub struct Contract {
+                owner_id: String,
+                pub records: Vec<u64>,
+            }
+        }).unwrap();
+        let actual = LazyFieldsInfo::from_attr_args(quote! { lazy_fields }, &input).unwrap().unwrap().lazy_struct();
+        let expected = quote!(
+            #[derive(BorshDeserialize, BorshSerialize)]
+            pub struct Contract {
+                owner_id: near_sdk::collections::LazyOption<String>,
+                pub records: near_sdk::collections::LazyOption<Vec<u64> >,
+            }
+            impl Contract {
+                /// Creates the contract and writes each field under its own key.
+                pub fn new_lazy(owner_id: String, records: Vec<u64>, ) -> Self {
+                    Self {
+                        owner_id: near_sdk::collections::LazyOption::new("STATE:owner_id".as_bytes().to_vec(), Some(&owner_id)),
+                        records: near_sdk::collections::LazyOption::new("STATE:records".as_bytes().to_vec(), Some(&records)),
+                    }
+                }
+                fn get_owner_id(&self) -> String {
+                    match self.owner_id.get() {
+                        Some(value) => value,
+                        None => near_sdk::env::panic("The field owner_id is not initialized.".as_bytes()),
+                    }
+                }
+                fn set_owner_id(&mut self, value: &String) {
+                    self.owner_id.set(value);
+                }
+                pub fn get_records(&self) -> Vec<u64> {
+                    match self.records.get() {
+                        Some(value) => value,
+                        None => near_sdk::env::panic("The field records is not initialized.".as_bytes()),
+                    }
+                }
+                pub fn set_records(&mut self, value: &Vec<u64>) {
+                    self.records.set(value);
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn no_lazy_fields() {
+        let input: ItemStruct = syn::parse2(quote! { struct Contract { a: u64 } }).unwrap();
+        assert!(LazyFieldsInfo::from_attr_args(quote! {}, &input).unwrap().is_none());
+        let input: ItemStruct = syn::parse2(quote! { struct Contract(u64); }).unwrap();
+        assert!(LazyFieldsInfo::from_attr_args(quote! { lazy_fields }, &input).is_err());
+    }
+}
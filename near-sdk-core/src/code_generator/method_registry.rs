@@ -0,0 +1,106 @@
+use super::size_report::serializer_name;
+use crate::info_extractor::ItemImplInfo;
+use proc_macro2::Span;
+use quote::quote;
+use syn::export::TokenStream2;
+use syn::{LitByteStr, ReturnType};
+
+/// The name of the custom section of the wasm with the method registry.
+pub const METHOD_REGISTRY_SECTION: &str = "near_methods";
+
+impl ItemImplInfo {
+    /// The lines of the method registry for the exported methods of the impl section. Each line
+    /// has the method, its kind (`view`, `call` or `init`), the number of its arguments, and the
+    /// serializers of its input and its result separated with tabs, or `-` if the method has no
+    /// input or no result.
+    pub fn method_registry_lines(&self) -> String {
+        let mut lines = String::new();
+        for method in &self.methods {
+            if !method.is_public && !self.is_trait_impl {
+                continue;
+            }
+            let info = &method.attr_signature_info;
+            let kind = if info.is_init {
+                "init"
+            } else if info.is_view {
+                "view"
+            } else {
+                "call"
+            };
+            let arg_count = info.input_args().count();
+            let input = if arg_count > 0 { serializer_name(&info.input_serializer) } else { "-" };
+            let result = match info.returns {
+                ReturnType::Type(..) if !info.is_init => serializer_name(&info.result_serializer),
+                _ => "-",
+            };
+            lines.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                info.ident, kind, arg_count, input, result
+            ));
+        }
+        lines
+    }
+
+    /// Generate the static that puts the lines of the method registry into the `near_methods`
+    /// custom section of the wasm. The linker concatenates the sections of all the impl sections,
+    /// so the tools read the methods of the whole contract from the binary without calling it.
+    pub fn method_registry(&self) -> TokenStream2 {
+        let lines = self.method_registry_lines();
+        if lines.is_empty() {
+            return TokenStream2::new();
+        }
+        let len = lines.len();
+        let lines = LitByteStr::new(lines.as_bytes(), Span::call_site());
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            const _: () = {
+                #[link_section = #METHOD_REGISTRY_SECTION]
+                #[used]
+                static METHOD_REGISTRY: [u8; #len] = *#lines;
+            };
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemImpl};
+    use quote::quote;
+    use crate::info_extractor::ItemImplInfo;
+
+    #[test]
+    fn method_registry() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Contract {
+                #[init]
+                pub fn new(owner_id: AccountId) -> Self { }
+                pub fn get(&self) -> u64 { }
+                #[result_serializer(borsh)]
+                pub fn set(&mut self, #[serializer(borsh)] value: u64, #[serializer(borsh)] memo: String) -> u64 { }
+                fn private(&self) { }
+            }
+        };
+        let actual = ItemImplInfo::new(&mut input).unwrap().method_registry();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            const _: () = {
+                #[link_section = "near_methods"]
+                #[used]
+                static METHOD_REGISTRY: [u8; 59usize] = *b"new\tinit\t1\tjson\t-\nget\tview\t0\t-\tjson\nset\tcall\t2\tborsh\tborsh\n";
+            };
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn no_exported_methods() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Contract {
+                fn private(&self) { }
+            }
+        };
+        assert!(ItemImplInfo::new(&mut input).unwrap().method_registry().is_empty());
+    }
+}
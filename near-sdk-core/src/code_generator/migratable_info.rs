@@ -0,0 +1,109 @@
+use crate::info_extractor::MigratableInfo;
+use quote::quote;
+use syn::export::TokenStream2;
+
+impl MigratableInfo {
+    /// Generate the implementation of `near_sdk::Migratable`.
+    pub fn migratable_impl(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let current = self.versions.last().unwrap();
+        let current_ty = &current.ty;
+        let current_version = (self.versions.len() - 1) as u8;
+        let mut read_arms = TokenStream2::new();
+        for (version, info) in self.versions.iter().enumerate() {
+            let version = version as u8;
+            let variant = &info.ident;
+            read_arms.extend(quote! {
+                #version => Self::#variant(near_sdk::migration::read_state()),
+            });
+        }
+        let mut into_current_arms = TokenStream2::new();
+        for pair in self.versions.windows(2) {
+            let variant = &pair[0].ident;
+            let next_variant = &pair[1].ident;
+            into_current_arms.extend(quote! {
+                Self::#variant(state) => near_sdk::Migratable::into_current(
+                    Self::#next_variant(::std::convert::From::from(state))
+                ),
+            });
+        }
+        let current_variant = &current.ident;
+        into_current_arms.extend(quote! { Self::#current_variant(state) => state, });
+        quote! {
+            impl near_sdk::Migratable for #ident {
+                type Current = #current_ty;
+                const CURRENT_VERSION: u8 = #current_version;
+                fn read_versioned_state() -> Self {
+                    match near_sdk::migration::state_version() {
+                        #read_arms
+                        version => near_sdk::env::panic(
+                            format!("Unknown state version {}.", version).as_bytes()
+                        ),
+                    }
+                }
+                fn into_current(self) -> Self::Current {
+                    match self {
+                        #into_current_arms
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::DeriveInput;
+    use quote::quote;
+    use crate::info_extractor::MigratableInfo;
+
+    #[test]
+    fn versioned_state() {
+        let input: DeriveInput = syn::parse2(quote! {
+            pub enum VersionedContract {
+                V0(ContractV0),
+                V1(ContractV1),
+                V2(Contract),
+            }
+        }).unwrap();
+        let actual = MigratableInfo::new(&input).unwrap().migratable_impl();
+        let expected = quote!(
+            impl near_sdk::Migratable for VersionedContract {
+                type Current = Contract;
+                const CURRENT_VERSION: u8 = 2u8;
+                fn read_versioned_state() -> Self {
+                    match near_sdk::migration::state_version() {
+                        0u8 => Self::V0(near_sdk::migration::read_state()),
+                        1u8 => Self::V1(near_sdk::migration::read_state()),
+                        2u8 => Self::V2(near_sdk::migration::read_state()),
+                        version => near_sdk::env::panic(
+                            format!("Unknown state version {}.", version).as_bytes()
+                        ),
+                    }
+                }
+                fn into_current(self) -> Self::Current {
+                    match self {
+                        Self::V0(state) => near_sdk::Migratable::into_current(
+                            Self::V1(::std::convert::From::from(state))
+                        ),
+                        Self::V1(state) => near_sdk::Migratable::into_current(
+                            Self::V2(::std::convert::From::from(state))
+                        ),
+                        Self::V2(state) => state,
+                    }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn named_variant() {
+        let input: DeriveInput = syn::parse2(quote! {
+            enum VersionedContract { V0 { state: Contract } }
+        }).unwrap();
+        assert!(MigratableInfo::new(&input).is_err());
+    }
+}
@@ -12,3 +12,39 @@ pub use trait_item_method_info::*;
 
 mod item_impl_info;
 pub use item_impl_info::*;
+
+mod contract_attr_args;
+pub use contract_attr_args::*;
+
+mod contract_metadata_info;
+pub use contract_metadata_info::*;
+
+mod contract_version_info;
+pub use contract_version_info::*;
+
+mod lazy_fields_info;
+pub use lazy_fields_info::*;
+
+mod near_event_info;
+pub use near_event_info::*;
+
+mod migratable_info;
+pub use migratable_info::*;
+
+mod partial_state_info;
+pub use partial_state_info::*;
+
+mod async_method_info;
+pub use async_method_info::*;
+
+mod size_report;
+pub use size_report::*;
+
+mod method_registry;
+pub use method_registry::*;
+
+mod client;
+pub use client::*;
+
+mod interface;
+pub use interface::*;
@@ -0,0 +1,218 @@
+use crate::info_extractor::{EventInfo, EventStandard, NearEventInfo};
+use quote::{format_ident, quote};
+use syn::export::TokenStream2;
+use syn::Fields;
+
+impl NearEventInfo {
+    /// Generate the implementation of `near_sdk::NearEvent`.
+    pub fn near_event_impl(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let standard = match &self.standard {
+            EventStandard::Name(name) => quote! { #name },
+            EventStandard::Contract(contract) => quote! { #contract::EVENT_PREFIX },
+        };
+        let version = &self.version;
+        let mut event_arms = TokenStream2::new();
+        let mut data_arms = TokenStream2::new();
+        for event in &self.events {
+            let path = if self.is_enum {
+                let variant = &event.ident;
+                quote! { Self::#variant }
+            } else {
+                quote! { Self }
+            };
+            let name = &event.name;
+            let wildcard = match &event.fields {
+                Fields::Named(_) => quote! { #path { .. } },
+                Fields::Unnamed(_) => quote! { #path(..) },
+                Fields::Unit => quote! { #path },
+            };
+            event_arms.extend(quote! { #wildcard => #name, });
+            data_arms.extend(event.data_arm(&path));
+        }
+        quote! {
+            impl #impl_generics near_sdk::NearEvent for #ident #ty_generics #where_clause {
+                fn standard(&self) -> &'static str {
+                    #standard
+                }
+                fn version(&self) -> &'static str {
+                    #version
+                }
+                fn event(&self) -> &'static str {
+                    match self {
+                        #event_arms
+                    }
+                }
+                fn data(&self) -> Option<near_sdk::serde_json::Value> {
+                    match self {
+                        #data_arms
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl EventInfo {
+    /// Generate the match arm that builds the data of the event.
+    fn data_arm(&self, path: &TokenStream2) -> TokenStream2 {
+        match &self.fields {
+            Fields::Named(fields) => {
+                let idents: Vec<_> =
+                    fields.named.iter().map(|f| f.ident.clone().unwrap()).collect();
+                let keys = idents.iter().map(|ident| ident.to_string());
+                quote! {
+                    #path { #(#idents),* } => {
+                        let mut data = near_sdk::serde_json::Map::new();
+                        #(data.insert(#keys.to_string(), near_sdk::events::event_data(#idents));)*
+                        Some(near_sdk::serde_json::Value::Object(data))
+                    }
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                #path(__f0) => Some(near_sdk::events::event_data(__f0)),
+            },
+            Fields::Unnamed(fields) => {
+                let idents: Vec<_> =
+                    (0..fields.unnamed.len()).map(|i| format_ident!("__f{}", i)).collect();
+                quote! {
+                    #path(#(#idents),*) => Some(near_sdk::serde_json::Value::Array(vec![
+                        #(near_sdk::events::event_data(#idents)),*
+                    ])),
+                }
+            }
+            Fields::Unit => quote! { #path => None, },
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::DeriveInput;
+    use quote::quote;
+    use crate::info_extractor::NearEventInfo;
+
+    #[test]
+    fn enum_events() {
+        let input: DeriveInput = syn::parse2(quote! {
+            #[near_event(standard = "nep171", version = "1.0.0")]
+            pub enum NftEvent {
+                NftMint { owner_id: String, token_ids: Vec<String> },
+                NftBurn(Vec<String>),
+                Paused,
+            }
+        }).unwrap();
+        let actual = NearEventInfo::new(&input).unwrap().near_event_impl();
+        let expected = quote!(
+            impl near_sdk::NearEvent for NftEvent {
+                fn standard(&self) -> &'static str {
+                    "nep171"
+                }
+                fn version(&self) -> &'static str {
+                    "1.0.0"
+                }
+                fn event(&self) -> &'static str {
+                    match self {
+                        Self::NftMint { .. } => "nft_mint",
+                        Self::NftBurn(..) => "nft_burn",
+                        Self::Paused => "paused",
+                    }
+                }
+                fn data(&self) -> Option<near_sdk::serde_json::Value> {
+                    match self {
+                        Self::NftMint { owner_id, token_ids } => {
+                            let mut data = near_sdk::serde_json::Map::new();
+                            data.insert("owner_id".to_string(), near_sdk::events::event_data(owner_id));
+                            data.insert("token_ids".to_string(), near_sdk::events::event_data(token_ids));
+                            Some(near_sdk::serde_json::Value::Object(data))
+                        }
+                        Self::NftBurn(__f0) => Some(near_sdk::events::event_data(__f0)),
+                        Self::Paused => None,
+                    }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn struct_event() {
+        let input: DeriveInput = syn::parse2(quote! {
+            #[near_event(standard = "nep141", version = "1.0.0")]
+            struct FtBurn<T>(String, T);
+        }).unwrap();
+        let actual = NearEventInfo::new(&input).unwrap().near_event_impl();
+        let expected = quote!(
+            impl<T> near_sdk::NearEvent for FtBurn<T> {
+                fn standard(&self) -> &'static str {
+                    "nep141"
+                }
+                fn version(&self) -> &'static str {
+                    "1.0.0"
+                }
+                fn event(&self) -> &'static str {
+                    match self {
+                        Self(..) => "ft_burn",
+                    }
+                }
+                fn data(&self) -> Option<near_sdk::serde_json::Value> {
+                    match self {
+                        Self(__f0, __f1) => Some(near_sdk::serde_json::Value::Array(vec![
+                            near_sdk::events::event_data(__f0),
+                            near_sdk::events::event_data(__f1)
+                        ])),
+                    }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn contract_event() {
+        let input: DeriveInput = syn::parse2(quote! {
+            #[near_event(contract = "crate::Token", version = "1.0.0")]
+            enum TokenEvent { Paused }
+        }).unwrap();
+        let actual = NearEventInfo::new(&input).unwrap().near_event_impl();
+        let expected = quote!(
+            impl near_sdk::NearEvent for TokenEvent {
+                fn standard(&self) -> &'static str {
+                    crate::Token::EVENT_PREFIX
+                }
+                fn version(&self) -> &'static str {
+                    "1.0.0"
+                }
+                fn event(&self) -> &'static str {
+                    match self {
+                        Self::Paused => "paused",
+                    }
+                }
+                fn data(&self) -> Option<near_sdk::serde_json::Value> {
+                    match self {
+                        Self::Paused => None,
+                    }
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+
+        let input: DeriveInput = syn::parse2(quote! {
+            #[near_event(standard = "nep171", contract = "Token", version = "1.0.0")]
+            enum TokenEvent { Paused }
+        }).unwrap();
+        assert!(NearEventInfo::new(&input).is_err());
+    }
+
+    #[test]
+    fn missing_version() {
+        let input: DeriveInput = syn::parse2(quote! {
+            #[near_event(standard = "nep171")]
+            enum NftEvent { NftMint }
+        }).unwrap();
+        assert!(NearEventInfo::new(&input).is_err());
+    }
+}
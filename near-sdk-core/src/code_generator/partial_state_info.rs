@@ -0,0 +1,154 @@
+use crate::info_extractor::PartialStateInfo;
+use quote::{format_ident, quote};
+use syn::export::TokenStream2;
+
+impl PartialStateInfo {
+    /// Generate the functions that read and write single fields of the stored state.
+    pub fn partial_state_impl(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let mut methods = TokenStream2::new();
+        // The statements that compute the offset of the current field.
+        let mut offset = TokenStream2::new();
+        // The size of the fixed-size fields since the last variable-size one.
+        let mut fixed = 0usize;
+        let mut after_variable = false;
+        for field in &self.fields {
+            let field_ident = &field.ident;
+            let ty = &field.ty;
+            let offset_stmt = if after_variable && fixed == 0 {
+                TokenStream2::new()
+            } else if after_variable {
+                quote! { let offset = offset + #fixed; }
+            } else {
+                quote! { let offset = #fixed; }
+            };
+            let read = format_ident!("read_state_{}", field_ident);
+            let write = format_ident!("write_state_{}", field_ident);
+            let read_doc = format!(" Reads `{}` from the stored state.", field_ident);
+            let write_doc = format!(" Writes `{}` to the stored state.", field_ident);
+            methods.extend(quote! {
+                #[doc = #read_doc]
+                pub fn #read() -> #ty {
+                    let data = near_sdk::partial_state::state_data();
+                    #offset
+                    #offset_stmt
+                    near_sdk::partial_state::read_field(&data, offset)
+                }
+                #[doc = #write_doc]
+                pub fn #write(value: &#ty) {
+                    let data = near_sdk::partial_state::state_data();
+                    #offset
+                    #offset_stmt
+                    near_sdk::partial_state::write_field(data, offset, value);
+                }
+            });
+            match field.fixed_size {
+                Some(size) => fixed += size,
+                None => {
+                    offset.extend(offset_stmt);
+                    offset.extend(quote! {
+                        let offset = near_sdk::partial_state::skip::<#ty>(&data, offset);
+                    });
+                    fixed = 0;
+                    after_variable = true;
+                }
+            }
+        }
+        quote! {
+            impl #ident {
+                #methods
+            }
+        }
+    }
+}
+
+// Rustfmt removes comas.
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use syn::DeriveInput;
+    use quote::quote;
+    use crate::info_extractor::PartialStateInfo;
+
+    #[test]
+    fn field_offsets() {
+        let input: DeriveInput = syn::parse2(quote! {
+            pub struct Contract {
+                total: u128,
+                owner: AccountId,
+                paused: bool,
+                #[borsh_skip]
+                cache: u64,
+                counts: [u32; 2],
+            }
+        }).unwrap();
+        let actual = PartialStateInfo::new(&input).unwrap().partial_state_impl();
+        let expected = quote!(
+            impl Contract {
+                #[doc = " Reads `total` from the stored state."]
+                pub fn read_state_total() -> u128 {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 0usize;
+                    near_sdk::partial_state::read_field(&data, offset)
+                }
+                #[doc = " Writes `total` to the stored state."]
+                pub fn write_state_total(value: &u128) {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 0usize;
+                    near_sdk::partial_state::write_field(data, offset, value);
+                }
+                #[doc = " Reads `owner` from the stored state."]
+                pub fn read_state_owner() -> AccountId {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 16usize;
+                    near_sdk::partial_state::read_field(&data, offset)
+                }
+                #[doc = " Writes `owner` to the stored state."]
+                pub fn write_state_owner(value: &AccountId) {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 16usize;
+                    near_sdk::partial_state::write_field(data, offset, value);
+                }
+                #[doc = " Reads `paused` from the stored state."]
+                pub fn read_state_paused() -> bool {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 16usize;
+                    let offset = near_sdk::partial_state::skip::<AccountId>(&data, offset);
+                    near_sdk::partial_state::read_field(&data, offset)
+                }
+                #[doc = " Writes `paused` to the stored state."]
+                pub fn write_state_paused(value: &bool) {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 16usize;
+                    let offset = near_sdk::partial_state::skip::<AccountId>(&data, offset);
+                    near_sdk::partial_state::write_field(data, offset, value);
+                }
+                #[doc = " Reads `counts` from the stored state."]
+                pub fn read_state_counts() -> [u32; 2] {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 16usize;
+                    let offset = near_sdk::partial_state::skip::<AccountId>(&data, offset);
+                    let offset = offset + 1usize;
+                    near_sdk::partial_state::read_field(&data, offset)
+                }
+                #[doc = " Writes `counts` to the stored state."]
+                pub fn write_state_counts(value: &[u32; 2]) {
+                    let data = near_sdk::partial_state::state_data();
+                    let offset = 16usize;
+                    let offset = near_sdk::partial_state::skip::<AccountId>(&data, offset);
+                    let offset = offset + 1usize;
+                    near_sdk::partial_state::write_field(data, offset, value);
+                }
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn tuple_struct() {
+        let input: DeriveInput = syn::parse2(quote! {
+            struct Contract(u64, String);
+        }).unwrap();
+        assert!(PartialStateInfo::new(&input).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::info_extractor::{ItemImplInfo, SerializerType};
+use proc_macro2::{Span, TokenTree};
+use quote::quote;
+use std::fs::OpenOptions;
+use std::io::Write;
+use syn::export::TokenStream2;
+use syn::ReturnType;
+
+/// The environment variable with the path of the code-size report. When it is set during the
+/// build, `#[near_bindgen]` appends a line for each exported method to the file.
+pub const SIZE_REPORT_ENV: &str = "NEAR_SDK_SIZE_REPORT";
+
+/// The number of tokens in the stream, counting the tokens inside the groups.
+fn count_tokens(stream: TokenStream2) -> usize {
+    stream
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Group(group) => 2 + count_tokens(group.stream()),
+            _ => 1,
+        })
+        .sum()
+}
+
+pub(crate) fn serializer_name(serializer: &SerializerType) -> &'static str {
+    match serializer {
+        SerializerType::JSON => "json",
+        SerializerType::Borsh => "borsh",
+    }
+}
+
+impl ItemImplInfo {
+    /// The lines of the code-size report for the exported methods of the impl section. Each line
+    /// has the method, the number of tokens of its generated wrapper, and the serializers of its
+    /// input and its result separated with tabs, or `-` if the method has no input or no result.
+    pub fn size_report(&self) -> String {
+        let ty = &self.ty;
+        let ty = quote! { #ty }.to_string();
+        let mut report = String::new();
+        for method in &self.methods {
+            if !method.is_public && !self.is_trait_impl {
+                continue;
+            }
+            let info = &method.attr_signature_info;
+            let input = if info.input_args().next().is_some() {
+                serializer_name(&info.input_serializer)
+            } else {
+                "-"
+            };
+            let result = match info.returns {
+                ReturnType::Type(..) if !info.is_init => serializer_name(&info.result_serializer),
+                _ => "-",
+            };
+            report.push_str(&format!(
+                "{}::{}\t{}\t{}\t{}\n",
+                ty,
+                info.ident,
+                count_tokens(method.method_wrapper()),
+                input,
+                result
+            ));
+        }
+        report
+    }
+
+    /// Appends the code-size report to the file from `NEAR_SDK_SIZE_REPORT`, if it is set.
+    pub fn write_size_report(&self) -> syn::Result<()> {
+        let path = match std::env::var_os(SIZE_REPORT_ENV) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(self.size_report().as_bytes()))
+            .map_err(|err| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!("Cannot write the code-size report to {:?}: {}", path, err),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::info_extractor::ItemImplInfo;
+    use syn::{parse_quote, ItemImpl};
+
+    #[test]
+    fn size_report() {
+        let mut input: ItemImpl = parse_quote! {
+            impl Contract {
+                #[init]
+                pub fn new(owner: AccountId) -> Self { }
+                pub fn get(&self) -> u64 { }
+                #[result_serializer(borsh)]
+                pub fn set(&mut self, #[serializer(borsh)] value: u64, #[serializer(borsh)] memo: String) -> u64 { }
+                pub fn ping(&mut self) { }
+                fn private(&self) { }
+            }
+        };
+        let report = ItemImplInfo::new(&mut input).unwrap().size_report();
+        let lines: Vec<Vec<&str>> = report.lines().map(|line| line.split('\t').collect()).collect();
+        assert_eq!(lines.len(), 4);
+        let columns =
+            |line: &Vec<&str>| (line[0].to_string(), line[2].to_string(), line[3].to_string());
+        assert_eq!(columns(&lines[0]), ("Contract::new".into(), "json".into(), "-".into()));
+        assert_eq!(columns(&lines[1]), ("Contract::get".into(), "-".into(), "json".into()));
+        assert_eq!(columns(&lines[2]), ("Contract::set".into(), "borsh".into(), "borsh".into()));
+        assert_eq!(columns(&lines[3]), ("Contract::ping".into(), "-".into(), "-".into()));
+        let size = |line: &Vec<&str>| line[1].parse::<usize>().unwrap();
+        assert!(size(&lines[2]) > size(&lines[3]));
+    }
+}
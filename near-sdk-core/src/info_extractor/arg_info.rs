@@ -1,7 +1,10 @@
+use crate::info_extractor::attr_sig_info::{check_no_args, generic_type_arg};
 use crate::info_extractor::serializer_attr::SerializerAttr;
+use crate::info_extractor::validate_attr::{ValidateAttr, ValidationRule};
 use crate::info_extractor::SerializerType;
 use quote::ToTokens;
 use syn::export::Span;
+use syn::spanned::Spanned;
 use syn::{Attribute, Error, Ident, Pat, PatType, Token, Type};
 
 pub enum BindgenArgType {
@@ -11,6 +14,9 @@ pub enum BindgenArgType {
     CallbackArg,
     /// An argument that we read from all `env::promise_result()`.
     CallbackArgVec,
+    /// An argument that we read from a single `env::promise_result()` as
+    /// `Result<T, PromiseError>`, without panicking when the promise failed.
+    CallbackResultArg,
 }
 
 /// A single argument of a function after it was processed by the bindgen.
@@ -33,6 +39,11 @@ pub struct ArgInfo {
     pub bindgen_ty: BindgenArgType,
     /// Type of serializer that we use for this argument.
     pub serializer_ty: SerializerType,
+    /// Where the serializer of the argument is set: the `#[serializer(...)]` attribute, or the
+    /// argument itself for the default JSON. The errors about the serializer point at it.
+    pub serializer_span: Span,
+    /// The checks of the argument, set with `#[validate(...)]`.
+    pub validations: Vec<ValidationRule>,
     /// The original `PatType` of the argument.
     pub original: PatType,
 }
@@ -52,7 +63,7 @@ impl ArgInfo {
             }
             _ => {
                 return Err(Error::new(
-                    Span::call_site(),
+                    original.pat.span(),
                     "Only identity patterns are supported in function arguments.",
                 ));
             }
@@ -62,24 +73,44 @@ impl ArgInfo {
                 (None, None, (*x).clone())
             }
             Type::Reference(r) => (Some(r.and_token), r.mutability, (*r.elem.as_ref()).clone()),
-            _ => return Err(Error::new(Span::call_site(), "Unsupported argument type.")),
+            _ => return Err(Error::new(original.ty.span(), "Unsupported argument type.")),
         };
         // In the absence of callback attributes this is a regular argument.
         let mut bindgen_ty = BindgenArgType::Regular;
         // In the absence of serialization attributes this is a JSON serialization.
         let mut serializer_ty = SerializerType::JSON;
+        let mut serializer_span = None;
+        let mut validations = vec![];
         for attr in &mut original.attrs {
             let attr_str = attr.path.to_token_stream().to_string();
             match attr_str.as_str() {
                 "callback" => {
+                    check_no_args(attr)?;
                     bindgen_ty = BindgenArgType::CallbackArg;
                 }
                 "callback_vec" => {
+                    check_no_args(attr)?;
                     bindgen_ty = BindgenArgType::CallbackArgVec;
                 }
+                "callback_result" => {
+                    check_no_args(attr)?;
+                    if generic_type_arg(&ty, "Result").is_none() {
+                        return Err(Error::new(
+                            ty.span(),
+                            "Argument with `#[callback_result]` should be a \
+                             `Result<T, near_sdk::PromiseError>`.",
+                        ));
+                    }
+                    bindgen_ty = BindgenArgType::CallbackResultArg;
+                }
                 "serializer" => {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
                     serializer_ty = serializer.serializer_type;
+                    serializer_span = Some(attr.span());
+                }
+                "validate" => {
+                    let validate: ValidateAttr = syn::parse2(attr.tokens.clone())?;
+                    validations.extend(validate.rules);
                 }
                 _ => {
                     non_bindgen_attrs.push((*attr).clone());
@@ -89,7 +120,11 @@ impl ArgInfo {
 
         original.attrs.retain(|attr| {
             let attr_str = attr.path.to_token_stream().to_string();
-            attr_str != "callback" && attr_str != "callback_vec" && attr_str != "serializer"
+            attr_str != "callback"
+                && attr_str != "callback_vec"
+                && attr_str != "callback_result"
+                && attr_str != "serializer"
+                && attr_str != "validate"
         });
 
         Ok(Self {
@@ -102,6 +137,8 @@ impl ArgInfo {
             ty,
             bindgen_ty,
             serializer_ty,
+            serializer_span: serializer_span.unwrap_or_else(|| original.span()),
+            validations,
             original: original.clone(),
         })
     }
@@ -0,0 +1,295 @@
+use proc_macro2::TokenTree;
+use quote::quote;
+use syn::export::TokenStream2;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{
+    Error, Expr, ExprAwait, FnArg, Ident, ImplItemMethod, LitInt, Local, Pat, PatIdent, Stmt, Type,
+};
+
+/// Information extracted from an `async` method of the contract with the experimental
+/// `async-methods` feature. The body is split at its `.await` statements into segments, and each
+/// segment after the first one becomes a callback of the promise awaited before it.
+pub struct AsyncMethodInfo {
+    /// The original method, without `#[continuation_gas(...)]`.
+    pub method: ImplItemMethod,
+    /// The gas of the callbacks, set with `#[continuation_gas(...)]`. `None` for
+    /// `wrapper::CONTINUATION_GAS`.
+    pub continuation_gas: Option<u64>,
+    /// The arguments of the method, the awaited results and the local variables bound to
+    /// identifiers with a type, in the order of their bindings. They are passed to the callbacks of
+    /// the later segments that use them.
+    pub variables: Vec<AsyncVariable>,
+    /// The segments of the body, in the order of execution.
+    pub segments: Vec<AsyncSegment>,
+}
+
+/// An argument of an `async` method, an awaited result, like `x` of `let x: u64 = promise.await;`,
+/// or a local variable, like `y` of `let y: u64 = x + 1;`.
+pub struct AsyncVariable {
+    /// The name of the variable.
+    pub ident: Ident,
+    /// The pattern that binds the variable, like `mut x`.
+    pub pat: Pat,
+    /// The type of the variable.
+    pub ty: Type,
+    /// The index of the segment where the variable is bound.
+    pub segment: usize,
+}
+
+/// The part of the body of an `async` method between two `.await` statements.
+pub struct AsyncSegment {
+    /// Whether the segment runs in the callback of the promise awaited before it, `false` for the
+    /// first segment.
+    pub is_continuation: bool,
+    /// The binding of the awaited result that starts the segment, like `x: u64` of
+    /// `let x: u64 = promise.await;`. `None` if the result is not used, like in `promise.await;`.
+    pub binding: Option<(Pat, Type)>,
+    /// The statements of the segment.
+    pub stmts: Vec<Stmt>,
+    /// The promise awaited at the end of the segment, `None` for the last segment.
+    pub promise: Option<Expr>,
+}
+
+/// Finds the `.await` expressions that are left after the body is split at its `.await`
+/// statements.
+#[derive(Default)]
+struct NestedAwaits {
+    awaits: Vec<ExprAwait>,
+}
+
+impl<'ast> Visit<'ast> for NestedAwaits {
+    fn visit_expr_await(&mut self, i: &'ast ExprAwait) {
+        self.awaits.push(i.clone());
+        syn::visit::visit_expr_await(self, i);
+    }
+}
+
+/// Finds the identifiers bound by a pattern, like `a` and `b` of `(a, mut b)`.
+#[derive(Default)]
+struct BoundIdents {
+    idents: Vec<Ident>,
+}
+
+impl<'ast> Visit<'ast> for BoundIdents {
+    fn visit_pat_ident(&mut self, i: &'ast PatIdent) {
+        self.idents.push(i.ident.clone());
+        syn::visit::visit_pat_ident(self, i);
+    }
+}
+
+impl AsyncMethodInfo {
+    /// Splits the body of the `async` method.
+    pub fn new(method: &ImplItemMethod) -> syn::Result<Self> {
+        let sig = &method.sig;
+        if !sig.generics.params.is_empty() {
+            return Err(Error::new(
+                sig.generics.span(),
+                "Async methods of the contract cannot be generic.",
+            ));
+        }
+        match sig.inputs.first() {
+            Some(FnArg::Receiver(receiver))
+                if receiver.reference.is_some() && receiver.mutability.is_some() => {}
+            receiver => {
+                let span = receiver.map_or_else(|| sig.ident.span(), |receiver| receiver.span());
+                return Err(Error::new(
+                    span,
+                    "Async methods of the contract should take `&mut self`, since they create \
+                     promises and write the contract state.",
+                ));
+            }
+        }
+        let mut method = method.clone();
+        let mut continuation_gas = None;
+        for attr in &method.attrs {
+            if attr.path.is_ident("continuation_gas") {
+                continuation_gas = Some(attr.parse_args::<LitInt>()?.base10_parse::<u64>()?);
+            }
+        }
+        method.attrs.retain(|attr| !attr.path.is_ident("continuation_gas"));
+        let mut variables = vec![];
+        for arg in &sig.inputs {
+            if let FnArg::Typed(pat_type) = arg {
+                if let Some(attr) = pat_type.attrs.first() {
+                    return Err(Error::new(
+                        attr.span(),
+                        "Arguments of async methods cannot have attributes, they are passed to the \
+                         callbacks as JSON.",
+                    ));
+                }
+                match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => variables.push(AsyncVariable {
+                        ident: pat_ident.ident.clone(),
+                        pat: pat_type.pat.as_ref().clone(),
+                        ty: pat_type.ty.as_ref().clone(),
+                        segment: 0,
+                    }),
+                    pat => {
+                        return Err(Error::new(
+                            pat.span(),
+                            "Arguments of async methods should be identifiers.",
+                        ))
+                    }
+                }
+            }
+        }
+
+        let mut segments = vec![];
+        let mut current =
+            AsyncSegment { is_continuation: false, binding: None, stmts: vec![], promise: None };
+        for stmt in &method.block.stmts {
+            let (promise, binding) = match stmt {
+                Stmt::Semi(Expr::Await(expr_await), _) => (expr_await.base.as_ref(), None),
+                Stmt::Local(local) => match awaited_local(local)? {
+                    Some((promise, binding)) => (promise, Some(binding)),
+                    None => {
+                        current.stmts.push(stmt.clone());
+                        continue;
+                    }
+                },
+                _ => {
+                    current.stmts.push(stmt.clone());
+                    continue;
+                }
+            };
+            current.promise = Some(promise.clone());
+            segments.push(current);
+            current = AsyncSegment { is_continuation: true, binding, stmts: vec![], promise: None };
+        }
+        segments.push(current);
+
+        let mut nested = NestedAwaits::default();
+        for segment in &segments {
+            for stmt in &segment.stmts {
+                nested.visit_stmt(stmt);
+            }
+            if let Some(promise) = &segment.promise {
+                nested.visit_expr(promise);
+            }
+        }
+        if let Some(expr_await) = nested.awaits.first() {
+            return Err(Error::new(
+                expr_await.span(),
+                "Only the statements like `let x: T = promise.await;` or `promise.await;` can await \
+                 in the async methods of the contract.",
+            ));
+        }
+
+        // The bindings are tracked in their order, so a binding shadows the earlier ones with the
+        // same name. The ones without a type can't be passed to the callbacks.
+        for (index, segment) in segments.iter().enumerate() {
+            let bindings = segment.binding.iter().map(|(pat, ty)| (pat, Some(ty))).chain(
+                segment.stmts.iter().filter_map(|stmt| match stmt {
+                    Stmt::Local(local) => Some(match &local.pat {
+                        Pat::Type(pat_type) => (pat_type.pat.as_ref(), Some(pat_type.ty.as_ref())),
+                        pat => (pat, None),
+                    }),
+                    _ => None,
+                }),
+            );
+            for (pat, ty) in bindings {
+                match (pat, ty) {
+                    (Pat::Ident(pat_ident), Some(ty)) if pat_ident.subpat.is_none() => variables
+                        .push(AsyncVariable {
+                            ident: pat_ident.ident.clone(),
+                            pat: pat.clone(),
+                            ty: ty.clone(),
+                            segment: index,
+                        }),
+                    _ => {
+                        let used = idents_used_from(&segments, index + 1);
+                        let mut bound = BoundIdents::default();
+                        bound.visit_pat(pat);
+                        if let Some(ident) = bound.idents.iter().find(|ident| used.contains(ident))
+                        {
+                            return Err(Error::new(
+                                ident.span(),
+                                format!(
+                                    "`{}` is used after `.await`, so it's passed to the callback \
+                                     and should be bound with a type, e.g. `let {}: u64 = ...;`.",
+                                    ident, ident
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Self { method, continuation_gas, variables, segments })
+    }
+
+    /// The name of the method that runs the segment with the given index.
+    pub fn segment_ident(&self, index: usize) -> Ident {
+        let ident = &self.method.sig.ident;
+        if index == 0 {
+            ident.clone()
+        } else {
+            Ident::new(&format!("__{}_then_{}", ident, index), ident.span())
+        }
+    }
+
+    /// The variables bound before the segment with the given index and used in the segments from
+    /// it on, so they are passed to the callback that runs the segment. A variable shadows the
+    /// earlier ones with the same name.
+    pub fn variables_used_from(&self, index: usize) -> Vec<&AsyncVariable> {
+        let used = idents_used_from(&self.segments, index);
+        let mut variables: Vec<&AsyncVariable> = vec![];
+        for variable in self.variables.iter().rev().filter(|variable| variable.segment < index) {
+            if used.contains(&variable.ident)
+                && variables.iter().all(|other| other.ident != variable.ident)
+            {
+                variables.insert(0, variable);
+            }
+        }
+        variables
+    }
+}
+
+/// Returns the awaited promise and the binding of `let x: T = promise.await;`.
+fn awaited_local(local: &Local) -> syn::Result<Option<(&Expr, (Pat, Type))>> {
+    let init = match &local.init {
+        Some((_, init)) => init.as_ref(),
+        None => return Ok(None),
+    };
+    let expr_await = match init {
+        Expr::Await(expr_await) => expr_await,
+        _ => return Ok(None),
+    };
+    match &local.pat {
+        Pat::Type(pat_type) => Ok(Some((
+            expr_await.base.as_ref(),
+            (pat_type.pat.as_ref().clone(), pat_type.ty.as_ref().clone()),
+        ))),
+        pat => Err(Error::new(
+            pat.span(),
+            "The result of `.await` needs a type to be deserialized, e.g. \
+             `let x: u64 = promise.await;`.",
+        )),
+    }
+}
+
+/// The identifiers used in the segments from the given index on.
+fn idents_used_from(segments: &[AsyncSegment], index: usize) -> Vec<Ident> {
+    let mut used = vec![];
+    for segment in segments.iter().skip(index) {
+        for stmt in &segment.stmts {
+            collect_idents(quote! { #stmt }, &mut used);
+        }
+        if let Some(promise) = &segment.promise {
+            collect_idents(quote! { #promise }, &mut used);
+        }
+    }
+    used
+}
+
+/// Collects the identifiers of the tokens, including the ones in the macros like `format!`.
+fn collect_idents(tokens: TokenStream2, idents: &mut Vec<Ident>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => idents.push(ident),
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
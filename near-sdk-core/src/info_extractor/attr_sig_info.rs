@@ -1,10 +1,15 @@
 use crate::info_extractor::arg_info::{ArgInfo, BindgenArgType};
 use crate::info_extractor::serializer_attr::SerializerAttr;
+use crate::info_extractor::NonReentrantAttr;
 use crate::info_extractor::SerializerType;
 use quote::ToTokens;
+use std::fmt::Display;
 use syn::export::Span;
 use syn::spanned::Spanned;
-use syn::{Attribute, Error, FnArg, Ident, Receiver, ReturnType, Signature};
+use syn::{
+    parse_quote, Attribute, Error, FnArg, GenericArgument, Ident, Lit, Meta, NestedMeta,
+    PathArguments, Receiver, ReturnType, Signature, Type,
+};
 
 /// Information extracted from method attributes and signature.
 pub struct AttrSigInfo {
@@ -16,8 +21,31 @@ pub struct AttrSigInfo {
     pub args: Vec<ArgInfo>,
     /// Whether method can be used as initializer.
     pub is_init: bool,
+    /// Whether the initializer may replace an existing state, set with `#[init(ignore_state)]`.
+    pub ignores_state: bool,
+    /// Whether the initializer returns `Result<Self, E>`. The wrapper panics with the error and
+    /// doesn't write the state on `Err`.
+    pub is_fallible_init: bool,
     /// Whether method accepting $NEAR.
     pub is_payable: bool,
+    /// Whether the method requires exactly 1 yoctoNEAR, set with `#[one_yocto]`.
+    pub is_one_yocto: bool,
+    /// Whether method can only be called by the owner of the contract.
+    pub is_only_owner: bool,
+    /// Whether method can only be called by the whitelisted accounts.
+    pub is_only_whitelisted: bool,
+    /// The feature that stops the method while it's paused, set with `#[if_paused(panic)]`.
+    pub pause_feature: Option<String>,
+    /// The roles that are allowed to call the method, set with
+    /// `#[access_control(roles = "...")]`. Empty if the method is not restricted to any roles.
+    pub access_control_roles: Vec<String>,
+    /// Whether the method starts or finishes a non-reentrant operation.
+    pub non_reentrant: Option<NonReentrantAttr>,
+    /// Whether the `Err` of the `Result` returned by the method is returned as `{"error": ...}`
+    /// instead of a panic, set with `#[graceful]`.
+    pub is_graceful: bool,
+    /// Whether the wrapper logs the gas used by the method, set with `#[measure_gas]`.
+    pub is_measure_gas: bool,
     /// The serializer that we use for `env::input()`.
     pub input_serializer: SerializerType,
     /// Whether the method doesn't mutate state
@@ -41,7 +69,8 @@ impl AttrSigInfo {
         if original_sig.asyncness.is_some() {
             return Err(Error::new(
                 original_sig.span(),
-                "Contract API is not allowed to be async.",
+                "Contract API is not allowed to be async. The experimental `async-methods` feature \
+                 of near-sdk compiles the async methods into promise callbacks.",
             ));
         }
         if original_sig.abi.is_some() {
@@ -61,24 +90,77 @@ impl AttrSigInfo {
         let mut non_bindgen_attrs = vec![];
         let mut args = vec![];
         let mut is_init = false;
+        let mut ignores_state = false;
         let mut is_payable = false;
+        let mut is_one_yocto = false;
+        let mut is_only_owner = false;
+        let mut is_only_whitelisted = false;
+        let mut pause_feature = None;
+        let mut access_control_roles = vec![];
+        let mut non_reentrant = None;
+        let mut graceful_attr = None;
+        let mut is_measure_gas = false;
+        // The attribute of a check that is inserted into the method body.
+        let mut guard_attr = None;
         // By the default we serialize the result with JSON.
         let mut result_serializer = SerializerType::JSON;
 
+        let mut init_attr = None;
         let mut payable_attr = None;
+        let mut one_yocto_attr = None;
+        let mut result_serializer_attr = None;
         for attr in original_attrs.iter() {
             let attr_str = attr.path.to_token_stream().to_string();
             match attr_str.as_str() {
                 "init" => {
+                    init_attr = Some(attr);
                     is_init = true;
+                    ignores_state = parse_init(attr)?;
                 }
                 "payable" => {
+                    check_no_args(attr)?;
                     payable_attr = Some(attr);
                     is_payable = true;
                 }
+                "one_yocto" => {
+                    check_no_args(attr)?;
+                    one_yocto_attr = Some(attr);
+                    is_one_yocto = true;
+                }
+                "only_owner" => {
+                    check_no_args(attr)?;
+                    is_only_owner = true;
+                    guard_attr = Some(attr);
+                }
+                "only_whitelisted" => {
+                    check_no_args(attr)?;
+                    is_only_whitelisted = true;
+                    guard_attr = Some(attr);
+                }
+                "if_paused" => {
+                    let feature = parse_if_paused(attr)?.unwrap_or_else(|| ident.to_string());
+                    pause_feature = Some(feature);
+                    guard_attr = Some(attr);
+                }
+                "access_control" => {
+                    access_control_roles = parse_access_control(attr)?;
+                    guard_attr = Some(attr);
+                }
+                "non_reentrant" => {
+                    non_reentrant = Some(syn::parse2(attr.tokens.clone())?);
+                }
+                "graceful" => {
+                    check_no_args(attr)?;
+                    graceful_attr = Some(attr);
+                }
+                "measure_gas" => {
+                    check_no_args(attr)?;
+                    is_measure_gas = true;
+                }
                 "result_serializer" => {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
                     result_serializer = serializer.serializer_type;
+                    result_serializer_attr = Some(attr);
                 }
                 _ => {
                     non_bindgen_attrs.push((*attr).clone());
@@ -102,21 +184,134 @@ impl AttrSigInfo {
             !is_init
         };
 
+        // The notes point at what makes the method a view method or an initializer.
+        let view_note = || match &receiver {
+            Some(receiver) => {
+                (receiver.span(), format!("note: `{}` is a view method, it takes `&self`", ident))
+            }
+            None => (
+                ident.span(),
+                format!("note: `{}` is a view method, it doesn't take `self`", ident),
+            ),
+        };
+        let init_note = |init_attr: Option<&Attribute>| {
+            let span = init_attr.map_or_else(|| ident.span(), |attr| attr.span());
+            (span, format!("note: `{}` is an initializer", ident))
+        };
+
         if let Some(payable_attr) = payable_attr {
             if is_view {
-                return Err(Error::new(
+                let (note_span, note) = view_note();
+                return Err(error_with_note(
                     payable_attr.span(),
                     "Payable method must be mutable (not view)",
+                    note_span,
+                    note,
+                ));
+            }
+        }
+
+        if let Some(one_yocto_attr) = one_yocto_attr {
+            if is_view || is_payable {
+                let (note_span, note) = match payable_attr {
+                    Some(payable_attr) => {
+                        (payable_attr.span(), format!("note: `{}` is payable", ident))
+                    }
+                    None => view_note(),
+                };
+                return Err(error_with_note(
+                    one_yocto_attr.span(),
+                    "Method with `#[one_yocto]` must be mutable (not view) and not payable",
+                    note_span,
+                    note,
+                ));
+            }
+        }
+
+        if let Some(guard_attr) = guard_attr {
+            if receiver.is_none() || is_init {
+                let (note_span, note) = if is_init {
+                    init_note(init_attr)
+                } else {
+                    (ident.span(), format!("note: `{}` doesn't take `self`", ident))
+                };
+                return Err(error_with_note(
+                    guard_attr.span(),
+                    format!(
+                        "Method with `#[{}]` must take `self` and cannot be an initializer",
+                        guard_attr.path.to_token_stream()
+                    ),
+                    note_span,
+                    note,
+                ));
+            }
+        }
+
+        if let Some(graceful_attr) = graceful_attr {
+            let note = if is_init {
+                Some(init_note(init_attr))
+            } else if let ReturnType::Default = original_sig.output {
+                Some((ident.span(), format!("note: `{}` doesn't return a value", ident)))
+            } else if result_serializer != SerializerType::JSON {
+                let span = result_serializer_attr.map_or_else(|| ident.span(), |attr| attr.span());
+                Some((span, format!("note: the result of `{}` is serialized with Borsh", ident)))
+            } else {
+                match &original_sig.output {
+                    ReturnType::Type(_, ty) if generic_type_arg(ty, "Result").is_none() => {
+                        Some((ty.span(), format!("note: `{}` doesn't return a `Result`", ident)))
+                    }
+                    _ => None,
+                }
+            };
+            if let Some((note_span, note)) = note {
+                return Err(error_with_note(
+                    graceful_attr.span(),
+                    "Method with `#[graceful]` must return a `Result` serialized with JSON and cannot be an initializer",
+                    note_span,
+                    note,
+                ));
+            }
+        }
+
+        // A panic rolls back the release of the lock, so the lock would stay taken.
+        if let Some(NonReentrantAttr::Unlock(_)) = &non_reentrant {
+            let callback = args.iter().find(|arg| {
+                matches!(
+                    arg.bindgen_ty,
+                    BindgenArgType::CallbackArg | BindgenArgType::CallbackArgVec
+                )
+            });
+            if let Some(arg) = callback {
+                return Err(Error::new(
+                    arg.original.span(),
+                    "The method with `#[non_reentrant(unlock = ...)]` cannot panic on the results of \
+                     the promises, use `#[callback_result] x: Result<T, near_sdk::PromiseError>`.",
                 ));
             }
         }
 
+        let is_graceful = graceful_attr.is_some();
         original_attrs.retain(|attr| {
             let attr_str = attr.path.to_token_stream().to_string();
-            attr_str != "init" && attr_str != "result_serializer" && attr_str != "payable"
+            attr_str != "init"
+                && attr_str != "result_serializer"
+                && attr_str != "payable"
+                && attr_str != "one_yocto"
+                && attr_str != "only_owner"
+                && attr_str != "only_whitelisted"
+                && attr_str != "if_paused"
+                && attr_str != "access_control"
+                && attr_str != "non_reentrant"
+                && attr_str != "graceful"
+                && attr_str != "measure_gas"
         });
 
         let returns = original_sig.output.clone();
+        let is_fallible_init = is_init
+            && match &returns {
+                ReturnType::Type(_, ty) => generic_type_arg(ty, "Result").is_some(),
+                ReturnType::Default => false,
+            };
 
         let mut result = Self {
             ident,
@@ -124,7 +319,17 @@ impl AttrSigInfo {
             args,
             input_serializer: SerializerType::JSON,
             is_init,
+            ignores_state,
+            is_fallible_init,
             is_payable,
+            is_one_yocto,
+            is_only_owner,
+            is_only_whitelisted,
+            pause_feature,
+            access_control_roles,
+            non_reentrant,
+            is_graceful,
+            is_measure_gas,
             is_view,
             result_serializer,
             receiver,
@@ -132,19 +337,68 @@ impl AttrSigInfo {
             original_sig: original_sig.clone(),
         };
 
-        let input_serializer =
-            if result.input_args().all(|arg: &ArgInfo| arg.serializer_ty == SerializerType::JSON) {
-                SerializerType::JSON
-            } else if result.input_args().all(|arg| arg.serializer_ty == SerializerType::Borsh) {
-                SerializerType::Borsh
-            } else {
+        result.input_serializer = result.input_args_serializer()?;
+        Ok(result)
+    }
+
+    /// Returns the serializer of the input arguments. They are deserialized from a single input, so
+    /// they have to use the same one.
+    fn input_args_serializer(&self) -> syn::Result<SerializerType> {
+        let mut input_args = self.input_args();
+        let first = match input_args.next() {
+            Some(first) => first,
+            None => return Ok(SerializerType::JSON),
+        };
+        if let Some(arg) = input_args.find(|arg| arg.serializer_ty != first.serializer_ty) {
+            let message = format!(
+                "Input arguments should be all of the same serialization type, `{}` is serialized \
+                 with {} and `{}` with {}.",
+                arg.ident, arg.serializer_ty, first.ident, first.serializer_ty
+            );
+            let note = format!(
+                "note: the arguments of `{}` are deserialized from a single input",
+                self.ident
+            );
+            return Err(error_with_note(arg.serializer_span, message, self.ident.span(), note));
+        }
+        match first.serializer_ty {
+            SerializerType::JSON => Ok(SerializerType::JSON),
+            SerializerType::Borsh => Ok(SerializerType::Borsh),
+        }
+    }
+
+    /// Checks that the wrapper of the method doesn't use JSON, which is not available without the
+    /// `json` feature of near-sdk.
+    pub fn check_borsh_only(&self) -> syn::Result<()> {
+        if let Some(arg) = self.args.iter().find(|arg| arg.serializer_ty == SerializerType::JSON) {
+            return Err(Error::new(
+                arg.serializer_span,
+                "The `json` feature of near-sdk is off, use `#[serializer(borsh)]` on the argument.",
+            ));
+        }
+        if let ReturnType::Type(_, ty) = &self.returns {
+            if self.result_serializer == SerializerType::JSON {
                 return Err(Error::new(
-                    Span::call_site(),
-                    "Input arguments should be all of the same serialization type.",
+                    ty.span(),
+                    "The `json` feature of near-sdk is off, use `#[result_serializer(borsh)]` on the method.",
                 ));
-            };
-        result.input_serializer = input_serializer;
-        Ok(result)
+            }
+        }
+        Ok(())
+    }
+
+    /// The owned type with the same serialized form as the result of the method, e.g. `String` for
+    /// `&str` or `Cow<str>`, and `Vec<T>` for `&[T]`. A view method can return the data borrowed
+    /// from the state, but the client and the metadata need the type of the deserialized value.
+    /// It's `Self` for the initializer that returns `Result<Self, E>`.
+    pub fn owned_result_type(&self) -> Option<Type> {
+        match &self.returns {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) if self.is_fallible_init => {
+                generic_type_arg(ty, "Result").cloned()
+            }
+            ReturnType::Type(_, ty) => Some(owned_type(ty)),
+        }
     }
 
     /// Only get args that correspond to `env::input()`.
@@ -155,3 +409,162 @@ impl AttrSigInfo {
         })
     }
 }
+
+/// Replaces the borrowed types with the owned ones, see `AttrSigInfo::owned_result_type`.
+fn owned_type(ty: &Type) -> Type {
+    match ty {
+        Type::Reference(reference) => match reference.elem.as_ref() {
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+                parse_quote! { String }
+            }
+            Type::Slice(slice) => {
+                let elem = &slice.elem;
+                parse_quote! { Vec<#elem> }
+            }
+            elem => owned_type(elem),
+        },
+        ty => match generic_type_arg(ty, "Cow") {
+            Some(borrowed) => owned_type(&parse_quote! { &#borrowed }),
+            None => ty.clone(),
+        },
+    }
+}
+
+/// The first type argument of the path type with the last segment `name`, e.g. `T` of `Cow<T>`.
+pub(crate) fn generic_type_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// An error on `span` with a note on `note_span`. The compiler reports the note as another error,
+/// so that it can point at the other part of the method that conflicts with the first one.
+fn error_with_note(
+    span: Span,
+    message: impl Display,
+    note_span: Span,
+    note: impl Display,
+) -> Error {
+    let mut error = Error::new(span, message);
+    error.combine(Error::new(note_span, note));
+    error
+}
+
+/// Checks that an attribute like `#[payable]` is used without arguments, so that e.g.
+/// `#[payable(true)]` is not silently taken for `#[payable]`.
+pub(crate) fn check_no_args(attr: &Attribute) -> syn::Result<()> {
+    if attr.tokens.is_empty() {
+        return Ok(());
+    }
+    Err(Error::new(
+        attr.tokens.span(),
+        format!("`#[{}]` doesn't take arguments.", attr.path.to_token_stream()),
+    ))
+}
+
+/// Parses `#[init]` or `#[init(ignore_state)]`, and returns whether the state is ignored.
+fn parse_init(attr: &Attribute) -> syn::Result<bool> {
+    if attr.tokens.is_empty() {
+        return Ok(false);
+    }
+    let err = || Error::new(attr.span(), "Expected `#[init]` or `#[init(ignore_state)]`.");
+    let list = match attr.parse_meta()? {
+        Meta::List(list) => list,
+        _ => return Err(err()),
+    };
+    let mut nested = list.nested.iter();
+    match nested.next() {
+        Some(NestedMeta::Meta(Meta::Path(path))) if path.is_ident("ignore_state") => {}
+        Some(other) => {
+            return Err(Error::new(
+                other.span(),
+                format!(
+                    "Unknown argument `{}` of `#[init]`, the only argument is `ignore_state`.",
+                    other.to_token_stream()
+                ),
+            ))
+        }
+        None => return Err(err()),
+    }
+    match nested.next() {
+        Some(other) => Err(Error::new(other.span(), "`#[init]` takes a single `ignore_state`.")),
+        None => Ok(true),
+    }
+}
+
+/// Parses `#[if_paused(panic)]` or `#[if_paused(panic, feature = "...")]`, returning the feature if
+/// it is given explicitly.
+fn parse_if_paused(attr: &Attribute) -> syn::Result<Option<String>> {
+    let err = || {
+        Error::new(
+            attr.span(),
+            "Expected `#[if_paused(panic)]` or `#[if_paused(panic, feature = \"...\")]`.",
+        )
+    };
+    let list = match attr.parse_meta()? {
+        Meta::List(list) => list,
+        _ => return Err(err()),
+    };
+    let mut nested = list.nested.iter();
+    match nested.next() {
+        Some(NestedMeta::Meta(Meta::Path(path))) if path.is_ident("panic") => {}
+        _ => return Err(err()),
+    }
+    let feature = match nested.next() {
+        None => None,
+        Some(NestedMeta::Meta(Meta::NameValue(name_value)))
+            if name_value.path.is_ident("feature") =>
+        {
+            match &name_value.lit {
+                Lit::Str(feature) => Some(feature.value()),
+                _ => return Err(err()),
+            }
+        }
+        Some(_) => return Err(err()),
+    };
+    if nested.next().is_some() {
+        return Err(err());
+    }
+    Ok(feature)
+}
+
+/// Parses `#[access_control(roles = "...")]`, where the roles are separated with commas.
+fn parse_access_control(attr: &Attribute) -> syn::Result<Vec<String>> {
+    let err = || Error::new(attr.span(), "Expected `#[access_control(roles = \"...\")]`.");
+    let list = match attr.parse_meta()? {
+        Meta::List(list) => list,
+        _ => return Err(err()),
+    };
+    if list.nested.len() != 1 {
+        return Err(err());
+    }
+    let roles = match &list.nested[0] {
+        NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("roles") => {
+            match &name_value.lit {
+                Lit::Str(roles) => roles.value(),
+                _ => return Err(err()),
+            }
+        }
+        _ => return Err(err()),
+    };
+    let roles: Vec<String> = roles
+        .split(',')
+        .map(|role| role.trim().to_string())
+        .filter(|role| !role.is_empty())
+        .collect();
+    if roles.is_empty() {
+        return Err(Error::new(attr.span(), "At least one role is required."));
+    }
+    Ok(roles)
+}
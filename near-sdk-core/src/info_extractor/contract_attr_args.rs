@@ -0,0 +1,87 @@
+use crate::info_extractor::contract_metadata_info::{parse_standard, string_value};
+use crate::info_extractor::ContractMetadataInfo;
+use syn::export::TokenStream2;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Error, Meta, NestedMeta, Token};
+
+/// The configuration of the contract, extracted from `#[near_bindgen(...)]` on the contract struct.
+/// The arguments are parsed once, and each generated feature takes its part of them.
+#[derive(Default)]
+pub struct ContractAttrArgs {
+    /// The metadata of NEP-330, set with `contract_metadata(...)` or `metadata(...)`. The
+    /// `contract_source_metadata` method is generated if it's set.
+    pub contract_metadata: Option<ContractMetadataInfo>,
+    /// Whether the `version` method is generated, set with `contract_version`.
+    pub contract_version: bool,
+    /// Whether the fields of the contract are stored under their own keys, set with `lazy_fields`.
+    pub lazy_fields: bool,
+    /// The standard of the events of the contract, set with `event_prefix = "..."`. It's the
+    /// `EVENT_PREFIX` of the contract that `#[near_event(contract = "...")]` uses.
+    pub event_prefix: Option<String>,
+    /// Whether the methods of the contract check the schema of the state, set with
+    /// `state_schema`.
+    pub state_schema: bool,
+}
+
+impl ContractAttrArgs {
+    /// Parses the arguments of `near_bindgen` on the contract struct.
+    pub fn parse(args: TokenStream2) -> syn::Result<Self> {
+        let args = Punctuated::<NestedMeta, Token![,]>::parse_terminated.parse2(args)?;
+        let mut result = Self::default();
+        // The standards of `metadata(...)` are added to the ones of `contract_metadata(...)`.
+        let mut has_contract_metadata = false;
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("contract_metadata") => {
+                    if has_contract_metadata {
+                        return Err(Error::new(
+                            list.span(),
+                            "`contract_metadata(...)` can only be set once.",
+                        ));
+                    }
+                    has_contract_metadata = true;
+                    let info = ContractMetadataInfo::new(list)?;
+                    let metadata = result.contract_metadata.get_or_insert_with(Default::default);
+                    metadata.version = info.version;
+                    metadata.link = info.link;
+                    metadata.standards.extend(info.standards);
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("metadata") => {
+                    let standard = parse_standard(&list)?;
+                    let metadata = result.contract_metadata.get_or_insert_with(Default::default);
+                    metadata.standards.push(standard);
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("contract_version") => {
+                    result.contract_version = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("lazy_fields") => {
+                    result.lazy_fields = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("state_schema") => {
+                    result.state_schema = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("event_prefix") =>
+                {
+                    let prefix = string_value(&name_value.lit)?;
+                    if prefix.is_empty() {
+                        return Err(Error::new(
+                            name_value.lit.span(),
+                            "The event prefix can't be empty.",
+                        ));
+                    }
+                    result.event_prefix = Some(prefix);
+                }
+                arg => {
+                    return Err(Error::new(
+                        arg.span(),
+                        "Unsupported near_bindgen argument, expected `contract_metadata(...)`, `metadata(...)`, `contract_version`, `lazy_fields`, `state_schema` or `event_prefix = \"...\"`.",
+                    ))
+                }
+            }
+        }
+        Ok(result)
+    }
+}
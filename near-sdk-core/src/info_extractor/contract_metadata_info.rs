@@ -0,0 +1,100 @@
+use crate::info_extractor::ContractAttrArgs;
+use quote::ToTokens;
+use syn::export::TokenStream2;
+use syn::spanned::Spanned;
+use syn::{Error, Lit, Meta, MetaList, NestedMeta};
+
+/// Information extracted from `#[near_bindgen(contract_metadata(...))]` on the contract struct.
+#[derive(Default)]
+pub struct ContractMetadataInfo {
+    /// Version of the contract. Taken from the build environment if not set.
+    pub version: Option<String>,
+    /// Link to the source code. Taken from the build environment if not set.
+    pub link: Option<String>,
+    /// Pairs of the standard and its version.
+    pub standards: Vec<(String, String)>,
+}
+
+impl ContractMetadataInfo {
+    /// Parses the arguments of `near_bindgen`. Returns `None` if neither `contract_metadata` nor
+    /// `metadata` is there.
+    pub fn from_attr_args(args: TokenStream2) -> syn::Result<Option<Self>> {
+        Ok(ContractAttrArgs::parse(args)?.contract_metadata)
+    }
+
+    /// Parses `contract_metadata(...)`.
+    pub(crate) fn new(list: MetaList) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                    let value = string_value(&name_value.lit)?;
+                    if name_value.path.is_ident("version") {
+                        result.version = Some(value);
+                    } else if name_value.path.is_ident("link") {
+                        result.link = Some(value);
+                    } else {
+                        return Err(Error::new(
+                            name_value.path.span(),
+                            "Unsupported contract_metadata argument, expected `version`, `link` or `standard(...)`.",
+                        ));
+                    }
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("standard") => {
+                    result.standards.push(parse_standard(&list)?);
+                }
+                nested => {
+                    return Err(Error::new(
+                        nested.span(),
+                        "Unsupported contract_metadata argument, expected `version`, `link` or `standard(...)`.",
+                    ))
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Parses the standard and its version from `standard(standard = "...", version = "...")` in
+/// `contract_metadata(...)`, or from `metadata(standard = "...", version = "...")`.
+pub(crate) fn parse_standard(list: &MetaList) -> syn::Result<(String, String)> {
+    let mut standard = None;
+    let mut version = None;
+    for nested in &list.nested {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if name_value.path.is_ident("standard") =>
+            {
+                standard = Some(string_value(&name_value.lit)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if name_value.path.is_ident("version") =>
+            {
+                version = Some(string_value(&name_value.lit)?);
+            }
+            nested => {
+                return Err(Error::new(
+                    nested.span(),
+                    "Expected `standard = \"...\"` or `version = \"...\"`.",
+                ))
+            }
+        }
+    }
+    match (standard, version) {
+        (Some(standard), Some(version)) => Ok((standard, version)),
+        _ => Err(Error::new(
+            list.span(),
+            format!(
+                "Expected `{}(standard = \"...\", version = \"...\")`.",
+                list.path.to_token_stream()
+            ),
+        )),
+    }
+}
+
+pub(crate) fn string_value(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(value) => Ok(value.value()),
+        lit => Err(Error::new(lit.span(), "Expected a string literal.")),
+    }
+}
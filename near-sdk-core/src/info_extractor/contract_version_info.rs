@@ -0,0 +1,12 @@
+use crate::info_extractor::ContractAttrArgs;
+use syn::export::TokenStream2;
+
+/// Information extracted from `#[near_bindgen(contract_version)]` on the contract struct.
+pub struct ContractVersionInfo;
+
+impl ContractVersionInfo {
+    /// Parses the arguments of `near_bindgen`. Returns `None` if `contract_version` is not there.
+    pub fn from_attr_args(args: TokenStream2) -> syn::Result<Option<Self>> {
+        Ok(if ContractAttrArgs::parse(args)?.contract_version { Some(Self) } else { None })
+    }
+}
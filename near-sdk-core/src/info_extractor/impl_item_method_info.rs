@@ -1,5 +1,5 @@
-use crate::info_extractor::AttrSigInfo;
-use syn::{ImplItemMethod, Type, Visibility};
+use crate::info_extractor::{AttrSigInfo, NonReentrantAttr};
+use syn::{parse_quote, ImplItemMethod, Type, Visibility};
 
 /// Information extracted from `ImplItemMethod`.
 pub struct ImplItemMethodInfo {
@@ -9,6 +9,15 @@ pub struct ImplItemMethodInfo {
     pub is_public: bool,
     /// The type of the contract struct.
     pub struct_type: Type,
+    /// Whether the wrapper logs the `method_failed` event when the method fails, set by
+    /// `#[near_bindgen(failure_events)]` on the impl section.
+    pub failure_events: bool,
+    /// The largest input in bytes that the wrapper deserializes, set by
+    /// `#[near_bindgen(max_input_len = ...)]` on the impl section.
+    pub max_input_len: Option<u64>,
+    /// Whether the wrapper is called by the entry point of the impl section instead of being
+    /// exported, set by `#[near_bindgen(dispatch = "...")]` on the impl section.
+    pub is_dispatched: bool,
 }
 
 impl ImplItemMethodInfo {
@@ -16,10 +25,38 @@ impl ImplItemMethodInfo {
     pub fn new(original: &mut ImplItemMethod, struct_type: Type) -> syn::Result<Self> {
         let ImplItemMethod { attrs, sig, .. } = original;
         let attr_signature_info = AttrSigInfo::new(attrs, sig)?;
+        // The checks are a part of the method itself, so they also guard the calls from the tests
+        // and from the other methods of the contract.
+        let mut checks = vec![];
+        if attr_signature_info.is_only_owner {
+            checks.push(parse_quote! { self.assert_owner(); });
+        }
+        if attr_signature_info.is_only_whitelisted {
+            checks.push(parse_quote! { self.assert_whitelisted(); });
+        }
+        if !attr_signature_info.access_control_roles.is_empty() {
+            let roles = &attr_signature_info.access_control_roles;
+            checks.push(parse_quote! { self.assert_any_role(&[#(#roles),*]); });
+        }
+        if let Some(feature) = &attr_signature_info.pause_feature {
+            checks.push(parse_quote! { self.assert_not_paused(#feature); });
+        }
+        // The lock is released by the wrapper, see `method_wrapper`.
+        if let Some(NonReentrantAttr::Lock(operation)) = &attr_signature_info.non_reentrant {
+            checks.push(parse_quote! { near_sdk::reentrancy::lock(#operation); });
+        }
+        original.block.stmts.splice(0..0, checks);
         let is_public = match original.vis {
             Visibility::Public(_) => true,
             _ => false,
         };
-        Ok(Self { attr_signature_info, is_public, struct_type })
+        Ok(Self {
+            attr_signature_info,
+            is_public,
+            struct_type,
+            failure_events: false,
+            max_input_len: None,
+            is_dispatched: false,
+        })
     }
 }
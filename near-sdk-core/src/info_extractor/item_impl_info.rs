@@ -1,6 +1,9 @@
 use crate::ImplItemMethodInfo;
+use syn::export::TokenStream2;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Error, ImplItem, ItemImpl, Type};
+use syn::{Error, Ident, ImplItem, ItemImpl, Lit, Meta, NestedMeta, Token, Type};
 
 /// Information extracted from `impl` section.
 pub struct ItemImplInfo {
@@ -10,6 +13,10 @@ pub struct ItemImplInfo {
     pub ty: Type,
     /// Info extracted for each method.
     pub methods: Vec<ImplItemMethodInfo>,
+    /// The name of the trait with the public methods, set by `#[near_bindgen(interface = "...")]`.
+    pub interface: Option<Ident>,
+    /// The single entry point of the methods, set by `#[near_bindgen(dispatch = "...")]`.
+    pub dispatch: Option<Ident>,
 }
 
 impl ItemImplInfo {
@@ -27,9 +34,85 @@ impl ItemImplInfo {
         for subitem in &mut original.items {
             if let ImplItem::Method(m) = subitem {
                 let method_info = ImplItemMethodInfo::new(m, ty.clone())?;
+                if !cfg!(feature = "json") && (method_info.is_public || is_trait_impl) {
+                    method_info.attr_signature_info.check_borsh_only()?;
+                }
                 methods.push(method_info);
             }
         }
-        Ok(Self { is_trait_impl, ty, methods })
+        Ok(Self { is_trait_impl, ty, methods, interface: None, dispatch: None })
     }
+
+    /// Applies the arguments of `near_bindgen` on the impl section.
+    pub fn apply_attr_args(&mut self, args: TokenStream2) -> syn::Result<()> {
+        if is_legacy_init(&args) {
+            return Ok(());
+        }
+        let args = Punctuated::<NestedMeta, Token![,]>::parse_terminated.parse2(args)?;
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("state_schema") => {
+                    return Err(Error::new(
+                        path.span(),
+                        "`state_schema` is a setting of the whole contract, set it with `#[near_bindgen(state_schema)]` on the contract struct.",
+                    ));
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("failure_events") => {
+                    for method in &mut self.methods {
+                        method.failure_events = true;
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("max_input_len") =>
+                {
+                    let max_input_len = match &name_value.lit {
+                        Lit::Int(len) => len.base10_parse::<u64>()?,
+                        lit => return Err(Error::new(lit.span(), "Expected an integer literal.")),
+                    };
+                    for method in &mut self.methods {
+                        method.max_input_len = Some(max_input_len);
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("interface") =>
+                {
+                    if self.is_trait_impl {
+                        return Err(Error::new(
+                            name_value.span(),
+                            "The interface can only be extracted from an inherent impl section.",
+                        ));
+                    }
+                    self.interface = match &name_value.lit {
+                        Lit::Str(name) => Some(name.parse()?),
+                        lit => return Err(Error::new(lit.span(), "Expected a string literal.")),
+                    };
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("dispatch") =>
+                {
+                    self.dispatch = match &name_value.lit {
+                        Lit::Str(name) => Some(name.parse()?),
+                        lit => return Err(Error::new(lit.span(), "Expected a string literal.")),
+                    };
+                    for method in &mut self.methods {
+                        method.is_dispatched = true;
+                    }
+                }
+                arg => {
+                    return Err(Error::new(
+                        arg.span(),
+                        "Unsupported near_bindgen argument, expected `failure_events`, `max_input_len = ...`, `interface = \"...\"` or `dispatch = \"...\"`.",
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the arguments are `init => new` of the older versions of `near_bindgen`, which are
+/// ignored since the initializers are marked with `#[init]`.
+fn is_legacy_init(args: &TokenStream2) -> bool {
+    let tokens: Vec<String> = args.clone().into_iter().map(|token| token.to_string()).collect();
+    tokens.len() == 4 && tokens[0] == "init" && tokens[1] == "=" && tokens[2] == ">"
 }
@@ -0,0 +1,73 @@
+use crate::info_extractor::ContractAttrArgs;
+use syn::export::TokenStream2;
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, Fields, Ident, ItemStruct, Type, Visibility};
+
+/// Information extracted from the contract struct with `#[near_bindgen(lazy_fields)]`.
+pub struct LazyFieldsInfo {
+    /// The attributes of the struct.
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the struct.
+    pub vis: Visibility,
+    /// The name of the struct.
+    pub ident: Ident,
+    /// The fields of the struct.
+    pub fields: Vec<LazyFieldInfo>,
+}
+
+/// Information about a single field of the contract struct.
+pub struct LazyFieldInfo {
+    /// The attributes of the field.
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the field, also used for its accessors.
+    pub vis: Visibility,
+    /// The name of the field.
+    pub ident: Ident,
+    /// The type of the value of the field.
+    pub ty: Type,
+}
+
+impl LazyFieldsInfo {
+    /// Parses the arguments of `near_bindgen`. Returns `None` if `lazy_fields` is not there.
+    pub fn from_attr_args(args: TokenStream2, input: &ItemStruct) -> syn::Result<Option<Self>> {
+        if ContractAttrArgs::parse(args)?.lazy_fields {
+            Self::new(input).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Extracts the fields of the contract struct with `lazy_fields`.
+    pub fn new(input: &ItemStruct) -> syn::Result<Self> {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                input.generics.span(),
+                "Contract struct with lazy fields cannot be generic.",
+            ));
+        }
+        let named = match &input.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.fields.span(),
+                    "Contract struct with lazy fields should have named fields.",
+                ))
+            }
+        };
+        let fields = named
+            .iter()
+            .map(|field| LazyFieldInfo {
+                attrs: field.attrs.clone(),
+                vis: field.vis.clone(),
+                ident: field.ident.clone().unwrap(),
+                ty: field.ty.clone(),
+            })
+            .collect();
+        Ok(Self {
+            attrs: input.attrs.clone(),
+            vis: input.vis.clone(),
+            ident: input.ident.clone(),
+            fields,
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Type};
+
+/// Information extracted from the enum deriving `Migratable`.
+pub struct MigratableInfo {
+    /// The name of the enum.
+    pub ident: Ident,
+    /// The versions of the state, from the oldest to the current one.
+    pub versions: Vec<VersionInfo>,
+}
+
+/// Information about a single version of the state.
+pub struct VersionInfo {
+    /// The name of the variant.
+    pub ident: Ident,
+    /// The struct of the state of the version.
+    pub ty: Type,
+}
+
+impl MigratableInfo {
+    pub fn new(input: &DeriveInput) -> syn::Result<Self> {
+        let data = match &input.data {
+            Data::Enum(data) => data,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "Migratable can only be derived for enums.",
+                ))
+            }
+        };
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                input.generics.span(),
+                "Migratable cannot be derived for a generic enum.",
+            ));
+        }
+        if data.variants.is_empty() {
+            return Err(Error::new(
+                input.ident.span(),
+                "Migratable cannot be derived for an enum without variants.",
+            ));
+        }
+        if data.variants.len() > usize::from(u8::MAX) + 1 {
+            return Err(Error::new(
+                input.ident.span(),
+                "Migratable supports at most 256 versions of the state.",
+            ));
+        }
+        let mut versions = vec![];
+        for variant in &data.variants {
+            let ty = match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    fields.unnamed[0].ty.clone()
+                }
+                _ => {
+                    return Err(Error::new(
+                        variant.span(),
+                        "Each variant should wrap the state struct of its version.",
+                    ))
+                }
+            };
+            versions.push(VersionInfo { ident: variant.ident.clone(), ty });
+        }
+        Ok(Self { ident: input.ident.clone(), versions })
+    }
+}
@@ -1,6 +1,12 @@
 mod serializer_attr;
 pub use serializer_attr::SerializerAttr;
 
+mod non_reentrant_attr;
+pub use non_reentrant_attr::NonReentrantAttr;
+
+mod validate_attr;
+pub use validate_attr::{ValidateAttr, ValidationRule};
+
 mod arg_info;
 pub use arg_info::{ArgInfo, BindgenArgType};
 
@@ -19,9 +25,42 @@ pub use item_trait_info::ItemTraitInfo;
 mod item_impl_info;
 pub use item_impl_info::ItemImplInfo;
 
+mod contract_attr_args;
+pub use contract_attr_args::ContractAttrArgs;
+
+mod contract_metadata_info;
+pub use contract_metadata_info::ContractMetadataInfo;
+
+mod contract_version_info;
+pub use contract_version_info::ContractVersionInfo;
+
+mod lazy_fields_info;
+pub use lazy_fields_info::{LazyFieldInfo, LazyFieldsInfo};
+
+mod near_event_info;
+pub use near_event_info::{EventInfo, EventStandard, NearEventInfo};
+
+mod migratable_info;
+pub use migratable_info::{MigratableInfo, VersionInfo};
+
+mod partial_state_info;
+pub use partial_state_info::{PartialStateInfo, StateFieldInfo};
+
+mod async_method_info;
+pub use async_method_info::{AsyncMethodInfo, AsyncSegment, AsyncVariable};
+
 /// Type of serialization we use.
 #[derive(PartialEq, Eq)]
 pub enum SerializerType {
     JSON,
     Borsh,
 }
+
+impl std::fmt::Display for SerializerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SerializerType::JSON => f.write_str("JSON"),
+            SerializerType::Borsh => f.write_str("Borsh"),
+        }
+    }
+}
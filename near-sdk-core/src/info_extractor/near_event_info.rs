@@ -0,0 +1,139 @@
+use inflector::Inflector;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Generics, Ident, Lit, Meta, NestedMeta, Path};
+
+/// Information extracted from the type deriving `NearEvent`.
+pub struct NearEventInfo {
+    /// The name of the type.
+    pub ident: Ident,
+    /// The generics of the type.
+    pub generics: Generics,
+    /// The name of the standard that defines the events.
+    pub standard: EventStandard,
+    /// The version of the standard.
+    pub version: String,
+    /// The events of the type, one per variant of an enum or a single one for a struct.
+    pub events: Vec<EventInfo>,
+    /// Whether the type is an enum.
+    pub is_enum: bool,
+}
+
+/// Where the name of the standard of the events comes from.
+pub enum EventStandard {
+    /// The name set with `standard = "..."`.
+    Name(String),
+    /// The `EVENT_PREFIX` of the contract, set with `contract = "..."` for the contract with
+    /// `#[near_bindgen(event_prefix = "...")]`.
+    Contract(Path),
+}
+
+/// Information about a single event.
+pub struct EventInfo {
+    /// The name of the variant, or of the struct.
+    pub ident: Ident,
+    /// The name of the event, the snake case of `ident`.
+    pub name: String,
+    /// The fields of the event, which make its data.
+    pub fields: Fields,
+}
+
+impl NearEventInfo {
+    pub fn new(input: &DeriveInput) -> syn::Result<Self> {
+        let mut standard = None;
+        let mut version = None;
+        for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("near_event")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => {
+                    return Err(Error::new(
+                        meta.span(),
+                        "Expected `#[near_event(standard = \"...\", version = \"...\")]` or `#[near_event(contract = \"...\", version = \"...\")]`.",
+                    ))
+                }
+            };
+            for nested in list.nested {
+                let name_value = match nested {
+                    NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+                    nested => {
+                        return Err(Error::new(nested.span(), "Expected `key = \"value\"`."));
+                    }
+                };
+                let value = match &name_value.lit {
+                    Lit::Str(value) => value,
+                    lit => return Err(Error::new(lit.span(), "Expected a string literal.")),
+                };
+                if name_value.path.is_ident("standard") || name_value.path.is_ident("contract") {
+                    if standard.is_some() {
+                        return Err(Error::new(
+                            name_value.path.span(),
+                            "Expected either `standard` or `contract` in near_event.",
+                        ));
+                    }
+                    standard = Some(if name_value.path.is_ident("standard") {
+                        EventStandard::Name(value.value())
+                    } else {
+                        EventStandard::Contract(value.parse()?)
+                    });
+                } else if name_value.path.is_ident("version") {
+                    version = Some(value.value());
+                } else {
+                    return Err(Error::new(
+                        name_value.path.span(),
+                        "Unsupported near_event attribute, expected `standard`, `contract` or `version`.",
+                    ));
+                }
+            }
+        }
+        let standard = standard.ok_or_else(|| {
+            Error::new(
+                input.ident.span(),
+                "NearEvent requires `#[near_event(standard = \"...\")]` or `#[near_event(contract = \"...\")]`.",
+            )
+        })?;
+        let version = version.ok_or_else(|| {
+            Error::new(input.ident.span(), "NearEvent requires `#[near_event(version = \"...\")]`.")
+        })?;
+
+        let (events, is_enum) = match &input.data {
+            Data::Struct(data) => (vec![EventInfo::new(&input.ident, &data.fields)], false),
+            Data::Enum(data) => {
+                if data.variants.is_empty() {
+                    return Err(Error::new(
+                        input.ident.span(),
+                        "NearEvent cannot be derived for an enum without variants.",
+                    ));
+                }
+                let events = data
+                    .variants
+                    .iter()
+                    .map(|variant| EventInfo::new(&variant.ident, &variant.fields))
+                    .collect();
+                (events, true)
+            }
+            Data::Union(_) => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "NearEvent can only be derived for structs and enums.",
+                ))
+            }
+        };
+        Ok(Self {
+            ident: input.ident.clone(),
+            generics: input.generics.clone(),
+            standard,
+            version,
+            events,
+            is_enum,
+        })
+    }
+}
+
+impl EventInfo {
+    fn new(ident: &Ident, fields: &Fields) -> Self {
+        Self {
+            ident: ident.clone(),
+            name: ident.to_string().to_snake_case(),
+            fields: fields.clone(),
+        }
+    }
+}
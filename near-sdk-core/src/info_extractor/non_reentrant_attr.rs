@@ -0,0 +1,31 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Error, LitStr, Token};
+
+/// What the method does with the lock of an operation, set with
+/// `#[non_reentrant(lock = "...")]` or `#[non_reentrant(unlock = "...")]`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NonReentrantAttr {
+    /// The method starts the operation and panics if it's already in progress.
+    Lock(String),
+    /// The method, usually the resolver of the promises, finishes the operation.
+    Unlock(String),
+}
+
+impl Parse for NonReentrantAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let ident: Ident = content.parse()?;
+        content.parse::<Token![=]>()?;
+        let operation: LitStr = content.parse()?;
+        if !content.is_empty() {
+            return Err(Error::new(content.span(), "Expected a single `lock` or `unlock`."));
+        }
+        match ident.to_string().as_str() {
+            "lock" => Ok(NonReentrantAttr::Lock(operation.value())),
+            "unlock" => Ok(NonReentrantAttr::Unlock(operation.value())),
+            _ => Err(Error::new(ident.span(), "Expected `lock = \"...\"` or `unlock = \"...\"`.")),
+        }
+    }
+}
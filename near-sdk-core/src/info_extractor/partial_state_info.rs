@@ -0,0 +1,90 @@
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Expr, Fields, Ident, Lit, Type};
+
+/// Information extracted from the state struct deriving `PartialState`.
+pub struct PartialStateInfo {
+    /// The name of the struct.
+    pub ident: Ident,
+    /// The serialized fields of the struct, in the order of serialization.
+    pub fields: Vec<StateFieldInfo>,
+}
+
+/// Information about a single serialized field of the state struct.
+pub struct StateFieldInfo {
+    /// The name of the field.
+    pub ident: Ident,
+    /// The type of the field.
+    pub ty: Type,
+    /// The size of the serialized field, if it does not depend on the value.
+    pub fixed_size: Option<usize>,
+}
+
+impl PartialStateInfo {
+    pub fn new(input: &DeriveInput) -> syn::Result<Self> {
+        let data = match &input.data {
+            Data::Struct(data) => data,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "PartialState can only be derived for structs.",
+                ))
+            }
+        };
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                input.generics.span(),
+                "PartialState cannot be derived for a generic struct.",
+            ));
+        }
+        let named = match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    data.fields.span(),
+                    "PartialState can only be derived for a struct with named fields.",
+                ))
+            }
+        };
+        let fields = named
+            .iter()
+            .filter(|field| !field.attrs.iter().any(|attr| attr.path.is_ident("borsh_skip")))
+            .map(|field| StateFieldInfo {
+                ident: field.ident.clone().unwrap(),
+                ty: field.ty.clone(),
+                fixed_size: fixed_size(&field.ty),
+            })
+            .collect();
+        Ok(Self { ident: input.ident.clone(), fields })
+    }
+}
+
+/// The size of the Borsh serialization of the type, if it is the same for all the values.
+fn fixed_size(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let ident = path.path.get_ident()?;
+            match ident.to_string().as_str() {
+                "u8" | "i8" | "bool" => Some(1),
+                "u16" | "i16" => Some(2),
+                "u32" | "i32" | "f32" => Some(4),
+                "u64" | "i64" | "f64" => Some(8),
+                "u128" | "i128" => Some(16),
+                _ => None,
+            }
+        }
+        Type::Array(array) => {
+            let len = match &array.len {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Int(len) => len.base10_parse::<usize>().ok()?,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            Some(fixed_size(&array.elem)? * len)
+        }
+        Type::Tuple(tuple) => tuple.elems.iter().map(fixed_size).sum(),
+        Type::Paren(paren) => fixed_size(&paren.elem),
+        Type::Group(group) => fixed_size(&group.elem),
+        _ => None,
+    }
+}
@@ -17,7 +17,12 @@ impl Parse for SerializerAttr {
         let serializer_type = match ident.to_string().as_str() {
             "borsh" => SerializerType::Borsh,
             "json" => SerializerType::JSON,
-            _ => return Err(Error::new(input.span(), "Unsupported serializer type.")),
+            other => {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("Unsupported serializer type `{}`, expected `json` or `borsh`.", other),
+                ))
+            }
         };
         Ok(Self { paren_token, serializer_type })
     }
@@ -1,4 +1,4 @@
-use crate::info_extractor::AttrSigInfo;
+use crate::info_extractor::{AttrSigInfo, SerializerType};
 use syn::export::Span;
 use syn::spanned::Spanned;
 use syn::{Error, LitByteStr, TraitItemMethod};
@@ -27,6 +27,16 @@ impl TraitItemMethodInfo {
         let TraitItemMethod { attrs, sig, .. } = original;
 
         let attr_sig_info = AttrSigInfo::new(attrs, sig)?;
+        // The arguments of the cross-contract call are serialized with the result serializer.
+        if !cfg!(feature = "json")
+            && attr_sig_info.input_args().next().is_some()
+            && attr_sig_info.result_serializer == SerializerType::JSON
+        {
+            return Err(Error::new(
+                sig.span(),
+                "The `json` feature of near-sdk is off, use `#[result_serializer(borsh)]` on the method.",
+            ));
+        }
 
         let ident_byte_str =
             LitByteStr::new(attr_sig_info.ident.to_string().as_bytes(), Span::call_site());
@@ -0,0 +1,90 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Error, Expr, LitInt, LitStr, Token};
+
+/// A check of an argument, set with `#[validate(...)]` on the argument.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationRule {
+    /// `range(min = ..., max = ...)`: the value is within the inclusive bounds.
+    Range { min: Option<Box<Expr>>, max: Option<Box<Expr>> },
+    /// `len(min = ..., max = ...)`: the length of the string or the collection is within the
+    /// inclusive bounds.
+    Len { min: Option<usize>, max: Option<usize> },
+    /// `regex = "..."`: the string matches the regular expression.
+    Regex(String),
+}
+
+/// The checks of `#[validate(range(min = 1), len(max = 64), regex = "...")]`.
+pub struct ValidateAttr {
+    pub rules: Vec<ValidationRule>,
+}
+
+impl Parse for ValidateAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let rules = Punctuated::<ValidationRule, Token![,]>::parse_terminated(&content)?;
+        if rules.is_empty() {
+            return Err(Error::new(input.span(), "Expected at least one check in `#[validate]`."));
+        }
+        Ok(Self { rules: rules.into_iter().collect() })
+    }
+}
+
+impl Parse for ValidationRule {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "regex" => {
+                input.parse::<Token![=]>()?;
+                let regex: LitStr = input.parse()?;
+                Ok(ValidationRule::Regex(regex.value()))
+            }
+            "range" => {
+                let (min, max) = parse_bounds(input, |input| input.parse::<Expr>().map(Box::new))?;
+                if min.is_none() && max.is_none() {
+                    return Err(Error::new(ident.span(), "Expected `min` or `max` in `range`."));
+                }
+                Ok(ValidationRule::Range { min, max })
+            }
+            "len" => {
+                let (min, max) =
+                    parse_bounds(input, |input| input.parse::<LitInt>()?.base10_parse::<usize>())?;
+                if min.is_none() && max.is_none() {
+                    return Err(Error::new(ident.span(), "Expected `min` or `max` in `len`."));
+                }
+                Ok(ValidationRule::Len { min, max })
+            }
+            _ => Err(Error::new(
+                ident.span(),
+                "Expected `range(...)`, `len(...)` or `regex = \"...\"`.",
+            )),
+        }
+    }
+}
+
+/// Parses `(min = ..., max = ...)`, where both bounds are optional.
+fn parse_bounds<T>(
+    input: ParseStream,
+    parse_bound: impl Fn(ParseStream) -> syn::Result<T>,
+) -> syn::Result<(Option<T>, Option<T>)> {
+    let content;
+    parenthesized!(content in input);
+    let mut min = None;
+    let mut max = None;
+    while !content.is_empty() {
+        let name: Ident = content.parse()?;
+        content.parse::<Token![=]>()?;
+        let bound = parse_bound(&content)?;
+        match name.to_string().as_str() {
+            "min" if min.is_none() => min = Some(bound),
+            "max" if max.is_none() => max = Some(bound),
+            _ => return Err(Error::new(name.span(), "Expected a single `min` and `max`.")),
+        }
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+    Ok((min, max))
+}
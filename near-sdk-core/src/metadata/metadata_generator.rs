@@ -2,7 +2,6 @@ use crate::{BindgenArgType, ImplItemMethodInfo, SerializerType};
 
 use quote::quote;
 use syn::export::TokenStream2;
-use syn::ReturnType;
 
 impl ImplItemMethodInfo {
     /// Generates metadata struct for this method.
@@ -29,7 +28,7 @@ impl ImplItemMethodInfo {
     ///     },
     ///     callbacks: vec![],
     ///     callbacks_vec: None,
-    ///     result: Some(Result < IsOk, Error > ::schema_container())
+    ///     result: Some(<Result<IsOk, Error>>::schema_container())
     /// }
     /// ```
     /// If args are serialized with Borsh it will not include `#[derive(borsh::BorshSchema)]`.
@@ -72,7 +71,7 @@ impl ImplItemMethodInfo {
             .map(|arg| {
                 let ty = &arg.ty;
                 quote! {
-                    #ty::schema_container()
+                    <#ty>::schema_container()
                 }
             })
             .collect();
@@ -94,19 +93,28 @@ impl ImplItemMethodInfo {
             Some(arg) => {
                 let ty = &arg.ty;
                 quote! {
-                    Some(#ty::schema_container())
+                    Some(<#ty>::schema_container())
                 }
             }
         };
-        let result = match &self.attr_signature_info.returns {
-            ReturnType::Default => {
+        let result = match self.attr_signature_info.owned_result_type() {
+            None => {
                 quote! {
                     None
                 }
             }
-            ReturnType::Type(_, ty) => {
+            // The metadata is generated outside of the impl section, where `Self` is not defined.
+            Some(_) if is_init => {
+                let struct_type = &self.struct_type;
+                quote! {
+                    Some(#struct_type::schema_container())
+                }
+            }
+            // The qualified path also works for the tuples and the generic types, like `(u64, String)`
+            // or `Vec<u64>`, that cannot be followed by `::` directly.
+            Some(ty) => {
                 quote! {
-                    Some(#ty::schema_container())
+                    Some(<#ty>::schema_container())
                 }
             }
         };
@@ -2,7 +2,7 @@
 //! it decorates. Note, that this in an inner attribute. For it to work we should be
 //! able to visit every method in the module intended to be a contract method.
 //! For this we implement the visitor.
-use crate::ItemImplInfo;
+use crate::{desugar_async_methods, ItemImplInfo};
 
 use quote::quote;
 use syn::export::{ToTokens, TokenStream2};
@@ -24,7 +24,11 @@ impl<'ast> Visit<'ast> for MetadataVisitor {
             .iter()
             .any(|attr| attr.path.to_token_stream().to_string().as_str() == "near_bindgen");
         if has_near_sdk_attr {
-            match ItemImplInfo::new(&mut i.clone()) {
+            let mut item_impl = i.clone();
+            // The async methods are rejected by `near_bindgen` itself without `async-methods`.
+            let info = desugar_async_methods(&mut item_impl)
+                .and_then(|_| ItemImplInfo::new(&mut item_impl));
+            match info {
                 Ok(info) => self.impl_item_infos.push(info),
                 Err(err) => self.errors.push(err),
             }
@@ -149,7 +153,48 @@ mod tests {
                         },
                         callbacks: vec![],
                         callbacks_vec: None,
-                        result: Some(Result < IsOk, Error > ::schema_container())
+                        result: Some(< Result < IsOk, Error > > ::schema_container())
+                    }
+                ]);
+                let data = near_sdk::borsh::BorshSerialize::try_to_vec(&metadata)
+                    .expect("Failed to serialize the metadata using Borsh");
+                near_sdk::env::value_return(&data);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn tuple_result() {
+        let code = quote! {
+            #[near_bindgen]
+            impl Hello {
+                pub fn get_range(&self) -> (u64, Option<String>) { }
+            }
+        };
+
+        let file: syn::File = syn::parse2(code).unwrap();
+
+        let mut visitor = MetadataVisitor::new();
+        visitor.visit_file(&file);
+
+        let actual = visitor.generate_metadata_method().unwrap();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn metadata() {
+                near_sdk::env::setup_panic_hook();
+                near_sdk::env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
+                use borsh::*;
+                let metadata = near_sdk::Metadata::new(vec![
+                    near_sdk::MethodMetadata {
+                        name: "get_range".to_string(),
+                        is_view: true,
+                        is_init: false,
+                        args: None,
+                        callbacks: vec![],
+                        callbacks_vec: None,
+                        result: Some(<(u64, Option<String>)>::schema_container())
                     }
                 ]);
                 let data = near_sdk::borsh::BorshSerialize::try_to_vec(&metadata)
@@ -297,6 +297,39 @@ pub mod near_blockchain {
             sys::promise_return(promise_id)
         }
 
+        #[cfg(feature = "yield-resume")]
+        unsafe fn promise_yield_create(
+            &self,
+            function_name_len: u64,
+            function_name_ptr: u64,
+            arguments_len: u64,
+            arguments_ptr: u64,
+            gas: u64,
+            gas_weight: u64,
+            register_id: u64,
+        ) -> u64 {
+            sys::promise_yield_create(
+                function_name_len,
+                function_name_ptr,
+                arguments_len,
+                arguments_ptr,
+                gas,
+                gas_weight,
+                register_id,
+            )
+        }
+
+        #[cfg(feature = "yield-resume")]
+        unsafe fn promise_yield_resume(
+            &self,
+            data_id_len: u64,
+            data_id_ptr: u64,
+            payload_len: u64,
+            payload_ptr: u64,
+        ) -> u32 {
+            sys::promise_yield_resume(data_id_len, data_id_ptr, payload_len, payload_ptr)
+        }
+
         unsafe fn storage_write(
             &self,
             key_len: u64,
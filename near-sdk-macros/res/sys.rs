@@ -130,6 +130,26 @@ pub mod sys {
         pub fn promise_results_count() -> u64;
         pub fn promise_result(result_idx: u64, register_id: u64) -> u64;
         pub fn promise_return(promise_id: u64);
+        // ####################
+        // # Yield and resume #
+        // ####################
+        #[cfg(feature = "yield-resume")]
+        pub fn promise_yield_create(
+            function_name_len: u64,
+            function_name_ptr: u64,
+            arguments_len: u64,
+            arguments_ptr: u64,
+            gas: u64,
+            gas_weight: u64,
+            register_id: u64,
+        ) -> u64;
+        #[cfg(feature = "yield-resume")]
+        pub fn promise_yield_resume(
+            data_id_len: u64,
+            data_id_ptr: u64,
+            payload_len: u64,
+            payload_ptr: u64,
+        ) -> u32;
         // ###############
         // # Storage API #
         // ###############
@@ -6,30 +6,91 @@ use proc_macro::TokenStream;
 use near_sdk_core::*;
 use proc_macro2::Span;
 use quote::quote;
+use syn::fold::Fold;
 use syn::visit::Visit;
-use syn::{File, ItemImpl, ItemStruct, ItemTrait};
+use syn::{
+    Attribute, DeriveInput, File, ForeignItem, ImplItem, ItemForeignMod, ItemImpl, ItemStruct,
+    ItemTrait,
+};
 
 #[proc_macro_attribute]
-pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Ok(input) = syn::parse::<ItemStruct>(item.clone()) {
         let sys_file = rust_file(include_bytes!("../res/sys.rs"));
         let near_environment = rust_file(include_bytes!("../res/near_blockchain.rs"));
+        let config = match ContractAttrArgs::parse(attr.into()) {
+            Ok(config) => config,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        let contract_metadata = match &config.contract_metadata {
+            Some(info) => info.contract_source_metadata_method(),
+            None => proc_macro2::TokenStream::new(),
+        };
+        let contract_version = if config.contract_version {
+            ContractVersionInfo.version_method()
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        let contract_struct = if config.lazy_fields {
+            match LazyFieldsInfo::new(&input) {
+                Ok(info) => info.lazy_struct(),
+                Err(err) => return err.to_compile_error().into(),
+            }
+        } else {
+            quote! { #input }
+        };
+        let event_prefix = config.event_prefix_const(&input);
+        let contract_state = config.contract_state_impl(&input);
+        let client = if cfg!(feature = "client") {
+            client_struct(&input)
+        } else {
+            proc_macro2::TokenStream::new()
+        };
         TokenStream::from(quote! {
-            #input
+            #contract_struct
             #sys_file
             #near_environment
+            #contract_metadata
+            #contract_version
+            #event_prefix
+            #contract_state
+            #client
         })
     } else if let Ok(mut input) = syn::parse::<ItemImpl>(item) {
-        let item_impl_info = match ItemImplInfo::new(&mut input) {
+        if cfg!(feature = "async-methods") {
+            if let Err(err) = desugar_async_methods(&mut input) {
+                return err.to_compile_error().into();
+            }
+        }
+        let mut item_impl_info = match ItemImplInfo::new(&mut input) {
             Ok(x) => x,
             Err(err) => {
                 return err.to_compile_error().into();
             }
         };
+        if let Err(err) = item_impl_info.apply_attr_args(attr.into()) {
+            return err.to_compile_error().into();
+        }
+        if let Err(err) = item_impl_info.write_size_report() {
+            return err.to_compile_error().into();
+        }
         let generated_code = item_impl_info.wrapper_code();
+        let method_registry = item_impl_info.method_registry();
+        let interface = item_impl_info.interface_trait();
+        let client = if cfg!(feature = "client") {
+            match item_impl_info.client_code() {
+                Ok(client) => client,
+                Err(err) => return err.to_compile_error().into(),
+            }
+        } else {
+            proc_macro2::TokenStream::new()
+        };
         TokenStream::from(quote! {
             #input
             #generated_code
+            #method_registry
+            #interface
+            #client
         })
     } else {
         TokenStream::from(
@@ -44,7 +105,45 @@ pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 fn rust_file(data: &[u8]) -> File {
     let data = std::str::from_utf8(data).unwrap();
-    syn::parse_file(data).unwrap()
+    let file = syn::parse_file(data).unwrap();
+    FeatureItems { yield_resume: cfg!(feature = "yield-resume") }.fold_file(file)
+}
+
+/// Resolves `#[cfg(feature = "yield-resume")]` on the host functions of `res/*.rs` with the features
+/// of this crate: the generated code is compiled in the contract crate, which doesn't have them.
+/// The marked items are kept without the attribute if the feature is enabled, and removed otherwise.
+struct FeatureItems {
+    yield_resume: bool,
+}
+
+impl FeatureItems {
+    /// Strips the marker and returns whether to keep the item.
+    fn keep(&self, attrs: &mut Vec<Attribute>) -> bool {
+        let len = attrs.len();
+        attrs.retain(|attr| {
+            !(attr.path.is_ident("cfg")
+                && attr.tokens.to_string().replace(' ', "") == "(feature=\"yield-resume\")")
+        });
+        attrs.len() == len || self.yield_resume
+    }
+}
+
+impl Fold for FeatureItems {
+    fn fold_item_foreign_mod(&mut self, mut node: ItemForeignMod) -> ItemForeignMod {
+        node.items.retain_mut(|item| match item {
+            ForeignItem::Fn(f) => self.keep(&mut f.attrs),
+            _ => true,
+        });
+        node
+    }
+
+    fn fold_item_impl(&mut self, mut node: ItemImpl) -> ItemImpl {
+        node.items.retain_mut(|item| match item {
+            ImplItem::Method(method) => self.keep(&mut method.attrs),
+            _ => true,
+        });
+        node
+    }
 }
 
 #[proc_macro_attribute]
@@ -79,6 +178,53 @@ pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// `NearEvent` implements `near_sdk::NearEvent` for an enum or a struct, so that its values can be
+/// emitted as events in the NEP-297 format. The type requires
+/// `#[near_event(standard = "...", version = "...")]`, or `#[near_event(contract = "...", version =
+/// "...")]` to use the `event_prefix` of the contract struct with
+/// `#[near_bindgen(event_prefix = "...")]` as the standard.
+#[proc_macro_derive(NearEvent, attributes(near_event))]
+pub fn near_event(item: TokenStream) -> TokenStream {
+    let input = match syn::parse::<DeriveInput>(item) {
+        Ok(x) => x,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    match NearEventInfo::new(&input) {
+        Ok(info) => info.near_event_impl().into(),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+/// `Migratable` implements `near_sdk::Migratable` for an enum that lists the versions of the
+/// contract state, from the oldest to the current one. Each variant wraps the state struct of its
+/// version, and each struct implements `From` of the struct of the previous version.
+#[proc_macro_derive(Migratable)]
+pub fn migratable(item: TokenStream) -> TokenStream {
+    let input = match syn::parse::<DeriveInput>(item) {
+        Ok(x) => x,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    match MigratableInfo::new(&input) {
+        Ok(info) => info.migratable_impl().into(),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+/// `PartialState` generates `read_state_<field>` and `write_state_<field>` functions for the fields
+/// of the contract state struct, which decode and replace only the bytes of the field in the stored
+/// state. See `near_sdk::partial_state`.
+#[proc_macro_derive(PartialState)]
+pub fn partial_state(item: TokenStream) -> TokenStream {
+    let input = match syn::parse::<DeriveInput>(item) {
+        Ok(x) => x,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    match PartialStateInfo::new(&input) {
+        Ok(info) => info.partial_state_impl().into(),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
 // The below attributes a marker-attributes and therefore they are no-op.
 
 /// `callback` is a marker attribute it does not generate code by itself.
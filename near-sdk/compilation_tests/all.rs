@@ -13,5 +13,10 @@ fn tests() {
     t.pass("compilation_tests/init_function.rs");
     t.pass("compilation_tests/lifetime_method.rs");
     t.pass("compilation_tests/cond_compilation.rs");
+    t.pass("compilation_tests/validate.rs");
     t.compile_fail("compilation_tests/payable_view.rs");
+    t.compile_fail("compilation_tests/init_typo.rs");
+    t.compile_fail("compilation_tests/unknown_serializer.rs");
+    t.compile_fail("compilation_tests/mixed_serializer.rs");
+    t.compile_fail("compilation_tests/one_yocto_view.rs");
 }
@@ -0,0 +1,18 @@
+//! Unknown arguments of `#[init]` are not valid
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Test {}
+
+#[near_bindgen]
+impl Test {
+    #[init(ignore_stat)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn main() {}
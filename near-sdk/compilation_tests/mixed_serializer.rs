@@ -0,0 +1,15 @@
+//! Arguments with different serializers are not valid
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Test {}
+
+#[near_bindgen]
+impl Test {
+    pub fn set(&mut self, key: String, #[serializer(borsh)] value: Vec<u8>) {}
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+//! `#[one_yocto]` on a view method is not valid
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Test {}
+
+#[near_bindgen]
+impl Test {
+    #[one_yocto]
+    pub fn transfer(&self) {}
+}
+
+fn main() {}
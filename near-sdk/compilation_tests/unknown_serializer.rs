@@ -0,0 +1,18 @@
+//! Only JSON and Borsh serializers are valid
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Test {}
+
+#[near_bindgen]
+impl Test {
+    #[result_serializer(bincode)]
+    pub fn get(&self) -> u64 {
+        0
+    }
+}
+
+fn main() {}
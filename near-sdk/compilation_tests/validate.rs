@@ -0,0 +1,26 @@
+//! Arguments checked with `#[validate(...)]`, including the JSON integer wrappers.
+
+use near_sdk::json_types::U128;
+use near_sdk::near_bindgen;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Bank {
+    total: u128,
+}
+
+#[near_bindgen]
+impl Bank {
+    pub fn deposit(
+        &mut self,
+        #[validate(range(min = 1, max = 1_000_000_000_000_000_000_000_000))] amount: U128,
+        #[validate(range(max = 10))] priority: u8,
+        #[validate(len(max = 64))] memo: String,
+    ) {
+        let _ = (priority, memo);
+        self.total += amount.0;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,139 @@
+//! Support of the reproducible builds, for the build script of the contract. `record_build_info`
+//! passes the toolchain, the target, the profile and the features of the build to the compilation
+//! of the contract, and `contract_source_metadata` returns them in `toolchain`. It also passes the
+//! git commit of the source for `contract_version!`:
+//!
+//! ```ignore
+//! // build.rs, with `near-sdk` in `[build-dependencies]`.
+//! fn main() {
+//!     near_sdk::build::record_build_info();
+//! }
+//! ```
+//!
+//! Whoever has the published source can then rebuild the contract with the same toolchain and
+//! features, and compare the hash of the result with the hash of the deployed code.
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// The compiler version, e.g. `rustc 1.51.0 (2fd73fabe 2021-03-23)`.
+pub const BUILD_RUSTC_ENV: &str = "NEAR_SDK_BUILD_RUSTC";
+/// The target triple, e.g. `wasm32-unknown-unknown`.
+pub const BUILD_TARGET_ENV: &str = "NEAR_SDK_BUILD_TARGET";
+/// The profile, `release` or `debug`.
+pub const BUILD_PROFILE_ENV: &str = "NEAR_SDK_BUILD_PROFILE";
+/// The comma-separated enabled features of the contract crate.
+pub const BUILD_FEATURES_ENV: &str = "NEAR_SDK_BUILD_FEATURES";
+/// The hash of the git commit of the source, if it's built from a git repository.
+pub const BUILD_GIT_COMMIT_ENV: &str = "NEAR_SDK_BUILD_GIT_COMMIT";
+
+/// The variables read by `contract_source_metadata` at the build time, which have to rebuild the
+/// contract when they change.
+const NEP330_ENVS: &[&str] = &[
+    "NEP330_VERSION",
+    "NEP330_LINK",
+    "NEP330_BUILD_INFO_BUILD_ENVIRONMENT",
+    "NEP330_BUILD_INFO_BUILD_COMMAND",
+    "NEP330_BUILD_INFO_SOURCE_CODE_SNAPSHOT",
+    "NEP330_BUILD_INFO_CONTRACT_PATH",
+];
+
+/// Records the details of the build for the contract metadata. Call it from the build script of
+/// the contract.
+pub fn record_build_info() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = command_output(&rustc, &["--version"]).unwrap_or_default();
+    let git_commit = command_output("git", &["rev-parse", "HEAD"]);
+    for line in build_info_lines(&rustc_version, git_commit.as_deref(), env::vars()) {
+        println!("{}", line);
+    }
+    // Rebuild the contract when the commit changes: `HEAD` moves to another branch, or the branch
+    // moves to another commit.
+    if let Some(git_dir) = command_output("git", &["rev-parse", "--git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        if let Some(head_ref) = command_output("git", &["symbolic-ref", "-q", "HEAD"]) {
+            let ref_path = git_dir.join(head_ref);
+            if ref_path.exists() {
+                println!("cargo:rerun-if-changed={}", ref_path.display());
+            }
+        }
+    }
+}
+
+/// The trimmed standard output of the command, or `None` if it fails.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The instructions of the build script for the compiler version, the git commit, and the
+/// variables cargo sets for the build script.
+fn build_info_lines(
+    rustc_version: &str,
+    git_commit: Option<&str>,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Vec<String> {
+    let mut target = String::new();
+    let mut profile = String::new();
+    let mut features = vec![];
+    for (key, value) in vars {
+        match key.as_str() {
+            "TARGET" => target = value,
+            "PROFILE" => profile = value,
+            _ => {
+                // Cargo sets `CARGO_FEATURE_<NAME>` with the name uppercased and `-` replaced by `_`.
+                if let Some(feature) = key.strip_prefix("CARGO_FEATURE_") {
+                    features.push(feature.to_lowercase().replace('_', "-"));
+                }
+            }
+        }
+    }
+    features.sort();
+    let mut lines = vec![
+        format!("cargo:rustc-env={}={}", BUILD_RUSTC_ENV, rustc_version),
+        format!("cargo:rustc-env={}={}", BUILD_TARGET_ENV, target),
+        format!("cargo:rustc-env={}={}", BUILD_PROFILE_ENV, profile),
+        format!("cargo:rustc-env={}={}", BUILD_FEATURES_ENV, features.join(",")),
+    ];
+    if let Some(git_commit) = git_commit {
+        lines.push(format!("cargo:rustc-env={}={}", BUILD_GIT_COMMIT_ENV, git_commit));
+    }
+    lines.extend(NEP330_ENVS.iter().map(|name| format!("cargo:rerun-if-env-changed={}", name)));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_lines() {
+        let vars = vec![
+            ("TARGET", "wasm32-unknown-unknown"),
+            ("PROFILE", "release"),
+            ("CARGO_FEATURE_DEFAULT", "1"),
+            ("CARGO_FEATURE_GLOBAL_ALLOCATOR", "1"),
+            ("CARGO_PKG_NAME", "contract"),
+        ];
+        let vars = vars.into_iter().map(|(key, value)| (key.to_string(), value.to_string()));
+        let lines = build_info_lines("rustc 1.51.0", Some("0a1b2c3"), vars);
+        assert_eq!(
+            lines[..4],
+            [
+                "cargo:rustc-env=NEAR_SDK_BUILD_RUSTC=rustc 1.51.0",
+                "cargo:rustc-env=NEAR_SDK_BUILD_TARGET=wasm32-unknown-unknown",
+                "cargo:rustc-env=NEAR_SDK_BUILD_PROFILE=release",
+                "cargo:rustc-env=NEAR_SDK_BUILD_FEATURES=default,global-allocator",
+            ]
+        );
+        assert_eq!(lines[4], "cargo:rustc-env=NEAR_SDK_BUILD_GIT_COMMIT=0a1b2c3");
+        assert_eq!(lines[5], "cargo:rerun-if-env-changed=NEP330_VERSION");
+        let lines = build_info_lines("rustc 1.51.0", None, vec![].into_iter());
+        assert!(!lines.iter().any(|line| line.contains(BUILD_GIT_COMMIT_ENV)));
+    }
+}
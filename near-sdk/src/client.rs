@@ -0,0 +1,120 @@
+//! Off-chain client of the contract, enabled with the `client` feature. With the feature on,
+//! `#[near_bindgen]` generates `<Contract>Client` next to the contract struct, with an async function
+//! for each method of the contract. The functions serialize the arguments the same way the method
+//! wrappers deserialize them, and send them through a `Transport`, e.g. the JSON RPC of a node:
+//!
+//! ```ignore
+//! let client = StatusMessageClient::with_transport(rpc, "status.near".to_string());
+//! client.set_status("hello".to_string(), CallOptions::default()).await?;
+//! assert_eq!(client.get_status("alice.near".to_string()).await?, Some("hello".to_string()));
+//! ```
+//!
+//! The client is not compiled for wasm32, so the contract crate can enable the feature for its
+//! tests, or be a dependency of an indexer or a bot.
+use crate::{Balance, Gas};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+/// The future of the result of the call sent by the `Transport`.
+pub type TransportFuture<'a, E> = Pin<Box<dyn Future<Output = Result<Vec<u8>, E>> + Send + 'a>>;
+
+/// Sends the calls of the client to the contract.
+pub trait Transport {
+    /// The error of sending the call, or of its execution.
+    type Error;
+
+    /// Calls the view method and returns the value it returned.
+    fn view<'a>(
+        &'a self,
+        contract_id: &'a str,
+        method_name: &'a str,
+        args: Vec<u8>,
+    ) -> TransportFuture<'a, Self::Error>;
+
+    /// Sends a transaction with the function call and returns the value the method returned.
+    fn call<'a>(
+        &'a self,
+        contract_id: &'a str,
+        method_name: &'a str,
+        args: Vec<u8>,
+        options: CallOptions,
+    ) -> TransportFuture<'a, Self::Error>;
+}
+
+/// The gas and the deposit attached to the function call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallOptions {
+    pub gas: Gas,
+    pub deposit: Balance,
+}
+
+impl Default for CallOptions {
+    /// 100 Tgas without a deposit.
+    fn default() -> Self {
+        Self { gas: 100_000_000_000_000, deposit: 0 }
+    }
+}
+
+/// The error of a call of the client.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The transport failed to send the call, or the call failed.
+    Transport(E),
+    /// The arguments or the result do not match the method.
+    Serialization(String),
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(err) => write!(f, "Transport error: {}", err),
+            Error::Serialization(err) => write!(f, "Serialization error: {}", err),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for Error<E> {}
+
+pub fn to_json<T: Serialize, E>(value: &T) -> Result<Vec<u8>, Error<E>> {
+    serde_json::to_vec(value).map_err(|err| Error::Serialization(err.to_string()))
+}
+
+pub fn from_json<T: DeserializeOwned, E>(data: &[u8]) -> Result<T, Error<E>> {
+    serde_json::from_slice(data).map_err(|err| Error::Serialization(err.to_string()))
+}
+
+pub fn to_borsh<T: BorshSerialize, E>(value: &T) -> Result<Vec<u8>, Error<E>> {
+    value.try_to_vec().map_err(|err| Error::Serialization(err.to_string()))
+}
+
+pub fn from_borsh<T: BorshDeserialize, E>(data: &[u8]) -> Result<T, Error<E>> {
+    T::try_from_slice(data).map_err(|err| Error::Serialization(err.to_string()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(BorshSerialize, BorshDeserialize, Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Input {
+        account_id: String,
+        amount: u64,
+    }
+
+    #[test]
+    fn test_serialization() {
+        let input = Input { account_id: "alice.near".to_string(), amount: 1 };
+        let json = to_json::<_, ()>(&input).unwrap();
+        assert_eq!(json, br#"{"account_id":"alice.near","amount":1}"#.to_vec());
+        assert_eq!(from_json::<Input, ()>(&json), Ok(input));
+        let borsh = to_borsh::<_, ()>(&1u64).unwrap();
+        assert_eq!(from_borsh::<u64, ()>(&borsh), Ok(1));
+        assert!(matches!(from_json::<u64, ()>(b"\"a\""), Err(Error::Serialization(_))));
+        assert!(matches!(from_borsh::<u64, ()>(&[1]), Err(Error::Serialization(_))));
+    }
+}
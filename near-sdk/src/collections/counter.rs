@@ -0,0 +1,97 @@
+//! A persistent counter. Stores a `u64` under its own key, so the contract can take the next id
+//! of a token, a proposal or a nonce without keeping the number in the state struct that is read
+//! and written on every call.
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::collections::next_trie_id;
+use crate::collections::storage_backend::{storage_read, storage_write};
+use crate::env;
+
+const ERR_COUNTER_OVERFLOW: &[u8] = b"Counter overflow";
+const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
+
+/// A `u64` counter stored on the trie. It starts at zero, and `next` returns the incremented value,
+/// so the first id is 1 and zero can mean "none".
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct Counter {
+    storage_key: Vec<u8>,
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new(next_trie_id())
+    }
+}
+
+impl Counter {
+    /// Create a new counter. Use `storage_key` as the key of its value.
+    pub fn new(storage_key: Vec<u8>) -> Self {
+        Self { storage_key }
+    }
+
+    /// Returns the value of the counter, the last id returned by `next`.
+    pub fn current(&self) -> u64 {
+        match storage_read(&self.storage_key) {
+            Some(raw_value) => match u64::try_from_slice(&raw_value) {
+                Ok(x) => x,
+                Err(_) => env::panic(ERR_INCONSISTENT_STATE),
+            },
+            None => 0,
+        }
+    }
+
+    /// Increments the counter and returns the new value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter overflows.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u64 {
+        self.add(1)
+    }
+
+    /// Adds `n` to the counter and returns the new value, e.g. to reserve `n` ids at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter overflows.
+    pub fn add(&mut self, n: u64) -> u64 {
+        let value = match self.current().checked_add(n) {
+            Some(x) => x,
+            None => env::panic(ERR_COUNTER_OVERFLOW),
+        };
+        storage_write(&self.storage_key, &value.to_le_bytes());
+        value
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_next() {
+        test_env::setup();
+        let mut counter = Counter::default();
+        assert_eq!(counter.current(), 0);
+        assert_eq!(counter.next(), 1);
+        assert_eq!(counter.next(), 2);
+        assert_eq!(counter.add(10), 12);
+        assert_eq!(counter.add(0), 12);
+        assert_eq!(counter.current(), 12);
+        let counter = Counter::try_from_slice(&counter.try_to_vec().unwrap()).unwrap();
+        assert_eq!(counter.current(), 12);
+        assert_eq!(env::storage_read(&counter.storage_key), Some(12u64.to_le_bytes().to_vec()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Counter overflow")]
+    fn test_overflow() {
+        test_env::setup();
+        let mut counter = Counter::new(b"c".to_vec());
+        counter.add(u64::MAX);
+        counter.next();
+    }
+}
@@ -0,0 +1,215 @@
+//! A persistent map without iterators. Unlike `UnorderedMap` it doesn't store the keys and the
+//! values in vectors, so each operation takes a single storage access, but the elements cannot be
+//! enumerated and the map cannot be cleared.
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::collections::storage_backend::{
+    storage_has_key, storage_read, storage_remove_evicted, storage_write_evicted,
+};
+use crate::collections::{append_slice, next_trie_id};
+use crate::env;
+
+const ERR_KEY_SERIALIZATION: &[u8] = b"Cannot serialize key with Borsh";
+const ERR_VALUE_DESERIALIZATION: &[u8] = b"Cannot deserialize value with Borsh";
+const ERR_VALUE_SERIALIZATION: &[u8] = b"Cannot serialize value with Borsh";
+
+/// An non-iterable implementation of a map that stores its content directly on the trie.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LookupMap<K, V> {
+    key_prefix: Vec<u8>,
+    #[borsh_skip]
+    el: PhantomData<(K, V)>,
+}
+
+impl<K, V> Default for LookupMap<K, V> {
+    fn default() -> Self {
+        Self::new(next_trie_id())
+    }
+}
+
+impl<K, V> LookupMap<K, V> {
+    /// Create a new map. Use `key_prefix` as a unique prefix for keys.
+    pub fn new(key_prefix: Vec<u8>) -> Self {
+        Self { key_prefix, el: PhantomData }
+    }
+
+    fn raw_key_to_storage_key(&self, raw_key: &[u8]) -> Vec<u8> {
+        append_slice(&self.key_prefix, raw_key)
+    }
+
+    /// Returns `true` if the serialized key is present in the map.
+    pub fn contains_key_raw(&self, key_raw: &[u8]) -> bool {
+        let storage_key = self.raw_key_to_storage_key(key_raw);
+        storage_has_key(&storage_key)
+    }
+
+    /// Returns the serialized value corresponding to the serialized key.
+    pub fn get_raw(&self, key_raw: &[u8]) -> Option<Vec<u8>> {
+        let storage_key = self.raw_key_to_storage_key(key_raw);
+        storage_read(&storage_key)
+    }
+
+    /// Inserts a serialized key-value pair into the map.
+    /// If the map did not have this key present, `None` is returned. Otherwise returns
+    /// a serialized value.
+    pub fn insert_raw(&mut self, key_raw: &[u8], value_raw: &[u8]) -> Option<Vec<u8>> {
+        let storage_key = self.raw_key_to_storage_key(key_raw);
+        storage_write_evicted(&storage_key, value_raw)
+    }
+
+    /// Removes a serialized key from the map, returning the serialized value at the key if the key
+    /// was previously in the map.
+    pub fn remove_raw(&mut self, key_raw: &[u8]) -> Option<Vec<u8>> {
+        let storage_key = self.raw_key_to_storage_key(key_raw);
+        storage_remove_evicted(&storage_key)
+    }
+}
+
+impl<K, V> LookupMap<K, V>
+where
+    K: BorshSerialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn serialize_key(key: &K) -> Vec<u8> {
+        match key.try_to_vec() {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_KEY_SERIALIZATION),
+        }
+    }
+
+    fn deserialize_value(raw_value: &[u8]) -> V {
+        match V::try_from_slice(raw_value) {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_VALUE_DESERIALIZATION),
+        }
+    }
+
+    fn serialize_value(value: &V) -> Vec<u8> {
+        match value.try_to_vec() {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_VALUE_SERIALIZATION),
+        }
+    }
+
+    /// Returns true if the map contains a given key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.contains_key_raw(&Self::serialize_key(key))
+    }
+
+    /// Returns the value corresponding to the key.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.get_raw(&Self::serialize_key(key)).map(|value_raw| Self::deserialize_value(&value_raw))
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in the
+    /// map.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_raw(&Self::serialize_key(key))
+            .map(|value_raw| Self::deserialize_value(&value_raw))
+    }
+
+    /// Inserts a key-value pair into the map.
+    /// If the map did not have this key present, `None` is returned. Otherwise returns
+    /// the previous value.
+    pub fn insert(&mut self, key: &K, value: &V) -> Option<V> {
+        self.insert_raw(&Self::serialize_key(key), &Self::serialize_value(value))
+            .map(|value_raw| Self::deserialize_value(&value_raw))
+    }
+
+    /// Inserts all key-value pairs from the iterator.
+    pub fn extend<IT: IntoIterator<Item = (K, V)>>(&mut self, iter: IT) {
+        for (el_key, el_value) in iter {
+            self.insert(&el_key, &el_value);
+        }
+    }
+}
+
+impl<K, V> Extend<(K, V)> for LookupMap<K, V>
+where
+    K: BorshSerialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn extend<IT: IntoIterator<Item = (K, V)>>(&mut self, iter: IT) {
+        LookupMap::extend(self, iter)
+    }
+}
+
+/// Collects the entries into a new map with a prefix from `next_trie_id`, like `default`.
+impl<K, V> FromIterator<(K, V)> for LookupMap<K, V>
+where
+    K: BorshSerialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn from_iter<IT: IntoIterator<Item = (K, V)>>(iter: IT) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use crate::collections::LookupMap;
+    use crate::test_utils::test_env;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn test_insert_remove() {
+        test_env::setup();
+        let mut map = LookupMap::new(b"m".to_vec());
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+        let mut keys = vec![];
+        let mut key_to_value = HashMap::new();
+        for _ in 0..100 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>();
+            keys.push(key);
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+        keys.shuffle(&mut rng);
+        for key in keys {
+            assert!(map.contains_key(&key));
+            let actual = map.remove(&key).unwrap();
+            assert_eq!(actual, key_to_value[&key]);
+            assert!(!map.contains_key(&key));
+        }
+    }
+
+    #[test]
+    pub fn test_insert_override_get() {
+        test_env::setup();
+        let mut map = LookupMap::new(b"m".to_vec());
+        assert_eq!(map.insert(&1u64, &2u64), None);
+        assert_eq!(map.insert(&1u64, &3u64), Some(2));
+        assert_eq!(map.get(&1u64), Some(3));
+        assert_eq!(map.get(&2u64), None);
+        assert_eq!(map.remove(&2u64), None);
+    }
+
+    #[test]
+    pub fn test_extend() {
+        test_env::setup();
+        let mut map = LookupMap::new(b"m".to_vec());
+        map.extend((0..10u64).map(|i| (i, i * 2)));
+        for i in 0..10u64 {
+            assert_eq!(map.get(&i), Some(i * 2));
+        }
+    }
+
+    #[test]
+    pub fn test_from_iter() {
+        test_env::setup();
+        let mut map: LookupMap<u64, u64> = (0..10).map(|i| (i, i * i)).collect();
+        Extend::extend(&mut map, vec![(3, 0)]);
+        assert_eq!(map.get(&3), Some(0));
+        assert_eq!(map.get(&9), Some(81));
+        assert_eq!(map.get(&10), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! A persistent set without iterators. Unlike `UnorderedSet` it doesn't store the elements in a
+//! vector, so each operation takes a single storage access, but the elements cannot be enumerated
+//! and the set cannot be cleared.
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::collections::storage_backend::{storage_has_key, storage_remove, storage_write};
+use crate::collections::{append_slice, next_trie_id};
+use crate::env;
+
+const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element with Borsh";
+
+/// An non-iterable implementation of a set that stores its content directly on the trie.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LookupSet<T> {
+    element_prefix: Vec<u8>,
+    #[borsh_skip]
+    el: PhantomData<T>,
+}
+
+impl<T> Default for LookupSet<T> {
+    fn default() -> Self {
+        Self::new(next_trie_id())
+    }
+}
+
+impl<T> LookupSet<T> {
+    /// Create a new set. Use `element_prefix` as a unique prefix for the elements.
+    pub fn new(element_prefix: Vec<u8>) -> Self {
+        Self { element_prefix, el: PhantomData }
+    }
+
+    fn raw_element_to_storage_key(&self, element_raw: &[u8]) -> Vec<u8> {
+        append_slice(&self.element_prefix, element_raw)
+    }
+
+    /// Returns `true` if the serialized element is present in the set.
+    pub fn contains_raw(&self, element_raw: &[u8]) -> bool {
+        let storage_key = self.raw_element_to_storage_key(element_raw);
+        storage_has_key(&storage_key)
+    }
+
+    /// Adds a serialized element to the set.
+    /// If the set did not have this value present, `true` is returned.
+    /// If the set did have this value present, `false` is returned.
+    pub fn insert_raw(&mut self, element_raw: &[u8]) -> bool {
+        let storage_key = self.raw_element_to_storage_key(element_raw);
+        !storage_write(&storage_key, b"")
+    }
+
+    /// Removes a serialized element from the set.
+    /// Returns true if the element was present in the set.
+    pub fn remove_raw(&mut self, element_raw: &[u8]) -> bool {
+        let storage_key = self.raw_element_to_storage_key(element_raw);
+        storage_remove(&storage_key)
+    }
+}
+
+impl<T> LookupSet<T>
+where
+    T: BorshSerialize,
+{
+    fn serialize_element(element: &T) -> Vec<u8> {
+        match element.try_to_vec() {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_ELEMENT_SERIALIZATION),
+        }
+    }
+
+    /// Returns true if the set contains an element.
+    pub fn contains(&self, element: &T) -> bool {
+        self.contains_raw(&Self::serialize_element(element))
+    }
+
+    /// Removes a value from the set. Returns whether the value was present in the set.
+    pub fn remove(&mut self, element: &T) -> bool {
+        self.remove_raw(&Self::serialize_element(element))
+    }
+
+    /// Adds a value to the set.
+    /// If the set did not have this value present, `true` is returned.
+    /// If the set did have this value present, `false` is returned.
+    pub fn insert(&mut self, element: &T) -> bool {
+        self.insert_raw(&Self::serialize_element(element))
+    }
+
+    /// Adds all elements from the iterator.
+    pub fn extend<IT: IntoIterator<Item = T>>(&mut self, iter: IT) {
+        for el in iter {
+            self.insert(&el);
+        }
+    }
+}
+
+impl<T> Extend<T> for LookupSet<T>
+where
+    T: BorshSerialize,
+{
+    fn extend<IT: IntoIterator<Item = T>>(&mut self, iter: IT) {
+        LookupSet::extend(self, iter)
+    }
+}
+
+/// Collects the elements into a new set with a prefix from `next_trie_id`, like `default`.
+impl<T> FromIterator<T> for LookupSet<T>
+where
+    T: BorshSerialize,
+{
+    fn from_iter<IT: IntoIterator<Item = T>>(iter: IT) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use crate::collections::LookupSet;
+    use crate::test_utils::test_env;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    pub fn test_insert_remove() {
+        test_env::setup();
+        let mut set = LookupSet::new(b"s".to_vec());
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+        let mut keys = vec![];
+        for _ in 0..100 {
+            let key = rng.gen::<u64>();
+            keys.push(key);
+            assert!(set.insert(&key));
+        }
+        keys.shuffle(&mut rng);
+        for key in keys {
+            assert!(set.contains(&key));
+            assert!(!set.insert(&key));
+            assert!(set.remove(&key));
+            assert!(!set.contains(&key));
+            assert!(!set.remove(&key));
+        }
+    }
+
+    #[test]
+    pub fn test_extend() {
+        test_env::setup();
+        let mut set = LookupSet::new(b"s".to_vec());
+        set.extend(0..10u64);
+        for i in 0..10u64 {
+            assert!(set.contains(&i));
+        }
+        assert!(!set.contains(&10));
+    }
+
+    #[test]
+    pub fn test_from_iter() {
+        test_env::setup();
+        let mut set: LookupSet<u64> = (0..10).collect();
+        Extend::extend(&mut set, vec![20]);
+        assert!(set.contains(&9));
+        assert!(set.contains(&20));
+        assert!(!set.contains(&10));
+    }
+}
@@ -47,6 +47,37 @@ pub use unordered_map::UnorderedMap;
 mod tree_map;
 pub use tree_map::TreeMap;
 
+mod lazy_option;
+pub use lazy_option::LazyOption;
+
+mod lookup_map;
+pub use lookup_map::LookupMap;
+
+mod lookup_set;
+pub use lookup_set::LookupSet;
+
+mod counter;
+pub use counter::Counter;
+
+mod ring_buffer;
+pub use ring_buffer::RingBuffer;
+
+mod scheduled_queue;
+pub use scheduled_queue::ScheduledQueue;
+
+mod string_table;
+pub use string_table::StringTable;
+
+mod versioned;
+pub use versioned::{Versioned, VersionedValue};
+
+mod storage_backend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use storage_backend::{with_storage_backend, CountingStorage, InMemoryStorage};
+pub use storage_backend::{HostStorage, StorageBackend};
+
+mod schema;
+
 pub const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
 pub const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element with Borsh.";
 pub const ERR_ELEMENT_DESERIALIZATION: &[u8] = b"Cannot deserialize element with Borsh.";
@@ -0,0 +1,224 @@
+//! A vector with a fixed capacity that overwrites its oldest element when it's full, for the last
+//! N records of something, like the price points or the audit records, without the storage growing
+//! over time.
+use std::marker::PhantomData;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::collections::storage_backend::{storage_read, storage_remove, storage_write_evicted};
+use crate::collections::{append_slice, next_trie_id};
+use crate::env;
+
+const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
+const ERR_ELEMENT_DESERIALIZATION: &[u8] = b"Cannot deserialize element";
+const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element";
+const ERR_ZERO_CAPACITY: &[u8] = b"The capacity of a ring buffer must be positive";
+
+/// A ring buffer that stores its content on the trie. The elements are indexed from the oldest one,
+/// and each element is stored under the key of its slot, so pushing an element takes a single
+/// storage write whether the buffer is full or not.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[cfg_attr(not(feature = "expensive-debug"), derive(Debug))]
+pub struct RingBuffer<T> {
+    prefix: Vec<u8>,
+    capacity: u64,
+    /// The slot of the oldest element.
+    start: u64,
+    len: u64,
+    #[borsh_skip]
+    el: PhantomData<T>,
+}
+
+impl<T> RingBuffer<T> {
+    /// Create a new buffer that keeps up to `capacity` elements. Use `id` as a unique prefix for
+    /// the keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(id: Vec<u8>, capacity: u64) -> Self {
+        if capacity == 0 {
+            env::panic(ERR_ZERO_CAPACITY)
+        }
+        Self { prefix: id, capacity, start: 0, len: 0, el: PhantomData }
+    }
+
+    /// Create a new buffer with a prefix from `next_trie_id`, like `Vector::default`.
+    pub fn with_capacity(capacity: u64) -> Self {
+        Self::new(next_trie_id(), capacity)
+    }
+
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the buffer contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the next push overwrites the oldest element.
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity
+    }
+
+    /// Returns the maximum number of elements in the buffer.
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    fn slot_to_lookup_key(&self, slot: u64) -> Vec<u8> {
+        append_slice(&self.prefix, &slot.to_le_bytes()[..])
+    }
+
+    fn slot(&self, index: u64) -> u64 {
+        (self.start + index) % self.capacity
+    }
+
+    /// Returns the serialized element by index, counting from the oldest, or `None` if it is not
+    /// present.
+    pub fn get_raw(&self, index: u64) -> Option<Vec<u8>> {
+        if index >= self.len {
+            return None;
+        }
+        match storage_read(&self.slot_to_lookup_key(self.slot(index))) {
+            Some(raw_element) => Some(raw_element),
+            None => env::panic(ERR_INCONSISTENT_STATE),
+        }
+    }
+
+    /// Appends a serialized element as the newest one. If the buffer is full, overwrites the oldest
+    /// element and returns it in serialized form.
+    pub fn push_raw(&mut self, raw_element: &[u8]) -> Option<Vec<u8>> {
+        let lookup_key = self.slot_to_lookup_key(self.slot(self.len));
+        let evicted = storage_write_evicted(&lookup_key, raw_element);
+        if self.is_full() {
+            self.start = (self.start + 1) % self.capacity;
+            match evicted {
+                Some(x) => Some(x),
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            }
+        } else {
+            self.len += 1;
+            None
+        }
+    }
+
+    /// Removes all elements from the buffer.
+    pub fn clear(&mut self) {
+        for index in 0..self.len {
+            storage_remove(&self.slot_to_lookup_key(self.slot(index)));
+        }
+        self.start = 0;
+        self.len = 0;
+    }
+}
+
+impl<T> RingBuffer<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn serialize_element(element: &T) -> Vec<u8> {
+        match element.try_to_vec() {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_ELEMENT_SERIALIZATION),
+        }
+    }
+
+    fn deserialize_element(raw_element: &[u8]) -> T {
+        match T::try_from_slice(raw_element) {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_ELEMENT_DESERIALIZATION),
+        }
+    }
+
+    /// Appends an element as the newest one. If the buffer is full, overwrites the oldest element
+    /// and returns it.
+    pub fn push(&mut self, element: &T) -> Option<T> {
+        self.push_raw(&Self::serialize_element(element)).map(|x| Self::deserialize_element(&x))
+    }
+
+    /// Returns the element by index, counting from the oldest, or `None` if it is not present.
+    pub fn get(&self, index: u64) -> Option<T> {
+        self.get_raw(index).map(|x| Self::deserialize_element(&x))
+    }
+
+    /// Returns the newest element, or `None` if the buffer is empty.
+    pub fn last(&self) -> Option<T> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Iterate over the elements from the oldest to the newest.
+    pub fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = T> + 'a {
+        (0..self.len).map(move |index| match self.get(index) {
+            Some(x) => x,
+            None => env::panic(ERR_INCONSISTENT_STATE),
+        })
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+#[cfg(feature = "expensive-debug")]
+impl<T: std::fmt::Debug + BorshSerialize + BorshDeserialize> std::fmt::Debug for RingBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_vec().fmt(f)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_push() {
+        test_env::setup();
+        let mut buffer: RingBuffer<u64> = RingBuffer::with_capacity(3);
+        assert_eq!(buffer.last(), None);
+        assert_eq!(buffer.push(&1), None);
+        assert_eq!(buffer.push(&2), None);
+        assert_eq!(buffer.push(&3), None);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push(&4), Some(1));
+        assert_eq!(buffer.push(&5), Some(2));
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.to_vec(), vec![3, 4, 5]);
+        assert_eq!(buffer.iter().rev().collect::<Vec<_>>(), vec![5, 4, 3]);
+        assert_eq!(buffer.get(0), Some(3));
+        assert_eq!(buffer.get(3), None);
+        assert_eq!(buffer.last(), Some(5));
+        assert_eq!(env::storage_entries_with_prefix(&buffer.prefix).count(), 3);
+    }
+
+    #[test]
+    fn test_clear() {
+        test_env::setup();
+        let mut buffer: RingBuffer<u64> = RingBuffer::new(b"r".to_vec(), 4);
+        for i in 0..10 {
+            buffer.push(&i);
+        }
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(env::storage_entries_with_prefix(&buffer.prefix).count(), 0);
+        buffer.push(&7);
+        assert_eq!(buffer.to_vec(), vec![7]);
+    }
+
+    #[test]
+    fn test_persistence() {
+        test_env::setup();
+        let mut buffer: RingBuffer<u64> = RingBuffer::new(b"r".to_vec(), 2);
+        for i in 1..=3 {
+            buffer.push(&i);
+        }
+        let mut buffer: RingBuffer<u64> =
+            RingBuffer::try_from_slice(&buffer.try_to_vec().unwrap()).unwrap();
+        assert_eq!(buffer.push(&4), Some(2));
+        assert_eq!(buffer.to_vec(), vec![3, 4]);
+    }
+}
@@ -0,0 +1,139 @@
+//! A queue of items that become due at a timestamp or a block height, for the work a contract
+//! does later, like unlocking the tokens or expiring the offers. The contract doesn't get called
+//! when an item is due, so the items are popped by the next calls that come after it:
+//!
+//! ```ignore
+//! pub fn claim(&mut self) {
+//!     for unlock in self.unlocks.pop_due(env::block_timestamp(), 10) {
+//!         self.release(unlock);
+//!     }
+//!     // ...
+//! }
+//! ```
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::collections::{next_trie_id, TreeMap};
+use crate::env;
+
+const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
+
+/// A queue ordered by the time the items are due. The items due at the same time are popped in
+/// the order they were pushed. The time is a `u64`, either a timestamp or a block height, as long
+/// as the queue uses the same one for `push` and `pop_due`.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct ScheduledQueue<T> {
+    items: TreeMap<(u64, u64), T>,
+    next_seq: u64,
+}
+
+impl<T> Default for ScheduledQueue<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn default() -> Self {
+        Self::new(next_trie_id())
+    }
+}
+
+impl<T> ScheduledQueue<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    /// Create a new queue. Use `id` as a unique prefix for the keys.
+    pub fn new(id: Vec<u8>) -> Self {
+        Self { items: TreeMap::new(id), next_seq: 0 }
+    }
+
+    /// Returns the number of items in the queue, both due and not due yet.
+    pub fn len(&self) -> u64 {
+        self.items.len()
+    }
+
+    /// Returns `true` if the queue has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.len() == 0
+    }
+
+    /// Adds an item that becomes due at `at`.
+    pub fn push(&mut self, at: u64, item: &T) {
+        self.items.insert(&(at, self.next_seq), item);
+        self.next_seq += 1;
+    }
+
+    /// Returns the time the next item is due, or `None` if the queue is empty.
+    pub fn next_due(&self) -> Option<u64> {
+        self.items.min().map(|(at, _)| at)
+    }
+
+    /// Removes up to `limit` items that are due at `now` or earlier, and returns them in the order
+    /// they are due. The rest of the due items stay for the next call, so a large backlog is
+    /// processed over several calls without exceeding the gas limit.
+    pub fn pop_due(&mut self, now: u64, limit: u64) -> Vec<T> {
+        let mut due = vec![];
+        while (due.len() as u64) < limit {
+            match self.items.min() {
+                Some(key) if key.0 <= now => match self.items.remove(&key) {
+                    Some(item) => due.push(item),
+                    None => env::panic(ERR_INCONSISTENT_STATE),
+                },
+                _ => break,
+            }
+        }
+        due
+    }
+
+    /// Iterate over the items with the times they are due, in the order they are due.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (u64, T)> + 'a {
+        self.items.iter().map(|((at, _), item)| (at, item))
+    }
+
+    /// Removes all items from the queue.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_pop_due() {
+        test_env::setup();
+        let mut queue: ScheduledQueue<String> = ScheduledQueue::default();
+        assert_eq!(queue.next_due(), None);
+        queue.push(30, &"c".to_string());
+        queue.push(10, &"a".to_string());
+        queue.push(20, &"b1".to_string());
+        queue.push(20, &"b2".to_string());
+        queue.push(20, &"b3".to_string());
+        assert_eq!(queue.len(), 5);
+        assert_eq!(queue.next_due(), Some(10));
+
+        assert_eq!(queue.pop_due(5, 10), Vec::<String>::new());
+        assert_eq!(queue.pop_due(20, 3), vec!["a", "b1", "b2"]);
+        assert_eq!(queue.next_due(), Some(20));
+        assert_eq!(queue.pop_due(25, 0), Vec::<String>::new());
+        assert_eq!(queue.pop_due(25, 10), vec!["b3"]);
+        queue.push(15, &"d".to_string());
+        assert_eq!(
+            queue.iter().collect::<Vec<_>>(),
+            vec![(15, "d".to_string()), (30, "c".to_string())]
+        );
+        assert_eq!(queue.pop_due(u64::MAX, 10), vec!["d", "c"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_persistence() {
+        test_env::setup();
+        let mut queue: ScheduledQueue<u32> = ScheduledQueue::new(b"q".to_vec());
+        queue.push(7, &1);
+        let mut queue: ScheduledQueue<u32> =
+            ScheduledQueue::try_from_slice(&queue.try_to_vec().unwrap()).unwrap();
+        queue.push(7, &2);
+        assert_eq!(queue.pop_due(7, 10), vec![1, 2]);
+    }
+}
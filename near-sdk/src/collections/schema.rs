@@ -0,0 +1,68 @@
+//! `BorshSchema` of the collections, so that the contract state using them has a schema. The
+//! schema of a collection describes the fields stored in the contract state and includes the
+//! definitions of its elements, so it changes when the types of the elements change.
+use std::collections::HashMap;
+
+use borsh::schema::{Declaration, Definition, Fields};
+use borsh::BorshSchema;
+
+use super::tree_map::Node;
+use super::{
+    LazyOption, LookupMap, LookupSet, RingBuffer, TreeMap, UnorderedMap, UnorderedSet, Vector,
+};
+
+macro_rules! impl_borsh_schema {
+    ($name:ident<$($param:ident),*> { $($field:ident: $field_ty:ty),* }) => {
+        impl<$($param: BorshSchema),*> BorshSchema for $name<$($param),*> {
+            fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+                let fields = Fields::NamedFields(vec![
+                    $((stringify!($field).to_string(), <$field_ty>::declaration())),*
+                ]);
+                Self::add_definition(Self::declaration(), Definition::Struct { fields }, definitions);
+                $(<$field_ty>::add_definitions_recursively(definitions);)*
+                $($param::add_definitions_recursively(definitions);)*
+            }
+
+            fn declaration() -> Declaration {
+                let params: Vec<Declaration> = vec![$($param::declaration()),*];
+                format!("{}<{}>", stringify!($name), params.join(", "))
+            }
+        }
+    };
+}
+
+impl_borsh_schema!(Vector<T> { len: u64, prefix: Vec<u8> });
+impl_borsh_schema!(LookupMap<K, V> { key_prefix: Vec<u8> });
+impl_borsh_schema!(LookupSet<T> { element_prefix: Vec<u8> });
+impl_borsh_schema!(LazyOption<T> { storage_key: Vec<u8> });
+impl_borsh_schema!(UnorderedSet<T> { element_index_prefix: Vec<u8>, elements: Vector<T> });
+impl_borsh_schema!(UnorderedMap<K, V> {
+    key_index_prefix: Vec<u8>,
+    keys: Vector<K>,
+    values: Vector<V>
+});
+impl_borsh_schema!(TreeMap<K, V> { root: u64, val: UnorderedMap<K, V>, tree: Vector<Node<K>> });
+impl_borsh_schema!(RingBuffer<T> { prefix: Vec<u8>, capacity: u64, start: u64, len: u64 });
+impl_borsh_schema!(Node<K> { id: u64, key: K, lft: Option<u64>, rgt: Option<u64>, ht: u64 });
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unordered_map_schema() {
+        let container = UnorderedMap::<String, u64>::schema_container();
+        assert_eq!(container.declaration, "UnorderedMap<string, u64>");
+        assert_eq!(
+            container.definitions.get("Vector<u64>"),
+            Some(&Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("len".to_string(), "u64".to_string()),
+                    ("prefix".to_string(), "Vec<u8>".to_string()),
+                ])
+            })
+        );
+        assert!(container.definitions.contains_key("Vector<string>"));
+    }
+}
@@ -1,8 +1,14 @@
 //! A set implemented on a trie. Unlike `std::collections::HashSet` the elements in this set are not
 //! hashed but are instead serialized.
+use crate::collections::storage_backend::{
+    storage_has_key, storage_read, storage_remove, storage_remove_evicted, storage_write,
+};
 use crate::collections::{append, append_slice, next_trie_id, Vector};
 use crate::env;
 use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::iter::FromIterator;
 use std::mem::size_of;
 
 const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
@@ -57,7 +63,7 @@ impl<T> UnorderedSet<T> {
     /// Returns true if the set contains a serialized element.
     fn contains_raw(&self, element_raw: &[u8]) -> bool {
         let index_lookup = self.raw_element_to_index_lookup(element_raw);
-        env::storage_has_key(&index_lookup)
+        storage_has_key(&index_lookup)
     }
 
     /// Adds a value to the set.
@@ -65,13 +71,13 @@ impl<T> UnorderedSet<T> {
     /// If the set did have this value present, `false` is returned.
     pub fn insert_raw(&mut self, element_raw: &[u8]) -> bool {
         let index_lookup = self.raw_element_to_index_lookup(element_raw);
-        match env::storage_read(&index_lookup) {
+        match storage_read(&index_lookup) {
             Some(_index_raw) => false,
             None => {
                 // The element does not exist yet.
                 let next_index = self.len();
                 let next_index_raw = Self::serialize_index(next_index);
-                env::storage_write(&index_lookup, &next_index_raw);
+                storage_write(&index_lookup, &next_index_raw);
                 self.elements.push_raw(element_raw);
                 true
             }
@@ -81,26 +87,21 @@ impl<T> UnorderedSet<T> {
     /// Removes a value from the set. Returns whether the value was present in the set.
     pub fn remove_raw(&mut self, element_raw: &[u8]) -> bool {
         let index_lookup = self.raw_element_to_index_lookup(element_raw);
-        match env::storage_read(&index_lookup) {
+        match storage_remove_evicted(&index_lookup) {
             Some(index_raw) => {
-                if self.len() == 1 {
-                    // If there is only one element then swap remove simply removes it without
-                    // swapping with the last element.
-                    env::storage_remove(&index_lookup);
-                } else {
-                    // If there is more than one element then swap remove swaps it with the last
-                    // element.
+                // If there is only one element then swap remove simply removes it without swapping
+                // with the last element. Otherwise swap remove swaps it with the last element.
+                if self.len() > 1 {
                     let last_element_raw = match self.elements.get_raw(self.len() - 1) {
                         Some(x) => x,
                         None => env::panic(ERR_INCONSISTENT_STATE),
                     };
-                    env::storage_remove(&index_lookup);
                     // If the removed element was the last element from keys, then we don't need to
                     // reinsert the lookup back.
                     if last_element_raw != element_raw {
                         let last_lookup_element =
                             self.raw_element_to_index_lookup(&last_element_raw);
-                        env::storage_write(&last_lookup_element, &index_raw);
+                        storage_write(&last_lookup_element, &index_raw);
                     }
                 }
                 let index = Self::deserialize_index(&index_raw);
@@ -144,16 +145,40 @@ where
     pub fn clear(&mut self) {
         for raw_element in self.elements.iter_raw() {
             let index_lookup = self.raw_element_to_index_lookup(&raw_element);
-            env::storage_remove(&index_lookup);
+            storage_remove(&index_lookup);
         }
         self.elements.clear();
     }
 
+    /// Removes up to `limit` elements. Returns `true` if the set is empty. A large set is cleared
+    /// over several calls this way, when removing all the elements at once would exceed the gas
+    /// limit.
+    pub fn clear_partial(&mut self, limit: u64) -> bool {
+        for _ in 0..limit {
+            match self.elements.pop_raw() {
+                Some(raw_element) => {
+                    let index_lookup = self.raw_element_to_index_lookup(&raw_element);
+                    storage_remove(&index_lookup);
+                }
+                None => break,
+            }
+        }
+        self.elements.is_empty()
+    }
+
     /// Copies elements into an `std::vec::Vec`.
     pub fn to_vec(&self) -> std::vec::Vec<T> {
         self.iter().collect()
     }
 
+    /// Copies elements into an `std::collections::HashSet`.
+    pub fn to_hashset(&self) -> HashSet<T>
+    where
+        T: Eq + Hash,
+    {
+        self.iter().collect()
+    }
+
     /// Iterate over deserialized elements.
     pub fn iter<'a>(&'a self) -> impl Iterator<Item = T> + 'a {
         self.elements.iter()
@@ -172,6 +197,27 @@ where
     }
 }
 
+impl<T> Extend<T> for UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn extend<IT: IntoIterator<Item = T>>(&mut self, iter: IT) {
+        UnorderedSet::extend(self, iter)
+    }
+}
+
+/// Collects the elements into a new set with a prefix from `next_trie_id`, like `default`.
+impl<T> FromIterator<T> for UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn from_iter<IT: IntoIterator<Item = T>>(iter: IT) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
@@ -295,6 +341,20 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_clear_partial() {
+        test_env::setup();
+        let mut set = UnorderedSet::default();
+        set.extend(0..10u64);
+        assert!(!set.clear_partial(4));
+        assert_eq!(set.len(), 6);
+        assert!(set.contains(&0));
+        assert!(!set.contains(&9));
+        assert!(set.clear_partial(10));
+        assert!(set.to_vec().is_empty());
+        assert!(set.insert(&9));
+    }
+
     #[test]
     pub fn test_iter() {
         test_env::setup();
@@ -334,4 +394,14 @@ mod tests {
         let actual: HashSet<u64> = HashSet::from_iter(set.iter());
         assert_eq!(actual, keys);
     }
+
+    #[test]
+    pub fn test_from_iter_to_hashset() {
+        test_env::setup();
+        let mut set: UnorderedSet<u64> = vec![1, 2, 3, 2].into_iter().collect();
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.to_hashset(), HashSet::from_iter(vec![1, 2, 3]));
+        Extend::extend(&mut set, vec![3, 4]);
+        assert_eq!(set.to_hashset(), HashSet::from_iter(vec![1, 2, 3, 4]));
+    }
 }
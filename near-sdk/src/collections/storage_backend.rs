@@ -0,0 +1,335 @@
+//! The storage that the collections read and write. In a contract it's always the storage of the
+//! account, accessed through `env`. Off-chain, `with_storage_backend` runs the collections against
+//! another `StorageBackend` for the duration of a closure, e.g. `InMemoryStorage` for the property
+//! tests that would be slow with the mocked blockchain, or `CountingStorage` to check how many
+//! storage accesses an operation takes:
+//!
+//! ```
+//! # use near_sdk::collections::{with_storage_backend, CountingStorage, InMemoryStorage, UnorderedMap};
+//! let mut storage = CountingStorage::new(InMemoryStorage::default());
+//! with_storage_backend(&mut storage, || {
+//!     let mut map: UnorderedMap<u64, u64> = UnorderedMap::new(b"m".to_vec());
+//!     map.insert(&1, &10);
+//!     map.get(&1);
+//! });
+//! assert_eq!(storage.writes(), 3);
+//! ```
+use crate::env;
+#[cfg(not(target_arch = "wasm32"))]
+use std::cell::Cell;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::BTreeMap;
+
+/// A key-value storage for the collections.
+pub trait StorageBackend {
+    /// Returns the value of the key, or `None` if the key is not present.
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>>;
+
+    /// Writes the value of the key, returning the value it replaced.
+    fn write(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>>;
+
+    /// Removes the key, returning its value.
+    fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>>;
+
+    /// Returns `true` if the key is present.
+    fn has_key(&self, key: &[u8]) -> bool {
+        self.read(key).is_some()
+    }
+}
+
+/// The storage of the account, accessed through `env`. The collections use it unless another
+/// backend is set with `with_storage_backend`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HostStorage;
+
+impl StorageBackend for HostStorage {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        env::storage_read(key)
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        if env::storage_write(key, value) {
+            env::storage_get_evicted()
+        } else {
+            None
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        env::storage_remove_evicted(key)
+    }
+
+    fn has_key(&self, key: &[u8]) -> bool {
+        env::storage_has_key(key)
+    }
+}
+
+/// A storage in a `BTreeMap`, without the blockchain.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryStorage {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl InMemoryStorage {
+    /// Returns the number of keys in the storage.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the storage has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StorageBackend for InMemoryStorage {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        self.entries.insert(key.to_vec(), value.to_vec())
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.entries.remove(key)
+    }
+
+    fn has_key(&self, key: &[u8]) -> bool {
+        self.entries.contains_key(key)
+    }
+}
+
+/// Counts the accesses to another backend. `has_key` counts as a read.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default, Clone)]
+pub struct CountingStorage<S> {
+    inner: S,
+    reads: Cell<u64>,
+    writes: u64,
+    removes: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> CountingStorage<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner, reads: Cell::new(0), writes: 0, removes: 0 }
+    }
+
+    pub fn reads(&self) -> u64 {
+        self.reads.get()
+    }
+
+    pub fn writes(&self) -> u64 {
+        self.writes
+    }
+
+    pub fn removes(&self) -> u64 {
+        self.removes
+    }
+
+    /// Sets the counts to zero, e.g. after the collection under test is populated.
+    pub fn reset(&mut self) {
+        self.reads.set(0);
+        self.writes = 0;
+        self.removes = 0;
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S: StorageBackend> StorageBackend for CountingStorage<S> {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.read(key)
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        self.writes += 1;
+        self.inner.write(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.removes += 1;
+        self.inner.remove(key)
+    }
+
+    fn has_key(&self, key: &[u8]) -> bool {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.has_key(key)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    /// The backend set by `with_storage_backend`, or null for the host storage.
+    static BACKEND: Cell<Option<*mut dyn StorageBackend>> = Cell::new(None);
+}
+
+/// Runs `f` with the collections reading and writing `backend` instead of the host storage, and
+/// returns its result. The previous backend is restored afterwards, even if `f` panics.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn with_storage_backend<S, R, F>(backend: &mut S, f: F) -> R
+where
+    S: StorageBackend + 'static,
+    F: FnOnce() -> R,
+{
+    struct Restore(Option<*mut dyn StorageBackend>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BACKEND.with(|b| b.set(self.0));
+        }
+    }
+
+    let backend: *mut dyn StorageBackend = backend;
+    let _restore = Restore(BACKEND.with(|b| b.replace(Some(backend))));
+    f()
+}
+
+/// Calls `f` with the backend set by `with_storage_backend`, or returns `None` for the host
+/// storage.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_backend<R>(f: impl FnOnce(&mut dyn StorageBackend) -> R) -> Option<R> {
+    // The backend is borrowed by `with_storage_backend` until it restores the previous one, and the
+    // backends don't access the collections, so this is the only reference in use.
+    BACKEND.with(|b| b.get()).map(|backend| f(unsafe { &mut *backend }))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn with_backend<R>(_f: impl FnOnce(&mut dyn StorageBackend) -> R) -> Option<R> {
+    None
+}
+
+pub(crate) fn storage_read(key: &[u8]) -> Option<Vec<u8>> {
+    with_backend(|b| b.read(key)).unwrap_or_else(|| env::storage_read(key))
+}
+
+/// Reads the value into `buf`, like `env::storage_read_into`.
+pub(crate) fn storage_read_into(key: &[u8], buf: &mut Vec<u8>) -> bool {
+    match with_backend(|b| b.read(key)) {
+        Some(Some(value)) => {
+            buf.clear();
+            buf.extend_from_slice(&value);
+            true
+        }
+        Some(None) => false,
+        None => env::storage_read_into(key, buf),
+    }
+}
+
+pub(crate) fn storage_has_key(key: &[u8]) -> bool {
+    with_backend(|b| b.has_key(key)).unwrap_or_else(|| env::storage_has_key(key))
+}
+
+/// Writes the value, returning `true` if it replaced another one.
+pub(crate) fn storage_write(key: &[u8], value: &[u8]) -> bool {
+    with_backend(|b| b.write(key, value).is_some())
+        .unwrap_or_else(|| env::storage_write(key, value))
+}
+
+/// Writes the value, returning the value it replaced.
+pub(crate) fn storage_write_evicted(key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+    with_backend(|b| b.write(key, value)).unwrap_or_else(|| HostStorage.write(key, value))
+}
+
+/// Removes the key, returning `true` if it was present.
+pub(crate) fn storage_remove(key: &[u8]) -> bool {
+    with_backend(|b| b.remove(key).is_some()).unwrap_or_else(|| env::storage_remove(key))
+}
+
+/// Removes the key, returning its value.
+pub(crate) fn storage_remove_evicted(key: &[u8]) -> Option<Vec<u8>> {
+    with_backend(|b| b.remove(key)).unwrap_or_else(|| env::storage_remove_evicted(key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{LookupMap, TreeMap, UnorderedMap, Vector};
+    use crate::test_utils::test_env;
+    use quickcheck::quickcheck;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_in_memory() {
+        // Without the mocked blockchain, any access to the host storage would panic.
+        let mut storage = InMemoryStorage::default();
+        with_storage_backend(&mut storage, || {
+            let mut vec: Vector<u64> = Vector::new(b"v".to_vec());
+            vec.extend(0..10);
+            assert_eq!(vec.swap_remove(2), 2);
+            assert_eq!(vec.iter().nth(2), Some(9));
+            let mut map: TreeMap<u64, u64> = TreeMap::new(b"t".to_vec());
+            map.extend((0..10).map(|i| (i, i * i)));
+            map.remove(&3);
+            assert_eq!(map.floor_key(&3), Some(2));
+            assert_eq!(map.len(), 9);
+        });
+        assert!(!storage.is_empty());
+        let entries = storage.len();
+        with_storage_backend(&mut storage, || {
+            let mut vec: Vector<u64> = Vector::new(b"w".to_vec());
+            vec.extend(0..10);
+            vec.clear();
+        });
+        assert_eq!(storage.len(), entries);
+    }
+
+    #[test]
+    fn test_counting() {
+        let mut storage = CountingStorage::new(InMemoryStorage::default());
+        let mut map: UnorderedMap<u64, u64> = UnorderedMap::new(b"m".to_vec());
+        with_storage_backend(&mut storage, || map.extend((0..10).map(|i| (i, i))));
+        storage.reset();
+        assert_eq!(with_storage_backend(&mut storage, || map.get(&5)), Some(5));
+        assert_eq!((storage.reads(), storage.writes(), storage.removes()), (2, 0, 0));
+        assert_eq!(storage.inner().len(), 30);
+    }
+
+    #[test]
+    fn test_restores_host_storage() {
+        test_env::setup();
+        let mut storage = InMemoryStorage::default();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            with_storage_backend(&mut storage, || {
+                LookupMap::<u64, u64>::new(b"l".to_vec()).insert(&1, &1);
+                panic!("in the closure");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(storage.len(), 1);
+        LookupMap::<u64, u64>::new(b"l".to_vec()).insert(&2, &2);
+        assert!(env::storage_has_key(b"l\x02\x00\x00\x00\x00\x00\x00\x00"));
+        assert!(!env::storage_has_key(b"l\x01\x00\x00\x00\x00\x00\x00\x00"));
+    }
+
+    quickcheck! {
+        fn qc_in_memory_matches_host(ops: Vec<(bool, u8, u8)>) -> bool {
+            test_env::setup();
+            let run = |ops: &[(bool, u8, u8)]| {
+                let mut map: UnorderedMap<u8, u8> = UnorderedMap::new(b"q".to_vec());
+                for &(insert, key, value) in ops {
+                    if insert {
+                        map.insert(&key, &value);
+                    } else {
+                        map.remove(&key);
+                    }
+                }
+                let entries: HashMap<u8, u8> = map.to_hashmap();
+                map.clear();
+                entries
+            };
+            let mut storage = InMemoryStorage::default();
+            let in_memory = with_storage_backend(&mut storage, || run(&ops));
+            in_memory == run(&ops) && storage.is_empty()
+        }
+    }
+}
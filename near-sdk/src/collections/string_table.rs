@@ -0,0 +1,136 @@
+//! A table of interned strings. A contract that stores the same strings many times, like the token
+//! symbols or the account ids in a history of transfers, can store a `u32` id of the string
+//! instead, and keep the string itself once in the table.
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::collections::{append, next_trie_id, LookupMap, Vector};
+use crate::env;
+
+const ERR_TABLE_FULL: &[u8] = b"The string table is full";
+const ERR_STRING_SERIALIZATION: &[u8] = b"Cannot serialize string with Borsh";
+const ERR_ID_DESERIALIZATION: &[u8] = b"Cannot deserialize id with Borsh";
+
+/// Maps the strings to `u32` ids and back. The ids are assigned in the order the strings are
+/// interned, starting from zero, and the strings are never removed, so an id stays valid as long
+/// as the table exists.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct StringTable {
+    ids: LookupMap<String, u32>,
+    strings: Vector<String>,
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self::new(next_trie_id())
+    }
+}
+
+impl StringTable {
+    /// Create a new table. Use `id` as a unique prefix for the keys.
+    pub fn new(id: Vec<u8>) -> Self {
+        Self { ids: LookupMap::new(append(&id, b'i')), strings: Vector::new(append(&id, b's')) }
+    }
+
+    /// Returns the number of strings in the table.
+    pub fn len(&self) -> u32 {
+        self.strings.len() as u32
+    }
+
+    /// Returns `true` if the table has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    fn serialize_string(s: &str) -> Vec<u8> {
+        match s.try_to_vec() {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_STRING_SERIALIZATION),
+        }
+    }
+
+    fn deserialize_id(raw_id: &[u8]) -> u32 {
+        match u32::try_from_slice(raw_id) {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_ID_DESERIALIZATION),
+        }
+    }
+
+    /// Returns the id of the string, adding it to the table if it's not there yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table already has `u32::MAX` strings.
+    pub fn intern(&mut self, s: &str) -> u32 {
+        let raw_string = Self::serialize_string(s);
+        if let Some(raw_id) = self.ids.get_raw(&raw_string) {
+            return Self::deserialize_id(&raw_id);
+        }
+        if self.strings.len() >= u64::from(u32::MAX) {
+            env::panic(ERR_TABLE_FULL)
+        }
+        let id = self.len();
+        self.ids.insert_raw(&raw_string, &id.to_le_bytes());
+        self.strings.push_raw(&raw_string);
+        id
+    }
+
+    /// Returns the id of the string, or `None` if it's not in the table.
+    pub fn id_of(&self, s: &str) -> Option<u32> {
+        self.ids.get_raw(&Self::serialize_string(s)).map(|raw_id| Self::deserialize_id(&raw_id))
+    }
+
+    /// Returns the string with the id, or `None` if there is no such id.
+    pub fn resolve(&self, id: u32) -> Option<String> {
+        self.strings.get(u64::from(id))
+    }
+
+    /// Iterate over the strings in the order of their ids.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = String> + 'a {
+        self.strings.iter()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_intern() {
+        test_env::setup();
+        let mut table = StringTable::default();
+        assert!(table.is_empty());
+        assert_eq!(table.intern("alice.near"), 0);
+        assert_eq!(table.intern("bob.near"), 1);
+        assert_eq!(table.intern("alice.near"), 0);
+        assert_eq!(table.intern(""), 2);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.id_of("bob.near"), Some(1));
+        assert_eq!(table.id_of("carol.near"), None);
+        assert_eq!(table.resolve(1), Some("bob.near".to_string()));
+        assert_eq!(table.resolve(2), Some(String::new()));
+        assert_eq!(table.resolve(3), None);
+        assert_eq!(table.iter().collect::<Vec<_>>(), vec!["alice.near", "bob.near", ""]);
+    }
+
+    #[test]
+    fn test_persistence() {
+        test_env::setup();
+        let mut table = StringTable::new(b"t".to_vec());
+        table.intern("wNEAR");
+        table.intern("USDC");
+        let mut table = StringTable::try_from_slice(&table.try_to_vec().unwrap()).unwrap();
+        assert_eq!(table.intern("USDC"), 1);
+        assert_eq!(table.intern("DAI"), 2);
+        assert_eq!(table.resolve(0), Some("wNEAR".to_string()));
+    }
+
+    #[test]
+    fn test_schema() {
+        let container = StringTable::schema_container();
+        assert_eq!(container.declaration, "StringTable");
+        assert!(container.definitions.contains_key("LookupMap<string, u32>"));
+        assert!(container.definitions.contains_key("Vector<string>"));
+    }
+}
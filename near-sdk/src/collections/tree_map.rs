@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
 use std::ops::Bound;
 use borsh::{BorshDeserialize, BorshSerialize};
 
@@ -144,6 +146,12 @@ impl<K, V> TreeMap<K, V>
         }
     }
 
+    /// Returns the smallest key that is greater or equal to key given as the parameter.
+    /// Same as `ceil_key`.
+    pub fn ceiling_key(&self, key: &K) -> Option<K> {
+        self.ceil_key(key)
+    }
+
     /// Returns the largest key that is less or equal to key given as the parameter
     pub fn floor_key(&self, key: &K) -> Option<K> {
         if self.contains_key(key) {
@@ -158,6 +166,17 @@ impl<K, V> TreeMap<K, V>
         Cursor::asc(&self).into_iter()
     }
 
+    /// Iterate all keys in ascending order. Unlike `iter`, it doesn't read the values.
+    pub fn keys<'a>(&'a self) -> impl Iterator<Item = K> + 'a {
+        let mut cursor = Cursor::asc(self);
+        std::iter::from_fn(move || cursor.next_key())
+    }
+
+    /// Iterate all values in ascending order of their keys.
+    pub fn values<'a>(&'a self) -> impl Iterator<Item = V> + 'a {
+        self.iter().map(|(_, v)| v)
+    }
+
     /// Iterate entries in ascending order: given key (exclusive) to max (inclusive)
     pub fn iter_from<'a>(&'a self, key: K) -> impl Iterator<Item = (K, V)> + 'a {
         Cursor::asc_from(&self, key).into_iter()
@@ -191,10 +210,40 @@ impl<K, V> TreeMap<K, V>
         Cursor::range(&self, lo, hi).into_iter()
     }
 
+    /// Iterate entries in descending order according to specified bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range start > end.
+    /// Panics if range start == end and both bounds are Excluded.
+    pub fn range_rev<'a>(&'a self, r: (Bound<K>, Bound<K>)) -> impl Iterator<Item = (K, V)> + 'a {
+        let (lo, hi) = match r {
+            (Bound::Included(a), Bound::Included(b)) if a >  b => panic!("Invalid range."),
+            (Bound::Excluded(a), Bound::Included(b)) if a >  b => panic!("Invalid range."),
+            (Bound::Included(a), Bound::Excluded(b)) if a >  b => panic!("Invalid range."),
+            (Bound::Excluded(a), Bound::Excluded(b)) if a == b => panic!("Invalid range."),
+            (lo, hi) => (lo, hi)
+        };
+
+        Cursor::range_rev(self, lo, hi)
+    }
+
     pub fn to_vec(&self) -> Vec<(K, V)> {
         self.iter().collect()
     }
 
+    /// Copies the entries into an `std::collections::BTreeMap`.
+    pub fn to_btreemap(&self) -> BTreeMap<K, V> {
+        self.iter().collect()
+    }
+
+    /// Inserts all key-value pairs from the iterator.
+    pub fn extend<IT: IntoIterator<Item = (K, V)>>(&mut self, iter: IT) {
+        for (key, val) in iter {
+            self.insert(&key, &val);
+        }
+    }
+
     //
     // Internal utilities
     //
@@ -617,6 +666,29 @@ impl<K, V> TreeMap<K, V>
     }
 }
 
+impl<K, V> Extend<(K, V)> for TreeMap<K, V>
+    where
+        K: Ord + Clone + BorshSerialize + BorshDeserialize,
+        V: BorshSerialize + BorshDeserialize,
+{
+    fn extend<IT: IntoIterator<Item = (K, V)>>(&mut self, iter: IT) {
+        TreeMap::extend(self, iter)
+    }
+}
+
+/// Collects the entries into a new map with a prefix from `next_trie_id`, like `default`.
+impl<K, V> FromIterator<(K, V)> for TreeMap<K, V>
+    where
+        K: Ord + Clone + BorshSerialize + BorshDeserialize,
+        V: BorshSerialize + BorshDeserialize,
+{
+    fn from_iter<IT: IntoIterator<Item = (K, V)>>(iter: IT) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
 impl<'a, K, V> IntoIterator for &'a TreeMap<K, V>
     where
         K: Ord + Clone + BorshSerialize + BorshDeserialize,
@@ -638,20 +710,7 @@ impl<K, V> Iterator for Cursor<'_, K, V>
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let this_key = self.key.clone();
-
-        let next_key = self.key.take()
-            .and_then(|k| {
-                if self.asc {
-                    self.map.higher(&k)
-                } else {
-                    self.map.lower(&k)
-                }
-            })
-            .filter(|k| fits(k, &self.lo, &self.hi));
-        self.key = next_key;
-
-        this_key.and_then(|k| self.map.get(&k).map(|v| (k, v)))
+        self.next_key().and_then(|k| self.map.get(&k).map(|v| (k, v)))
     }
 }
 
@@ -725,11 +784,28 @@ impl<'a, K, V> Cursor<'a, K, V>
         }
     }
 
+    /// Moves the cursor to the next key without reading the value of the current one.
+    fn next_key(&mut self) -> Option<K> {
+        let this_key = self.key.take();
+
+        self.key = this_key.as_ref()
+            .and_then(|k| {
+                if self.asc {
+                    self.map.higher(k)
+                } else {
+                    self.map.lower(k)
+                }
+            })
+            .filter(|k| fits(k, &self.lo, &self.hi));
+
+        this_key
+    }
+
     fn range(map: &'a TreeMap<K, V>, lo: Bound<K>, hi: Bound<K>) -> Self {
         let key = match &lo {
             Bound::Included(k) if map.contains_key(k) => Some(k.clone()),
             Bound::Included(k) | Bound::Excluded(k) => map.higher(k),
-            _ => None
+            Bound::Unbounded => map.min()
         };
         let key = key.filter(|k| fits(k, &lo, &hi));
 
@@ -741,6 +817,23 @@ impl<'a, K, V> Cursor<'a, K, V>
             map
         }
     }
+
+    fn range_rev(map: &'a TreeMap<K, V>, lo: Bound<K>, hi: Bound<K>) -> Self {
+        let key = match &hi {
+            Bound::Included(k) if map.contains_key(k) => Some(k.clone()),
+            Bound::Included(k) | Bound::Excluded(k) => map.lower(k),
+            Bound::Unbounded => map.max()
+        };
+        let key = key.filter(|k| fits(k, &lo, &hi));
+
+        Self {
+            asc: false,
+            key,
+            lo,
+            hi,
+            map
+        }
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -1083,6 +1176,8 @@ mod tests {
         assert_eq!(map.ceil_key(&49), Some(50));
         assert_eq!(map.ceil_key(&50), Some(50));
         assert_eq!(map.ceil_key(&51), None);
+        assert_eq!(map.ceiling_key(&11), Some(20));
+        assert_eq!(map.ceiling_key(&51), None);
 
         map.clear();
     }
@@ -1452,6 +1547,28 @@ mod tests {
         map.clear();
     }
 
+    #[test]
+    fn test_keys_values() {
+        test_env::setup();
+        let mut map: TreeMap<u32, u32> = TreeMap::default();
+        let one: Vec<u32> = vec![10, 20, 30, 40];
+        let two: Vec<u32> = vec![15, 25, 35];
+
+        for x in &one {
+            map.insert(x, &(x + 1));
+        }
+        for x in &two {
+            map.insert(x, &(x + 1));
+        }
+
+        assert_eq!(map.keys().collect::<Vec<u32>>(), vec![10, 15, 20, 25, 30, 35, 40]);
+        assert_eq!(map.values().collect::<Vec<u32>>(), vec![11, 16, 21, 26, 31, 36, 41]);
+
+        map.clear();
+        assert_eq!(map.keys().count(), 0);
+        assert_eq!(map.values().count(), 0);
+    }
+
     #[test]
     fn test_iter_empty() {
         test_env::setup();
@@ -1596,6 +1713,78 @@ mod tests {
         map.clear();
     }
 
+    #[test]
+    fn test_range_unbounded() {
+        test_env::setup();
+        let mut map: TreeMap<u32, u32> = TreeMap::default();
+
+        for x in &[10, 20, 30, 40, 50] {
+            map.insert(x, &42);
+        }
+
+        assert_eq!(
+            map.range((Bound::Unbounded, Bound::Excluded(30))).collect::<Vec<(u32, u32)>>(),
+            vec![(10, 42), (20, 42)]);
+
+        assert_eq!(
+            map.range((Bound::Unbounded, Bound::Unbounded)).collect::<Vec<(u32, u32)>>(),
+            map.to_vec());
+
+        assert_eq!(
+            map.range((Bound::Unbounded, Bound::Excluded(10))).collect::<Vec<(u32, u32)>>(),
+            vec![]);
+
+        map.clear();
+    }
+
+    #[test]
+    fn test_range_rev() {
+        test_env::setup();
+        let mut map: TreeMap<u32, u32> = TreeMap::default();
+
+        for x in &[10, 20, 30, 40, 50] {
+            map.insert(x, &42);
+        }
+
+        assert_eq!(
+            map.range_rev((Bound::Included(20), Bound::Included(40))).collect::<Vec<(u32, u32)>>(),
+            vec![(40, 42), (30, 42), (20, 42)]);
+
+        assert_eq!(
+            map.range_rev((Bound::Excluded(20), Bound::Excluded(40))).collect::<Vec<(u32, u32)>>(),
+            vec![(30, 42)]);
+
+        assert_eq!(
+            map.range_rev((Bound::Included(15), Bound::Included(45))).collect::<Vec<(u32, u32)>>(),
+            vec![(40, 42), (30, 42), (20, 42)]);
+
+        assert_eq!(
+            map.range_rev((Bound::Unbounded, Bound::Excluded(30))).collect::<Vec<(u32, u32)>>(),
+            vec![(20, 42), (10, 42)]);
+
+        assert_eq!(
+            map.range_rev((Bound::Excluded(30), Bound::Unbounded)).collect::<Vec<(u32, u32)>>(),
+            vec![(50, 42), (40, 42)]);
+
+        assert_eq!(
+            map.range_rev((Bound::Unbounded, Bound::Unbounded)).collect::<Vec<(u32, u32)>>(),
+            map.iter_rev().collect::<Vec<(u32, u32)>>());
+
+        assert_eq!(
+            map.range_rev((Bound::Included(31), Bound::Included(39))).collect::<Vec<(u32, u32)>>(),
+            vec![]);
+
+        map.clear();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range.")]
+    fn test_range_rev_panics_non_overlap() {
+        test_env::setup();
+        let map: TreeMap<u32, u32> = TreeMap::default();
+        let _ = map.range_rev((Bound::Included(2), Bound::Included(1)));
+    }
+
     #[test]
     #[should_panic(expected = "Invalid range.")]
     fn test_range_panics_same_excluded() {
@@ -1765,7 +1954,12 @@ mod tests {
         let v2: Vec<(u32, u32)> = b.range(range)
             .map(|(k, v)| (*k, *v))
             .collect();
-        v1 == v2
+        let r1: Vec<(u32, u32)> = a.range_rev(range).collect();
+        let r2: Vec<(u32, u32)> = b.range(range)
+            .rev()
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        v1 == v2 && r1 == r2
     }
 
     type Prop = fn(std::vec::Vec<(u32, u32)>, std::vec::Vec<u32>, u32, u32) -> bool;
@@ -1820,4 +2014,15 @@ mod tests {
             .tests(300)
             .quickcheck(prop as Prop);
     }
+
+    #[test]
+    fn test_from_iter_to_btreemap() {
+        test_env::setup();
+        let baseline: BTreeMap<u32, u32> = vec![(30, 3), (10, 1), (20, 2)].into_iter().collect();
+        let mut map: TreeMap<u32, u32> = baseline.clone().into_iter().collect();
+        assert_eq!(map.to_btreemap(), baseline);
+        Extend::extend(&mut map, vec![(5, 0), (20, 0)]);
+        assert_eq!(map.to_vec(), vec![(5, 0), (10, 1), (20, 0), (30, 3)]);
+        map.clear();
+    }
 }
@@ -1,8 +1,14 @@
 //! A map implemented on a trie. Unlike `std::collections::HashMap` the keys in this map are not
 //! hashed but are instead serialized.
+use crate::collections::storage_backend::{
+    storage_read, storage_remove, storage_remove_evicted, storage_write,
+};
 use crate::collections::{append, append_slice, next_trie_id, Vector};
 use crate::env;
 use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::FromIterator;
 use std::mem::size_of;
 
 const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
@@ -77,7 +83,7 @@ impl<K, V> UnorderedMap<K, V> {
     /// Returns an index of the given raw key.
     fn get_index_raw(&self, key_raw: &[u8]) -> Option<u64> {
         let index_lookup = self.raw_key_to_index_lookup(key_raw);
-        env::storage_read(&index_lookup).map(|raw_index| Self::deserialize_index(&raw_index))
+        storage_read(&index_lookup).map(|raw_index| Self::deserialize_index(&raw_index))
     }
 
     /// Returns the serialized value corresponding to the serialized key.
@@ -94,7 +100,7 @@ impl<K, V> UnorderedMap<K, V> {
     /// the implementation.
     pub fn insert_raw(&mut self, key_raw: &[u8], value_raw: &[u8]) -> Option<Vec<u8>> {
         let index_lookup = self.raw_key_to_index_lookup(key_raw);
-        match env::storage_read(&index_lookup) {
+        match storage_read(&index_lookup) {
             Some(index_raw) => {
                 // The element already exists.
                 let index = Self::deserialize_index(&index_raw);
@@ -104,7 +110,7 @@ impl<K, V> UnorderedMap<K, V> {
                 // The element does not exist yet.
                 let next_index = self.len();
                 let next_index_raw = Self::serialize_index(next_index);
-                env::storage_write(&index_lookup, &next_index_raw);
+                storage_write(&index_lookup, &next_index_raw);
                 self.keys.push_raw(key_raw);
                 self.values.push_raw(value_raw);
                 None
@@ -116,25 +122,20 @@ impl<K, V> UnorderedMap<K, V> {
     /// was previously in the map.
     pub fn remove_raw(&mut self, key_raw: &[u8]) -> Option<Vec<u8>> {
         let index_lookup = self.raw_key_to_index_lookup(key_raw);
-        match env::storage_read(&index_lookup) {
+        match storage_remove_evicted(&index_lookup) {
             Some(index_raw) => {
-                if self.len() == 1 {
-                    // If there is only one element then swap remove simply removes it without
-                    // swapping with the last element.
-                    env::storage_remove(&index_lookup);
-                } else {
-                    // If there is more than one element then swap remove swaps it with the last
-                    // element.
+                // If there is only one element then swap remove simply removes it without swapping
+                // with the last element. Otherwise swap remove swaps it with the last element.
+                if self.len() > 1 {
                     let last_key_raw = match self.keys.get_raw(self.len() - 1) {
                         Some(x) => x,
                         None => env::panic(ERR_INCONSISTENT_STATE),
                     };
-                    env::storage_remove(&index_lookup);
                     // If the removed element was the last element from keys, then we don't need to
                     // reinsert the lookup back.
                     if last_key_raw != key_raw {
                         let last_lookup_key = self.raw_key_to_index_lookup(&last_key_raw);
-                        env::storage_write(&last_lookup_key, &index_raw);
+                        storage_write(&last_lookup_key, &index_raw);
                     }
                 }
                 let index = Self::deserialize_index(&index_raw);
@@ -197,23 +198,42 @@ where
     pub fn clear(&mut self) {
         for raw_key in self.keys.iter_raw() {
             let index_lookup = self.raw_key_to_index_lookup(&raw_key);
-            env::storage_remove(&index_lookup);
+            storage_remove(&index_lookup);
         }
         self.keys.clear();
         self.values.clear();
     }
 
+    /// Removes up to `limit` entries. Returns `true` if the map is empty. A large map is cleared
+    /// over several calls this way, when removing all the entries at once would exceed the gas
+    /// limit.
+    pub fn clear_partial(&mut self, limit: u64) -> bool {
+        for _ in 0..limit {
+            match self.keys.pop_raw() {
+                Some(raw_key) => {
+                    let index_lookup = self.raw_key_to_index_lookup(&raw_key);
+                    storage_remove(&index_lookup);
+                    self.values.pop_raw();
+                }
+                None => break,
+            }
+        }
+        self.keys.is_empty()
+    }
+
     /// Copies elements into an `std::vec::Vec`.
     pub fn to_vec(&self) -> std::vec::Vec<(K, V)> {
         self.iter().collect()
     }
 
     /// An iterator visiting all keys. The iterator element type is `K`.
+    /// The values are not read from the storage.
     pub fn keys<'a>(&'a self) -> impl Iterator<Item = K> + 'a {
         self.keys.iter()
     }
 
     /// An iterator visiting all values. The iterator element type is `V`.
+    /// The keys are not read from the storage.
     pub fn values<'a>(&'a self) -> impl Iterator<Item = V> + 'a {
         self.values.iter()
     }
@@ -229,6 +249,14 @@ where
         }
     }
 
+    /// Copies the entries into an `std::collections::HashMap`.
+    pub fn to_hashmap(&self) -> HashMap<K, V>
+    where
+        K: Eq + Hash,
+    {
+        self.iter().collect()
+    }
+
     /// Returns a view of keys as a vector.
     /// It's sometimes useful to have random access to the keys.
     pub fn keys_as_vector(&self) -> &Vector<K> {
@@ -242,6 +270,29 @@ where
     }
 }
 
+impl<K, V> Extend<(K, V)> for UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn extend<IT: IntoIterator<Item = (K, V)>>(&mut self, iter: IT) {
+        UnorderedMap::extend(self, iter)
+    }
+}
+
+/// Collects the entries into a new map with a prefix from `next_trie_id`, like `default`.
+impl<K, V> FromIterator<(K, V)> for UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn from_iter<IT: IntoIterator<Item = (K, V)>>(iter: IT) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
@@ -382,6 +433,20 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_clear_partial() {
+        test_env::setup();
+        let mut map = UnorderedMap::default();
+        map.extend((0..10u64).map(|i| (i, i * 2)));
+        assert!(!map.clear_partial(4));
+        assert_eq!(map.len(), 6);
+        assert_eq!(map.get(&5), Some(10));
+        assert_eq!(map.get(&9), None);
+        assert!(map.clear_partial(10));
+        assert!(map.to_vec().is_empty());
+        assert_eq!(map.insert(&9, &1), None);
+    }
+
     #[test]
     pub fn test_keys_values() {
         test_env::setup();
@@ -447,4 +512,16 @@ mod tests {
         let actual: HashMap<u64, u64> = HashMap::from_iter(map.iter());
         assert_eq!(actual, key_to_value);
     }
+
+    #[test]
+    pub fn test_from_iter_to_hashmap() {
+        test_env::setup();
+        let baseline: HashMap<u64, u64> = (0..10).map(|i| (i, i * i)).collect();
+        let mut map: UnorderedMap<u64, u64> = baseline.clone().into_iter().collect();
+        assert_eq!(map.to_hashmap(), baseline);
+        Extend::extend(&mut map, vec![(3, 0), (10, 100)]);
+        assert_eq!(map.len(), 11);
+        assert_eq!(map.get(&3), Some(0));
+        assert_eq!(map.get(&10), Some(100));
+    }
 }
@@ -1,9 +1,15 @@
 //! A vector implemented on a trie. Unlike standard vector does not support insertion and removal
 //! of an element results in the last element being placed in the empty position.
+use std::iter::FromIterator;
 use std::marker::PhantomData;
+use std::ops::{Bound, Range, RangeBounds};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use crate::collections::storage_backend::{
+    storage_read, storage_read_into, storage_remove, storage_remove_evicted, storage_write,
+    storage_write_evicted,
+};
 use crate::collections::{append_slice, next_trie_id};
 use crate::env;
 
@@ -49,7 +55,7 @@ impl<T> Vector<T> {
             return None;
         }
         let lookup_key = self.index_to_lookup_key(index);
-        match env::storage_read(&lookup_key) {
+        match storage_read(&lookup_key) {
             Some(raw_element) => Some(raw_element),
             None => env::panic(ERR_INCONSISTENT_STATE),
         }
@@ -73,13 +79,9 @@ impl<T> Vector<T> {
         } else {
             let lookup_key = self.index_to_lookup_key(index);
             let raw_last_value = self.pop_raw().expect("checked `index < len` above, so `len > 0`");
-            if env::storage_write(&lookup_key, &raw_last_value) {
-                match env::storage_get_evicted() {
-                    Some(x) => x,
-                    None => env::panic(ERR_INCONSISTENT_STATE),
-                }
-            } else {
-                env::panic(ERR_INCONSISTENT_STATE)
+            match storage_write_evicted(&lookup_key, &raw_last_value) {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
             }
         }
     }
@@ -88,7 +90,7 @@ impl<T> Vector<T> {
     pub fn push_raw(&mut self, raw_element: &[u8]) {
         let lookup_key = self.index_to_lookup_key(self.len);
         self.len += 1;
-        env::storage_write(&lookup_key, raw_element);
+        storage_write(&lookup_key, raw_element);
     }
 
     /// Removes the last element from a vector and returns it without deserializing, or `None` if it is empty.
@@ -100,13 +102,9 @@ impl<T> Vector<T> {
             let last_lookup_key = self.index_to_lookup_key(last_index);
 
             self.len -= 1;
-            let raw_last_value = if env::storage_remove(&last_lookup_key) {
-                match env::storage_get_evicted() {
-                    Some(x) => x,
-                    None => env::panic(ERR_INCONSISTENT_STATE),
-                }
-            } else {
-                env::panic(ERR_INCONSISTENT_STATE)
+            let raw_last_value = match storage_remove_evicted(&last_lookup_key) {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
             };
             Some(raw_last_value)
         }
@@ -122,22 +120,57 @@ impl<T> Vector<T> {
             env::panic(ERR_INDEX_OUT_OF_BOUNDS)
         } else {
             let lookup_key = self.index_to_lookup_key(index);
-            if env::storage_write(&lookup_key, &raw_element) {
-                match env::storage_get_evicted() {
-                    Some(x) => x,
-                    None => env::panic(ERR_INCONSISTENT_STATE),
-                }
-            } else {
-                env::panic(ERR_INCONSISTENT_STATE);
+            match storage_write_evicted(&lookup_key, raw_element) {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
             }
         }
     }
 
+    /// Removes the elements in `range` from the vector and returns them in serialized form.
+    /// The elements after the range are moved to fill the gap, so draining from the back is
+    /// cheaper than from the front.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end or the end is out of bounds.
+    pub fn drain_raw<R: RangeBounds<u64>>(&mut self, range: R) -> Vec<Vec<u8>> {
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.saturating_add(1),
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => self.len,
+        };
+        if start > end || end > self.len {
+            env::panic(ERR_INDEX_OUT_OF_BOUNDS)
+        }
+        let drained = (start..end)
+            .map(|i| match storage_remove_evicted(&self.index_to_lookup_key(i)) {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            })
+            .collect();
+        let count = end - start;
+        for i in end..self.len {
+            let raw_element = match storage_remove_evicted(&self.index_to_lookup_key(i)) {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            };
+            storage_write(&self.index_to_lookup_key(i - count), &raw_element);
+        }
+        self.len -= count;
+        drained
+    }
+
     /// Iterate over raw serialized elements.
     pub fn iter_raw<'a>(&'a self) -> impl Iterator<Item = Vec<u8>> + 'a {
         (0..self.len).map(move |i| {
             let lookup_key = self.index_to_lookup_key(i);
-            match env::storage_read(&lookup_key) {
+            match storage_read(&lookup_key) {
                 Some(x) => x,
                 None => env::panic(ERR_INCONSISTENT_STATE),
             }
@@ -157,10 +190,23 @@ impl<T> Vector<T> {
     pub fn clear(&mut self) {
         for i in 0..self.len {
             let lookup_key = self.index_to_lookup_key(i);
-            env::storage_remove(&lookup_key);
+            storage_remove(&lookup_key);
         }
         self.len = 0;
     }
+
+    /// Removes up to `limit` elements from the back of the collection. Returns `true` if the
+    /// collection is empty. A large collection is cleared over several calls this way, when
+    /// removing all the elements at once would exceed the gas limit.
+    pub fn clear_partial(&mut self, limit: u64) -> bool {
+        let new_len = self.len.saturating_sub(limit);
+        for i in new_len..self.len {
+            let lookup_key = self.index_to_lookup_key(i);
+            storage_remove(&lookup_key);
+        }
+        self.len = new_len;
+        self.is_empty()
+    }
 }
 
 impl<T> Vector<T>
@@ -221,9 +267,21 @@ where
         self.pop_raw().map(|x| Self::deserialize_element(&x))
     }
 
-    /// Iterate over deserialized elements.
-    pub fn iter<'a>(&'a self) -> impl Iterator<Item = T> + 'a {
-        self.iter_raw().map(|raw_element| Self::deserialize_element(&raw_element))
+    /// Removes the elements in `range` from the vector and returns them in order.
+    /// The elements after the range are moved to fill the gap, so draining from the back is
+    /// cheaper than from the front.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than the end or the end is out of bounds.
+    pub fn drain<R: RangeBounds<u64>>(&mut self, range: R) -> Vec<T> {
+        self.drain_raw(range).iter().map(|x| Self::deserialize_element(x)).collect()
+    }
+
+    /// Iterate over deserialized elements. Skipping elements with `skip` or `nth` doesn't read
+    /// them from the storage, so the iterator can be used for pagination.
+    pub fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = T> + ExactSizeIterator + 'a {
+        Iter { vec: self, index_range: 0..self.len, buf: vec![] }
     }
 
     pub fn to_vec(&self) -> Vec<T> {
@@ -253,12 +311,85 @@ impl<T: std::fmt::Debug + BorshDeserialize> std::fmt::Debug for Vector<T> {
     }
 }
 
+/// Iterator over the elements of `Vector` that reads an element only when it is returned.
+struct Iter<'a, T> {
+    vec: &'a Vector<T>,
+    index_range: Range<u64>,
+    /// The buffer that the elements are read into, reused across the elements.
+    buf: Vec<u8>,
+}
+
+impl<'a, T> Iter<'a, T>
+where
+    T: BorshDeserialize,
+{
+    fn element(&mut self, index: u64) -> T {
+        let lookup_key = self.vec.index_to_lookup_key(index);
+        if !storage_read_into(&lookup_key, &mut self.buf) {
+            env::panic(ERR_INCONSISTENT_STATE);
+        }
+        Vector::deserialize_element(&self.buf)
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: BorshDeserialize,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index_range.next()?;
+        Some(self.element(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.index_range.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.index_range.count()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.index_range.nth(n)?;
+        Some(self.element(index))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> where T: BorshDeserialize {}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T>
+where
+    T: BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.index_range.next_back()?;
+        Some(self.element(index))
+    }
+}
+
 impl<T> Default for Vector<T> {
     fn default() -> Self {
         Self::new(next_trie_id())
     }
 }
 
+impl<T: BorshSerialize> Extend<T> for Vector<T> {
+    fn extend<IT: IntoIterator<Item = T>>(&mut self, iter: IT) {
+        Vector::extend(self, iter)
+    }
+}
+
+/// Collects the elements into a new vector with a prefix from `next_trie_id`, like `default`.
+impl<T: BorshSerialize> FromIterator<T> for Vector<T> {
+    fn from_iter<IT: IntoIterator<Item = T>>(iter: IT) -> Self {
+        let mut vec = Self::default();
+        vec.extend(iter);
+        vec
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
@@ -385,6 +516,34 @@ mod tests {
         assert_eq!(actual, baseline);
     }
 
+    #[test]
+    pub fn test_drain() {
+        set_env();
+        let mut vec = Vector::default();
+        let mut baseline: Vec<u64> = (0..20).collect();
+        vec.extend(baseline.clone());
+        for range in &[3..7, 0..2, 10..14, 5..5, 9..10] {
+            let expected: Vec<u64> =
+                baseline.drain(range.start as usize..range.end as usize).collect();
+            assert_eq!(vec.drain(range.clone()), expected);
+            assert_eq!(vec.to_vec(), baseline);
+        }
+        assert_eq!(vec.drain(..=1), vec![2, 7]);
+        assert_eq!(vec.drain(5..), vec![13, 14]);
+        assert_eq!(vec.drain(..), vec![8, 9, 10, 11, 12]);
+        assert!(vec.is_empty());
+        assert_eq!(env::storage_entries_with_prefix(&vec.prefix).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    pub fn test_drain_out_of_bounds() {
+        set_env();
+        let mut vec = Vector::default();
+        vec.extend(0..3u64);
+        vec.drain(1..4);
+    }
+
     #[test]
     pub fn test_clear() {
         set_env();
@@ -398,9 +557,23 @@ mod tests {
             assert!(!vec.is_empty());
             vec.clear();
             assert!(vec.is_empty());
+            assert_eq!(env::storage_entries_with_prefix(&vec.prefix).count(), 0);
         }
     }
 
+    #[test]
+    pub fn test_clear_partial() {
+        set_env();
+        let mut vec = Vector::default();
+        vec.extend(0..10u64);
+        assert!(!vec.clear_partial(4));
+        assert_eq!(vec.to_vec(), (0..6u64).collect::<Vec<_>>());
+        assert!(vec.clear_partial(10));
+        assert!(vec.is_empty());
+        vec.push(&7u64);
+        assert_eq!(vec.to_vec(), vec![7u64]);
+    }
+
     #[test]
     pub fn test_extend() {
         set_env();
@@ -426,6 +599,35 @@ mod tests {
         assert_eq!(actual, baseline);
     }
 
+    #[test]
+    pub fn test_from_iter() {
+        set_env();
+        let mut vec: Vector<u64> = (0..5).collect();
+        assert_eq!(vec.to_vec(), vec![0, 1, 2, 3, 4]);
+        Extend::extend(&mut vec, vec![5, 6]);
+        assert_eq!(vec.to_vec(), (0..7).collect::<Vec<_>>());
+    }
+
+    #[test]
+    pub fn test_iter() {
+        set_env();
+        let mut vec = Vector::default();
+        let baseline: Vec<u64> = (0..20).collect();
+        vec.extend(baseline.clone());
+        assert_eq!(vec.iter().len(), 20);
+        assert_eq!(vec.iter().skip(5).take(3).collect::<Vec<_>>(), vec![5, 6, 7]);
+        assert_eq!(vec.iter().nth(19), Some(19));
+        assert_eq!(vec.iter().nth(20), None);
+        assert_eq!(
+            vec.iter().rev().collect::<Vec<_>>(),
+            baseline.into_iter().rev().collect::<Vec<_>>()
+        );
+        let mut iter = vec.iter();
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(19));
+        assert_eq!(iter.len(), 18);
+    }
+
     #[test]
     fn test_debug() {
         set_env();
@@ -0,0 +1,160 @@
+//! A value of a collection stored together with the version of its layout.
+//!
+//! Migrating all the entries of a large collection at once doesn't fit into the gas limit of a
+//! transaction. Instead the values are stored as `Versioned<T>`, and the values of an older version
+//! are converted to the current layout when they are read. A converted value is stored with the
+//! current layout the next time it is inserted:
+//!
+//! ```ignore
+//! impl VersionedValue for Account {
+//!     const VERSION: u8 = 1;
+//!
+//!     fn from_version(version: u8, data: &[u8]) -> Self {
+//!         match version {
+//!             0 => AccountV0::try_from_slice(data).unwrap().into(),
+//!             _ => env::panic(b"Unknown account version"),
+//!         }
+//!     }
+//! }
+//!
+//! let accounts: LookupMap<AccountId, Versioned<Account>> = LookupMap::new(b"a".to_vec());
+//! let account = accounts.get(&account_id).map(Versioned::into_current);
+//! ```
+//!
+//! `Versioned<T>` reads the rest of the data as the value, so it should be the whole value of an
+//! entry rather than a part of another struct, and it should be used from the first version of `T`.
+use std::io::{Error, ErrorKind, Write};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// The value with the layouts of its versions.
+pub trait VersionedValue: BorshSerialize + BorshDeserialize {
+    /// The version of the current layout, increased with each new layout.
+    const VERSION: u8;
+
+    /// Deserializes the value stored with an older version of the layout and converts it.
+    fn from_version(version: u8, data: &[u8]) -> Self;
+}
+
+/// A value stored with the version of its layout.
+pub enum Versioned<T> {
+    /// The value with the current layout.
+    Current(T),
+    /// The value stored with an older layout, not converted yet.
+    Outdated { version: u8, data: Vec<u8> },
+}
+
+impl<T: VersionedValue> Versioned<T> {
+    /// Returns `true` if the value has the current layout.
+    pub fn is_current(&self) -> bool {
+        match self {
+            Self::Current(_) => true,
+            Self::Outdated { .. } => false,
+        }
+    }
+
+    /// Returns the value with the current layout, converting it if needed.
+    pub fn into_current(self) -> T {
+        match self {
+            Self::Current(value) => value,
+            Self::Outdated { version, data } => T::from_version(version, &data),
+        }
+    }
+}
+
+impl<T> From<T> for Versioned<T> {
+    fn from(value: T) -> Self {
+        Self::Current(value)
+    }
+}
+
+impl<T: VersionedValue> BorshSerialize for Versioned<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            Self::Current(value) => {
+                T::VERSION.serialize(writer)?;
+                value.serialize(writer)
+            }
+            Self::Outdated { version, data } => {
+                version.serialize(writer)?;
+                writer.write_all(data)
+            }
+        }
+    }
+}
+
+impl<T: VersionedValue> BorshDeserialize for Versioned<T> {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let version = u8::deserialize(buf)?;
+        if version == T::VERSION {
+            Ok(Self::Current(T::deserialize(buf)?))
+        } else if version < T::VERSION {
+            let data = buf.to_vec();
+            *buf = &[];
+            Ok(Self::Outdated { version, data })
+        } else {
+            Err(Error::new(ErrorKind::InvalidInput, format!("Unknown version {}", version)))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::LookupMap;
+    use crate::test_utils::test_env;
+
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct AccountV0 {
+        balance: u64,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct Account {
+        balance: u128,
+        nonce: u64,
+    }
+
+    impl VersionedValue for AccountV0 {
+        const VERSION: u8 = 0;
+
+        fn from_version(_version: u8, _data: &[u8]) -> Self {
+            unreachable!()
+        }
+    }
+
+    impl VersionedValue for Account {
+        const VERSION: u8 = 1;
+
+        fn from_version(version: u8, data: &[u8]) -> Self {
+            assert_eq!(version, 0);
+            let account = AccountV0::try_from_slice(data).unwrap();
+            Self { balance: account.balance.into(), nonce: 0 }
+        }
+    }
+
+    #[test]
+    fn test_lazy_migration() {
+        test_env::setup();
+        let mut accounts_v0: LookupMap<String, Versioned<AccountV0>> =
+            LookupMap::new(b"a".to_vec());
+        accounts_v0.insert(&"alice".to_string(), &AccountV0 { balance: 5 }.into());
+
+        let mut accounts: LookupMap<String, Versioned<Account>> = LookupMap::new(b"a".to_vec());
+        let account = accounts.get(&"alice".to_string()).unwrap();
+        assert!(!account.is_current());
+        let account = account.into_current();
+        assert_eq!(account, Account { balance: 5, nonce: 0 });
+        accounts.insert(&"alice".to_string(), &Account { balance: 6, nonce: 1 }.into());
+        let account = accounts.get(&"alice".to_string()).unwrap();
+        assert!(account.is_current());
+        assert_eq!(account.into_current(), Account { balance: 6, nonce: 1 });
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let data = Versioned::Current(Account { balance: 6, nonce: 1 }).try_to_vec().unwrap();
+        assert!(Versioned::<AccountV0>::try_from_slice(&data).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Structured errors of the contracts. `ContractError` has a machine-readable code, a message for
+//! humans and optional data, so the clients and the indexers can tell the failures apart without
+//! parsing the messages. It's serialized with JSON as
+//! `{"code":"UNAUTHORIZED","message":"...","data":"..."}`, or with Borsh as the three fields in
+//! this order.
+//!
+//! The codes of the common failures are the constants of `ContractError`. The contracts define
+//! their own codes for the rest, in the same `UPPER_SNAKE_CASE`.
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The error of a contract.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractError {
+    /// The code of the failure, e.g. `UNAUTHORIZED`.
+    pub code: String,
+    /// The description of the failure.
+    pub message: String,
+    /// The details of the failure, e.g. a JSON value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+impl ContractError {
+    /// The callback result or the state can't be deserialized.
+    pub const DESERIALIZATION: &'static str = "DESERIALIZATION";
+    /// The arguments of the method are missing or can't be deserialized.
+    pub const INVALID_INPUT: &'static str = "INVALID_INPUT";
+    /// The attached deposit is not the one the method requires.
+    pub const INVALID_DEPOSIT: &'static str = "INVALID_DEPOSIT";
+    /// The private method is called by another account than the contract itself.
+    pub const PRIVATE_METHOD: &'static str = "PRIVATE_METHOD";
+    /// The promise of the callback failed.
+    pub const CALLBACK_FAILED: &'static str = "CALLBACK_FAILED";
+    /// The result of the method or the state can't be serialized.
+    pub const SERIALIZATION: &'static str = "SERIALIZATION";
+    /// The contract panicked without a code, e.g. with `env::panic` or `panic!`.
+    pub const PANIC: &'static str = "PANIC";
+    /// The `#[init]` method is called when the contract state already exists.
+    pub const ALREADY_INITIALIZED: &'static str = "ALREADY_INITIALIZED";
+    /// The result of an arithmetic operation doesn't fit into its type.
+    pub const OVERFLOW: &'static str = "OVERFLOW";
+    /// The caller is not allowed to call the method.
+    pub const UNAUTHORIZED: &'static str = "UNAUTHORIZED";
+
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self { code: code.to_string(), message: message.into(), data: None }
+    }
+
+    /// Adds the details of the failure.
+    pub fn with_data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    pub fn overflow() -> Self {
+        Self::new(Self::OVERFLOW, "Arithmetic overflow")
+    }
+
+    /// The account `account_id` is not allowed to call the method.
+    pub fn unauthorized(account_id: &str) -> Self {
+        Self::new(Self::UNAUTHORIZED, format!("The account {} is not allowed to call", account_id))
+            .with_data(account_id)
+    }
+
+    /// The JSON of the error. It's written without `serde_json`, so that `env::panic_err` works
+    /// without the `json` feature.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"code\":");
+        push_json_string(&mut json, &self.code);
+        json.push_str(",\"message\":");
+        push_json_string(&mut json, &self.message);
+        if let Some(data) = &self.data {
+            json.push_str(",\"data\":");
+            push_json_string(&mut json, data);
+        }
+        json.push('}');
+        json
+    }
+}
+
+/// The log of the `method_failed` event, written without `serde_json` like `to_json`.
+pub(crate) fn failure_event_log(method: &str, code: &str, message: &[u8]) -> String {
+    let mut log = String::from(
+        "EVENT_JSON:{\"standard\":\"near-sdk\",\"version\":\"1.0.0\",\"event\":\"method_failed\",\"data\":[{\"method\":",
+    );
+    push_json_string(&mut log, method);
+    log.push_str(",\"code\":");
+    push_json_string(&mut log, code);
+    log.push_str(",\"message\":");
+    push_json_string(&mut log, &String::from_utf8_lossy(message));
+    log.push_str("}]}");
+    log
+}
+
+/// Appends the string as a JSON string literal, escaped the way `serde_json` does it.
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ContractError {}
+
+/// The errors of Borsh.
+impl From<std::io::Error> for ContractError {
+    fn from(err: std::io::Error) -> Self {
+        Self::new(Self::DESERIALIZATION, err.to_string())
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for ContractError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(Self::DESERIALIZATION, err.to_string())
+    }
+}
+
+impl From<std::num::TryFromIntError> for ContractError {
+    fn from(_: std::num::TryFromIntError) -> Self {
+        Self::overflow()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_serialization() {
+        let error = ContractError::unauthorized("bob.near");
+        let json = error.to_json();
+        assert_eq!(
+            json,
+            r#"{"code":"UNAUTHORIZED","message":"The account bob.near is not allowed to call","data":"bob.near"}"#
+        );
+        assert_eq!(serde_json::from_str::<ContractError>(&json).unwrap(), error);
+        let error = ContractError::new("NOT_FOUND", "No such token");
+        assert_eq!(error.to_json(), r#"{"code":"NOT_FOUND","message":"No such token"}"#);
+        let borsh = error.try_to_vec().unwrap();
+        assert_eq!(ContractError::try_from_slice(&borsh).unwrap(), error);
+        assert_eq!(error.to_string(), "NOT_FOUND: No such token");
+    }
+
+    #[test]
+    fn test_json_escaping() {
+        let error = ContractError::new("CODE", "\"quoted\" \\ \n\t\u{1} \u{8}\u{c}\r é")
+            .with_data("{\"a\":1}");
+        assert_eq!(error.to_json(), serde_json::to_string(&error).unwrap());
+    }
+
+    #[test]
+    fn test_failure_event_log() {
+        assert_eq!(
+            failure_event_log("ft_transfer", ContractError::INVALID_DEPOSIT, b"Requires \"1\""),
+            r#"EVENT_JSON:{"standard":"near-sdk","version":"1.0.0","event":"method_failed","data":[{"method":"ft_transfer","code":"INVALID_DEPOSIT","message":"Requires \"1\""}]}"#
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let error: ContractError = u64::try_from_slice(&[1]).unwrap_err().into();
+        assert_eq!(error.code, ContractError::DESERIALIZATION);
+        let error: ContractError = u8::try_from(256u64).unwrap_err().into();
+        assert_eq!(error, ContractError::overflow());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_conversion() {
+        let error: ContractError = serde_json::from_str::<u64>("\"a\"").unwrap_err().into();
+        assert_eq!(error.code, ContractError::DESERIALIZATION);
+    }
+}
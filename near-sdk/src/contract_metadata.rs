@@ -0,0 +1,209 @@
+//! Contract source metadata of NEP-330. It tells where the source code of the deployed contract
+//! can be found and how it was built, so that the contract can be verified. The contract exports
+//! it with `#[near_bindgen(contract_metadata(...))]` as the `contract_source_metadata` view method.
+//! `#[near_bindgen(metadata(standard = "...", version = "..."))]` adds a standard to it.
+//!
+//! `ContractVersion` is the version of the contract crate and the git commit it was built from,
+//! captured at the build time with `contract_version!()`. The contract exports it with
+//! `#[near_bindgen(contract_version)]` as the `version` view method.
+use serde::{Deserialize, Serialize};
+
+/// The metadata returned by `contract_source_metadata`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct ContractSourceMetadata {
+    /// Version of the contract, e.g. a semver or a commit hash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Link to the source code of the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// Standards implemented by the contract.
+    pub standards: Vec<Standard>,
+    /// Details of the reproducible build of the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_info: Option<BuildInfo>,
+    /// The toolchain and the configuration of the build, recorded by `near_sdk::build`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+}
+
+/// A standard implemented by the contract, e.g. `nep171` of version `1.0.0`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Standard {
+    pub standard: String,
+    pub version: String,
+}
+
+/// Details of the build that produced the deployed code.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct BuildInfo {
+    /// Reference to the build environment, e.g. a docker image.
+    pub build_environment: String,
+    /// The command that was used to build the contract.
+    pub build_command: Vec<String>,
+    /// Reference to the snapshot of the source code, e.g. `git+https://...?rev=...`.
+    pub source_code_snapshot: String,
+    /// Path to the contract within the snapshot, if it's not the root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_path: Option<String>,
+}
+
+impl BuildInfo {
+    /// Builds the details from the values of the `NEP330_BUILD_INFO_*` environment variables at the
+    /// build time. Returns `None` unless the environment, the command and the snapshot are set.
+    /// The command is either a JSON array of strings or a string of whitespace-separated arguments.
+    pub fn from_parts(
+        build_environment: Option<&str>,
+        build_command: Option<&str>,
+        source_code_snapshot: Option<&str>,
+        contract_path: Option<&str>,
+    ) -> Option<Self> {
+        let build_command = build_command?;
+        let build_command = serde_json::from_str(build_command)
+            .unwrap_or_else(|_| build_command.split_whitespace().map(str::to_string).collect());
+        Some(Self {
+            build_environment: build_environment?.to_string(),
+            build_command,
+            source_code_snapshot: source_code_snapshot?.to_string(),
+            contract_path: contract_path.filter(|path| !path.is_empty()).map(str::to_string),
+        })
+    }
+}
+
+/// The toolchain and the configuration of the build that produced the deployed code.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Toolchain {
+    /// The version of the compiler, e.g. `rustc 1.51.0 (2fd73fabe 2021-03-23)`.
+    pub rustc: String,
+    /// The version of `near-sdk`.
+    pub near_sdk: String,
+    /// The target triple, e.g. `wasm32-unknown-unknown`.
+    pub target: String,
+    /// The profile, `release` or `debug`.
+    pub profile: String,
+    /// The enabled features of the contract crate.
+    pub features: Vec<String>,
+}
+
+impl Toolchain {
+    /// Builds the toolchain from the values of the `NEAR_SDK_BUILD_*` environment variables set by
+    /// `near_sdk::build::record_build_info` at the build time. Returns `None` unless the compiler
+    /// version is set, i.e. the contract has no such build script.
+    pub fn from_parts(
+        rustc: Option<&str>,
+        target: Option<&str>,
+        profile: Option<&str>,
+        features: Option<&str>,
+    ) -> Option<Self> {
+        let features = features.unwrap_or_default();
+        Some(Self {
+            rustc: rustc.filter(|rustc| !rustc.is_empty())?.to_string(),
+            near_sdk: env!("CARGO_PKG_VERSION").to_string(),
+            target: target.unwrap_or_default().to_string(),
+            profile: profile.unwrap_or_default().to_string(),
+            features: features.split(',').filter(|f| !f.is_empty()).map(str::to_string).collect(),
+        })
+    }
+}
+
+/// The version of the contract crate and the git commit of its source.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct ContractVersion {
+    /// The version of the contract crate, `CARGO_PKG_VERSION`.
+    pub version: String,
+    /// The hash of the git commit, recorded by `near_sdk::build::record_build_info`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+}
+
+impl ContractVersion {
+    pub fn from_parts(version: &str, git_commit: Option<&str>) -> Self {
+        Self {
+            version: version.to_string(),
+            git_commit: git_commit.filter(|commit| !commit.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+/// The `ContractVersion` of the crate that calls the macro, captured at its build time. The git
+/// commit is set if the crate has a build script with `near_sdk::build::record_build_info()`.
+#[macro_export]
+macro_rules! contract_version {
+    () => {
+        $crate::contract_metadata::ContractVersion::from_parts(
+            env!("CARGO_PKG_VERSION"),
+            option_env!("NEAR_SDK_BUILD_GIT_COMMIT"),
+        )
+    };
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_from_parts() {
+        let snapshot = "git+https://github.com/near/example?rev=abc";
+        let info = BuildInfo::from_parts(
+            Some("sourcescan/cargo-near:0.1.0"),
+            Some(r#"["cargo", "near", "build"]"#),
+            Some(snapshot),
+            Some(""),
+        )
+        .unwrap();
+        assert_eq!(info.build_command, vec!["cargo", "near", "build"]);
+        assert_eq!(info.contract_path, None);
+        let info =
+            BuildInfo::from_parts(Some("env"), Some("cargo build --release"), Some(snapshot), None);
+        assert_eq!(info.unwrap().build_command, vec!["cargo", "build", "--release"]);
+        assert_eq!(BuildInfo::from_parts(None, Some("cargo build"), Some(snapshot), None), None);
+    }
+
+    #[test]
+    fn test_toolchain_from_parts() {
+        let toolchain = Toolchain::from_parts(
+            Some("rustc 1.51.0"),
+            Some("wasm32-unknown-unknown"),
+            Some("release"),
+            Some("default,global-allocator"),
+        )
+        .unwrap();
+        assert_eq!(toolchain.near_sdk, env!("CARGO_PKG_VERSION"));
+        assert_eq!(toolchain.features, vec!["default", "global-allocator"]);
+        let toolchain = Toolchain::from_parts(Some("rustc 1.51.0"), None, None, Some("")).unwrap();
+        assert!(toolchain.features.is_empty());
+        assert_eq!(Toolchain::from_parts(None, Some("wasm32-unknown-unknown"), None, None), None);
+        assert_eq!(Toolchain::from_parts(Some(""), None, None, None), None);
+    }
+
+    #[test]
+    fn test_contract_version() {
+        let version = contract_version!();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        let version = ContractVersion::from_parts("1.0.0", Some("0a1b2c3"));
+        assert_eq!(
+            serde_json::to_string(&version).unwrap(),
+            r#"{"version":"1.0.0","git_commit":"0a1b2c3"}"#
+        );
+        assert_eq!(ContractVersion::from_parts("1.0.0", Some("")).git_commit, None);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let metadata = ContractSourceMetadata {
+            version: Some("1.0.0".to_string()),
+            link: None,
+            standards: vec![Standard {
+                standard: "nep330".to_string(),
+                version: "1.1.0".to_string(),
+            }],
+            build_info: None,
+            toolchain: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&metadata).unwrap(),
+            r#"{"version":"1.0.0","standards":[{"standard":"nep330","version":"1.1.0"}]}"#
+        );
+    }
+}
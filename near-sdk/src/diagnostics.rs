@@ -0,0 +1,61 @@
+//! Verbosity of the panics of the generated guards and the helpers of near-sdk. It's chosen once
+//! for the whole contract with the features of near-sdk, instead of at each check:
+//!
+//! * `short-panics`: the codes only, e.g. `E1`, see `near_sdk::wrapper`;
+//! * the default: the `ContractError` with the code of the failure class and the message, e.g.
+//!   `{"code":"INVALID_DEPOSIT","message":"Method doesn't accept deposit"}`;
+//! * `debug-diagnostics`: the same error with the context of the failure in `data`, like the
+//!   accounts of the call, the attached deposit or the start of the input. It's meant for the debug
+//!   builds, the context costs gas and code size.
+//!
+//! The checks pass the context to `env::panic_err_with` as a closure, which is only called with
+//! `debug-diagnostics`.
+use crate::env;
+
+/// The longest part of the input or the promise result in the context.
+const MAX_DATA_CONTEXT: usize = 256;
+
+/// The accounts of the call and the attached deposit, for the checks of the caller and the
+/// deposit. The view methods can't read them.
+pub fn call_context() -> String {
+    format!(
+        "predecessor: {}, signer: {}, current: {}, attached deposit: {}",
+        env::predecessor_account_id(),
+        env::signer_account_id(),
+        env::current_account_id(),
+        env::attached_deposit()
+    )
+}
+
+/// The length and the start of the input or the promise result that can't be deserialized. The
+/// bytes that are not UTF-8 are replaced.
+pub fn data_context(data: &[u8]) -> String {
+    let end = data.len().min(MAX_DATA_CONTEXT);
+    format!("{} bytes: {}", data.len(), String::from_utf8_lossy(&data[..end]))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_context() {
+        test_env::setup();
+        assert_eq!(
+            call_context(),
+            "predecessor: carol.near, signer: bob.near, current: alice.near, attached deposit: 0"
+        );
+        assert_eq!(data_context(b"{\"a\":1}"), "7 bytes: {\"a\":1}");
+        assert_eq!(data_context(&[b'a'; 300]).len(), "300 bytes: ".len() + MAX_DATA_CONTEXT);
+    }
+
+    #[cfg(all(feature = "debug-diagnostics", not(feature = "short-panics")))]
+    #[test]
+    #[should_panic(expected = "predecessor: carol.near, signer: bob.near")]
+    fn test_debug_diagnostics() {
+        test_env::setup();
+        crate::assert_one_yocto();
+    }
+}
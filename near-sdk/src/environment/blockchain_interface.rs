@@ -142,6 +142,28 @@ pub trait BlockchainInterface {
     unsafe fn promise_results_count(&self) -> u64;
     unsafe fn promise_result(&self, result_idx: u64, register_id: u64) -> u64;
     unsafe fn promise_return(&self, promise_id: u64);
+    // ####################
+    // # Yield and resume #
+    // ####################
+    #[cfg(feature = "yield-resume")]
+    unsafe fn promise_yield_create(
+        &self,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        register_id: u64,
+    ) -> u64;
+    #[cfg(feature = "yield-resume")]
+    unsafe fn promise_yield_resume(
+        &self,
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64,
+    ) -> u32;
     // ###############
     // # Storage API #
     // ###############
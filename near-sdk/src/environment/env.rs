@@ -4,24 +4,58 @@
 //! through `callback_args`, `callback_args_vec`, `ext_contract`, `Promise`, and `PromiseOrValue`.
 
 use std::borrow::Borrow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
 use std::mem::size_of;
 use std::panic as std_panic;
 
 use near_vm_logic::{
     mocks::mock_external::Receipt,
-    types::{
-        AccountId, Balance, BlockHeight, Gas, PromiseIndex, PromiseResult, PublicKey, StorageUsage,
-    },
+    types::{AccountId, Balance, BlockHeight, Gas, PromiseIndex, PromiseResult, PublicKey},
 };
 
 use crate::environment::blockchain_interface::BlockchainInterface;
+use crate::json_types::Base58PublicKey;
+use crate::wrapper::{unwrap_or_fail, unwrap_or_fail_with};
+use crate::{CryptoHash, StorageUsage};
 
 thread_local! {
 /// Low-level blockchain interface wrapped by the environment. Prefer using `env::*` and `testing_env`
 /// for interacting with the real and fake blockchains.
     pub static BLOCKCHAIN_INTERFACE: RefCell<Option<Box<dyn BlockchainInterface>>>
          = RefCell::new(None);
+/// The values of the context that do not change during the call, read from the blockchain
+/// interface on the first access.
+    static CONTEXT_CACHE: RefCell<ContextCache> = RefCell::new(ContextCache::default());
+/// The method that logs the `method_failed` event when it fails, see `set_failure_event_method`.
+    static FAILURE_EVENT_METHOD: Cell<Option<&'static str>> = const { Cell::new(None) };
+/// The checkpoints recorded with `gas_checkpoint` in the unit tests.
+    static GAS_CHECKPOINTS: RefCell<Vec<GasCheckpoint>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Cached values of the context API. Cleared when the blockchain interface is replaced.
+#[derive(Default)]
+struct ContextCache {
+    current_account_id: Option<AccountId>,
+    signer_account_id: Option<AccountId>,
+    signer_account_pk: Option<PublicKey>,
+    predecessor_account_id: Option<AccountId>,
+    block_index: Option<BlockHeight>,
+    block_timestamp: Option<u64>,
+    epoch_height: Option<u64>,
+    attached_deposit: Option<Balance>,
+    prepaid_gas: Option<Gas>,
+    random_seed: Option<Vec<u8>>,
+}
+
+/// The gas used by the method when `gas_checkpoint` was called with the label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasCheckpoint {
+    pub label: &'static str,
+    pub used_gas: Gas,
+    /// The gas used since the previous checkpoint, or since the start of the call for the first
+    /// one.
+    pub gas_since_previous: Gas,
 }
 
 const BLOCKCHAIN_INTERFACE_NOT_SET_ERR: &str = "Blockchain interface not set.";
@@ -67,6 +101,20 @@ macro_rules! method_into_register {
     }};
 }
 
+/// Returns the cached value of the context, or computes and caches it on the first access.
+macro_rules! cached {
+    ( $field:ident, $value:expr ) => {{
+        match CONTEXT_CACHE.with(|c| c.borrow().$field.clone()) {
+            Some(value) => value,
+            None => {
+                let value = $value;
+                CONTEXT_CACHE.with(|c| c.borrow_mut().$field = Some(value.clone()));
+                value
+            }
+        }
+    }};
+}
+
 /// Replaces the current low-level blockchain interface accessible through `env::*` with another
 /// low-level blockchain interfacr that implements `BlockchainInterface` trait. In most cases you
 /// want to use `testing_env!` macro to set it.
@@ -86,6 +134,7 @@ macro_rules! method_into_register {
 /// near_sdk::env::set_blockchain_interface(Box::new(mocked_blockchain));
 /// ```
 pub fn set_blockchain_interface(blockchain_interface: Box<dyn BlockchainInterface>) {
+    clear_call_state();
     BLOCKCHAIN_INTERFACE.with(|b| {
         *b.borrow_mut() = Some(blockchain_interface);
     })
@@ -109,9 +158,19 @@ pub fn set_blockchain_interface(blockchain_interface: Box<dyn BlockchainInterfac
 /// // env::account_balance();
 /// ```
 pub fn take_blockchain_interface() -> Option<Box<dyn BlockchainInterface>> {
+    clear_call_state();
     BLOCKCHAIN_INTERFACE.with(|b| b.replace(None))
 }
 
+/// Clears the state kept for the current call, so that it does not leak into the next blockchain
+/// interface.
+fn clear_call_state() {
+    CONTEXT_CACHE.with(|c| *c.borrow_mut() = ContextCache::default());
+    GAS_CHECKPOINTS.with(|c| c.borrow_mut().clear());
+    crate::wrapper::clear_dispatched_args();
+    crate::migration::clear_state_schema_stored();
+}
+
 /// Implements panic hook that converts `PanicInfo` into a string and provides it through the
 /// blockchain interface.
 fn panic_hook_impl(info: &std_panic::PanicInfo) {
@@ -125,15 +184,31 @@ pub fn setup_panic_hook() {
 
 /// Reads the content of the `register_id`. If register is not used returns `None`.
 pub fn read_register(register_id: u64) -> Option<Vec<u8>> {
-    let len = register_len(register_id)?;
-    let res = vec![0u8; len as usize];
+    let mut res = vec![];
+    if read_register_into(register_id, &mut res) {
+        Some(res)
+    } else {
+        None
+    }
+}
+
+/// Reads the content of the `register_id` into the buffer, replacing its content and reusing its
+/// allocation. Returns `false` and leaves the buffer empty if the register is not used.
+pub fn read_register_into(register_id: u64, buf: &mut Vec<u8>) -> bool {
+    buf.clear();
     BLOCKCHAIN_INTERFACE.with(|b| unsafe {
-        b.borrow()
-            .as_ref()
-            .expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR)
-            .read_register(register_id, res.as_ptr() as _)
-    });
-    Some(res)
+        let b = b.borrow();
+        let b = b.as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR);
+        let len = b.register_len(register_id);
+        if len == u64::MAX {
+            return false;
+        }
+        buf.reserve(len as usize);
+        b.read_register(register_id, buf.as_mut_ptr() as _);
+        // The host wrote exactly `len` bytes into the buffer, so they are initialized.
+        buf.set_len(len as usize);
+        true
+    })
 }
 
 /// Returns the size of the register. If register is not used returns `None`.
@@ -151,26 +226,41 @@ pub fn register_len(register_id: u64) -> Option<u64> {
 // ###############
 // # Context API #
 // ###############
+// The values that do not change during the call are read from the host once and then cached.
+
 /// The id of the account that owns the current contract.
 pub fn current_account_id() -> AccountId {
-    String::from_utf8(method_into_register!(current_account_id)).unwrap()
+    cached!(
+        current_account_id,
+        String::from_utf8(method_into_register!(current_account_id)).unwrap()
+    )
 }
 
 /// The id of the account that either signed the original transaction or issued the initial
 /// cross-contract call.
 pub fn signer_account_id() -> AccountId {
-    String::from_utf8(method_into_register!(signer_account_id)).unwrap()
+    cached!(signer_account_id, String::from_utf8(method_into_register!(signer_account_id)).unwrap())
 }
 
 /// The public key of the account that did the signing.
 pub fn signer_account_pk() -> PublicKey {
-    method_into_register!(signer_account_pk)
+    cached!(signer_account_pk, method_into_register!(signer_account_pk))
+}
+
+/// The public key of the account that did the signing, with its curve. Panics if the key is not a
+/// valid ED25519 or SECP256K1 key.
+pub fn signer_public_key() -> Base58PublicKey {
+    Base58PublicKey::try_from(signer_account_pk())
+        .unwrap_or_else(|_| panic(b"The signer public key is invalid"))
 }
 
 /// The id of the account that was the previous contract in the chain of cross-contract calls.
 /// If this is the first contract, it is equal to `signer_account_id`.
 pub fn predecessor_account_id() -> String {
-    String::from_utf8(method_into_register!(predecessor_account_id)).unwrap()
+    cached!(
+        predecessor_account_id,
+        String::from_utf8(method_into_register!(predecessor_account_id)).unwrap()
+    )
 }
 
 /// The input to the contract call serialized as bytes. If input is not provided returns `None`.
@@ -178,37 +268,70 @@ pub fn input() -> Option<Vec<u8>> {
     try_method_into_register!(input)
 }
 
+/// Reads the input of the contract call into the buffer, reusing its allocation. Returns `false`
+/// and leaves the buffer empty if the input is not provided.
+pub fn input_into(buf: &mut Vec<u8>) -> bool {
+    BLOCKCHAIN_INTERFACE.with(|b| unsafe {
+        b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).input(ATOMIC_OP_REGISTER);
+    });
+    read_register_into(ATOMIC_OP_REGISTER, buf)
+}
+
+/// The length of the input of the contract call in bytes, or `None` if the input is not provided.
+/// The input is not copied into the memory of the contract, so the length of a large input is
+/// cheap to check.
+pub fn input_len() -> Option<u64> {
+    BLOCKCHAIN_INTERFACE.with(|b| unsafe {
+        b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).input(ATOMIC_OP_REGISTER);
+    });
+    register_len(ATOMIC_OP_REGISTER)
+}
+
 /// Current block index.
 pub fn block_index() -> BlockHeight {
-    unsafe {
-        BLOCKCHAIN_INTERFACE
-            .with(|b| b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).block_index())
-    }
+    cached!(block_index, {
+        unsafe {
+            BLOCKCHAIN_INTERFACE.with(|b| {
+                b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).block_index()
+            })
+        }
+    })
 }
 
 /// Current block timestamp, i.e, number of non-leap-nanoseconds since January 1, 1970 0:00:00 UTC.
 pub fn block_timestamp() -> u64 {
-    unsafe {
-        BLOCKCHAIN_INTERFACE.with(|b| {
-            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).block_timestamp()
-        })
-    }
+    cached!(block_timestamp, {
+        unsafe {
+            BLOCKCHAIN_INTERFACE.with(|b| {
+                b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).block_timestamp()
+            })
+        }
+    })
+}
+
+/// Current block timestamp in milliseconds, rounded down. See `time::Timestamp` for the checked
+/// arithmetic on the timestamps.
+pub fn block_timestamp_ms() -> u64 {
+    block_timestamp() / 1_000_000
 }
 
 /// Current epoch height.
 pub fn epoch_height() -> u64 {
-    unsafe {
-        BLOCKCHAIN_INTERFACE
-            .with(|b| b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).epoch_height())
-    }
+    cached!(epoch_height, {
+        unsafe {
+            BLOCKCHAIN_INTERFACE.with(|b| {
+                b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).epoch_height()
+            })
+        }
+    })
 }
 
 /// Current total storage usage of this smart contract that this account would be paying for.
 pub fn storage_usage() -> StorageUsage {
-    unsafe {
+    StorageUsage::from_bytes(unsafe {
         BLOCKCHAIN_INTERFACE
             .with(|b| b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).storage_usage())
-    }
+    })
 }
 
 // #################
@@ -246,24 +369,29 @@ pub fn account_locked_balance() -> Balance {
 /// The balance that was attached to the call that will be immediately deposited before the
 /// contract execution starts
 pub fn attached_deposit() -> Balance {
-    let data = [0u8; size_of::<Balance>()];
-    unsafe {
-        BLOCKCHAIN_INTERFACE.with(|b| {
-            b.borrow()
-                .as_ref()
-                .expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR)
-                .attached_deposit(data.as_ptr() as u64)
-        })
-    };
-    Balance::from_le_bytes(data)
+    cached!(attached_deposit, {
+        let data = [0u8; size_of::<Balance>()];
+        unsafe {
+            BLOCKCHAIN_INTERFACE.with(|b| {
+                b.borrow()
+                    .as_ref()
+                    .expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR)
+                    .attached_deposit(data.as_ptr() as u64)
+            })
+        };
+        Balance::from_le_bytes(data)
+    })
 }
 
 /// The amount of gas attached to the call that can be used to pay for the gas fees.
 pub fn prepaid_gas() -> Gas {
-    unsafe {
-        BLOCKCHAIN_INTERFACE
-            .with(|b| b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).prepaid_gas())
-    }
+    cached!(prepaid_gas, {
+        unsafe {
+            BLOCKCHAIN_INTERFACE.with(|b| {
+                b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).prepaid_gas()
+            })
+        }
+    })
 }
 
 /// The gas that was already burnt during the contract execution (cannot exceed `prepaid_gas`)
@@ -274,12 +402,44 @@ pub fn used_gas() -> Gas {
     }
 }
 
+/// Records the gas used so far against the label, to find which part of a method burns the gas.
+/// In the unit tests the checkpoints are read with `take_gas_checkpoints`. On wasm32 the debug
+/// builds log `gas_checkpoint <label>: <used gas>`, and the release builds do nothing.
+pub fn gas_checkpoint(label: &'static str) {
+    if cfg!(target_arch = "wasm32") {
+        if cfg!(debug_assertions) {
+            log(format!("gas_checkpoint {}: {}", label, used_gas()).as_bytes());
+        }
+        return;
+    }
+    let used_gas = used_gas();
+    GAS_CHECKPOINTS.with(|c| {
+        let mut checkpoints = c.borrow_mut();
+        let previous = checkpoints.last().map_or(0, |checkpoint| checkpoint.used_gas);
+        let gas_since_previous = used_gas.saturating_sub(previous);
+        checkpoints.push(GasCheckpoint { label, used_gas, gas_since_previous });
+    });
+}
+
+/// Returns the checkpoints recorded with `gas_checkpoint` since the last call, in the order they
+/// were recorded. Only available in unit tests.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn take_gas_checkpoints() -> Vec<GasCheckpoint> {
+    GAS_CHECKPOINTS.with(|c| c.take())
+}
+
+/// The price of one byte of the contract storage in yoctoNEAR. The account has to keep at least
+/// `storage_usage().cost()` on its balance to pay for the storage it occupies.
+pub fn storage_byte_cost() -> Balance {
+    100_000_000_000_000_000_000
+}
+
 // ############
 // # Math API #
 // ############
 /// Get random seed from the register.
 pub fn random_seed() -> Vec<u8> {
-    method_into_register!(random_seed)
+    cached!(random_seed, method_into_register!(random_seed))
 }
 
 /// Hashes the random sequence of bytes using sha256.
@@ -310,6 +470,48 @@ pub fn keccak256(value: &[u8]) -> Vec<u8> {
     read_register(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR)
 }
 
+/// The SHA-256 hash of the bytes as `CryptoHash`, without allocating a vector.
+pub fn sha256_hash(value: &[u8]) -> CryptoHash {
+    unsafe {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).sha256(
+                value.len() as _,
+                value.as_ptr() as _,
+                ATOMIC_OP_REGISTER,
+            )
+        });
+    };
+    read_hash_register()
+}
+
+/// The keccak256 hash of the bytes as `CryptoHash`, without allocating a vector.
+pub fn keccak256_hash(value: &[u8]) -> CryptoHash {
+    unsafe {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).keccak256(
+                value.len() as _,
+                value.as_ptr() as _,
+                ATOMIC_OP_REGISTER,
+            )
+        });
+    };
+    read_hash_register()
+}
+
+/// Reads the 32-byte hash the host wrote into `ATOMIC_OP_REGISTER`.
+fn read_hash_register() -> CryptoHash {
+    let mut hash = [0u8; 32];
+    BLOCKCHAIN_INTERFACE.with(|b| unsafe {
+        let b = b.borrow();
+        let b = b.as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR);
+        if b.register_len(ATOMIC_OP_REGISTER) != hash.len() as u64 {
+            panic(REGISTER_EXPECTED_ERR.as_bytes());
+        }
+        b.read_register(ATOMIC_OP_REGISTER, hash.as_mut_ptr() as _);
+    });
+    CryptoHash::new(hash)
+}
+
 /// Hashes the random sequence of bytes using keccak512.
 pub fn keccak512(value: &[u8]) -> Vec<u8> {
     unsafe {
@@ -635,6 +837,56 @@ pub fn promise_return(promise_idx: PromiseIndex) {
     }
 }
 
+// ####################
+// # Yield and resume #
+// ####################
+/// Creates a promise that calls `function_name` of the current account with the arguments once
+/// it's resumed with `promise_yield_resume`, or when it times out. The function reads the payload
+/// of the resumption as the result of promise 0, which fails on the timeout. Returns the index of
+/// the promise and the id of the data that resumes it. Unused gas is shared between the promises in
+/// proportion to `gas_weight`.
+#[cfg(feature = "yield-resume")]
+pub fn promise_yield_create(
+    function_name: &str,
+    arguments: &[u8],
+    gas: Gas,
+    gas_weight: u64,
+) -> (PromiseIndex, Vec<u8>) {
+    let promise_index = unsafe {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).promise_yield_create(
+                function_name.len() as _,
+                function_name.as_ptr() as _,
+                arguments.len() as _,
+                arguments.as_ptr() as _,
+                gas,
+                gas_weight,
+                ATOMIC_OP_REGISTER,
+            )
+        })
+    };
+    (promise_index, read_register(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR))
+}
+/// Resumes the yielded promise with the data id, passing the payload to its function. Returns
+/// `false` if there is no such promise, or if it was already resumed or timed out.
+#[cfg(feature = "yield-resume")]
+pub fn promise_yield_resume(data_id: &[u8], payload: &[u8]) -> bool {
+    match unsafe {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).promise_yield_resume(
+                data_id.len() as _,
+                data_id.as_ptr() as _,
+                payload.len() as _,
+                payload.as_ptr() as _,
+            )
+        })
+    } {
+        0 => false,
+        1 => true,
+        _ => panic!("{}", RETURN_CODE_ERR),
+    }
+}
+
 // ###############
 // # Validator API #
 // ###############
@@ -683,6 +935,10 @@ pub fn value_return(value: &[u8]) {
 }
 /// Terminates the execution of the program with the UTF-8 encoded message.
 pub fn panic(message: &[u8]) -> ! {
+    log_failure_event(crate::ContractError::PANIC, message);
+    panic_utf8(message)
+}
+fn panic_utf8(message: &[u8]) -> ! {
     unsafe {
         BLOCKCHAIN_INTERFACE.with(|b| {
             b.borrow()
@@ -693,6 +949,54 @@ pub fn panic(message: &[u8]) -> ! {
     }
     unreachable!()
 }
+/// Terminates the execution of the program with the `ContractError` of the code and the message
+/// serialized as JSON, e.g. `{"code":"INVALID_DEPOSIT","message":"..."}`, so that the clients and
+/// the indexers can tell the failures apart by their codes. With the `short-panics` feature it
+/// terminates with the code only.
+#[cfg(not(feature = "short-panics"))]
+pub fn panic_err(code: &str, message: &str) -> ! {
+    log_failure_event(code, message.as_bytes());
+    panic_utf8(crate::ContractError::new(code, message).to_json().as_bytes())
+}
+/// Terminates the execution of the program with the `ContractError` of the code and the message
+/// serialized as JSON, e.g. `{"code":"INVALID_DEPOSIT","message":"..."}`, so that the clients and
+/// the indexers can tell the failures apart by their codes. With the `short-panics` feature it
+/// terminates with the code only.
+#[cfg(feature = "short-panics")]
+pub fn panic_err(code: &str, _message: &str) -> ! {
+    log_failure_event(code, b"");
+    panic_utf8(code.as_bytes())
+}
+/// Terminates the execution of the program like `panic_err`. With the `debug-diagnostics` feature
+/// the error has the context of the failure in `data`; the context is only computed then, see
+/// `near_sdk::diagnostics`.
+#[cfg(not(feature = "short-panics"))]
+pub fn panic_err_with(code: &str, message: &str, context: impl FnOnce() -> String) -> ! {
+    log_failure_event(code, message.as_bytes());
+    let error = crate::ContractError::new(code, message);
+    let error = if cfg!(feature = "debug-diagnostics") { error.with_data(context()) } else { error };
+    panic_utf8(error.to_json().as_bytes())
+}
+/// Terminates the execution of the program like `panic_err`. With the `debug-diagnostics` feature
+/// the error has the context of the failure in `data`; the context is only computed then, see
+/// `near_sdk::diagnostics`.
+#[cfg(feature = "short-panics")]
+pub fn panic_err_with(code: &str, _message: &str, _context: impl FnOnce() -> String) -> ! {
+    panic_err(code, "")
+}
+/// Makes `panic`, `panic_err` and the panics of the contract log the `method_failed` event of the
+/// `near-sdk` standard with the method, the error code and the message before terminating. The
+/// failed receipt keeps its logs, so the indexers get the event. Set by the wrappers of
+/// `#[near_bindgen(failure_events)]`.
+pub fn set_failure_event_method(method: &'static str) {
+    FAILURE_EVENT_METHOD.with(|m| m.set(Some(method)));
+}
+/// Logs the `method_failed` event once, if the method was set with `set_failure_event_method`.
+fn log_failure_event(code: &str, message: &[u8]) {
+    if let Some(method) = FAILURE_EVENT_METHOD.with(|m| m.take()) {
+        log(crate::contract_error::failure_event_log(method, code, message).as_bytes());
+    }
+}
 /// Log the UTF-8 encodable message.
 pub fn log(message: &[u8]) {
     unsafe {
@@ -743,6 +1047,27 @@ pub fn storage_read(key: &[u8]) -> Option<Vec<u8>> {
         _ => panic!(RETURN_CODE_ERR),
     }
 }
+/// Reads the value stored under the given key into the buffer, reusing its allocation, so a method
+/// that reads many values only allocates once. Returns `false` and leaves the buffer empty if the
+/// key is not in the storage.
+pub fn storage_read_into(key: &[u8], buf: &mut Vec<u8>) -> bool {
+    match unsafe {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).storage_read(
+                key.len() as _,
+                key.as_ptr() as _,
+                ATOMIC_OP_REGISTER,
+            )
+        })
+    } {
+        0 => {
+            buf.clear();
+            false
+        }
+        1 => read_register_into(ATOMIC_OP_REGISTER, buf),
+        _ => panic!("{}", RETURN_CODE_ERR),
+    }
+}
 /// Removes the value stored under the given key.
 /// If key-value existed returns `true`, otherwise `false`.
 pub fn storage_remove(key: &[u8]) -> bool {
@@ -760,6 +1085,16 @@ pub fn storage_remove(key: &[u8]) -> bool {
         _ => panic!(RETURN_CODE_ERR),
     }
 }
+/// Removes the value stored under the given key and returns it, or `None` if the key was not in
+/// the storage. Unlike `storage_read` followed by `storage_remove`, it reads the value from the
+/// eviction register of the removal, without another lookup in the storage.
+pub fn storage_remove_evicted(key: &[u8]) -> Option<Vec<u8>> {
+    if storage_remove(key) {
+        Some(read_register(EVICTED_REGISTER).expect(REGISTER_EXPECTED_ERR))
+    } else {
+        None
+    }
+}
 /// Reads the most recent value that was evicted with `storage_write` or `storage_remove` command.
 pub fn storage_get_evicted() -> Option<Vec<u8>> {
     read_register(EVICTED_REGISTER)
@@ -791,26 +1126,100 @@ pub fn created_receipts() -> Vec<Receipt> {
             .clone()
     })
 }
+/// The key-value pairs of the storage, sorted by their keys. Only available in unit tests.
+pub fn storage_entries() -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> {
+    storage_entries_with_prefix(&[])
+}
+/// The key-value pairs of the storage whose keys start with the prefix, e.g. the prefix of a
+/// collection, sorted by their keys. Only available in unit tests.
+pub fn storage_entries_with_prefix(prefix: &[u8]) -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> {
+    let mut entries: Vec<_> = BLOCKCHAIN_INTERFACE.with(|b| {
+        b.borrow()
+            .as_ref()
+            .expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR)
+            .as_mocked_blockchain()
+            .expect(NOT_MOCKED_BLOCKCHAIN_ERR)
+            .storage()
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    });
+    entries.sort();
+    entries.into_iter()
+}
 
 // ############################################
 // # Saving and loading of the contract state #
 // ############################################
 /// Load the state of the given object.
 pub fn state_read<T: borsh::BorshDeserialize>() -> Option<T> {
-    storage_read(STATE_KEY)
-        .map(|data| T::try_from_slice(&data).expect("Cannot deserialize the contract state."))
+    storage_read(STATE_KEY).map(|data| {
+        unwrap_or_fail_with(
+            T::try_from_slice(&data),
+            "E10",
+            "Cannot deserialize the contract state.",
+            || crate::diagnostics::data_context(&data),
+        )
+    })
 }
 
 pub fn state_write<T: borsh::BorshSerialize>(state: &T) {
-    let data = state.try_to_vec().expect("Cannot serialize the contract state.");
+    let data = unwrap_or_fail(state.try_to_vec(), "E11", "Cannot serialize the contract state.");
     storage_write(STATE_KEY, &data);
 }
 
-/// Returns `true` if the contract state exists and `false` otherwise.
+/// Load the state written with either the `New` or the `Old` layout. The state that doesn't
+/// deserialize as `New` is deserialized as `Old` and converted, so the contract can switch to the
+/// new layout without a migration call: the state is rewritten with the new layout on the next
+/// `state_write`.
+///
+/// Borsh doesn't store the layout itself, so the layouts are told apart only by whether the bytes
+/// deserialize. The bytes of the old state that also deserialize as `New`, for example when both
+/// layouts have fields of the same sizes, are silently read as the new layout. Use this only for
+/// the layouts of different sizes, and `migration::Migratable` with the stored state version
+/// otherwise.
+pub fn state_read_versioned<New, Old>() -> Option<New>
+where
+    New: borsh::BorshDeserialize + From<Old>,
+    Old: borsh::BorshDeserialize,
+{
+    storage_read(STATE_KEY).map(|data| match New::try_from_slice(&data) {
+        Ok(state) => state,
+        Err(_) => unwrap_or_fail_with(
+            Old::try_from_slice(&data),
+            "E10",
+            "Cannot deserialize the contract state.",
+            || crate::diagnostics::data_context(&data),
+        )
+        .into(),
+    })
+}
+
+/// Returns `true` if the contract state exists and `false` otherwise. The state exists once an
+/// `#[init]` method or a method that changes the contract struct has been called.
 pub fn state_exists() -> bool {
     storage_has_key(STATE_KEY)
 }
 
+/// The size of the contract state in bytes, or `None` if it doesn't exist. The state is read into
+/// a register, but not copied into the memory of the contract.
+pub fn state_size() -> Option<u64> {
+    match unsafe {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().as_ref().expect(BLOCKCHAIN_INTERFACE_NOT_SET_ERR).storage_read(
+                STATE_KEY.len() as _,
+                STATE_KEY.as_ptr() as _,
+                ATOMIC_OP_REGISTER,
+            )
+        })
+    } {
+        0 => None,
+        1 => Some(register_len(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR)),
+        _ => panic!("{}", RETURN_CODE_ERR),
+    }
+}
+
 // ##################
 // # Helper methods #
 // ##################
@@ -849,6 +1258,154 @@ pub fn is_valid_account_id(account_id: &[u8]) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_storage_read_into() {
+        crate::test_utils::test_env::setup();
+        storage_write(b"a", &[1, 2, 3]);
+        storage_write(b"b", &[4]);
+        let mut buf = Vec::with_capacity(8);
+        let ptr = buf.as_ptr();
+        assert!(storage_read_into(b"a", &mut buf));
+        assert_eq!(buf, [1, 2, 3]);
+        assert!(storage_read_into(b"b", &mut buf));
+        assert_eq!(buf, [4]);
+        assert_eq!(buf.as_ptr(), ptr);
+        assert!(!storage_read_into(b"c", &mut buf));
+        assert!(buf.is_empty());
+        assert_eq!(storage_read(b"a"), Some(vec![1, 2, 3]));
+        assert!(input_into(&mut buf));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_storage_remove_evicted() {
+        crate::test_utils::test_env::setup();
+        storage_write(b"a", &[1, 2, 3]);
+        assert_eq!(storage_remove_evicted(b"a"), Some(vec![1, 2, 3]));
+        assert!(!storage_has_key(b"a"));
+        assert_eq!(storage_remove_evicted(b"a"), None);
+    }
+
+    #[test]
+    fn test_context_cache() {
+        crate::test_utils::test_env::setup();
+        let gas = used_gas();
+        assert_eq!(current_account_id(), "alice.near");
+        let first_read = used_gas() - gas;
+        let gas = used_gas();
+        assert_eq!(current_account_id(), "alice.near");
+        assert!(used_gas() - gas < first_read);
+        assert_eq!(attached_deposit(), 0);
+        take_blockchain_interface();
+        CONTEXT_CACHE.with(|c| {
+            assert!(c.borrow().current_account_id.is_none());
+            assert!(c.borrow().attached_deposit.is_none());
+        });
+    }
+
+    #[test]
+    fn test_state_size() {
+        crate::test_utils::test_env::setup();
+        assert!(!state_exists());
+        assert_eq!(state_size(), None);
+        state_write(&(1u64, "abc".to_string()));
+        assert!(state_exists());
+        assert_eq!(state_size(), Some(15));
+    }
+
+    #[test]
+    fn test_gas_checkpoints() {
+        crate::test_utils::test_env::setup();
+        gas_checkpoint("start");
+        for i in 0..10u8 {
+            storage_write(&[i], &[i]);
+        }
+        gas_checkpoint("writes");
+        let checkpoints = take_gas_checkpoints();
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0].label, "start");
+        assert_eq!(checkpoints[0].gas_since_previous, checkpoints[0].used_gas);
+        assert_eq!(checkpoints[1].label, "writes");
+        assert!(checkpoints[1].gas_since_previous > 0);
+        assert_eq!(
+            checkpoints[1].used_gas,
+            checkpoints[0].used_gas + checkpoints[1].gas_since_previous
+        );
+        assert!(take_gas_checkpoints().is_empty());
+    }
+
+    #[test]
+    fn test_gas_checkpoints_cleared_with_interface() {
+        crate::test_utils::test_env::setup();
+        gas_checkpoint("previous test");
+        crate::test_utils::test_env::setup();
+        assert!(take_gas_checkpoints().is_empty());
+    }
+
+    #[test]
+    fn test_storage_entries() {
+        crate::test_utils::test_env::setup();
+        storage_write(b"b1", &[2]);
+        storage_write(b"a", &[1]);
+        storage_write(b"b0", &[3]);
+        assert_eq!(
+            storage_entries_with_prefix(b"b").collect::<Vec<_>>(),
+            vec![(b"b0".to_vec(), vec![3]), (b"b1".to_vec(), vec![2])]
+        );
+        storage_remove(b"b0");
+        let keys: Vec<_> = storage_entries().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![b"a".to_vec(), b"b1".to_vec()]);
+    }
+
+    #[test]
+    fn test_signer_public_key() {
+        crate::test_utils::test_env::setup();
+        let key = signer_public_key();
+        assert_eq!(key.curve_type(), crate::json_types::CurveType::ED25519);
+        assert_eq!(Vec::<u8>::from(key), signer_account_pk());
+    }
+
+    #[test]
+    fn test_failure_event() {
+        crate::test_utils::test_env::setup();
+        set_failure_event_method("transfer");
+        let result = std_panic::catch_unwind(|| panic_err(crate::ContractError::PANIC, "Failed"));
+        assert!(result.is_err());
+        assert!(FAILURE_EVENT_METHOD.with(|m| m.get()).is_none());
+    }
+
+    #[test]
+    fn test_require() {
+        crate::test_utils::test_env::setup();
+        crate::require!(attached_deposit() == 0);
+        crate::require!(attached_deposit() == 0, "Requires no deposit");
+        crate::require!(attached_deposit() == 0, format!("Requires {} deposit", 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit")]
+    fn test_require_failed() {
+        crate::test_utils::test_env::setup();
+        crate::require!(attached_deposit() == 1, "Requires attached deposit");
+    }
+
+    #[test]
+    #[should_panic(expected = "require! assertion failed")]
+    fn test_require_without_message() {
+        crate::test_utils::test_env::setup();
+        crate::require!(attached_deposit() == 1);
+    }
+
+    #[test]
+    fn test_log() {
+        crate::test_utils::test_env::setup();
+        let receiver_id = "bob.near";
+        crate::log!("Started");
+        crate::log!(String::from("Started"));
+        crate::log!("Transferred {} to {}", 10, receiver_id);
+        crate::debug_log!("Balance {}", account_balance());
+    }
+
     #[test]
     fn test_is_valid_account_id_strings() {
         // Valid
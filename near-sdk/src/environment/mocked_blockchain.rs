@@ -17,6 +17,12 @@ pub struct MockedBlockchain {
     // We keep ownership over logic fixture so that references in `VMLogic` are valid.
     #[allow(dead_code)]
     logic_fixture: LogicFixture,
+    /// The account that the yielded promises call back.
+    #[cfg(feature = "yield-resume")]
+    current_account_id: AccountId,
+    /// The data ids of the yielded promises, and whether they were resumed.
+    #[cfg(feature = "yield-resume")]
+    yields: RefCell<Vec<(Vec<u8>, bool)>>,
 }
 
 struct LogicFixture {
@@ -37,6 +43,8 @@ impl MockedBlockchain {
         storage: HashMap<Vec<u8>, Vec<u8>>,
         validators: HashMap<AccountId, Balance>,
     ) -> Self {
+        #[cfg(feature = "yield-resume")]
+        let current_account_id = context.current_account_id.clone();
         let mut ext = Box::new(MockedExternal::new());
         ext.fake_trie = storage;
         ext.validators = validators;
@@ -59,13 +67,25 @@ impl MockedBlockchain {
         };
 
         let logic = RefCell::new(logic);
-        Self { logic, logic_fixture }
+        Self {
+            logic,
+            logic_fixture,
+            #[cfg(feature = "yield-resume")]
+            current_account_id,
+            #[cfg(feature = "yield-resume")]
+            yields: Default::default(),
+        }
     }
 
     pub fn take_storage(&mut self) -> HashMap<Vec<u8>, Vec<u8>> {
         std::mem::take(&mut self.logic_fixture.ext.fake_trie)
     }
 
+    /// The keys and values of the storage.
+    pub fn storage(&self) -> &HashMap<Vec<u8>, Vec<u8>> {
+        &self.logic_fixture.ext.fake_trie
+    }
+
     pub fn created_receipts(&self) -> &Vec<Receipt> {
         self.logic_fixture.ext.get_receipt_create_calls()
     }
@@ -378,6 +398,62 @@ impl BlockchainInterface for MockedBlockchain {
         self.logic.borrow_mut().promise_return(promise_id).unwrap()
     }
 
+    /// `VMLogic` doesn't have yield and resume, so the yielded promise is a function call of the
+    /// current account, and its data id is the number of the yield.
+    #[cfg(feature = "yield-resume")]
+    unsafe fn promise_yield_create(
+        &self,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        _gas_weight: u64,
+        register_id: u64,
+    ) -> u64 {
+        let mut logic = self.logic.borrow_mut();
+        let account_id = self.current_account_id.as_bytes();
+        let promise_index =
+            logic.promise_batch_create(account_id.len() as _, account_id.as_ptr() as _).unwrap();
+        let amount = 0u128.to_le_bytes();
+        logic
+            .promise_batch_action_function_call(
+                promise_index,
+                function_name_len,
+                function_name_ptr,
+                arguments_len,
+                arguments_ptr,
+                amount.as_ptr() as _,
+                gas,
+            )
+            .unwrap();
+        let mut yields = self.yields.borrow_mut();
+        let mut data_id = vec![0u8; 32];
+        data_id[..8].copy_from_slice(&(yields.len() as u64).to_le_bytes());
+        logic.write_register(register_id, data_id.len() as _, data_id.as_ptr() as _).unwrap();
+        yields.push((data_id, false));
+        promise_index
+    }
+
+    #[cfg(feature = "yield-resume")]
+    unsafe fn promise_yield_resume(
+        &self,
+        data_id_len: u64,
+        data_id_ptr: u64,
+        _payload_len: u64,
+        _payload_ptr: u64,
+    ) -> u32 {
+        let data_id = std::slice::from_raw_parts(data_id_ptr as *const u8, data_id_len as usize);
+        let mut yields = self.yields.borrow_mut();
+        match yields.iter_mut().find(|(id, resumed)| id.as_slice() == data_id && !resumed) {
+            Some((_, resumed)) => {
+                *resumed = true;
+                1
+            }
+            None => 0,
+        }
+    }
+
     unsafe fn storage_write(
         &self,
         key_len: u64,
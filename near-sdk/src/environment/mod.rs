@@ -1,3 +1,5 @@
 pub mod blockchain_interface;
 pub mod env;
 pub mod mocked_blockchain;
+#[cfg(feature = "json")]
+pub mod storage_dump;
@@ -0,0 +1,215 @@
+//! Dry runs of the state migrations in the unit tests.
+//!
+//! A `StorageDump` holds the storage of a contract, taken from a test or parsed from the output of
+//! the `view_state` RPC of a real contract. `dry_run_migration` loads the dump into the mocked
+//! blockchain, runs the migration of the new version of the contract, and returns the difference
+//! between the storage before and after it:
+//!
+//! ```ignore
+//! let dump = StorageDump::from_view_state_json(&std::fs::read_to_string("state.json").unwrap());
+//! let diff = dry_run_migration(&dump, context, || {
+//!     let contract = Contract::migrate();
+//!     env::state_write(&contract);
+//! });
+//! assert!(diff.removed.is_empty());
+//! ```
+use std::collections::HashMap;
+
+use near_vm_logic::VMContext;
+use serde::Deserialize;
+
+use crate::env;
+use crate::json_types::Base64VecU8;
+use crate::MockedBlockchain;
+
+/// The storage of a contract.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageDump(pub HashMap<Vec<u8>, Vec<u8>>);
+
+#[derive(Deserialize)]
+struct ViewStateResult {
+    values: Vec<ViewStateItem>,
+}
+
+#[derive(Deserialize)]
+struct ViewStateItem {
+    key: Base64VecU8,
+    value: Base64VecU8,
+}
+
+impl StorageDump {
+    /// Takes the storage of the mocked blockchain, leaving the blockchain interface unset.
+    pub fn take() -> Self {
+        match env::take_blockchain_interface() {
+            Some(mut bi) => Self(bi.as_mut_mocked_blockchain().unwrap().take_storage()),
+            None => Self::default(),
+        }
+    }
+
+    /// Parses the result of the `view_state` RPC, with the base64 keys and values of the storage.
+    pub fn from_view_state_json(json: &str) -> Self {
+        let result: ViewStateResult =
+            serde_json::from_str(json).expect("Cannot parse the result of view_state.");
+        Self(result.values.into_iter().map(|item| (item.key.0, item.value.0)).collect())
+    }
+
+    /// Sets up the mocked blockchain with the given context and a copy of the storage.
+    pub fn load(&self, context: VMContext) {
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![],
+            self.0.clone(),
+            Default::default(),
+        )));
+    }
+}
+
+/// The difference between two dumps of the storage. The entries are sorted by their keys.
+#[derive(Debug, Default, PartialEq)]
+pub struct StorageDiff {
+    /// The entries that are only in the new storage.
+    pub added: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The entries that are only in the old storage.
+    pub removed: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The keys with different values, with the old and the new value.
+    pub changed: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+}
+
+impl StorageDiff {
+    pub fn new(before: &StorageDump, after: &StorageDump) -> Self {
+        let mut diff = Self::default();
+        for (key, value) in &after.0 {
+            match before.0.get(key) {
+                None => diff.added.push((key.clone(), value.clone())),
+                Some(old_value) if old_value != value => {
+                    diff.changed.push((key.clone(), old_value.clone(), value.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, value) in &before.0 {
+            if !after.0.contains_key(key) {
+                diff.removed.push((key.clone(), value.clone()));
+            }
+        }
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Returns `true` if the storage didn't change.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Loads `before` into the mocked blockchain with the given context, runs `migrate` and returns the
+/// changes it made to the storage. The blockchain interface is left unset.
+pub fn dry_run_migration<F: FnOnce()>(
+    before: &StorageDump,
+    context: VMContext,
+    migrate: F,
+) -> StorageDiff {
+    before.load(context);
+    migrate();
+    StorageDiff::new(before, &StorageDump::take())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::{self, Migratable};
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    struct CounterV0 {
+        value: u32,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    struct Counter {
+        value: u64,
+    }
+
+    enum VersionedCounter {
+        V0(CounterV0),
+        V1(Counter),
+    }
+
+    impl Migratable for VersionedCounter {
+        type Current = Counter;
+        const CURRENT_VERSION: u8 = 1;
+
+        fn read_versioned_state() -> Self {
+            match migration::state_version() {
+                0 => Self::V0(migration::read_state()),
+                _ => Self::V1(migration::read_state()),
+            }
+        }
+
+        fn into_current(self) -> Counter {
+            match self {
+                Self::V0(counter) => Counter { value: counter.value.into() },
+                Self::V1(counter) => counter,
+            }
+        }
+    }
+
+    fn context() -> VMContext {
+        VMContext {
+            current_account_id: "counter.near".to_string(),
+            signer_account_id: "alice.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: "alice.near".to_string(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 0,
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    #[test]
+    fn test_dry_run_migration() {
+        let mut storage = HashMap::new();
+        storage.insert(b"STATE".to_vec(), CounterV0 { value: 5 }.try_to_vec().unwrap());
+        storage.insert(b"other".to_vec(), vec![1]);
+        let dump = StorageDump(storage);
+        let diff = dry_run_migration(&dump, context(), || {
+            let counter = VersionedCounter::migrate();
+            env::state_write(&counter);
+        });
+        assert_eq!(
+            diff,
+            StorageDiff {
+                added: vec![(migration::STATE_VERSION_KEY.to_vec(), vec![1])],
+                removed: vec![],
+                changed: vec![(
+                    b"STATE".to_vec(),
+                    5u32.to_le_bytes().to_vec(),
+                    5u64.to_le_bytes().to_vec()
+                )],
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_view_state_json() {
+        let dump = StorageDump::from_view_state_json(
+            r#"{"values": [{"key": "U1RBVEU=", "value": "AQ==", "proof": []}], "proof": []}"#,
+        );
+        assert_eq!(dump.0.get(b"STATE".as_ref()), Some(&vec![1]));
+        assert!(StorageDiff::new(&dump, &dump.clone()).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Events in the format of NEP-297. An event is logged as `EVENT_JSON:` followed by a JSON object
+//! with the `standard`, `version` and `event` fields, and optional `data`, so the indexers can
+//! tell the events from the other logs.
+use serde::Serialize;
+
+use crate::env;
+
+/// The prefix of the logs that contain events.
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// The envelope of an event.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EventLog<'a> {
+    /// Name of the standard that defines the event, e.g. `nep171`.
+    pub standard: &'a str,
+    /// Version of the standard, e.g. `1.0.0`.
+    pub version: &'a str,
+    /// Name of the event, e.g. `nft_mint`.
+    pub event: &'a str,
+    /// Data of the event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// Formats `event` as an event log line: `EVENT_JSON:` followed by the JSON of `event`.
+pub fn event_log<T: Serialize + ?Sized>(event: &T) -> String {
+    match serde_json::to_string(event) {
+        Ok(json) => format!("{}{}", EVENT_JSON_PREFIX, json),
+        Err(_) => env::panic(b"Cannot serialize the event to JSON"),
+    }
+}
+
+/// Logs `event` in the event format. `event` is expected to serialize into the envelope of
+/// NEP-297, e.g. `EventLog`.
+pub fn emit_event<T: Serialize + ?Sized>(event: &T) {
+    env::log(event_log(event).as_bytes());
+}
+
+/// Serializes a piece of the event data to JSON. Used by `#[derive(NearEvent)]`.
+pub fn event_data<T: Serialize + ?Sized>(value: &T) -> serde_json::Value {
+    match serde_json::to_value(value) {
+        Ok(value) => value,
+        Err(_) => env::panic(b"Cannot serialize the event data to JSON"),
+    }
+}
+
+/// An event that can be emitted in the NEP-297 format. Usually derived with `#[derive(NearEvent)]`:
+///
+/// ```
+/// use near_sdk::NearEvent;
+///
+/// #[derive(NearEvent)]
+/// #[near_event(standard = "nep171", version = "1.0.0")]
+/// pub enum NftEvent {
+///     NftMint { owner_id: String, token_ids: Vec<String> },
+///     NftBurn(Vec<String>),
+/// }
+/// ```
+///
+/// Each variant of an enum is an event named after the variant in snake case, e.g. `nft_mint`.
+/// The named fields of the variant become the fields of `data`, the single unnamed field becomes
+/// `data` itself, and unit variants have no `data`. A struct is a single event named after the
+/// struct, with the struct as `data`.
+pub trait NearEvent {
+    /// Name of the standard that defines the event.
+    fn standard(&self) -> &'static str;
+
+    /// Version of the standard.
+    fn version(&self) -> &'static str;
+
+    /// Name of the event.
+    fn event(&self) -> &'static str;
+
+    /// Data of the event.
+    fn data(&self) -> Option<serde_json::Value>;
+
+    /// Returns the envelope of the event.
+    fn to_event_log(&self) -> EventLog<'static> {
+        EventLog {
+            standard: self.standard(),
+            version: self.version(),
+            event: self.event(),
+            data: self.data(),
+        }
+    }
+
+    /// Logs the event.
+    fn emit(&self) {
+        emit_event(&self.to_event_log());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    struct Transfer;
+
+    impl NearEvent for Transfer {
+        fn standard(&self) -> &'static str {
+            "nep141"
+        }
+
+        fn version(&self) -> &'static str {
+            "1.0.0"
+        }
+
+        fn event(&self) -> &'static str {
+            "ft_transfer"
+        }
+
+        fn data(&self) -> Option<serde_json::Value> {
+            Some(serde_json::json!([{"amount": "1"}]))
+        }
+    }
+
+    #[test]
+    fn test_event_log() {
+        let log = EventLog { standard: "nep297", version: "1.0.0", event: "ping", data: None };
+        assert_eq!(
+            event_log(&log),
+            r#"EVENT_JSON:{"standard":"nep297","version":"1.0.0","event":"ping"}"#
+        );
+    }
+
+    #[test]
+    fn test_near_event() {
+        assert_eq!(
+            event_log(&Transfer.to_event_log()),
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"amount":"1"}]}"#
+        );
+        test_env::setup();
+        Transfer.emit();
+    }
+}
@@ -0,0 +1,203 @@
+use crate::env;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// A 32-byte hash, like the result of `env::sha256_hash` and `env::keccak256_hash` or the hash of
+/// the code of an account. It's written as base58, like the hashes in the NEAR RPC, and parsed
+/// from base58 or hex. It's serialized with JSON as the base58 string and with Borsh as the 32
+/// bytes.
+///
+/// The comparison takes the same time wherever the hashes differ, so comparing a secret hash with
+/// the input doesn't leak how much of it matched.
+#[derive(Clone, Copy, Default, BorshDeserialize, BorshSerialize)]
+pub struct CryptoHash([u8; 32]);
+
+impl CryptoHash {
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// The SHA-256 hash of the value.
+    pub fn sha256(value: &[u8]) -> Self {
+        env::sha256_hash(value)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let mut bytes = [0u8; 32];
+        for (byte, i) in bytes.iter_mut().zip((0..64).step_by(2)) {
+            *byte = u8::from_str_radix(&hex[i..i + 2], 16).ok()?;
+        }
+        Some(Self(bytes))
+    }
+}
+
+impl PartialEq for CryptoHash {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.iter().zip(other.0.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+    }
+}
+
+impl Eq for CryptoHash {}
+
+impl Hash for CryptoHash {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl From<[u8; 32]> for CryptoHash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<CryptoHash> for [u8; 32] {
+    fn from(hash: CryptoHash) -> Self {
+        hash.0
+    }
+}
+
+impl AsRef<[u8]> for CryptoHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Fails unless the slice has 32 bytes.
+impl TryFrom<&[u8]> for CryptoHash {
+    type Error = ParseCryptoHashError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; 32]>::try_from(bytes).map(Self).map_err(|_| ParseCryptoHashError)
+    }
+}
+
+impl fmt::Display for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(&self.0).into_string())
+    }
+}
+
+impl fmt::Debug for CryptoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CryptoHash({})", self)
+    }
+}
+
+/// The string is neither 64 hex digits nor the base58 of 32 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCryptoHashError;
+
+impl fmt::Display for ParseCryptoHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid hash, expected 32 bytes in base58 or hex")
+    }
+}
+
+impl std::error::Error for ParseCryptoHashError {}
+
+impl FromStr for CryptoHash {
+    type Err = ParseCryptoHashError;
+
+    /// Parses 64 hex digits, optionally prefixed with `0x`, or the base58 of 32 bytes, which is at
+    /// most 44 characters long.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix("0x").unwrap_or(s);
+        if hex.len() == 64 {
+            return Self::from_hex(hex).ok_or(ParseCryptoHashError);
+        }
+        let bytes = bs58::decode(s).into_vec().map_err(|_| ParseCryptoHashError)?;
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl Serialize for CryptoHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for CryptoHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    /// The SHA-256 of `hello`.
+    const HELLO_HEX: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_env_hashes() {
+        test_env::setup();
+        let hash = env::sha256_hash(b"hello");
+        assert_eq!(hash.to_hex(), HELLO_HEX);
+        assert_eq!(hash.as_bytes().to_vec(), env::sha256(b"hello"));
+        assert_eq!(CryptoHash::sha256(b"hello"), hash);
+        assert_eq!(env::keccak256_hash(b"hello").as_ref(), env::keccak256(b"hello").as_slice());
+    }
+
+    #[test]
+    fn test_parse() {
+        let hash = CryptoHash::from_hex(HELLO_HEX).unwrap();
+        assert_eq!(HELLO_HEX.parse(), Ok(hash));
+        assert_eq!(format!("0x{}", HELLO_HEX.to_uppercase()).parse(), Ok(hash));
+        assert_eq!(hash.to_string().parse(), Ok(hash));
+        assert_eq!(
+            "11111111111111111111111111111111".parse::<CryptoHash>(),
+            Ok(CryptoHash::default())
+        );
+        assert!(HELLO_HEX[2..].parse::<CryptoHash>().is_err());
+        assert!(format!("{}zz", &HELLO_HEX[2..]).parse::<CryptoHash>().is_err());
+        assert!("3tysLvy7KGoE8pznUgXvSHa4".parse::<CryptoHash>().is_err());
+        assert!("0OIl".parse::<CryptoHash>().is_err());
+        assert_eq!(CryptoHash::try_from(&[1u8; 31][..]), Err(ParseCryptoHashError));
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut bytes = [7u8; 32];
+        assert_eq!(CryptoHash::new(bytes), CryptoHash::from(bytes));
+        bytes[31] = 8;
+        assert_ne!(CryptoHash::new(bytes), CryptoHash::new([7u8; 32]));
+        bytes[0] = 8;
+        assert_ne!(CryptoHash::new(bytes), CryptoHash::new([8u8; 32]));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let hash = CryptoHash::new([1u8; 32]);
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{}\"", bs58::encode([1u8; 32]).into_string()));
+        assert_eq!(serde_json::from_str::<CryptoHash>(&json).unwrap(), hash);
+        assert_eq!(hash.try_to_vec().unwrap(), vec![1u8; 32]);
+        assert_eq!(CryptoHash::try_from_slice(&[1u8; 32]).unwrap(), hash);
+    }
+}
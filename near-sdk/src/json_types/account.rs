@@ -1,8 +1,12 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::Serialize;
+use std::borrow::Borrow;
 use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::ops::Deref;
 
 use crate::env::is_valid_account_id;
+use crate::json_types::{Base58PublicKey, CurveType};
 use crate::AccountId;
 
 /// Helper class to validate account ID during serialization and deserializiation
@@ -15,6 +19,150 @@ impl ValidAccountId {
     fn is_valid(&self) -> bool {
         is_valid_account_id(&self.0.as_bytes())
     }
+
+    /// The direct sub-account `<name>.<self>`, e.g. `alice.factory.near` for `alice` under
+    /// `factory.near`, which only `self` can create. Fails if `name` contains a dot or the account
+    /// ID is invalid.
+    pub fn create_subaccount(
+        &self,
+        name: &str,
+    ) -> Result<ValidAccountId, Box<dyn std::error::Error>> {
+        if name.contains('.') {
+            return Err("The name of the sub-account cannot contain dots".into());
+        }
+        Self::try_from(format!("{}.{}", name, self.0))
+    }
+
+    /// The implicit account of the ED25519 public key, or `None` for the other curves.
+    pub fn implicit_for(public_key: &Base58PublicKey) -> Option<ValidAccountId> {
+        match public_key.curve_type() {
+            CurveType::ED25519 => {
+                Some(Self(public_key.0[1..].iter().map(|b| format!("{:02x}", b)).collect()))
+            }
+            CurveType::SECP256K1 => None,
+        }
+    }
+}
+
+/// The borrowed counterpart of `ValidAccountId`, like `str` of `String`: a valid account ID that
+/// the functions can take as `&AccountIdRef` without allocating. `ValidAccountId` dereferences to
+/// it, and `to_owned` turns it back into a `ValidAccountId`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct AccountIdRef(str);
+
+impl AccountIdRef {
+    /// Validates the account ID without copying it.
+    pub fn new(account_id: &str) -> Result<&AccountIdRef, Box<dyn std::error::Error>> {
+        if is_valid_account_id(account_id.as_bytes()) {
+            Ok(Self::new_unchecked(account_id))
+        } else {
+            Err("The account ID is invalid".into())
+        }
+    }
+
+    /// Skips the validation, for the account IDs that come from the runtime, like
+    /// `env::predecessor_account_id()`.
+    pub fn new_unchecked(account_id: &str) -> &AccountIdRef {
+        // `AccountIdRef` is `#[repr(transparent)]` over `str`.
+        unsafe { &*(account_id as *const str as *const AccountIdRef) }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the account is a direct sub-account of `parent`, like `alice.factory.near` of
+    /// `factory.near`, but not `bob.alice.factory.near`.
+    pub fn is_sub_account_of(&self, parent: &AccountIdRef) -> bool {
+        let name = self.0.strip_suffix(&parent.0).and_then(|prefix| prefix.strip_suffix('.'));
+        matches!(name, Some(name) if !name.is_empty() && !name.contains('.'))
+    }
+
+    /// The account that can create this one, e.g. `factory.near` for `alice.factory.near`, or
+    /// `None` for the top-level and the implicit accounts.
+    pub fn parent(&self) -> Option<&AccountIdRef> {
+        self.0.find('.').map(|dot| Self::new_unchecked(&self.0[dot + 1..]))
+    }
+
+    /// Whether the account is an implicit account: 64 lowercase hex digits of an ED25519 public
+    /// key, which exists once it receives NEAR, without being created.
+    pub fn is_implicit(&self) -> bool {
+        self.0.len() == 64 && self.0.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    }
+
+    /// Whether the account is a named account without a dot, like `near` or `aurora`, which is
+    /// created by the registrar.
+    pub fn is_top_level(&self) -> bool {
+        !self.0.contains('.') && !self.is_implicit()
+    }
+}
+
+impl Deref for ValidAccountId {
+    type Target = AccountIdRef;
+
+    fn deref(&self) -> &AccountIdRef {
+        AccountIdRef::new_unchecked(&self.0)
+    }
+}
+
+impl Borrow<AccountIdRef> for ValidAccountId {
+    fn borrow(&self) -> &AccountIdRef {
+        self
+    }
+}
+
+impl ToOwned for AccountIdRef {
+    type Owned = ValidAccountId;
+
+    fn to_owned(&self) -> ValidAccountId {
+        ValidAccountId(self.0.to_string())
+    }
+}
+
+impl AsRef<str> for AccountIdRef {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AccountIdRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for AccountIdRef {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for AccountIdRef {
+    fn eq(&self, other: &str) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<AccountId> for AccountIdRef {
+    fn eq(&self, other: &AccountId) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<AccountIdRef> for ValidAccountId {
+    fn eq(&self, other: &AccountIdRef) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialEq<ValidAccountId> for AccountIdRef {
+    fn eq(&self, other: &ValidAccountId) -> bool {
+        self.0 == other.0
+    }
 }
 
 impl AsRef<AccountId> for ValidAccountId {
@@ -82,6 +230,58 @@ mod tests {
         assert_eq!(actual, "\"alice.near\"");
     }
 
+    fn account(account_id: &str) -> ValidAccountId {
+        account_id.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_sub_accounts() {
+        let factory = account("factory.near");
+        let alice = factory.create_subaccount("alice").unwrap();
+        assert_eq!(alice, account("alice.factory.near"));
+        assert!(alice.is_sub_account_of(&factory));
+        assert_eq!(alice.parent(), Some(&*factory));
+        assert!(!factory.is_sub_account_of(&factory));
+        assert!(!account("bob.alice.factory.near").is_sub_account_of(&factory));
+        assert!(!account("alicefactory.near").is_sub_account_of(&factory));
+        assert!(factory.create_subaccount("bob.alice").is_err());
+        assert!(factory.create_subaccount("Alice").is_err());
+        assert!(factory.create_subaccount("").is_err());
+        assert_eq!(account("near").parent(), None);
+    }
+
+    #[test]
+    fn test_account_id_ref() {
+        let alice = account("alice.near");
+        let borrowed: &AccountIdRef = &alice;
+        assert_eq!(borrowed, "alice.near");
+        assert_eq!(borrowed, &"alice.near".to_string());
+        assert_eq!(&alice, borrowed);
+        assert_eq!(borrowed.to_owned(), alice);
+        assert_eq!(AccountIdRef::new("alice.near").unwrap(), borrowed);
+        assert!(AccountIdRef::new("Alice.near").is_err());
+        assert_eq!(borrowed.parent().unwrap().as_str(), "near");
+        assert_eq!(borrowed.to_string(), "alice.near");
+        assert_eq!(serde_json::to_string(borrowed).unwrap(), "\"alice.near\"");
+        let mut accounts = std::collections::BTreeSet::new();
+        accounts.insert(alice.clone());
+        assert!(accounts.contains(AccountIdRef::new_unchecked("alice.near")));
+    }
+
+    #[test]
+    fn test_implicit_accounts() {
+        let key: Base58PublicKey = [vec![0], vec![0xab; 32]].concat().try_into().unwrap();
+        let implicit = ValidAccountId::implicit_for(&key).unwrap();
+        assert_eq!(implicit.as_ref(), &"ab".repeat(32));
+        assert!(implicit.is_implicit());
+        assert!(!implicit.is_top_level());
+        assert!(account("near").is_top_level());
+        assert!(!account("alice.near").is_top_level());
+        assert!(!account(&"ab".repeat(31)).is_implicit());
+        let key: Base58PublicKey = [vec![1], vec![1; 64]].concat().try_into().unwrap();
+        assert_eq!(ValidAccountId::implicit_for(&key), None);
+    }
+
     #[test]
     fn test_from_str() {
         let key = ValidAccountId::try_from("alice.near").unwrap();
@@ -0,0 +1,160 @@
+//! Compatibility of the JSON arguments and results with the AssemblyScript SDK, `near-sdk-as`.
+//!
+//! Both SDKs pass the arguments as a JSON object keyed by the names of the parameters, and return
+//! nothing for the methods without a result. They differ in the encoding of the values, so the
+//! methods that are called by the clients generated for `near-sdk-as`, or that call its contracts,
+//! should use the wire format of `near-sdk-as`:
+//!
+//! * `u64`, `i64`, `u128` and `i128` are decimal strings. Use `U64`, `I64`, `U128` and `I128`, or
+//!   `#[serde(with = "string_number")]` on the fields of the structs;
+//! * `Uint8Array` is a base64 string. Use `Base64VecU8`, or `#[serde(with = "base64_bytes")]`;
+//! * `u32` and the smaller integers, `bool`, `string` and the arrays are the same in both SDKs, and
+//!   the missing nullable values are `null`, like `Option`;
+//! * the parameters and the fields of the classes keep the names they have in the AssemblyScript
+//!   source, so the Rust parameters and fields should have the same names, with `#[serde(rename)]`
+//!   on the fields that are camelCase there.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(crate = "near_sdk::serde")]
+//! pub struct Deposit {
+//!     #[serde(rename = "accountId")]
+//!     pub account_id: String,
+//!     #[serde(with = "near_sdk::json_types::assemblyscript::string_number")]
+//!     pub amount: u128,
+//! }
+//! ```
+//!
+//! The helpers also read the integers written as JSON numbers, which older clients send for the
+//! small values.
+
+/// Serializes an integer as a decimal string, and deserializes it from a string or a number.
+pub mod string_number {
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt::{self, Display};
+    use std::marker::PhantomData;
+    use std::str::FromStr;
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(StringNumberVisitor(PhantomData))
+    }
+
+    struct StringNumberVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for StringNumberVisitor<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an integer or a decimal string")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+            value.parse().map_err(E::custom)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+            self.visit_str(&value.to_string())
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+            self.visit_str(&value.to_string())
+        }
+    }
+}
+
+/// Serializes bytes as a base64 string, the JSON of `Uint8Array` in `near-sdk-as`.
+pub mod base64_bytes {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let value: String = Deserialize::deserialize(deserializer)?;
+        base64::decode(&value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_types::{Base64VecU8, U128, U64};
+    use serde::{Deserialize, Serialize};
+
+    /// The arguments of `deposit(accountId: string, amount: u128, nonce: u64, data: Uint8Array,
+    /// memo: string | null, count: u32)` of an AssemblyScript contract.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Deposit {
+        #[serde(rename = "accountId")]
+        account_id: String,
+        #[serde(with = "string_number")]
+        amount: u128,
+        #[serde(with = "string_number")]
+        nonce: u64,
+        #[serde(with = "base64_bytes")]
+        data: Vec<u8>,
+        memo: Option<String>,
+        count: u32,
+    }
+
+    /// The same arguments with the wrapper types.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct WrappedDeposit {
+        #[serde(rename = "accountId")]
+        account_id: String,
+        amount: U128,
+        nonce: U64,
+        data: Base64VecU8,
+        memo: Option<String>,
+        count: u32,
+    }
+
+    /// The arguments as `near-sdk-as` writes them.
+    const AS_JSON: &str = r#"{"accountId":"alice.near","amount":"340282366920938463463374607431768211455","nonce":"18446744073709551615","data":"AQID","memo":null,"count":7}"#;
+
+    #[test]
+    fn test_wire_format() {
+        let deposit = Deposit {
+            account_id: "alice.near".to_string(),
+            amount: u128::MAX,
+            nonce: u64::MAX,
+            data: vec![1, 2, 3],
+            memo: None,
+            count: 7,
+        };
+        assert_eq!(serde_json::to_string(&deposit).unwrap(), AS_JSON);
+        assert_eq!(serde_json::from_str::<Deposit>(AS_JSON).unwrap(), deposit);
+        let wrapped: WrappedDeposit = serde_json::from_str(AS_JSON).unwrap();
+        assert_eq!(serde_json::to_string(&wrapped).unwrap(), AS_JSON);
+    }
+
+    #[test]
+    fn test_lenient_numbers() {
+        let json = r#"{"accountId":"a","amount":10,"nonce":"1","data":"","memo":"m","count":0}"#;
+        let deposit: Deposit = serde_json::from_str(json).unwrap();
+        assert_eq!(deposit.amount, 10);
+        assert_eq!(deposit.memo, Some("m".to_string()));
+        let json = r#"{"accountId":"a","amount":"-1","nonce":"1","data":"","memo":null,"count":0}"#;
+        assert!(serde_json::from_str::<Deposit>(json).is_err());
+        let json = r#"{"accountId":"a","amount":"1","nonce":"1","data":"!","memo":null,"count":0}"#;
+        assert!(serde_json::from_str::<Deposit>(json).is_err());
+    }
+}
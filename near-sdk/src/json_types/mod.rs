@@ -1,11 +1,14 @@
 //! Helper types for JSON serialization.
 
+pub mod assemblyscript;
+pub mod near_amount;
+
 mod account;
 mod integers;
 mod public_key;
 mod vector;
 
-pub use account::ValidAccountId;
+pub use account::{AccountIdRef, ValidAccountId};
 pub use integers::{I128, I64, U128, U64};
 pub use public_key::{Base58PublicKey, CurveType};
 pub use vector::Base64VecU8;
@@ -0,0 +1,116 @@
+//! The amounts of NEAR with a denomination, like `"1.5 NEAR"` or `"10 mNEAR"`, for the methods
+//! that take the amounts from the people rather than from the programs, e.g. the configuration of
+//! a contract set from a CLI. It's opt-in, with `#[serde(with = "near_amount")]` on a `Balance`
+//! field:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(crate = "near_sdk::serde")]
+//! pub struct Config {
+//!     #[serde(with = "near_sdk::json_types::near_amount")]
+//!     pub min_deposit: Balance,
+//! }
+//! ```
+//!
+//! The amount is serialized in NEAR, e.g. `"0.25 NEAR"`, and deserialized from a number with one of
+//! the denominations `NEAR`, `mNEAR` (10^-3 NEAR), `uNEAR` or `µNEAR` (10^-6 NEAR) and `yoctoNEAR`
+//! (10^-24 NEAR), or from a string of yoctoNEAR without a denomination, like `U128`.
+use crate::math::FixedU128;
+use crate::Balance;
+use serde::{de, Deserialize, Deserializer, Serializer};
+use std::fmt;
+
+/// The string is not an amount of NEAR, has more digits after the point than a yoctoNEAR, or
+/// doesn't fit into `Balance`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAmountError;
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid amount of NEAR, expected e.g. `1.5 NEAR` or `10 mNEAR`")
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
+/// Parses the amount of NEAR with a denomination into yoctoNEAR.
+pub fn parse(s: &str) -> Result<Balance, ParseAmountError> {
+    let s = s.trim();
+    // `NEAR` goes last, as it's the suffix of the other denominations.
+    let parsed = if let Some(number) = s.strip_suffix("yoctoNEAR") {
+        number.trim_end().parse::<FixedU128<0>>().map(|amount| amount.raw())
+    } else if let Some(number) = s.strip_suffix("mNEAR") {
+        number.trim_end().parse::<FixedU128<21>>().map(|amount| amount.raw())
+    } else if let Some(number) = s.strip_suffix("uNEAR").or_else(|| s.strip_suffix("µNEAR")) {
+        number.trim_end().parse::<FixedU128<18>>().map(|amount| amount.raw())
+    } else if let Some(number) = s.strip_suffix("NEAR") {
+        number.trim_end().parse::<FixedU128<24>>().map(|amount| amount.raw())
+    } else {
+        s.parse::<FixedU128<0>>().map(|amount| amount.raw())
+    };
+    parsed.map_err(|_| ParseAmountError)
+}
+
+/// Formats yoctoNEAR as NEAR without the trailing zeros, e.g. `"0.25 NEAR"`.
+pub fn format(amount: Balance) -> String {
+    format!("{} NEAR", FixedU128::<24>::from_raw(amount))
+}
+
+pub fn serialize<S: Serializer>(amount: &Balance, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(*amount))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+    let s: String = Deserialize::deserialize(deserializer)?;
+    parse(&s).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    const NEAR: Balance = 10u128.pow(24);
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("1.5 NEAR"), Ok(NEAR * 3 / 2));
+        assert_eq!(parse("10 mNEAR"), Ok(NEAR / 100));
+        assert_eq!(parse("2uNEAR"), Ok(2 * NEAR / 1_000_000));
+        assert_eq!(parse("0.5 µNEAR"), Ok(NEAR / 2_000_000));
+        assert_eq!(parse("7 yoctoNEAR"), Ok(7));
+        assert_eq!(parse(" 1000 "), Ok(1000));
+        assert_eq!(parse("340282366920938.463463374607431768211455 NEAR"), Ok(Balance::MAX));
+        for invalid in
+            &["", "NEAR", "1.5", "0.5 yoctoNEAR", "1 near", "1 kNEAR", "-1 NEAR", "1e3 NEAR"]
+        {
+            assert_eq!(parse(invalid), Err(ParseAmountError), "{}", invalid);
+        }
+        assert!(parse("340282366920938.463463374607431768211456 NEAR").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(NEAR * 3 / 2), "1.5 NEAR");
+        assert_eq!(format(0), "0 NEAR");
+        assert_eq!(format(1), "0.000000000000000000000001 NEAR");
+        assert_eq!(parse(&format(Balance::MAX)), Ok(Balance::MAX));
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        #[serde(with = "super")]
+        min_deposit: Balance,
+    }
+
+    #[test]
+    fn test_serde() {
+        let config = Config { min_deposit: NEAR / 4 };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r#"{"min_deposit":"0.25 NEAR"}"#);
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+        let config: Config = serde_json::from_str(r#"{"min_deposit":"250 mNEAR"}"#).unwrap();
+        assert_eq!(config.min_deposit, NEAR / 4);
+        assert!(serde_json::from_str::<Config>(r#"{"min_deposit":"1 ETH"}"#).is_err());
+    }
+}
@@ -38,6 +38,54 @@ impl Base58PublicKey {
             Ok((CurveType::ED25519, value))
         }
     }
+
+    /// The curve of the key, stored in the first byte.
+    pub fn curve_type(&self) -> CurveType {
+        match self.0[0] {
+            0 => CurveType::ED25519,
+            1 => CurveType::SECP256K1,
+            _ => panic!("Unexpected curve"),
+        }
+    }
+
+    /// The secp256k1 key from its 64 bytes, the uncompressed point without the `0x04` prefix, or
+    /// from the 65 bytes with the prefix, like Ethereum writes the keys.
+    pub fn from_secp256k1(key: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let key = match key {
+            [0x04, point @ ..] if point.len() == 64 => point,
+            point if point.len() == 64 => point,
+            _ => return Err("Invalid length of the secp256k1 public key".into()),
+        };
+        Ok(Self([&[1], key].concat()))
+    }
+
+    /// The key without the curve byte: the 32 bytes of an ED25519 key or the 64 bytes of a
+    /// secp256k1 key.
+    pub fn key_data(&self) -> &[u8] {
+        &self.0[1..]
+    }
+
+    /// The Ethereum address of a secp256k1 key: the last 20 bytes of the keccak256 of the
+    /// uncompressed point, or `None` for an ED25519 key. Uses `env::keccak256_hash`, so it needs
+    /// the blockchain interface.
+    pub fn ethereum_address(&self) -> Option<[u8; 20]> {
+        match self.curve_type() {
+            CurveType::SECP256K1 => {
+                let hash = crate::env::keccak256_hash(self.key_data());
+                let mut address = [0u8; 20];
+                address.copy_from_slice(&hash.as_bytes()[12..]);
+                Some(address)
+            }
+            CurveType::ED25519 => None,
+        }
+    }
+
+    /// The Ethereum address of a secp256k1 key as `0x` and 40 lowercase hex digits.
+    pub fn ethereum_address_hex(&self) -> Option<String> {
+        self.ethereum_address().map(|address| {
+            address.iter().fold(String::from("0x"), |hex, byte| hex + &format!("{:02x}", byte))
+        })
+    }
 }
 
 impl From<Base58PublicKey> for Vec<u8> {
@@ -156,6 +204,45 @@ mod tests {
         assert_eq!(key.0, binary_key());
     }
 
+    #[test]
+    fn test_public_key_curve_type() {
+        let key: Base58PublicKey = binary_key().try_into().unwrap();
+        assert_eq!(key.curve_type(), CurveType::ED25519);
+        let key = Base58PublicKey::try_from(vec![1; 65]).unwrap();
+        assert_eq!(key.curve_type(), CurveType::SECP256K1);
+    }
+
+    /// The uncompressed secp256k1 key of the private key 1, the generator point.
+    const SECP256K1_HEX: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    fn secp256k1_key() -> Vec<u8> {
+        (0..SECP256K1_HEX.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&SECP256K1_HEX[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_secp256k1() {
+        crate::test_utils::test_env::setup();
+        let key = Base58PublicKey::from_secp256k1(&secp256k1_key()).unwrap();
+        assert_eq!(key.curve_type(), CurveType::SECP256K1);
+        assert_eq!(key.key_data(), secp256k1_key().as_slice());
+        let prefixed = Base58PublicKey::from_secp256k1(&[vec![4], secp256k1_key()].concat());
+        assert_eq!(prefixed.unwrap(), key);
+        assert!(Base58PublicKey::from_secp256k1(&[vec![2], secp256k1_key()].concat()).is_err());
+        assert!(Base58PublicKey::from_secp256k1(&secp256k1_key()[..33]).is_err());
+        let string = String::from(&key);
+        assert!(string.starts_with("secp256k1:"));
+        assert_eq!(Base58PublicKey::try_from(string).unwrap(), key);
+        assert_eq!(
+            key.ethereum_address_hex().unwrap(),
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+        let key: Base58PublicKey = binary_key().try_into().unwrap();
+        assert_eq!(key.ethereum_address(), None);
+    }
+
     #[test]
     fn test_public_key_to_string() {
         let key: Base58PublicKey = binary_key().try_into().unwrap();
@@ -3,7 +3,7 @@ extern crate quickcheck;
 
 pub use near_sdk_macros::{
     callback, callback_vec, ext_contract, init, metadata, near_bindgen, result_serializer,
-    serializer,
+    serializer, Migratable, NearEvent, PartialState,
 };
 
 pub mod collections;
@@ -11,14 +11,70 @@ mod environment;
 pub use environment::env;
 
 mod promise;
-pub use promise::{Promise, PromiseOrValue};
+pub use promise::{Promise, PromiseError, PromiseOrValue};
+#[cfg(feature = "yield-resume")]
+pub use promise::YieldedPromise;
 
 mod metadata;
-pub use metadata::{Metadata, MethodMetadata};
+pub use metadata::{Metadata, MethodMetadata, TypeScriptClient};
 
 pub mod json_types;
 
+#[cfg(feature = "light-json")]
+pub mod light_json;
+
+#[cfg(feature = "json")]
+pub mod contract_metadata;
+#[cfg(feature = "json")]
+pub use contract_metadata::ContractSourceMetadata;
+
+#[cfg(feature = "json")]
+pub mod events;
+#[cfg(feature = "json")]
+pub use events::{emit_event, NearEvent};
+
+pub mod reentrancy;
+
+pub mod contract_error;
+pub use contract_error::ContractError;
+
+pub mod utils;
+pub use utils::{assert_at_least_attached, assert_one_yocto, refund_excess_deposit};
+
+pub mod validation;
+
+pub mod diagnostics;
+
+#[doc(hidden)]
+pub mod wrapper;
+
+pub mod migration;
+pub use migration::Migratable;
+
+pub mod partial_state;
+
+pub mod time;
+
+mod storage_usage;
+pub use storage_usage::StorageUsage;
+
+mod hash;
+pub use hash::{CryptoHash, ParseCryptoHashError};
+
+pub mod math;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod build;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod method_registry;
+
 pub use environment::mocked_blockchain::MockedBlockchain;
+#[cfg(feature = "json")]
+pub use environment::storage_dump::{dry_run_migration, StorageDiff, StorageDump};
 pub use near_runtime_fees::RuntimeFeesConfig;
 pub use near_vm_logic::types::*;
 pub use near_vm_logic::VMConfig;
@@ -49,6 +105,52 @@ macro_rules! testing_env {
     };
 }
 
+/// Panics with the message unless the condition holds, like `assert!`, but without formatting the
+/// message: the check is a branch and a call of `env::panic` with the bytes of the message, which
+/// is cheaper in gas and code size. The message is a `&str` or a `String`; without it the panic
+/// message is `require! assertion failed`.
+#[macro_export]
+macro_rules! require {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::env::panic(b"require! assertion failed")
+        }
+    };
+    ($cond:expr, $message:expr $(,)?) => {
+        if !$cond {
+            $crate::env::panic(::core::convert::AsRef::<str>::as_ref(&$message).as_bytes())
+        }
+    };
+}
+
+/// Logs the message with `env::log`. With the arguments the message is formatted like `format!`,
+/// without them the `&str` is logged as is, without the formatting machinery:
+///
+/// ```ignore
+/// log!("Started");
+/// log!("Transferred {} to {}", amount, receiver_id);
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($message:expr $(,)?) => {
+        $crate::env::log(::core::convert::AsRef::<str>::as_ref(&$message).as_bytes())
+    };
+    ($($arg:tt)*) => {
+        $crate::env::log(format!($($arg)*).as_bytes())
+    };
+}
+
+/// `log!` that is only compiled into the debug builds of the contract. In the release builds the
+/// message is neither formatted nor logged.
+#[macro_export]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        if cfg!(debug_assertions) {
+            $crate::log!($($arg)*);
+        }
+    };
+}
+
 pub use environment::blockchain_interface::BlockchainInterface;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -69,8 +171,16 @@ pub use bs58;
 #[doc(hidden)]
 pub use serde;
 
+#[cfg(feature = "json")]
 #[doc(hidden)]
 pub use serde_json;
 
 #[doc(hidden)]
 pub use wee_alloc;
+
+/// The `global-allocator` feature sets `wee_alloc` as the allocator of the contract, which is much
+/// smaller than the default one and fits the short-lived allocations of a contract call. Without
+/// the feature the contract declares its own `#[global_allocator]`.
+#[cfg(all(target_arch = "wasm32", feature = "global-allocator"))]
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc<'_> = wee_alloc::WeeAlloc::INIT;
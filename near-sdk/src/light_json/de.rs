@@ -0,0 +1,387 @@
+use std::borrow::Cow;
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use super::{Error, Result};
+
+/// Deserializes the value from the JSON bytes. Only whitespace may follow the value.
+pub fn from_slice<'de, T: de::Deserialize<'de>>(input: &'de [u8]) -> Result<T> {
+    let mut de = Deserializer::new(input);
+    let value = T::deserialize(&mut de)?;
+    if de.peek().is_some() {
+        return Err(Error::new("Trailing characters after the JSON value"));
+    }
+    Ok(value)
+}
+
+/// Deserializes the values from JSON bytes, borrowing the strings without escapes.
+pub struct Deserializer<'de> {
+    input: &'de [u8],
+    pos: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(input: &'de [u8]) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    /// Skips the whitespace and returns the next byte without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(b' ') | Some(b'\n') | Some(b'\r') | Some(b'\t') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(Error::new("Unexpected character"))
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &[u8]) -> Result<()> {
+        if self.input[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(Error::new("Invalid literal"))
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<u8> {
+        let byte =
+            *self.input.get(self.pos).ok_or_else(|| Error::new("Unexpected end of the input"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = (self.next_byte()? as char)
+                .to_digit(16)
+                .ok_or_else(|| Error::new("Invalid unicode escape"))?;
+            value = value * 16 + digit;
+        }
+        Ok(value)
+    }
+
+    /// Parses a string, borrowing it from the input if it has no escapes.
+    fn parse_str(&mut self) -> Result<Cow<'de, str>> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        let mut owned: Option<Vec<u8>> = None;
+        loop {
+            let byte = self.next_byte()?;
+            match byte {
+                b'"' => {
+                    return match owned {
+                        None => std::str::from_utf8(&self.input[start..self.pos - 1])
+                            .map(Cow::Borrowed)
+                            .map_err(|_| Error::new("Invalid UTF-8 in a string")),
+                        Some(buf) => String::from_utf8(buf)
+                            .map(Cow::Owned)
+                            .map_err(|_| Error::new("Invalid UTF-8 in a string")),
+                    };
+                }
+                b'\\' => {
+                    let buf = owned.get_or_insert_with(|| self.input[start..self.pos - 1].to_vec());
+                    let unescaped = match self.input.get(self.pos) {
+                        Some(b'"') => b'"',
+                        Some(b'\\') => b'\\',
+                        Some(b'/') => b'/',
+                        Some(b'b') => 0x08,
+                        Some(b'f') => 0x0c,
+                        Some(b'n') => b'\n',
+                        Some(b'r') => b'\r',
+                        Some(b't') => b'\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let mut code = self.parse_hex4()?;
+                            if (0xD800..0xDC00).contains(&code) {
+                                self.expect_literal(b"\\u")?;
+                                let low = self.parse_hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(Error::new("Invalid unicode surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            let c = std::char::from_u32(code)
+                                .ok_or_else(|| Error::new("Invalid unicode escape"))?;
+                            let mut utf8 = [0u8; 4];
+                            buf.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(Error::new("Invalid escape")),
+                    };
+                    buf.push(unescaped);
+                    self.pos += 1;
+                }
+                0..=0x1f => return Err(Error::new("Control character in a string")),
+                _ => {
+                    if let Some(buf) = owned.as_mut() {
+                        buf.push(byte);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parses an integer. The floating point numbers are not supported.
+    fn parse_number<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value> {
+        let negative = self.input.get(self.pos) == Some(&b'-');
+        if negative {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        let mut value: u64 = 0;
+        while let Some(&digit @ b'0'..=b'9') = self.input.get(self.pos) {
+            value = value
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(u64::from(digit - b'0')))
+                .ok_or_else(|| Error::new("The number is out of range"))?;
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(Error::new("Invalid number"));
+        }
+        if let Some(b'.') | Some(b'e') | Some(b'E') = self.input.get(self.pos) {
+            return Err(Error::new("Floating point numbers are not supported"));
+        }
+        if !negative {
+            visitor.visit_u64(value)
+        } else if value <= 1 << 63 {
+            visitor.visit_i64(0u64.wrapping_sub(value) as i64)
+        } else {
+            Err(Error::new("The number is out of range"))
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.peek() {
+            Some(b'n') => {
+                self.expect_literal(b"null")?;
+                visitor.visit_unit()
+            }
+            Some(b't') => {
+                self.expect_literal(b"true")?;
+                visitor.visit_bool(true)
+            }
+            Some(b'f') => {
+                self.expect_literal(b"false")?;
+                visitor.visit_bool(false)
+            }
+            Some(b'"') => match self.parse_str()? {
+                Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+                Cow::Owned(s) => visitor.visit_string(s),
+            },
+            Some(b'[') => {
+                self.pos += 1;
+                let value = visitor.visit_seq(SeqAccess { de: self, first: true })?;
+                self.expect(b']')?;
+                Ok(value)
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let value = visitor.visit_map(MapAccess { de: self, first: true })?;
+                self.expect(b'}')?;
+                Ok(value)
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.parse_number(visitor),
+            Some(_) => Err(Error::new("Unexpected character")),
+            None => Err(Error::new("Unexpected end of the input")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.peek() == Some(b'n') {
+            self.expect_literal(b"null")?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.peek() {
+            Some(b'"') => visitor.visit_enum(self.parse_str()?.into_owned().into_deserializer()),
+            Some(b'{') => {
+                self.pos += 1;
+                let value = visitor.visit_enum(EnumAccess { de: self })?;
+                self.expect(b'}')?;
+                Ok(value)
+            }
+            _ => Err(Error::new("Expected an enum")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for SeqAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.de.peek() == Some(b']') {
+            return Ok(None);
+        }
+        if !self.first {
+            self.de.expect(b',')?;
+        }
+        self.first = false;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+struct MapAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for MapAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.de.peek() == Some(b'}') {
+            return Ok(None);
+        }
+        if !self.first {
+            self.de.expect(b',')?;
+        }
+        self.first = false;
+        seed.deserialize(MapKey { de: &mut *self.de }).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        self.de.expect(b':')?;
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct EnumAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant = seed.deserialize(MapKey { de: &mut *self.de })?;
+        self.de.expect(b':')?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for EnumAccess<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self.de, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_any(self.de, visitor)
+    }
+}
+
+/// Deserializes the keys of the objects, which are strings that may hold the integer keys.
+struct MapKey<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+macro_rules! deserialize_integer_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                let key = self.de.parse_str()?;
+                visitor.$visit(key.parse().map_err(|_| Error::new("Invalid integer key"))?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for MapKey<'a, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.de.parse_str()? {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
+    }
+
+    deserialize_integer_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self.de.parse_str()?.into_owned().into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
@@ -0,0 +1,134 @@
+//! A small JSON serializer and deserializer for the arguments and the results of the methods,
+//! enabled with the `light-json` feature. It supports the subset of JSON that the contracts
+//! exchange: the numbers are limited to `u64` and `i64`, the larger integers are expected to be
+//! passed as strings (see `json_types`), the floating point numbers are not supported, and the
+//! output is always compact. In exchange it leaves out the number formatting of `serde_json`, which
+//! makes it smaller and spends less gas on parsing.
+//!
+//! With the feature on, the wrappers that `#[near_bindgen]` generates use it instead of
+//! `serde_json`.
+mod de;
+mod ser;
+
+pub use de::{from_slice, Deserializer};
+pub use ser::{to_vec, Serializer};
+
+use std::fmt;
+
+/// An error of the serialization or the deserialization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Error {
+    fn new(msg: &str) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Kind {
+        Empty,
+        Amount(u64),
+        Pair(u8, String),
+        Named { id: i32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Args {
+        account_id: String,
+        amount: u64,
+        delta: i64,
+        memo: Option<String>,
+        flags: Vec<bool>,
+        kinds: Vec<Kind>,
+        balances: HashMap<u32, u64>,
+        pair: (u8, char),
+    }
+
+    fn args() -> Args {
+        let mut balances = HashMap::new();
+        balances.insert(7, 100);
+        Args {
+            account_id: "alice.near \"quoted\" \n \u{1F600}".to_string(),
+            amount: u64::MAX,
+            delta: -5,
+            memo: None,
+            flags: vec![true, false],
+            kinds: vec![
+                Kind::Empty,
+                Kind::Amount(3),
+                Kind::Pair(1, "a".to_string()),
+                Kind::Named { id: -1 },
+            ],
+            balances,
+            pair: (2, 'x'),
+        }
+    }
+
+    #[test]
+    fn test_same_as_serde_json() {
+        let args = args();
+        let data = to_vec(&args).unwrap();
+        assert_eq!(data, serde_json::to_vec(&args).unwrap());
+        assert_eq!(from_slice::<Args>(&data).unwrap(), args);
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let data = br#" { "account_id" : "a\u0062\ud83d\ude00\/" , "amount": 1, "delta": 2,
+            "memo": "m", "flags": [], "kinds": ["Empty", {"Amount": 5}],
+            "balances": {"1": 2}, "pair": [0, "y"], "unknown": {"a": [null]} } "#;
+        let args: Args = from_slice(data).unwrap();
+        assert_eq!(args.account_id, "ab\u{1F600}/");
+        assert_eq!(args.memo, Some("m".to_string()));
+        assert_eq!(args.kinds, vec![Kind::Empty, Kind::Amount(5)]);
+        assert_eq!(args.balances.get(&1), Some(&2));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(from_slice::<u8>(b"256").is_err());
+        assert!(from_slice::<u64>(b"-1").is_err());
+        assert!(from_slice::<u64>(b"18446744073709551616").is_err());
+        assert_eq!(from_slice::<i64>(b"-9223372036854775808"), Ok(i64::MIN));
+        assert!(from_slice::<f64>(b"1.5").is_err());
+        assert!(to_vec(&1.5).is_err());
+        assert!(from_slice::<Vec<u8>>(b"[1,]").is_err());
+        assert!(from_slice::<Vec<u8>>(b"[1] 2").is_err());
+        assert!(from_slice::<String>(b"\"a").is_err());
+        assert!(from_slice::<String>(b"\"\\x\"").is_err());
+        assert!(from_slice::<bool>(b"tru").is_err());
+        let mut map = HashMap::new();
+        map.insert(vec![1u8], 1u8);
+        assert!(to_vec(&map).is_err());
+    }
+}
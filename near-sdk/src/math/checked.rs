@@ -0,0 +1,69 @@
+use crate::env;
+
+/// The checked arithmetic that panics with the message on overflow, for the guards like
+/// `balance.checked_add_or_panic(amount, "Balance overflow")` on `Balance`, `Gas` and the other
+/// unsigned integers. The message is passed to `env::panic` as is, without formatting.
+pub trait CheckedOrPanic: Sized {
+    fn checked_add_or_panic(self, rhs: Self, message: &str) -> Self;
+    fn checked_sub_or_panic(self, rhs: Self, message: &str) -> Self;
+    fn checked_mul_or_panic(self, rhs: Self, message: &str) -> Self;
+    /// Panics with the message if `rhs` is zero.
+    fn checked_div_or_panic(self, rhs: Self, message: &str) -> Self;
+}
+
+macro_rules! impl_checked_or_panic {
+    ($($ty: ty),*) => {
+        $(
+            impl CheckedOrPanic for $ty {
+                fn checked_add_or_panic(self, rhs: Self, message: &str) -> Self {
+                    self.checked_add(rhs).unwrap_or_else(|| env::panic(message.as_bytes()))
+                }
+
+                fn checked_sub_or_panic(self, rhs: Self, message: &str) -> Self {
+                    self.checked_sub(rhs).unwrap_or_else(|| env::panic(message.as_bytes()))
+                }
+
+                fn checked_mul_or_panic(self, rhs: Self, message: &str) -> Self {
+                    self.checked_mul(rhs).unwrap_or_else(|| env::panic(message.as_bytes()))
+                }
+
+                fn checked_div_or_panic(self, rhs: Self, message: &str) -> Self {
+                    self.checked_div(rhs).unwrap_or_else(|| env::panic(message.as_bytes()))
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_or_panic!(u8, u16, u32, u64, u128);
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Balance, Gas};
+
+    #[test]
+    fn test_checked() {
+        let balance: Balance = 10;
+        assert_eq!(balance.checked_add_or_panic(5, "Balance overflow"), 15);
+        assert_eq!(balance.checked_sub_or_panic(10, "Not enough balance"), 0);
+        let gas: Gas = 5_000_000_000_000;
+        assert_eq!(gas.checked_mul_or_panic(2, "Gas overflow"), 10_000_000_000_000);
+        assert_eq!(gas.checked_div_or_panic(5, "Zero"), 1_000_000_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Balance overflow")]
+    fn test_add_overflow() {
+        crate::test_utils::test_env::setup();
+        Balance::MAX.checked_add_or_panic(1, "Balance overflow");
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough balance")]
+    fn test_sub_underflow() {
+        crate::test_utils::test_env::setup();
+        1u128.checked_sub_or_panic(2, "Not enough balance");
+    }
+}
@@ -0,0 +1,234 @@
+use super::{mul_div, Rounding};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// An unsigned fixed-point number with `DECIMALS` digits after the point, stored as the `u128`
+/// number of the smallest units, e.g. `1.5` with 2 decimals is `150`. It's serialized with JSON
+/// as a decimal string, like `"1.5"`, and with Borsh as the `u128`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedU128<const DECIMALS: u8>(u128);
+
+/// The fixed-point number with the 24 decimals of the NEAR amounts.
+pub type Decimal = FixedU128<24>;
+
+impl<const DECIMALS: u8> FixedU128<DECIMALS> {
+    /// The number of the smallest units in one.
+    pub const SCALE: u128 = 10u128.pow(DECIMALS as u32);
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(Self::SCALE);
+    pub const MAX: Self = Self(u128::MAX);
+
+    /// The number with `raw` smallest units.
+    pub const fn from_raw(raw: u128) -> Self {
+        Self(raw)
+    }
+
+    pub const fn raw(self) -> u128 {
+        self.0
+    }
+
+    /// Returns `None` if the integer doesn't fit.
+    pub fn from_integer(integer: u128) -> Option<Self> {
+        integer.checked_mul(Self::SCALE).map(Self)
+    }
+
+    /// `numerator / denominator`, e.g. a fee of 3 / 1000. Returns `None` if `denominator` is zero
+    /// or the result doesn't fit.
+    pub fn from_ratio(numerator: u128, denominator: u128, rounding: Rounding) -> Option<Self> {
+        mul_div(numerator, Self::SCALE, denominator, rounding).map(Self)
+    }
+
+    /// The integer part, or the integer the number is rounded to.
+    pub fn to_integer(self, rounding: Rounding) -> u128 {
+        // Can't overflow, the result is at most `self.0`.
+        mul_div(self.0, 1, Self::SCALE, rounding).unwrap_or_else(|| unreachable!())
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// The product rounded to `DECIMALS` digits.
+    pub fn checked_mul(self, other: Self, rounding: Rounding) -> Option<Self> {
+        mul_div(self.0, other.0, Self::SCALE, rounding).map(Self)
+    }
+
+    /// The quotient rounded to `DECIMALS` digits. Returns `None` if `other` is zero.
+    pub fn checked_div(self, other: Self, rounding: Rounding) -> Option<Self> {
+        mul_div(self.0, Self::SCALE, other.0, rounding).map(Self)
+    }
+
+    /// `amount * self` rounded to an integer, e.g. the fee of a transfer of `amount` yoctoNEAR.
+    pub fn checked_mul_int(self, amount: u128, rounding: Rounding) -> Option<u128> {
+        mul_div(amount, self.0, Self::SCALE, rounding)
+    }
+
+    /// `amount / self` rounded to an integer. Returns `None` if `self` is zero.
+    pub fn checked_div_int(self, amount: u128, rounding: Rounding) -> Option<u128> {
+        mul_div(amount, Self::SCALE, self.0, rounding)
+    }
+}
+
+impl<const DECIMALS: u8> fmt::Display for FixedU128<DECIMALS> {
+    /// Writes the number without the trailing zeros of the fraction, e.g. `1.5` or `2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let integer = self.0 / Self::SCALE;
+        let fraction = self.0 % Self::SCALE;
+        if fraction == 0 {
+            return write!(f, "{}", integer);
+        }
+        let fraction = format!("{:0width$}", fraction, width = DECIMALS as usize);
+        write!(f, "{}.{}", integer, fraction.trim_end_matches('0'))
+    }
+}
+
+/// The string is not a decimal number, has more digits after the point than the type, or doesn't
+/// fit into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError;
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid decimal number")
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl<const DECIMALS: u8> FromStr for FixedU128<DECIMALS> {
+    type Err = ParseDecimalError;
+
+    /// Parses `12`, `12.5` or `0.125`, without a sign or an exponent.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = match s.find('.') {
+            Some(point) => (&s[..point], &s[point + 1..]),
+            None => (s, ""),
+        };
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty()
+            || !is_digits(integer)
+            || !is_digits(fraction)
+            || (s.contains('.') && fraction.is_empty())
+            || fraction.len() > DECIMALS as usize
+        {
+            return Err(ParseDecimalError);
+        }
+        let integer: u128 = integer.parse().map_err(|_| ParseDecimalError)?;
+        let fraction = if fraction.is_empty() {
+            0
+        } else {
+            let digits: u128 = fraction.parse().map_err(|_| ParseDecimalError)?;
+            digits * 10u128.pow(DECIMALS as u32 - fraction.len() as u32)
+        };
+        integer
+            .checked_mul(Self::SCALE)
+            .and_then(|raw| raw.checked_add(fraction))
+            .map(Self)
+            .ok_or(ParseDecimalError)
+    }
+}
+
+impl<const DECIMALS: u8> Serialize for FixedU128<DECIMALS> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de, const DECIMALS: u8> Deserialize<'de> for FixedU128<DECIMALS> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(|_| de::Error::custom(format!("Invalid decimal number: {}", s)))
+    }
+}
+
+impl<const DECIMALS: u8> BorshSerialize for FixedU128<DECIMALS> {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        BorshSerialize::serialize(&self.0, writer)
+    }
+}
+
+impl<const DECIMALS: u8> BorshDeserialize for FixedU128<DECIMALS> {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        <u128 as BorshDeserialize>::deserialize(buf).map(Self)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Percent = FixedU128<2>;
+
+    #[test]
+    fn test_parse_and_display() {
+        let price: Decimal = "1.25".parse().unwrap();
+        assert_eq!(price.raw(), 125 * 10u128.pow(22));
+        assert_eq!(price.to_string(), "1.25");
+        assert_eq!(Decimal::from_integer(7).unwrap().to_string(), "7");
+        assert_eq!(Decimal::from_raw(1).to_string(), "0.000000000000000000000001");
+        assert_eq!("0.5".parse::<Percent>(), Ok(Percent::from_raw(50)));
+        for invalid in &["", ".5", "1.", "1.234", "-1", "1e3", "1.2.3", " 1"] {
+            assert_eq!(invalid.parse::<Percent>(), Err(ParseDecimalError), "{}", invalid);
+        }
+        assert_eq!(Percent::MAX.to_string().parse::<Percent>(), Ok(Percent::MAX));
+        assert!("3402823669209384634633746074317682114.56".parse::<Percent>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let fee = Decimal::from_ratio(3, 1000, Rounding::Down).unwrap();
+        assert_eq!(fee.to_string(), "0.003");
+        let amount = 10u128.pow(30);
+        assert_eq!(fee.checked_mul_int(amount, Rounding::Down), Some(3 * 10u128.pow(27)));
+        assert_eq!(fee.checked_mul_int(999, Rounding::Down), Some(2));
+        assert_eq!(fee.checked_mul_int(999, Rounding::Up), Some(3));
+        let third = Percent::ONE.checked_div(Percent::from_integer(3).unwrap(), Rounding::Down);
+        assert_eq!(third, Some(Percent::from_raw(33)));
+        let two_thirds = Percent::from_integer(2)
+            .unwrap()
+            .checked_div(Percent::from_integer(3).unwrap(), Rounding::HalfUp);
+        assert_eq!(two_thirds, Some(Percent::from_raw(67)));
+        assert_eq!(Percent::ONE.checked_div(Percent::ZERO, Rounding::Down), None);
+        let half: Percent = "0.5".parse().unwrap();
+        assert_eq!(half.checked_mul(half, Rounding::Down), Some(Percent::from_raw(25)));
+        assert_eq!(
+            "0.05".parse::<Percent>().unwrap().checked_mul(half, Rounding::Up),
+            Some(Percent::from_raw(3))
+        );
+        assert_eq!(half.checked_div_int(10, Rounding::Down), Some(20));
+        assert_eq!("2.5".parse::<Percent>().unwrap().to_integer(Rounding::HalfUp), 3);
+        assert_eq!("2.5".parse::<Percent>().unwrap().to_integer(Rounding::Down), 2);
+        assert_eq!(Percent::MAX.checked_add(Percent::from_raw(1)), None);
+        assert_eq!(Decimal::from_integer(u128::MAX), None);
+        assert_eq!(
+            Decimal::MAX.checked_mul(Decimal::from_integer(2).unwrap(), Rounding::Down),
+            None
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let price: Decimal = "1.25".parse().unwrap();
+        let json = serde_json::to_string(&price).unwrap();
+        assert_eq!(json, "\"1.25\"");
+        assert_eq!(serde_json::from_str::<Decimal>(&json).unwrap(), price);
+        assert!(serde_json::from_str::<Decimal>("1.25").is_err());
+        let borsh = price.try_to_vec().unwrap();
+        assert_eq!(borsh, price.raw().try_to_vec().unwrap());
+        assert_eq!(Decimal::try_from_slice(&borsh).unwrap(), price);
+    }
+}
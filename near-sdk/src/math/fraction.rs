@@ -0,0 +1,94 @@
+use super::{mul_div, Rounding};
+use crate::{env, ContractError};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The fraction `num / den` of an amount, e.g. a royalty of 250 / 10000 or the elapsed part of a
+/// vesting period. The amount is multiplied by `num` in 256 bits before it's divided by `den`, so
+/// `amount * num / den` doesn't overflow unless the result does. It's serialized with JSON as
+/// `{"num":"250","den":"10000"}`.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub struct Fraction {
+    #[serde(with = "crate::json_types::assemblyscript::string_number")]
+    pub num: u128,
+    #[serde(with = "crate::json_types::assemblyscript::string_number")]
+    pub den: u128,
+}
+
+impl Fraction {
+    pub const ZERO: Fraction = Fraction::new(0, 1);
+    pub const ONE: Fraction = Fraction::new(1, 1);
+
+    pub const fn new(num: u128, den: u128) -> Self {
+        Self { num, den }
+    }
+
+    /// Whether the fraction is a valid share of an amount: the denominator is not zero and the
+    /// fraction is at most one.
+    pub fn is_share(&self) -> bool {
+        self.den != 0 && self.num <= self.den
+    }
+
+    /// `amount * num / den` rounded as requested. Returns `None` if `den` is zero or the result
+    /// doesn't fit into `u128`, which can only happen if the fraction is more than one.
+    pub fn checked_mul_int(&self, amount: u128, rounding: Rounding) -> Option<u128> {
+        mul_div(amount, self.num, self.den, rounding)
+    }
+
+    /// `amount * num / den` rounded as requested. Panics with `OVERFLOW` if `den` is zero or the
+    /// result doesn't fit into `u128`.
+    pub fn mul_int(&self, amount: u128, rounding: Rounding) -> u128 {
+        self.checked_mul_int(amount, rounding)
+            .unwrap_or_else(|| env::panic_err(ContractError::OVERFLOW, "Arithmetic overflow"))
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_int() {
+        let royalty = Fraction::new(250, 10_000);
+        assert!(royalty.is_share());
+        assert_eq!(royalty.mul_int(10_000, Rounding::Down), 250);
+        assert_eq!(royalty.mul_int(39, Rounding::Down), 0);
+        assert_eq!(royalty.mul_int(39, Rounding::Up), 1);
+        assert_eq!(royalty.mul_int(u128::MAX, Rounding::Down), u128::MAX / 40);
+        let elapsed = Fraction::new(u64::MAX as u128 - 1, u64::MAX as u128);
+        assert_eq!(elapsed.mul_int(u128::MAX, Rounding::Down), u128::MAX - (u64::MAX as u128 + 2));
+        assert_eq!(Fraction::ONE.mul_int(u128::MAX, Rounding::Up), u128::MAX);
+        assert_eq!(Fraction::ZERO.mul_int(u128::MAX, Rounding::Up), 0);
+        assert!(!Fraction::new(3, 2).is_share());
+        assert_eq!(Fraction::new(3, 2).checked_mul_int(u128::MAX, Rounding::Down), None);
+        assert!(!Fraction::new(0, 0).is_share());
+        assert_eq!(Fraction::new(0, 0).checked_mul_int(1, Rounding::Down), None);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Arithmetic overflow")]
+    fn test_mul_int_zero_denominator() {
+        crate::test_utils::test_env::setup();
+        Fraction::new(1, 0).mul_int(1, Rounding::Down);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let fraction = Fraction::new(250, 10_000);
+        let json = serde_json::to_string(&fraction).unwrap();
+        assert_eq!(json, r#"{"num":"250","den":"10000"}"#);
+        assert_eq!(serde_json::from_str::<Fraction>(&json).unwrap(), fraction);
+        assert_eq!(fraction.to_string(), "250/10000");
+    }
+}
@@ -0,0 +1,127 @@
+//! Token math without the hand-rolled scaling. The amounts of the tokens are `u128`, and the
+//! products of two of them, like `amount * price`, don't fit into `u128` before they are divided
+//! back. `mul_div` keeps the product in 256 bits, and `Decimal` and `Fraction` are built on it.
+//! `CheckedOrPanic` turns the plain checked arithmetic into one call with the panic message:
+//!
+//! ```ignore
+//! let price: Decimal = "1.25".parse().unwrap();
+//! let cost = price.checked_mul_int(amount, Rounding::Up).expect("Overflow");
+//! let royalty = Fraction::new(250, 10_000).mul_int(cost, Rounding::Down);
+//! self.total_fees = self.total_fees.checked_add_or_panic(royalty, "Total fees overflow");
+//! ```
+
+mod checked;
+mod decimal;
+mod fraction;
+
+pub use checked::CheckedOrPanic;
+pub use decimal::{Decimal, FixedU128, ParseDecimalError};
+pub use fraction::Fraction;
+
+/// How the result of a division is rounded to an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero, e.g. for the amounts the contract pays out.
+    Down,
+    /// Away from zero, e.g. for the amounts the contract charges.
+    Up,
+    /// To the nearest integer, with the halves rounded up.
+    HalfUp,
+}
+
+/// `a * b / c` with the product in 256 bits, rounded as requested. Returns `None` if `c` is zero
+/// or the result doesn't fit into `u128`.
+pub fn mul_div(a: u128, b: u128, c: u128, rounding: Rounding) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+    let (quotient, remainder) = match a.checked_mul(b) {
+        Some(product) => (product / c, product % c),
+        None => {
+            let (high, low) = mul_wide(a, b);
+            div_wide(high, low, c)?
+        }
+    };
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder > 0,
+        Rounding::HalfUp => remainder >= c - remainder,
+    };
+    if round_up {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// The full product of two `u128` as the high and the low halves.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_low, a_high) = (a & MASK, a >> 64);
+    let (b_low, b_high) = (b & MASK, b >> 64);
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+    let middle = (low_low >> 64) + (low_high & MASK) + (high_low & MASK);
+    let low = (low_low & MASK) | (middle << 64);
+    let high = high_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Divides the 256-bit number by `divisor`, returning the quotient and the remainder, or `None` if
+/// the quotient doesn't fit into `u128`.
+fn div_wide(high: u128, low: u128, divisor: u128) -> Option<(u128, u128)> {
+    if high >= divisor {
+        return None;
+    }
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1 << bit;
+        }
+    }
+    Some((quotient, remainder))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::quickcheck;
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(mul_div(7, 3, 2, Rounding::Down), Some(10));
+        assert_eq!(mul_div(7, 3, 2, Rounding::Up), Some(11));
+        assert_eq!(mul_div(7, 3, 2, Rounding::HalfUp), Some(11));
+        assert_eq!(mul_div(10, 1, 3, Rounding::HalfUp), Some(3));
+        assert_eq!(mul_div(6, 6, 6, Rounding::Up), Some(6));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Up), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 3, 4, Rounding::Down), Some(u128::MAX / 4 * 3 + 2));
+        assert_eq!(mul_div(u128::MAX, 2, 1, Rounding::Down), None);
+        assert_eq!(mul_div(u128::MAX, 1, 1, Rounding::Up), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 3, 3, Rounding::Up), Some(u128::MAX));
+        let yocto = 10u128.pow(24);
+        assert_eq!(
+            mul_div(1_000_000 * yocto, 1_000_000 * yocto, yocto, Rounding::Down),
+            Some(1_000_000_000_000 * yocto)
+        );
+    }
+
+    quickcheck! {
+        fn qc_mul_div_inverse(a: u64, b: u64, c: u64) -> bool {
+            let (a, b, c) = (a as u128 * u64::MAX as u128, b as u128 + 1, c as u128 + 1);
+            match mul_div(a, b, c, Rounding::Down) {
+                Some(q) => mul_wide(q, c) <= mul_wide(a, b)
+                    && mul_div(a, b, c, Rounding::Up) == Some(q + (mul_wide(q, c) != mul_wide(a, b)) as u128),
+                None => mul_wide(a, b).0 >= c,
+            }
+        }
+    }
+}
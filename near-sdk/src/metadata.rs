@@ -1,4 +1,13 @@
 use borsh::{schema::BorshSchemaContainer, BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[cfg(feature = "json")]
+mod json_schema;
+#[cfg(feature = "json")]
+mod openrpc;
+mod typescript;
+mod wit;
+pub use typescript::TypeScriptClient;
+
 /// Version of the metadata format.
 const METADATA_SEMVER: [u32; 3] = [0, 1, 0];
 
@@ -34,3 +43,23 @@ pub struct MethodMetadata {
     /// Schema of the return type.
     pub result: Option<BorshSchemaContainer>,
 }
+
+/// The name of the type declared in Rust that is valid in the generated code, e.g. `Pair_u64` for
+/// `Pair<u64>`.
+fn definition_name(declaration: &str) -> String {
+    let name: String =
+        declaration.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    name.split('_').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("_")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition_name() {
+        assert_eq!(definition_name("Pair<u64, Vec<u8>>"), "Pair_u64_Vec_u8");
+        assert_eq!(definition_name("Status"), "Status");
+    }
+}
@@ -0,0 +1,353 @@
+//! JSON Schemas of the arguments and the results of the methods, generated from the Borsh schemas
+//! in the metadata of the contract.
+use super::{definition_name, Metadata};
+use borsh::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// The JSON Schema draft of the generated schemas.
+const JSON_SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+impl Metadata {
+    /// Generates the JSON ABI of the contract: the methods with the JSON Schemas of their arguments
+    /// and their results. The schemas describe the JSON that the wrappers of `#[near_bindgen]`
+    /// accept and return. The structs and the enums of the contract are in `definitions` and are
+    /// referenced with `#/definitions/<name>`.
+    pub fn json_abi(&self) -> Value {
+        let mut schemas = JsonSchemas::default();
+        let methods: Vec<Value> = self
+            .methods
+            .iter()
+            .map(|method| {
+                let args = method.args.as_ref().map(|args| schemas.args_schema(args));
+                let result = method.result.as_ref().map(|result| schemas.container_schema(result));
+                json!({
+                    "name": method.name,
+                    "kind": if method.is_view && !method.is_init { "view" } else { "call" },
+                    "is_init": method.is_init,
+                    "args": args,
+                    "result": result,
+                })
+            })
+            .collect();
+        let [major, minor, patch] = self.version;
+        json!({
+            "$schema": JSON_SCHEMA_DRAFT,
+            "metadata_version": format!("{}.{}.{}", major, minor, patch),
+            "methods": methods,
+            "definitions": schemas.definitions,
+        })
+    }
+}
+
+/// The JSON Schemas of the JSON representation of the Borsh schemas. The structs and the enums of
+/// the contract become definitions, the rest is written inline.
+pub(crate) struct JsonSchemas {
+    /// The schemas of the structs and the enums by their names.
+    pub(crate) definitions: Map<String, Value>,
+    /// The path of the definitions in the document, prepended to their names in `$ref`.
+    ref_prefix: &'static str,
+}
+
+impl Default for JsonSchemas {
+    fn default() -> Self {
+        Self::with_ref_prefix("#/definitions/")
+    }
+}
+
+impl JsonSchemas {
+    pub(crate) fn with_ref_prefix(ref_prefix: &'static str) -> Self {
+        Self { definitions: Map::new(), ref_prefix }
+    }
+
+    /// The schema of the arguments, written inline from the fields of the `Input` struct.
+    pub(crate) fn args_schema(&mut self, container: &BorshSchemaContainer) -> Value {
+        match container.definitions.get(&container.declaration) {
+            Some(Definition::Struct { fields }) => {
+                self.fields_schema(fields, &container.definitions)
+            }
+            _ => self.container_schema(container),
+        }
+    }
+
+    pub(crate) fn container_schema(&mut self, container: &BorshSchemaContainer) -> Value {
+        self.schema(&container.declaration, &container.definitions)
+    }
+
+    pub(crate) fn schema(
+        &mut self,
+        declaration: &str,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> Value {
+        match declaration {
+            "bool" => json!({ "type": "boolean" }),
+            "u8" => json!({ "type": "integer", "minimum": 0, "maximum": u8::MAX }),
+            "u16" => json!({ "type": "integer", "minimum": 0, "maximum": u16::MAX }),
+            "u32" => json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX }),
+            "u64" | "u128" => json!({ "type": "integer", "minimum": 0 }),
+            "i8" => json!({ "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX }),
+            "i16" => json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX }),
+            "i32" => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+            "i64" | "i128" => json!({ "type": "integer" }),
+            "f32" | "f64" => json!({ "type": "number" }),
+            "string" => json!({ "type": "string" }),
+            "char" => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+            "nil" => json!({ "type": "null" }),
+            _ => match definitions.get(declaration) {
+                Some(definition) => self.definition_schema(declaration, definition, definitions),
+                None => json!({}),
+            },
+        }
+    }
+
+    fn definition_schema(
+        &mut self,
+        declaration: &str,
+        definition: &Definition,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> Value {
+        match definition {
+            // The elements of a map are the `Tuple<K, V>` of its entries.
+            Definition::Sequence { elements } if declaration.starts_with("HashMap<") => {
+                match definitions.get(elements) {
+                    Some(Definition::Tuple { elements }) if elements.len() == 2 => json!({
+                        "type": "object",
+                        "additionalProperties": self.schema(&elements[1], definitions),
+                    }),
+                    _ => json!({ "type": "object" }),
+                }
+            }
+            Definition::Sequence { elements } => {
+                json!({ "type": "array", "items": self.schema(elements, definitions) })
+            }
+            Definition::Array { length, elements } => json!({
+                "type": "array",
+                "items": self.schema(elements, definitions),
+                "minItems": length,
+                "maxItems": length,
+            }),
+            Definition::Tuple { elements } => self.tuple_schema(elements, definitions),
+            Definition::Enum { variants } if declaration.starts_with("Option<") => json!({
+                "anyOf": [self.schema(&variants[1].1, definitions), { "type": "null" }],
+            }),
+            Definition::Enum { variants } if declaration.starts_with("Result<") => {
+                let variants: Vec<Value> = variants
+                    .iter()
+                    .map(|(name, declaration)| {
+                        tagged_schema(name, self.schema(declaration, definitions))
+                    })
+                    .collect();
+                json!({ "oneOf": variants })
+            }
+            Definition::Enum { .. } | Definition::Struct { .. } => {
+                self.definition_ref(declaration, definition, definitions)
+            }
+        }
+    }
+
+    fn definition_ref(
+        &mut self,
+        declaration: &str,
+        definition: &Definition,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> Value {
+        let name = definition_name(declaration);
+        if !self.definitions.contains_key(&name) {
+            // Reserve the name for the recursive types.
+            self.definitions.insert(name.clone(), Value::Null);
+            let schema = match definition {
+                Definition::Struct { fields } => self.fields_schema(fields, definitions),
+                // Serde writes the unit variants as strings and the others as `{ "Variant": value }`.
+                Definition::Enum { variants } => {
+                    let variants: Vec<Value> = variants
+                        .iter()
+                        .map(|(variant, declaration)| match definitions.get(declaration) {
+                            Some(Definition::Struct { fields: Fields::Empty }) => {
+                                json!({ "const": variant })
+                            }
+                            Some(Definition::Struct { fields }) => {
+                                tagged_schema(variant, self.fields_schema(fields, definitions))
+                            }
+                            _ => tagged_schema(variant, self.schema(declaration, definitions)),
+                        })
+                        .collect();
+                    json!({ "oneOf": variants })
+                }
+                _ => unreachable!(),
+            };
+            self.definitions.insert(name.clone(), schema);
+        }
+        json!({ "$ref": format!("{}{}", self.ref_prefix, name) })
+    }
+
+    fn fields_schema(
+        &mut self,
+        fields: &Fields,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> Value {
+        match fields {
+            Fields::NamedFields(fields) => {
+                let mut properties = Map::new();
+                let mut required = vec![];
+                for (name, declaration) in fields {
+                    properties.insert(name.clone(), self.schema(declaration, definitions));
+                    // Serde reads the missing optional fields as `None`.
+                    if !declaration.starts_with("Option<") {
+                        required.push(name.clone());
+                    }
+                }
+                json!({ "type": "object", "properties": properties, "required": required })
+            }
+            Fields::UnnamedFields(elements) if elements.len() == 1 => {
+                self.schema(&elements[0], definitions)
+            }
+            Fields::UnnamedFields(elements) => self.tuple_schema(elements, definitions),
+            Fields::Empty => json!({ "type": "null" }),
+        }
+    }
+
+    fn tuple_schema(
+        &mut self,
+        elements: &[Declaration],
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> Value {
+        let items: Vec<Value> =
+            elements.iter().map(|element| self.schema(element, definitions)).collect();
+        json!({
+            "type": "array",
+            "items": items,
+            "minItems": elements.len(),
+            "maxItems": elements.len(),
+        })
+    }
+}
+
+/// The schema of `{ "<tag>": value }`.
+fn tagged_schema(tag: &str, schema: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { tag: schema },
+        "required": [tag],
+        "additionalProperties": false,
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    use crate::MethodMetadata;
+    use borsh::BorshSchema;
+
+    #[derive(BorshSchema)]
+    struct Input {
+        account_id: String,
+        amount: u32,
+        memo: Option<String>,
+    }
+
+    #[derive(BorshSchema)]
+    enum Kind {
+        Empty,
+        Amount(u64),
+        Named { id: i32 },
+    }
+
+    #[test]
+    fn test_json_abi() {
+        let metadata = Metadata::new(vec![
+            MethodMetadata {
+                name: "transfer".to_string(),
+                is_view: false,
+                is_init: false,
+                args: Some(Input::schema_container()),
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(Vec::<Kind>::schema_container()),
+            },
+            MethodMetadata {
+                name: "get_pair".to_string(),
+                is_view: true,
+                is_init: false,
+                args: None,
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(<(bool, [u8; 2])>::schema_container()),
+            },
+        ]);
+        let expected = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "metadata_version": "0.1.0",
+            "methods": [
+                {
+                    "name": "transfer",
+                    "kind": "call",
+                    "is_init": false,
+                    "args": {
+                        "type": "object",
+                        "properties": {
+                            "account_id": { "type": "string" },
+                            "amount": { "type": "integer", "minimum": 0, "maximum": 4294967295u32 },
+                            "memo": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                        },
+                        "required": ["account_id", "amount"],
+                    },
+                    "result": { "type": "array", "items": { "$ref": "#/definitions/Kind" } },
+                },
+                {
+                    "name": "get_pair",
+                    "kind": "view",
+                    "is_init": false,
+                    "args": null,
+                    "result": {
+                        "type": "array",
+                        "items": [
+                            { "type": "boolean" },
+                            {
+                                "type": "array",
+                                "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+                                "minItems": 2,
+                                "maxItems": 2,
+                            },
+                        ],
+                        "minItems": 2,
+                        "maxItems": 2,
+                    },
+                },
+            ],
+            "definitions": {
+                "Kind": {
+                    "oneOf": [
+                        { "const": "Empty" },
+                        {
+                            "type": "object",
+                            "properties": { "Amount": { "type": "integer", "minimum": 0 } },
+                            "required": ["Amount"],
+                            "additionalProperties": false,
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Named": {
+                                    "type": "object",
+                                    "properties": {
+                                        "id": {
+                                            "type": "integer",
+                                            "minimum": -2147483648,
+                                            "maximum": 2147483647,
+                                        },
+                                    },
+                                    "required": ["id"],
+                                },
+                            },
+                            "required": ["Named"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+            },
+        });
+        assert_eq!(metadata.json_abi(), expected);
+    }
+}
@@ -0,0 +1,141 @@
+//! OpenRPC document of the view methods of the contract, generated from its metadata.
+use super::json_schema::JsonSchemas;
+use super::Metadata;
+use borsh::schema::{Definition, Fields};
+use serde_json::{json, Value};
+
+/// The version of the OpenRPC specification of the generated document.
+const OPENRPC_VERSION: &str = "1.2.6";
+
+impl Metadata {
+    /// Generates the OpenRPC document `title` of version `version` with the view methods of the
+    /// contract, for the gateways that proxy the `call_function` queries of the RPC. The params
+    /// are passed by name, as the fields of the JSON arguments, and the structs and the enums of
+    /// the contract are in `components.schemas`.
+    pub fn openrpc(&self, title: &str, version: &str) -> Value {
+        let mut schemas = JsonSchemas::with_ref_prefix("#/components/schemas/");
+        let methods: Vec<Value> = self
+            .methods
+            .iter()
+            .filter(|method| method.is_view && !method.is_init)
+            .map(|method| {
+                let mut params = vec![];
+                if let Some(args) = &method.args {
+                    match args.definitions.get(&args.declaration) {
+                        Some(Definition::Struct { fields: Fields::NamedFields(fields) }) => {
+                            for (name, declaration) in fields {
+                                params.push(json!({
+                                    "name": name,
+                                    "schema": schemas.schema(declaration, &args.definitions),
+                                    // Serde reads the missing optional fields as `None`.
+                                    "required": !declaration.starts_with("Option<"),
+                                }));
+                            }
+                        }
+                        _ => params.push(json!({
+                            "name": "args",
+                            "schema": schemas.container_schema(args),
+                            "required": true,
+                        })),
+                    }
+                }
+                let result = match &method.result {
+                    Some(result) => schemas.container_schema(result),
+                    None => json!({ "type": "null" }),
+                };
+                json!({
+                    "name": method.name,
+                    "paramStructure": "by-name",
+                    "params": params,
+                    "result": { "name": "result", "schema": result },
+                })
+            })
+            .collect();
+        json!({
+            "openrpc": OPENRPC_VERSION,
+            "info": { "title": title, "version": version },
+            "methods": methods,
+            "components": { "schemas": schemas.definitions },
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    use crate::MethodMetadata;
+    use borsh::BorshSchema;
+
+    #[derive(BorshSchema)]
+    struct Input {
+        account_id: String,
+        from_index: Option<u64>,
+    }
+
+    #[derive(BorshSchema)]
+    struct Token {
+        token_id: String,
+        owner_id: String,
+    }
+
+    fn method(name: &str, is_view: bool, is_init: bool) -> MethodMetadata {
+        MethodMetadata {
+            name: name.to_string(),
+            is_view,
+            is_init,
+            args: Some(Input::schema_container()),
+            callbacks: vec![],
+            callbacks_vec: None,
+            result: Some(Vec::<Token>::schema_container()),
+        }
+    }
+
+    #[test]
+    fn test_openrpc() {
+        let metadata = Metadata::new(vec![
+            method("new", true, true),
+            method("tokens_for_owner", true, false),
+            method("transfer", false, false),
+        ]);
+        let expected = json!({
+            "openrpc": "1.2.6",
+            "info": { "title": "NFT", "version": "1.0.0" },
+            "methods": [
+                {
+                    "name": "tokens_for_owner",
+                    "paramStructure": "by-name",
+                    "params": [
+                        { "name": "account_id", "schema": { "type": "string" }, "required": true },
+                        {
+                            "name": "from_index",
+                            "schema": { "anyOf": [{ "type": "integer", "minimum": 0 }, { "type": "null" }] },
+                            "required": false,
+                        },
+                    ],
+                    "result": {
+                        "name": "result",
+                        "schema": {
+                            "type": "array",
+                            "items": { "$ref": "#/components/schemas/Token" },
+                        },
+                    },
+                },
+            ],
+            "components": {
+                "schemas": {
+                    "Token": {
+                        "type": "object",
+                        "properties": {
+                            "token_id": { "type": "string" },
+                            "owner_id": { "type": "string" },
+                        },
+                        "required": ["token_id", "owner_id"],
+                    },
+                },
+            },
+        });
+        assert_eq!(metadata.openrpc("NFT", "1.0.0"), expected);
+    }
+}
@@ -0,0 +1,368 @@
+//! Generation of a TypeScript client of the contract from its metadata.
+use super::{definition_name, Metadata, MethodMetadata};
+use borsh::schema::{BorshSchemaContainer, Declaration, Definition, FieldName, Fields};
+use std::collections::{BTreeMap, HashMap};
+
+/// The header of the generated files.
+const HEADER: &str = "// Generated from the metadata of the contract, do not edit.\n";
+
+/// The account and the options of the calls, compatible with the `Account` of `near-api-js`.
+const DECLARATIONS_PRELUDE: &str = "
+export interface NearAccount {
+  viewFunction(contractId: string, methodName: string, args?: object): Promise<any>;
+  functionCall(options: {
+    contractId: string;
+    methodName: string;
+    args?: object;
+    gas?: string;
+    attachedDeposit?: string;
+  }): Promise<any>;
+}
+
+export interface CallOptions {
+  /** The gas attached to the call. */
+  gas?: string;
+  /** The deposit attached to the call in yoctoNEAR. */
+  attachedDeposit?: string;
+}
+";
+
+/// Decodes the value returned by the function call from the outcome of the transaction.
+const MODULE_PRELUDE: &str = "
+function lastResult(outcome) {
+  const value = outcome.status && outcome.status.SuccessValue;
+  if (!value) {
+    return undefined;
+  }
+  const bytes = Uint8Array.from(atob(value), (c) => c.charCodeAt(0));
+  return JSON.parse(new TextDecoder().decode(bytes));
+}
+";
+
+/// The TypeScript client of the contract. The client calls the methods with the JSON arguments and
+/// parses their JSON results.
+#[derive(Debug, PartialEq)]
+pub struct TypeScriptClient {
+    /// The content of the `.d.ts` file with the types of the arguments and the results.
+    pub declarations: String,
+    /// The content of the `.js` module with the client class.
+    pub module: String,
+}
+
+impl Metadata {
+    /// Generates the client class `class_name` with a function for each method of the contract.
+    /// The view methods are called with `viewFunction`, the others with `functionCall` and take
+    /// the gas and the deposit in `CallOptions`.
+    pub fn typescript_client(&self, class_name: &str) -> TypeScriptClient {
+        let mut types = Types::default();
+        let mut methods = String::new();
+        let mut functions = String::new();
+        for method in &self.methods {
+            let is_view = method.is_view && !method.is_init;
+            let mut params = vec![];
+            if let Some(args) = &method.args {
+                params.push(format!("args: {}", types.args_type(args)));
+            }
+            if !is_view {
+                params.push("options?: CallOptions".to_string());
+            }
+            let result = match &method.result {
+                Some(result) => types.container_type(result),
+                None => "void".to_string(),
+            };
+            methods.push_str(&format!(
+                "  {}({}): Promise<{}>;\n",
+                method.name,
+                params.join(", "),
+                result
+            ));
+            functions.push_str(&function(method, is_view));
+        }
+        let declarations = format!(
+            "{}{}\n{}\nexport class {} {{\n  constructor(account: NearAccount, contractId: string);\n  readonly account: NearAccount;\n  readonly contractId: string;\n{}}}\n",
+            HEADER,
+            DECLARATIONS_PRELUDE,
+            types.named.values().cloned().collect::<String>(),
+            class_name,
+            methods
+        );
+        let module = format!(
+            "{}{}\nexport class {} {{\n  constructor(account, contractId) {{\n    this.account = account;\n    this.contractId = contractId;\n  }}\n{}}}\n",
+            HEADER, MODULE_PRELUDE, class_name, functions
+        );
+        TypeScriptClient { declarations, module }
+    }
+}
+
+/// The JavaScript function that calls the method.
+fn function(method: &MethodMetadata, is_view: bool) -> String {
+    let name = &method.name;
+    let (params, args) = if method.args.is_some() { ("args", "args") } else { ("", "{}") };
+    if is_view {
+        format!(
+            "\n  {}({}) {{\n    return this.account.viewFunction(this.contractId, \"{}\", {});\n  }}\n",
+            name, params, name, args
+        )
+    } else {
+        let params = if params.is_empty() {
+            "options = {}".to_string()
+        } else {
+            format!("{}, options = {{}}", params)
+        };
+        format!(
+            "\n  async {}({}) {{\n    const outcome = await this.account.functionCall({{\n      contractId: this.contractId,\n      methodName: \"{}\",\n      args: {},\n      ...options,\n    }});\n    return lastResult(outcome);\n  }}\n",
+            name, params, name, args
+        )
+    }
+}
+
+/// The TypeScript types of the JSON representation of the Borsh schemas. The structs and the enums
+/// of the contract become named types, the rest is written inline.
+#[derive(Default)]
+struct Types {
+    /// The declarations of the named types by their names.
+    named: BTreeMap<String, String>,
+}
+
+impl Types {
+    /// The type of the arguments, written inline from the fields of the `Input` struct.
+    fn args_type(&mut self, container: &BorshSchemaContainer) -> String {
+        match container.definitions.get(&container.declaration) {
+            Some(Definition::Struct { fields }) => self.fields_type(fields, &container.definitions),
+            _ => self.container_type(container),
+        }
+    }
+
+    fn container_type(&mut self, container: &BorshSchemaContainer) -> String {
+        self.ts_type(&container.declaration, &container.definitions)
+    }
+
+    fn ts_type(
+        &mut self,
+        declaration: &str,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        match declaration {
+            "bool" => "boolean".to_string(),
+            "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128"
+            | "f32" | "f64" => "number".to_string(),
+            "string" | "char" => "string".to_string(),
+            "nil" => "null".to_string(),
+            _ => match definitions.get(declaration) {
+                Some(definition) => self.definition_type(declaration, definition, definitions),
+                None => "unknown".to_string(),
+            },
+        }
+    }
+
+    fn definition_type(
+        &mut self,
+        declaration: &str,
+        definition: &Definition,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        match definition {
+            // The elements of a map are the `Tuple<K, V>` of its entries.
+            Definition::Sequence { elements } if declaration.starts_with("HashMap<") => {
+                match definitions.get(elements) {
+                    Some(Definition::Tuple { elements }) if elements.len() == 2 => {
+                        format!("Record<string, {}>", self.ts_type(&elements[1], definitions))
+                    }
+                    _ => "unknown".to_string(),
+                }
+            }
+            Definition::Array { elements, .. } | Definition::Sequence { elements } => {
+                format!("Array<{}>", self.ts_type(elements, definitions))
+            }
+            Definition::Tuple { elements } => self.tuple_type(elements, definitions),
+            Definition::Enum { variants } if declaration.starts_with("Option<") => {
+                format!("{} | null", self.ts_type(&variants[1].1, definitions))
+            }
+            Definition::Enum { variants } if declaration.starts_with("Result<") => variants
+                .iter()
+                .map(|(name, declaration)| {
+                    format!("{{ {}: {} }}", name, self.ts_type(declaration, definitions))
+                })
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Definition::Enum { .. } | Definition::Struct { .. } => {
+                self.named_type(declaration, definition, definitions)
+            }
+        }
+    }
+
+    fn named_type(
+        &mut self,
+        declaration: &str,
+        definition: &Definition,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        let name = definition_name(declaration);
+        if self.named.contains_key(&name) {
+            return name;
+        }
+        // Reserve the name for the recursive types.
+        self.named.insert(name.clone(), String::new());
+        let named = match definition {
+            Definition::Struct { fields: fields @ Fields::NamedFields(_) } => {
+                format!("export interface {} {}\n", name, self.fields_type(fields, definitions))
+            }
+            Definition::Struct { fields } => {
+                format!("export type {} = {};\n", name, self.fields_type(fields, definitions))
+            }
+            // Serde writes the unit variants as strings and the others as `{ "Variant": value }`.
+            Definition::Enum { variants } => {
+                let variants: Vec<_> = variants
+                    .iter()
+                    .map(|(variant, declaration)| match definitions.get(declaration) {
+                        Some(Definition::Struct { fields: Fields::Empty }) => {
+                            format!("\"{}\"", variant)
+                        }
+                        Some(Definition::Struct { fields }) => {
+                            format!("{{ {}: {} }}", variant, self.fields_type(fields, definitions))
+                        }
+                        _ => {
+                            format!("{{ {}: {} }}", variant, self.ts_type(declaration, definitions))
+                        }
+                    })
+                    .collect();
+                format!("export type {} = {};\n", name, variants.join(" | "))
+            }
+            _ => unreachable!(),
+        };
+        self.named.insert(name.clone(), named);
+        name
+    }
+
+    fn fields_type(
+        &mut self,
+        fields: &Fields,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        match fields {
+            Fields::NamedFields(fields) => self.object_type(fields, definitions),
+            Fields::UnnamedFields(elements) if elements.len() == 1 => {
+                self.ts_type(&elements[0], definitions)
+            }
+            Fields::UnnamedFields(elements) => self.tuple_type(elements, definitions),
+            Fields::Empty => "null".to_string(),
+        }
+    }
+
+    fn object_type(
+        &mut self,
+        fields: &[(FieldName, Declaration)],
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|(name, declaration)| {
+                format!("{}: {}", name, self.ts_type(declaration, definitions))
+            })
+            .collect();
+        format!("{{ {} }}", fields.join("; "))
+    }
+
+    fn tuple_type(
+        &mut self,
+        elements: &[Declaration],
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        let elements: Vec<_> =
+            elements.iter().map(|element| self.ts_type(element, definitions)).collect();
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    use borsh::BorshSchema;
+    use std::collections::HashMap;
+
+    #[derive(BorshSchema)]
+    struct Input {
+        account_id: String,
+        amounts: Vec<u64>,
+        memo: Option<String>,
+    }
+
+    #[derive(BorshSchema)]
+    struct Status {
+        owner: String,
+        kind: Kind,
+        balances: HashMap<String, u128>,
+        pair: (u8, bool),
+    }
+
+    #[derive(BorshSchema)]
+    enum Kind {
+        Empty,
+        Amount(u64),
+        Named { id: i32 },
+    }
+
+    fn metadata() -> Metadata {
+        Metadata::new(vec![
+            MethodMetadata {
+                name: "get_status".to_string(),
+                is_view: true,
+                is_init: false,
+                args: None,
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(Status::schema_container()),
+            },
+            MethodMetadata {
+                name: "transfer".to_string(),
+                is_view: false,
+                is_init: false,
+                args: Some(Input::schema_container()),
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(Option::<u64>::schema_container()),
+            },
+            MethodMetadata {
+                name: "new".to_string(),
+                is_view: true,
+                is_init: true,
+                args: None,
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: None,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_declarations() {
+        let client = metadata().typescript_client("TokenClient");
+        let declarations = client.declarations;
+        assert!(declarations.contains(
+            "export interface Status { owner: string; kind: Kind; balances: Record<string, number>; pair: [number, boolean] }\n"
+        ));
+        assert!(declarations.contains(
+            "export type Kind = \"Empty\" | { Amount: number } | { Named: { id: number } };\n"
+        ));
+        assert!(declarations.contains("export class TokenClient {\n"));
+        assert!(declarations.contains("  get_status(): Promise<Status>;\n"));
+        assert!(declarations.contains(
+            "  transfer(args: { account_id: string; amounts: Array<number>; memo: string | null }, options?: CallOptions): Promise<number | null>;\n"
+        ));
+        assert!(declarations.contains("  new(options?: CallOptions): Promise<void>;\n"));
+    }
+
+    #[test]
+    fn test_module() {
+        let module = metadata().typescript_client("TokenClient").module;
+        assert!(module.contains("export class TokenClient {\n"));
+        assert!(module.contains(
+            "  get_status() {\n    return this.account.viewFunction(this.contractId, \"get_status\", {});\n  }\n"
+        ));
+        assert!(module.contains("  async transfer(args, options = {}) {\n"));
+        assert!(module.contains("      methodName: \"transfer\",\n      args: args,\n"));
+        assert!(module.contains("  async new(options = {}) {\n"));
+    }
+}
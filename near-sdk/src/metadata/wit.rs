@@ -0,0 +1,394 @@
+//! WebAssembly Interface Types (WIT) description of the contract, generated from its metadata.
+use super::{definition_name, Metadata};
+use borsh::schema::{Declaration, Definition, Fields};
+use std::collections::{BTreeMap, HashMap};
+
+/// The words of WIT that have to be escaped with `%` to be used as names.
+const WIT_KEYWORDS: &[&str] = &[
+    "as",
+    "bool",
+    "borrow",
+    "char",
+    "constructor",
+    "enum",
+    "export",
+    "f32",
+    "f64",
+    "flags",
+    "from",
+    "func",
+    "import",
+    "include",
+    "interface",
+    "list",
+    "option",
+    "own",
+    "package",
+    "record",
+    "resource",
+    "result",
+    "s16",
+    "s32",
+    "s64",
+    "s8",
+    "static",
+    "string",
+    "tuple",
+    "type",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "use",
+    "variant",
+    "with",
+    "world",
+];
+
+impl Metadata {
+    /// Generates the WIT package `package` with the `contract` interface, which has a function
+    /// for each method of the contract and the types of their arguments and results, and the world
+    /// `world` exporting it. The 128-bit integers, which WIT does not have, are described as
+    /// decimal strings.
+    pub fn wit(&self, package: &str, world: &str) -> String {
+        let mut types = WitTypes::default();
+        let mut functions = String::new();
+        for method in &self.methods {
+            let mut params = vec![];
+            if let Some(args) = &method.args {
+                match args.definitions.get(&args.declaration) {
+                    Some(Definition::Struct { fields: Fields::NamedFields(fields) }) => {
+                        for (name, declaration) in fields {
+                            let ty = types.wit_type(declaration, &args.definitions);
+                            params.push(format!("{}: {}", wit_name(name), ty));
+                        }
+                    }
+                    _ => {
+                        let ty = types.wit_type(&args.declaration, &args.definitions);
+                        params.push(format!("args: {}", ty));
+                    }
+                }
+            }
+            let result = match &method.result {
+                Some(result) => {
+                    format!(" -> {}", types.wit_type(&result.declaration, &result.definitions))
+                }
+                None => String::new(),
+            };
+            let kind = if method.is_init {
+                "Initializes the contract."
+            } else if method.is_view {
+                "View method."
+            } else {
+                "Change method."
+            };
+            functions.push_str(&format!(
+                "  /// {}\n  {}: func({}){};\n",
+                kind,
+                wit_name(&method.name),
+                params.join(", "),
+                result
+            ));
+        }
+        let types: String = types.named.values().map(|named| format!("  {}\n", named)).collect();
+        format!(
+            "package {};\n\ninterface contract {{\n{}{}{}}}\n\nworld {} {{\n  export contract;\n}}\n",
+            package,
+            types,
+            if types.is_empty() { "" } else { "\n" },
+            functions,
+            wit_name(world)
+        )
+    }
+}
+
+/// The WIT name of the Rust name: `kebab-case`, escaped with `%` if it is a keyword.
+fn wit_name(name: &str) -> String {
+    let mut kebab = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            if !kebab.is_empty() && !kebab.ends_with('-') {
+                kebab.push('-');
+            }
+            previous_lowercase = false;
+        } else if c.is_ascii_uppercase() {
+            if previous_lowercase {
+                kebab.push('-');
+            }
+            kebab.push(c.to_ascii_lowercase());
+            previous_lowercase = false;
+        } else {
+            kebab.push(c);
+            previous_lowercase = true;
+        }
+    }
+    let kebab = kebab.trim_end_matches('-').to_string();
+    if WIT_KEYWORDS.contains(&kebab.as_str()) {
+        format!("%{}", kebab)
+    } else {
+        kebab
+    }
+}
+
+/// The WIT types of the Borsh schemas. The structs and the enums of the contract become named
+/// types, the rest is written inline.
+#[derive(Default)]
+struct WitTypes {
+    /// The declarations of the named types by their names.
+    named: BTreeMap<String, String>,
+}
+
+impl WitTypes {
+    fn wit_type(
+        &mut self,
+        declaration: &str,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        match declaration {
+            "bool" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64" | "char" | "string" => {
+                declaration.to_string()
+            }
+            "i8" => "s8".to_string(),
+            "i16" => "s16".to_string(),
+            "i32" => "s32".to_string(),
+            "i64" => "s64".to_string(),
+            "u128" | "i128" => "string".to_string(),
+            "nil" => "tuple<>".to_string(),
+            _ => match definitions.get(declaration) {
+                Some(definition) => self.definition_type(declaration, definition, definitions),
+                None => "string".to_string(),
+            },
+        }
+    }
+
+    fn definition_type(
+        &mut self,
+        declaration: &str,
+        definition: &Definition,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        match definition {
+            Definition::Array { elements, .. } | Definition::Sequence { elements } => {
+                format!("list<{}>", self.wit_type(elements, definitions))
+            }
+            Definition::Tuple { elements } => self.tuple_type(elements, definitions),
+            Definition::Enum { variants } if declaration.starts_with("Option<") => {
+                format!("option<{}>", self.wit_type(&variants[1].1, definitions))
+            }
+            Definition::Enum { variants } if declaration.starts_with("Result<") => {
+                let ok = self.result_case(&variants[0].1, definitions);
+                let err = self.result_case(&variants[1].1, definitions);
+                format!("result<{}, {}>", ok, err)
+            }
+            Definition::Enum { .. } | Definition::Struct { .. } => {
+                self.named_type(declaration, definition, definitions)
+            }
+        }
+    }
+
+    fn result_case(
+        &mut self,
+        declaration: &str,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        if declaration == "nil" {
+            "_".to_string()
+        } else {
+            self.wit_type(declaration, definitions)
+        }
+    }
+
+    fn named_type(
+        &mut self,
+        declaration: &str,
+        definition: &Definition,
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        let name = wit_name(&definition_name(declaration));
+        if self.named.contains_key(&name) {
+            return name;
+        }
+        // Reserve the name for the recursive types.
+        self.named.insert(name.clone(), String::new());
+        let named = match definition {
+            Definition::Struct { fields: Fields::NamedFields(fields) } => {
+                self.record(&name, fields, definitions)
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(elements) }
+                if elements.len() == 1 =>
+            {
+                format!("type {} = {};", name, self.wit_type(&elements[0], definitions))
+            }
+            Definition::Struct { fields: Fields::UnnamedFields(elements) } => {
+                format!("type {} = {};", name, self.tuple_type(elements, definitions))
+            }
+            Definition::Struct { fields: Fields::Empty } => format!("type {} = tuple<>;", name),
+            Definition::Enum { variants } => {
+                let is_unit = |declaration: &Declaration| {
+                    matches!(
+                        definitions.get(declaration),
+                        Some(Definition::Struct { fields: Fields::Empty })
+                    )
+                };
+                if variants.iter().all(|(_, declaration)| is_unit(declaration)) {
+                    let cases: Vec<_> = variants.iter().map(|(case, _)| wit_name(case)).collect();
+                    format!("enum {} {{ {} }}", name, cases.join(", "))
+                } else {
+                    let cases: Vec<_> = variants
+                        .iter()
+                        .map(|(case, declaration)| {
+                            let case = wit_name(case);
+                            match definitions.get(declaration) {
+                                Some(Definition::Struct { fields: Fields::Empty }) => case,
+                                // The named fields of the variant become a record of its own.
+                                Some(Definition::Struct {
+                                    fields: Fields::NamedFields(fields),
+                                }) => {
+                                    let record =
+                                        format!("{}-{}", name, case.trim_start_matches('%'));
+                                    let declaration = self.record(&record, fields, definitions);
+                                    self.named.insert(record.clone(), declaration);
+                                    format!("{}({})", case, record)
+                                }
+                                Some(Definition::Struct {
+                                    fields: Fields::UnnamedFields(elements),
+                                }) if elements.len() == 1 => {
+                                    format!(
+                                        "{}({})",
+                                        case,
+                                        self.wit_type(&elements[0], definitions)
+                                    )
+                                }
+                                Some(Definition::Struct {
+                                    fields: Fields::UnnamedFields(elements),
+                                }) => {
+                                    format!("{}({})", case, self.tuple_type(elements, definitions))
+                                }
+                                _ => {
+                                    format!("{}({})", case, self.wit_type(declaration, definitions))
+                                }
+                            }
+                        })
+                        .collect();
+                    format!("variant {} {{ {} }}", name, cases.join(", "))
+                }
+            }
+            _ => unreachable!(),
+        };
+        self.named.insert(name.clone(), named);
+        name
+    }
+
+    fn record(
+        &mut self,
+        name: &str,
+        fields: &[(String, Declaration)],
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|(field, declaration)| {
+                format!("{}: {}", wit_name(field), self.wit_type(declaration, definitions))
+            })
+            .collect();
+        format!("record {} {{ {} }}", name, fields.join(", "))
+    }
+
+    fn tuple_type(
+        &mut self,
+        elements: &[Declaration],
+        definitions: &HashMap<Declaration, Definition>,
+    ) -> String {
+        let elements: Vec<_> =
+            elements.iter().map(|element| self.wit_type(element, definitions)).collect();
+        format!("tuple<{}>", elements.join(", "))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    use super::*;
+    use crate::MethodMetadata;
+    use borsh::BorshSchema;
+
+    #[derive(BorshSchema)]
+    struct Input {
+        account_id: String,
+        amount: u128,
+        memo: Option<String>,
+    }
+
+    #[derive(BorshSchema)]
+    struct TokenStatus {
+        owner: String,
+        kind: Kind,
+        flag: Flag,
+    }
+
+    #[derive(BorshSchema)]
+    enum Kind {
+        Empty,
+        Amount(i64),
+        Named { id: u32 },
+    }
+
+    #[derive(BorshSchema)]
+    enum Flag {
+        On,
+        Off,
+    }
+
+    #[test]
+    fn test_wit() {
+        let metadata = Metadata::new(vec![
+            MethodMetadata {
+                name: "get_status".to_string(),
+                is_view: true,
+                is_init: false,
+                args: None,
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(TokenStatus::schema_container()),
+            },
+            MethodMetadata {
+                name: "transfer".to_string(),
+                is_view: false,
+                is_init: false,
+                args: Some(Input::schema_container()),
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(Vec::<(u8, bool)>::schema_container()),
+            },
+        ]);
+        let expected = "package near:token;
+
+interface contract {
+  enum flag { on, off }
+  variant kind { empty, amount(s64), named(kind-named) }
+  record kind-named { id: u32 }
+  record token-status { owner: string, kind: kind, flag: flag }
+
+  /// View method.
+  get-status: func() -> token-status;
+  /// Change method.
+  transfer: func(account-id: string, amount: string, memo: option<string>) -> list<tuple<u8, bool>>;
+}
+
+world token {
+  export contract;
+}
+";
+        assert_eq!(metadata.wit("near:token", "token"), expected);
+    }
+
+    #[test]
+    fn test_wit_name() {
+        assert_eq!(wit_name("ft_transfer_call"), "ft-transfer-call");
+        assert_eq!(wit_name("TokenStatus"), "token-status");
+        assert_eq!(wit_name("Pair_u64"), "pair-u64");
+        assert_eq!(wit_name("type"), "%type");
+    }
+}
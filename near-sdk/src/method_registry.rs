@@ -0,0 +1,179 @@
+//! Method registry of the contract, for the command-line tools. `#[near_bindgen]` puts a line for
+//! each exported method into the `near_methods` custom section of the wasm: the name of the
+//! method, its kind, the number of its arguments, and the serializers of its input and its result.
+//! `read_method_registry` reads it back from the binary, without running the contract or
+//! generating the full ABI:
+//!
+//! ```ignore
+//! let wasm = std::fs::read("res/contract.wasm")?;
+//! for method in near_sdk::method_registry::read_method_registry(&wasm)? {
+//!     println!("{} takes {} arguments", method.name, method.arg_count);
+//! }
+//! ```
+//!
+//! Tools that strip the custom sections, like `wasm-opt --strip`, remove the registry too.
+
+/// The name of the custom section of the wasm with the method registry.
+pub const METHOD_REGISTRY_SECTION: &str = "near_methods";
+
+/// How the method is called.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MethodKind {
+    /// A view method, which doesn't change the state.
+    View,
+    /// A method that is called with a transaction.
+    Call,
+    /// An initialization method.
+    Init,
+}
+
+/// The serializer of the input or the result of the method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SerializerKind {
+    Json,
+    Borsh,
+}
+
+/// A method exported by the contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisteredMethod {
+    pub name: String,
+    pub kind: MethodKind,
+    /// The number of the arguments of the method, without the callback results.
+    pub arg_count: usize,
+    /// The serializer of the arguments, `None` if the method takes no arguments.
+    pub input_serializer: Option<SerializerKind>,
+    /// The serializer of the result, `None` if the method returns nothing.
+    pub result_serializer: Option<SerializerKind>,
+}
+
+/// Reads the method registry from the custom sections of the wasm binary.
+pub fn read_method_registry(wasm: &[u8]) -> Result<Vec<RegisteredMethod>, String> {
+    if wasm.len() < 8 || &wasm[..4] != b"\0asm" {
+        return Err("The binary is not a wasm module".to_string());
+    }
+    let mut registry = vec![];
+    let mut offset = 8;
+    while offset < wasm.len() {
+        let id = wasm[offset];
+        offset += 1;
+        let size = read_leb128(wasm, &mut offset)? as usize;
+        let end = offset.checked_add(size).filter(|end| *end <= wasm.len());
+        let end = end.ok_or_else(|| "The wasm section is truncated".to_string())?;
+        if id == 0 {
+            let mut name_offset = offset;
+            let name_len = read_leb128(wasm, &mut name_offset)? as usize;
+            let name_end = name_offset + name_len;
+            if name_end > end {
+                return Err("The name of the custom section is truncated".to_string());
+            }
+            if &wasm[name_offset..name_end] == METHOD_REGISTRY_SECTION.as_bytes() {
+                registry.extend(parse_method_registry(&wasm[name_end..end])?);
+            }
+        }
+        offset = end;
+    }
+    Ok(registry)
+}
+
+/// Parses the content of the `near_methods` custom section.
+pub fn parse_method_registry(section: &[u8]) -> Result<Vec<RegisteredMethod>, String> {
+    let section = std::str::from_utf8(section).map_err(|err| err.to_string())?;
+    section
+        .lines()
+        .map(|line| {
+            let invalid = || format!("Invalid method registry line: {:?}", line);
+            let columns: Vec<&str> = line.split('\t').collect();
+            if columns.len() != 5 {
+                return Err(invalid());
+            }
+            let kind = match columns[1] {
+                "view" => MethodKind::View,
+                "call" => MethodKind::Call,
+                "init" => MethodKind::Init,
+                _ => return Err(invalid()),
+            };
+            let serializer = |column: &str| match column {
+                "json" => Ok(Some(SerializerKind::Json)),
+                "borsh" => Ok(Some(SerializerKind::Borsh)),
+                "-" => Ok(None),
+                _ => Err(invalid()),
+            };
+            Ok(RegisteredMethod {
+                name: columns[0].to_string(),
+                kind,
+                arg_count: columns[2].parse().map_err(|_| invalid())?,
+                input_serializer: serializer(columns[3])?,
+                result_serializer: serializer(columns[4])?,
+            })
+        })
+        .collect()
+}
+
+/// Reads the unsigned LEB128 integer of the wasm encoding.
+fn read_leb128(data: &[u8], offset: &mut usize) -> Result<u32, String> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *data.get(*offset).ok_or_else(|| "The wasm integer is truncated".to_string())?;
+        *offset += 1;
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("The wasm integer is too long".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_section(name: &str, content: &[u8]) -> Vec<u8> {
+        let mut payload = vec![name.len() as u8];
+        payload.extend(name.as_bytes());
+        payload.extend(content);
+        let mut section = vec![0, payload.len() as u8];
+        section.extend(payload);
+        section
+    }
+
+    #[test]
+    fn test_read_method_registry() {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // The type section with no types.
+        wasm.extend(&[1, 1, 0]);
+        wasm.extend(custom_section("near_methods", b"new\tinit\t1\tjson\t-\n"));
+        wasm.extend(custom_section("name", b"\0"));
+        wasm.extend(custom_section("near_methods", b"set\tcall\t2\tborsh\tborsh\n"));
+        let registry = read_method_registry(&wasm).unwrap();
+        assert_eq!(
+            registry,
+            vec![
+                RegisteredMethod {
+                    name: "new".to_string(),
+                    kind: MethodKind::Init,
+                    arg_count: 1,
+                    input_serializer: Some(SerializerKind::Json),
+                    result_serializer: None,
+                },
+                RegisteredMethod {
+                    name: "set".to_string(),
+                    kind: MethodKind::Call,
+                    arg_count: 2,
+                    input_serializer: Some(SerializerKind::Borsh),
+                    result_serializer: Some(SerializerKind::Borsh),
+                },
+            ]
+        );
+        assert!(read_method_registry(b"not wasm").is_err());
+        assert!(read_method_registry(&wasm[..wasm.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_registry() {
+        assert!(parse_method_registry(b"get\tview\t0\t-\n").is_err());
+        assert!(parse_method_registry(b"get\tpure\t0\t-\tjson\n").is_err());
+        assert!(parse_method_registry(b"get\tview\tx\t-\tjson\n").is_err());
+        assert_eq!(parse_method_registry(b"").unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,373 @@
+//! Migrations of the contract state between the layouts of its versions.
+//!
+//! The versions of the state are listed as the variants of an enum deriving `Migratable`, from the
+//! oldest to the current one, each wrapping the struct of its version. The version of the stored
+//! state is kept under its own key, and `Migratable::migrate` reads the state with the struct of
+//! that version and converts it to the current struct through the `From` implementations between
+//! the consecutive versions:
+//!
+//! ```ignore
+//! #[derive(Migratable)]
+//! pub enum VersionedContract {
+//!     V0(ContractV0),
+//!     V1(Contract),
+//! }
+//!
+//! impl From<ContractV0> for Contract { ... }
+//!
+//! #[near_bindgen]
+//! impl Contract {
+//!     #[init]
+//!     pub fn new() -> Self {
+//!         VersionedContract::mark_current();
+//!         Self { ... }
+//!     }
+//!
+//!     #[init(ignore_state)]
+//!     pub fn migrate() -> Self {
+//!         VersionedContract::migrate()
+//!     }
+//! }
+//! ```
+//!
+//! The methods of the contract read and write the state through `ContractState`, which
+//! `#[near_bindgen]` implements for the contract struct. With `#[near_bindgen(state_schema)]` on
+//! the struct, every method of the contract, including the ones of the `impl_*!` trait sections,
+//! also stores the fingerprint of the Borsh schema of the state struct next to the state, and
+//! checks it when it reads the state. The state struct then derives `BorshSchema`. A method of a
+//! new version with a different state layout fails with a clear error until the state is migrated,
+//! instead of deserializing garbage.
+//!
+//! `upgrade_and_migrate` deploys the new code and calls its migration method in a single batch.
+//! Without a migration call, `env::state_read_versioned` reads the state of the previous layout
+//! and converts it, and the state is rewritten with the new layout by the next write.
+use crate::env;
+use borsh::schema::BorshSchemaContainer;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use near_vm_logic::types::{Gas, PromiseIndex};
+use std::cell::Cell;
+
+/// The storage key of the version of the contract state.
+pub const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+/// The version of the stored contract state. The state written before the versions were tracked
+/// has the version 0.
+pub fn state_version() -> u8 {
+    match env::storage_read(STATE_VERSION_KEY) {
+        Some(value) if value.len() == 1 => value[0],
+        Some(_) => env::panic(b"Cannot deserialize the state version."),
+        None => 0,
+    }
+}
+
+/// Sets the version of the stored contract state.
+pub fn set_state_version(version: u8) {
+    env::storage_write(STATE_VERSION_KEY, &[version]);
+}
+
+/// Panics unless the stored contract state has the given version.
+pub fn assert_state_version(version: u8) {
+    let state_version = state_version();
+    if state_version != version {
+        env::panic(
+            format!("Expected the state version {}, found {}.", version, state_version).as_bytes(),
+        );
+    }
+}
+
+/// Deploys `code` to the current account and calls `migrate_method_name` of the new code with
+/// `gas` in the same batch. The actions of a batch are applied together, so the code stays
+/// deployed only if the migration succeeds. Panics unless the stored state has the version
+/// `from_version`, so an upgrade prepared for one version of the state is not applied to another.
+/// Returns the index of the batch, which the caller can return with `env::promise_return`.
+pub fn upgrade_and_migrate(
+    code: &[u8],
+    from_version: u8,
+    migrate_method_name: &str,
+    gas: Gas,
+) -> PromiseIndex {
+    assert_state_version(from_version);
+    if code.is_empty() {
+        env::panic(b"The code should not be empty.");
+    }
+    let promise_index = env::promise_batch_create(env::current_account_id());
+    env::promise_batch_action_deploy_contract(promise_index, code);
+    env::promise_batch_action_function_call(
+        promise_index,
+        migrate_method_name.as_bytes(),
+        &[],
+        0,
+        gas,
+    );
+    promise_index
+}
+
+/// Reads the contract state as `T`. Panics if there is no state.
+pub fn read_state<T: BorshDeserialize>() -> T {
+    match env::state_read() {
+        Some(state) => state,
+        None => env::panic(b"The contract state is not initialized."),
+    }
+}
+
+/// The storage key of the fingerprint of the schema of the contract state.
+pub const STATE_SCHEMA_KEY: &[u8] = b"STATE_SCHEMA";
+
+/// The SHA-256 hash of the Borsh schema of `T`. The definitions are sorted by their declarations,
+/// the names of the types, so the fingerprint doesn't depend on the order of the definitions in
+/// the map.
+pub fn state_schema_fingerprint<T: BorshSchema>() -> Vec<u8> {
+    let BorshSchemaContainer { declaration, definitions } = T::schema_container();
+    let mut definitions: Vec<_> = definitions.into_iter().collect();
+    definitions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let data = (declaration, definitions).try_to_vec().expect("Cannot serialize the state schema.");
+    env::sha256(&data)
+}
+
+thread_local! {
+    /// Whether the stored fingerprint is the one of the state schema, known from the check on read
+    /// or from the write during the call. The fingerprint is then neither computed nor read again.
+    static STATE_SCHEMA_STORED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Forgets the stored fingerprint when the blockchain interface is replaced.
+pub(crate) fn clear_state_schema_stored() {
+    STATE_SCHEMA_STORED.with(|stored| stored.set(false));
+}
+
+/// Reads the contract state as `T`, like `env::state_read`, after checking that the state was
+/// written with the same schema. The state written without a fingerprint is not checked.
+pub fn state_read_checked<T: BorshDeserialize + BorshSchema>() -> Option<T> {
+    if let Some(fingerprint) = env::storage_read(STATE_SCHEMA_KEY) {
+        if fingerprint != state_schema_fingerprint::<T>() {
+            env::panic(b"The state layout changed, run the state migration.");
+        }
+        STATE_SCHEMA_STORED.with(|stored| stored.set(true));
+    }
+    env::state_read()
+}
+
+/// Writes the contract state, like `env::state_write`, and the fingerprint of its schema unless
+/// it's already stored.
+pub fn state_write_checked<T: BorshSerialize + BorshSchema>(state: &T) {
+    env::state_write(state);
+    if !STATE_SCHEMA_STORED.with(|stored| stored.replace(true)) {
+        env::storage_write(STATE_SCHEMA_KEY, &state_schema_fingerprint::<T>());
+    }
+}
+
+/// How the methods of the contract read and write the contract state. `#[near_bindgen]` on the
+/// contract struct implements it with `env::state_read` and `env::state_write`, or with
+/// `state_read_checked` and `state_write_checked` with `#[near_bindgen(state_schema)]`.
+pub trait ContractState: Sized {
+    fn state_read() -> Option<Self>
+    where
+        Self: BorshDeserialize,
+    {
+        env::state_read()
+    }
+
+    fn state_write(&self)
+    where
+        Self: BorshSerialize,
+    {
+        env::state_write(self)
+    }
+}
+
+/// The versions of the contract state. Derived with `#[derive(Migratable)]` for an enum with one
+/// variant per version.
+pub trait Migratable: Sized {
+    /// The struct of the current version.
+    type Current;
+
+    /// The version of the current struct, the index of its variant.
+    const CURRENT_VERSION: u8;
+
+    /// Reads the stored state with the struct of its version.
+    fn read_versioned_state() -> Self;
+
+    /// Converts the state of any version to the current struct.
+    fn into_current(self) -> Self::Current;
+
+    /// Marks the stored state as the current version. Called by the initializers of the contract.
+    fn mark_current() {
+        set_state_version(Self::CURRENT_VERSION);
+    }
+
+    /// Reads the stored state, converts it to the current struct and marks the state as the
+    /// current version. The returned struct should then be written as the contract state, for
+    /// example by returning it from an `#[init(ignore_state)]` method.
+    fn migrate() -> Self::Current {
+        let state = Self::read_versioned_state().into_current();
+        Self::mark_current();
+        state
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+    use borsh::BorshSerialize;
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    struct StateV0 {
+        value: u32,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+    struct State {
+        value: u64,
+        owner: String,
+    }
+
+    impl From<StateV0> for State {
+        fn from(state: StateV0) -> Self {
+            Self { value: state.value.into(), owner: "alice.near".to_string() }
+        }
+    }
+
+    enum VersionedState {
+        V0(StateV0),
+        V1(State),
+    }
+
+    impl Migratable for VersionedState {
+        type Current = State;
+        const CURRENT_VERSION: u8 = 1;
+
+        fn read_versioned_state() -> Self {
+            match state_version() {
+                0 => Self::V0(read_state()),
+                1 => Self::V1(read_state()),
+                _ => env::panic(b"Unknown state version."),
+            }
+        }
+
+        fn into_current(self) -> State {
+            match self {
+                Self::V0(state) => Self::V1(state.into()).into_current(),
+                Self::V1(state) => state,
+            }
+        }
+    }
+
+    #[test]
+    fn test_migrate() {
+        test_env::setup();
+        env::state_write(&StateV0 { value: 5 });
+        assert_eq!(state_version(), 0);
+        let state = VersionedState::migrate();
+        assert_eq!(state, State { value: 5, owner: "alice.near".to_string() });
+        assert_eq!(state_version(), 1);
+        env::state_write(&state);
+        assert_eq!(VersionedState::migrate(), state);
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+    struct SchemaState {
+        value: u64,
+        owners: crate::collections::UnorderedSet<String>,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+    struct OtherSchemaState {
+        value: u64,
+        owners: crate::collections::UnorderedSet<u64>,
+    }
+
+    #[test]
+    fn test_state_schema() {
+        test_env::setup();
+        let state =
+            SchemaState { value: 5, owners: crate::collections::UnorderedSet::new(b"o".to_vec()) };
+        state_write_checked(&state);
+        assert_eq!(
+            env::storage_read(STATE_SCHEMA_KEY),
+            Some(state_schema_fingerprint::<SchemaState>())
+        );
+        assert_eq!(state_read_checked::<SchemaState>().unwrap().value, 5);
+        assert_ne!(
+            state_schema_fingerprint::<SchemaState>(),
+            state_schema_fingerprint::<OtherSchemaState>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The state layout changed, run the state migration.")]
+    fn test_state_schema_changed() {
+        test_env::setup();
+        let state =
+            SchemaState { value: 5, owners: crate::collections::UnorderedSet::new(b"o".to_vec()) };
+        state_write_checked(&state);
+        state_read_checked::<OtherSchemaState>();
+    }
+
+    impl ContractState for SchemaState {
+        fn state_read() -> Option<Self> {
+            state_read_checked()
+        }
+
+        fn state_write(&self) {
+            state_write_checked(self)
+        }
+    }
+
+    #[test]
+    fn test_contract_state() {
+        test_env::setup();
+        let state =
+            SchemaState { value: 5, owners: crate::collections::UnorderedSet::new(b"o".to_vec()) };
+        crate::wrapper::state_write(&state);
+        assert_eq!(
+            env::storage_read(STATE_SCHEMA_KEY),
+            Some(state_schema_fingerprint::<SchemaState>())
+        );
+        let state: SchemaState = crate::wrapper::state_read().unwrap();
+        assert_eq!(state.value, 5);
+    }
+
+    #[test]
+    fn test_state_read_versioned() {
+        test_env::setup();
+        assert_eq!(env::state_read_versioned::<State, StateV0>(), None);
+        env::state_write(&StateV0 { value: 5 });
+        let state = env::state_read_versioned::<State, StateV0>().unwrap();
+        assert_eq!(state, State { value: 5, owner: "alice.near".to_string() });
+        let state = State { value: 6, owner: "bob.near".to_string() };
+        env::state_write(&state);
+        assert_eq!(env::state_read_versioned::<State, StateV0>(), Some(state));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot deserialize the contract state.")]
+    fn test_state_read_versioned_invalid() {
+        test_env::setup();
+        env::state_write(&[1u8, 2]);
+        env::state_read_versioned::<State, StateV0>();
+    }
+
+    #[test]
+    fn test_upgrade_and_migrate() {
+        test_env::setup();
+        set_state_version(1);
+        assert_eq!(upgrade_and_migrate(b"\0asm", 1, "migrate", 10u64.pow(14)), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected the state version 0, found 1.")]
+    fn test_upgrade_and_migrate_wrong_version() {
+        test_env::setup();
+        set_state_version(1);
+        upgrade_and_migrate(b"\0asm", 0, "migrate", 10u64.pow(14));
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract state is not initialized.")]
+    fn test_migrate_without_state() {
+        test_env::setup();
+        VersionedState::migrate();
+    }
+}
@@ -0,0 +1,104 @@
+//! Access to single fields of the stored contract state. The methods of a contract read and write
+//! the whole state struct, which gets expensive when the struct is large and the method only
+//! changes one field. A state struct deriving `PartialState` gets `read_state_<field>` and
+//! `write_state_<field>` functions that decode and replace only the bytes of the field in the
+//! stored state:
+//!
+//! ```ignore
+//! #[near_bindgen]
+//! #[derive(Default, BorshDeserialize, BorshSerialize, PartialState)]
+//! pub struct Contract {
+//!     total_supply: u128,
+//!     accounts: LookupMap<AccountId, Balance>,
+//!     paused: bool,
+//! }
+//!
+//! Contract::write_state_paused(&true);
+//! ```
+//!
+//! The offset of a field is computed at compile time while the fields before it have a fixed size
+//! (the primitive integers, `bool`, and the arrays and tuples of them). After a field of a variable
+//! size, like a `String` or a collection, the offset is found by decoding that field.
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::env;
+
+const STATE_KEY: &[u8] = b"STATE";
+
+/// Reads the stored state. Panics if the state is not initialized.
+pub fn state_data() -> Vec<u8> {
+    env::storage_read(STATE_KEY)
+        .unwrap_or_else(|| env::panic(b"The contract state is not initialized."))
+}
+
+fn decode<T: BorshDeserialize>(data: &[u8], offset: usize) -> (T, usize) {
+    let mut buf = data
+        .get(offset..)
+        .unwrap_or_else(|| env::panic(b"The field is outside of the contract state."));
+    let value = T::deserialize(&mut buf)
+        .unwrap_or_else(|_| env::panic(b"Cannot deserialize the field of the contract state."));
+    (value, data.len() - buf.len())
+}
+
+/// Returns the offset right after the value of type `T` that starts at `offset`.
+pub fn skip<T: BorshDeserialize>(data: &[u8], offset: usize) -> usize {
+    decode::<T>(data, offset).1
+}
+
+/// Decodes the field that starts at `offset`.
+pub fn read_field<T: BorshDeserialize>(data: &[u8], offset: usize) -> T {
+    decode(data, offset).0
+}
+
+/// Replaces the field that starts at `offset` with the value and writes the state.
+pub fn write_field<T: BorshDeserialize + BorshSerialize>(
+    mut data: Vec<u8>,
+    offset: usize,
+    value: &T,
+) {
+    let end = skip::<T>(&data, offset);
+    let bytes = value
+        .try_to_vec()
+        .unwrap_or_else(|_| env::panic(b"Cannot serialize the field of the contract state."));
+    data.splice(offset..end, bytes);
+    env::storage_write(STATE_KEY, &data);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+    struct State {
+        total: u64,
+        name: String,
+        paused: bool,
+    }
+
+    #[test]
+    fn test_read_write_field() {
+        test_env::setup();
+        env::state_write(&State { total: 1, name: "a".to_string(), paused: false });
+        let data = state_data();
+        assert_eq!(read_field::<u64>(&data, 0), 1);
+        let paused_offset = skip::<String>(&data, 8);
+        assert!(!read_field::<bool>(&data, paused_offset));
+
+        write_field(state_data(), 8, &"longer".to_string());
+        let data = state_data();
+        write_field(data.clone(), skip::<String>(&data, 8), &true);
+        assert_eq!(
+            env::state_read(),
+            Some(State { total: 1, name: "longer".to_string(), paused: true })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract state is not initialized.")]
+    fn test_no_state() {
+        test_env::setup();
+        state_data();
+    }
+}
@@ -397,6 +397,15 @@ impl serde::Serialize for Promise {
     }
 }
 
+/// Why the result of a promise, read with `#[callback_result]`, is not available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromiseError {
+    /// The promise failed.
+    Failed,
+    /// The promise succeeded, but its result can't be deserialized into the type of the argument.
+    InvalidResult,
+}
+
 pub enum PromiseOrValue<T> {
     Promise(Promise),
     Value(T),
@@ -447,3 +456,81 @@ impl<T: borsh::BorshSerialize> borsh::BorshSerialize for PromiseOrValue<T> {
         }
     }
 }
+
+/// A promise that waits for the data of another call, e.g. the response of an off-chain service
+/// like an oracle or an MPC signer. The contract creates it, saves its `data_id` and returns it;
+/// the service then calls a method of the contract that resumes it with `YieldedPromise::resume`,
+/// and the runtime calls `function_name` with the payload as the result of promise 0:
+///
+/// ```ignore
+/// pub fn request_price(&mut self, pair: String) {
+///     let promise = YieldedPromise::new("on_price", &[], 10_000_000_000_000, 1);
+///     self.requests.insert(&pair, &promise.data_id().to_vec());
+///     promise.as_return();
+/// }
+///
+/// pub fn respond_price(&mut self, pair: String, price: U128) {
+///     let data_id = self.requests.remove(&pair).expect("No such request");
+///     YieldedPromise::resume(&data_id, &serde_json::to_vec(&price).unwrap());
+/// }
+/// ```
+///
+/// If nobody resumes the promise, it times out after a number of blocks set by the runtime, and
+/// `function_name` is called with a failed promise result. Requires the `yield-resume` feature.
+#[cfg(feature = "yield-resume")]
+pub struct YieldedPromise {
+    promise_index: PromiseIndex,
+    data_id: Vec<u8>,
+}
+
+#[cfg(feature = "yield-resume")]
+impl YieldedPromise {
+    /// Yields a call of `function_name` of the current account, see `env::promise_yield_create`.
+    pub fn new(function_name: &str, arguments: &[u8], gas: Gas, gas_weight: u64) -> Self {
+        let (promise_index, data_id) =
+            crate::env::promise_yield_create(function_name, arguments, gas, gas_weight);
+        Self { promise_index, data_id }
+    }
+
+    /// The id of the data that resumes the promise.
+    pub fn data_id(&self) -> &[u8] {
+        &self.data_id
+    }
+
+    pub fn promise_index(&self) -> PromiseIndex {
+        self.promise_index
+    }
+
+    /// Makes the result of `function_name` the result of the current method.
+    pub fn as_return(self) {
+        crate::env::promise_return(self.promise_index);
+    }
+
+    /// Resumes the promise with the data id. Returns `false` if there is no such promise, or if it
+    /// was already resumed or timed out.
+    pub fn resume(data_id: &[u8], payload: &[u8]) -> bool {
+        crate::env::promise_yield_resume(data_id, payload)
+    }
+}
+
+#[cfg(all(feature = "yield-resume", not(target_arch = "wasm32")))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_yielded_promise() {
+        test_env::setup();
+        let first = YieldedPromise::new("on_response", b"{}", 10u64.pow(13), 1);
+        let second = YieldedPromise::new("on_response", b"{}", 10u64.pow(13), 1);
+        assert_ne!(first.data_id(), second.data_id());
+        assert_eq!(first.data_id().len(), 32);
+        let data_id = first.data_id().to_vec();
+        first.as_return();
+        assert!(YieldedPromise::resume(&data_id, b"42"));
+        assert!(!YieldedPromise::resume(&data_id, b"42"));
+        assert!(!YieldedPromise::resume(&[7; 32], b"42"));
+        assert_eq!(crate::env::created_receipts().len(), 2);
+    }
+}
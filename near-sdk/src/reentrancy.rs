@@ -0,0 +1,96 @@
+//! Guards against the interleaving of the calls of a contract. A call of a contract that schedules
+//! promises finishes before the promises are executed, so other calls may change the state of the
+//! contract before its callback runs. An operation locks its name in the storage before scheduling
+//! the promises, and the callback unlocks it, so that the operation can't be started again while
+//! it's in progress.
+//!
+//! The methods of a contract inside `#[near_bindgen]` take the lock with
+//! `#[non_reentrant(lock = "withdraw")]` and release it with `#[non_reentrant(unlock = "withdraw")]`.
+//! The lock stays taken until it's released, so the method that takes it should always schedule
+//! the callback that releases it, or release it with `unlock` when it doesn't.
+//!
+//! The method with `unlock` is a callback: its wrapper panics unless the contract calls it, and
+//! releases the lock before it deserializes the arguments and the results of the promises. A panic
+//! still rolls back all the changes of the call, including the release, so the callback should not
+//! panic. It reads the results of the promises with `#[callback_result]`, which doesn't panic when
+//! the promise failed, and `#[callback]` and `#[callback_vec]` are rejected:
+//!
+//! ```ignore
+//! #[non_reentrant(unlock = "withdraw")]
+//! pub fn on_withdraw(&mut self, #[callback_result] result: Result<(), PromiseError>) { ... }
+//! ```
+//!
+//! The lock of an operation is shared by all the users, so a lock that stays taken after a bug
+//! blocks the operation for everyone. The owner can release it with `force_unlock`:
+//!
+//! ```ignore
+//! #[only_owner]
+//! pub fn force_unlock(&mut self, operation: String) -> bool {
+//!     near_sdk::reentrancy::force_unlock(&operation)
+//! }
+//! ```
+use crate::env;
+
+/// The prefix of the storage keys of the locks.
+const LOCK_KEY_PREFIX: &[u8] = b"__lock:";
+
+fn lock_key(operation: &str) -> Vec<u8> {
+    [LOCK_KEY_PREFIX, operation.as_bytes()].concat()
+}
+
+/// Returns `true` if the operation is in progress.
+pub fn is_locked(operation: &str) -> bool {
+    env::storage_has_key(&lock_key(operation))
+}
+
+/// Marks the operation as in progress. Panics if it's already in progress.
+pub fn lock(operation: &str) {
+    if env::storage_write(&lock_key(operation), &[]) {
+        env::panic(format!("The operation {} is already in progress", operation).as_bytes());
+    }
+}
+
+/// Marks the operation as finished. Does nothing if it's not in progress.
+pub fn unlock(operation: &str) {
+    env::storage_remove(&lock_key(operation));
+}
+
+/// Marks the operation as finished outside of its callback, e.g. by the owner when the lock stays
+/// taken after a failure. Logs the release and returns `true` if the operation was in progress.
+pub fn force_unlock(operation: &str) -> bool {
+    let was_locked = env::storage_remove(&lock_key(operation));
+    if was_locked {
+        env::log(format!("Force-unlocked the operation {}", operation).as_bytes());
+    }
+    was_locked
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_lock_unlock() {
+        test_env::setup();
+        assert!(!is_locked("withdraw"));
+        lock("withdraw");
+        assert!(is_locked("withdraw"));
+        assert!(!is_locked("deposit"));
+        unlock("withdraw");
+        assert!(!is_locked("withdraw"));
+        lock("withdraw");
+        assert!(force_unlock("withdraw"));
+        assert!(!is_locked("withdraw"));
+        assert!(!force_unlock("withdraw"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The operation withdraw is already in progress")]
+    fn test_lock_twice() {
+        test_env::setup();
+        lock("withdraw");
+        lock("withdraw");
+    }
+}
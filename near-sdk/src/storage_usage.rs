@@ -0,0 +1,123 @@
+use crate::env;
+use crate::json_types::U64;
+use crate::Balance;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A number of bytes of the contract storage, like `env::storage_usage`. It's a separate type from
+/// the yoctoNEAR amounts, so the bytes are only turned into NEAR with `cost`. It's serialized with
+/// Borsh as `u64` and with JSON as a decimal string, like `U64`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshDeserialize,
+    BorshSerialize,
+)]
+pub struct StorageUsage(u64);
+
+impl StorageUsage {
+    pub const fn from_bytes(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(self) -> u64 {
+        self.0
+    }
+
+    /// The amount of yoctoNEAR the account has to keep on its balance to pay for the bytes, with
+    /// `env::storage_byte_cost`.
+    pub fn cost(self) -> Balance {
+        Balance::from(self.0) * env::storage_byte_cost()
+    }
+
+    pub fn checked_add(self, other: StorageUsage) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// The bytes added since `earlier`, or `None` if the storage shrank.
+    pub fn checked_sub(self, earlier: StorageUsage) -> Option<Self> {
+        self.0.checked_sub(earlier.0).map(Self)
+    }
+
+    /// The bytes added since `earlier`, or zero if the storage shrank.
+    pub fn saturating_sub(self, earlier: StorageUsage) -> Self {
+        Self(self.0.saturating_sub(earlier.0))
+    }
+}
+
+impl From<u64> for StorageUsage {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<StorageUsage> for u64 {
+    fn from(storage_usage: StorageUsage) -> Self {
+        storage_usage.0
+    }
+}
+
+impl Serialize for StorageUsage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Serialize::serialize(&U64(self.0), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StorageUsage {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <U64 as Deserialize>::deserialize(deserializer).map(|bytes| Self(bytes.0))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_cost() {
+        assert_eq!(StorageUsage::from_bytes(100).cost(), 100 * env::storage_byte_cost());
+        assert_eq!(
+            StorageUsage::from_bytes(u32::MAX.into()).cost(),
+            Balance::from(u32::MAX) * 10u128.pow(20)
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        test_env::setup();
+        let initial = env::storage_usage();
+        env::storage_write(b"key", b"value");
+        let added = env::storage_usage().checked_sub(initial).unwrap();
+        assert!(added.as_bytes() > 8);
+        env::storage_remove(b"key");
+        assert_eq!(env::storage_usage(), initial);
+        assert_eq!(initial.checked_sub(initial.checked_add(added).unwrap()), None);
+        assert_eq!(
+            initial.saturating_sub(initial.checked_add(added).unwrap()),
+            StorageUsage::default()
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let storage_usage = StorageUsage::from_bytes(125);
+        assert_eq!(serde_json::to_string(&storage_usage).unwrap(), "\"125\"");
+        assert_eq!(serde_json::from_str::<StorageUsage>("\"125\"").unwrap(), storage_usage);
+        assert_eq!(storage_usage.try_to_vec().unwrap(), 125u64.try_to_vec().unwrap());
+    }
+}
@@ -1,6 +1,6 @@
 pub(crate) mod test_env {
     use crate::{env, MockedBlockchain};
-    use near_vm_logic::types::AccountId;
+    use near_vm_logic::types::{AccountId, Balance};
     use near_vm_logic::{VMContext, VMConfig};
 
     fn alice() -> AccountId {
@@ -15,11 +15,18 @@ pub(crate) mod test_env {
         "carol.near".to_string()
     }
 
-    fn setup_with_config(vm_config: VMConfig) {
+    /// An ED25519 key: the curve byte and the 32 bytes of the key.
+    fn signer_pk() -> Vec<u8> {
+        let mut pk = vec![0];
+        pk.extend_from_slice(&[1; 32]);
+        pk
+    }
+
+    fn setup_with_config(vm_config: VMConfig, attached_deposit: Balance) {
         let context = VMContext {
             current_account_id: alice(),
             signer_account_id: bob(),
-            signer_account_pk: vec![0, 1, 2],
+            signer_account_pk: signer_pk(),
             predecessor_account_id: carol(),
             input: vec![],
             block_index: 0,
@@ -27,7 +34,7 @@ pub(crate) mod test_env {
             account_balance: 0,
             account_locked_balance: 0,
             storage_usage: 10u64.pow(6),
-            attached_deposit: 0,
+            attached_deposit,
             prepaid_gas: 10u64.pow(18),
             random_seed: vec![0, 1, 2],
             is_view: false,
@@ -49,11 +56,15 @@ pub(crate) mod test_env {
     }
 
     pub(crate) fn setup() {
-        setup_with_config(VMConfig::default());
+        setup_with_config(VMConfig::default(), 0);
+    }
+
+    pub(crate) fn setup_with_deposit(attached_deposit: Balance) {
+        setup_with_config(VMConfig::default(), attached_deposit);
     }
 
     // free == effectively unlimited gas
     pub(crate) fn setup_free() {
-        setup_with_config(VMConfig::free());
+        setup_with_config(VMConfig::free(), 0);
     }
 }
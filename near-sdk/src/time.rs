@@ -0,0 +1,322 @@
+//! Block time in nanoseconds. `env::block_timestamp` is the number of nanoseconds since the Unix
+//! epoch, while the clients often pass milliseconds or seconds, so the deadlines computed with raw
+//! `u64` values are easy to get wrong by a factor of a million. `Timestamp` and `Duration` keep the
+//! nanoseconds inside, and the arithmetic on them is checked:
+//!
+//! ```ignore
+//! let deadline = Timestamp::now().checked_add(Duration::DAY * 7).expect("Overflow");
+//! if Timestamp::now() > deadline { ... }
+//! ```
+//!
+//! Both are serialized with JSON as the decimal strings of the nanoseconds, like `U64`. With the
+//! `chrono` feature they convert to and from `chrono::DateTime<Utc>` and `chrono::Duration`, for
+//! the indexers and the clients that share the types with the contract.
+use crate::env;
+use crate::json_types::U64;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::num::TryFromIntError;
+use std::ops::Mul;
+
+const NANOS_PER_MILLI: u64 = 1_000_000;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// A point in time, in nanoseconds since January 1, 1970 0:00:00 UTC.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshDeserialize,
+    BorshSerialize,
+)]
+pub struct Timestamp(u64);
+
+/// A span of time in nanoseconds.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshDeserialize,
+    BorshSerialize,
+)]
+pub struct Duration(u64);
+
+impl Timestamp {
+    /// The timestamp of the current block.
+    pub fn now() -> Self {
+        Self(env::block_timestamp())
+    }
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+
+    /// Returns `None` if the timestamp doesn't fit into `u64` nanoseconds.
+    pub fn from_millis(millis: u64) -> Option<Self> {
+        millis.checked_mul(NANOS_PER_MILLI).map(Self)
+    }
+
+    /// Returns `None` if the timestamp doesn't fit into `u64` nanoseconds.
+    pub fn from_secs(secs: u64) -> Option<Self> {
+        secs.checked_mul(NANOS_PER_SEC).map(Self)
+    }
+
+    pub const fn as_nanos(self) -> u64 {
+        self.0
+    }
+
+    /// The whole milliseconds, rounded down.
+    pub const fn as_millis(self) -> u64 {
+        self.0 / NANOS_PER_MILLI
+    }
+
+    /// The whole seconds, rounded down.
+    pub const fn as_secs(self) -> u64 {
+        self.0 / NANOS_PER_SEC
+    }
+
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration.0).map(Self)
+    }
+
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration.0).map(Self)
+    }
+
+    /// The time from `earlier` to `self`, or `None` if `earlier` is later than `self`.
+    pub fn checked_duration_since(self, earlier: Timestamp) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration)
+    }
+}
+
+impl Duration {
+    pub const NANOSECOND: Duration = Duration(1);
+    pub const MILLISECOND: Duration = Duration(NANOS_PER_MILLI);
+    pub const SECOND: Duration = Duration(NANOS_PER_SEC);
+    pub const MINUTE: Duration = Duration(60 * NANOS_PER_SEC);
+    pub const HOUR: Duration = Duration(60 * 60 * NANOS_PER_SEC);
+    pub const DAY: Duration = Duration(24 * 60 * 60 * NANOS_PER_SEC);
+
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+
+    /// Returns `None` if the duration doesn't fit into `u64` nanoseconds.
+    pub fn from_millis(millis: u64) -> Option<Self> {
+        Self::MILLISECOND.checked_mul(millis)
+    }
+
+    /// Returns `None` if the duration doesn't fit into `u64` nanoseconds.
+    pub fn from_secs(secs: u64) -> Option<Self> {
+        Self::SECOND.checked_mul(secs)
+    }
+
+    /// Returns `None` if the duration doesn't fit into `u64` nanoseconds.
+    pub fn from_days(days: u64) -> Option<Self> {
+        Self::DAY.checked_mul(days)
+    }
+
+    pub const fn as_nanos(self) -> u64 {
+        self.0
+    }
+
+    /// The whole milliseconds, rounded down.
+    pub const fn as_millis(self) -> u64 {
+        self.0 / NANOS_PER_MILLI
+    }
+
+    /// The whole seconds, rounded down.
+    pub const fn as_secs(self) -> u64 {
+        self.0 / NANOS_PER_SEC
+    }
+
+    pub fn checked_add(self, other: Duration) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Duration) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn checked_mul(self, factor: u64) -> Option<Self> {
+        self.0.checked_mul(factor).map(Self)
+    }
+}
+
+/// Panics on overflow, for the constant expressions like `Duration::DAY * 7`. Use `checked_mul`
+/// with the values that come from the input.
+impl Mul<u64> for Duration {
+    type Output = Duration;
+
+    fn mul(self, factor: u64) -> Duration {
+        self.checked_mul(factor).unwrap_or_else(|| env::panic(b"Duration overflow"))
+    }
+}
+
+macro_rules! impl_nanos_serde {
+    ($iden: ident) => {
+        impl Serialize for $iden {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                Serialize::serialize(&U64(self.0), serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $iden {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                <U64 as Deserialize>::deserialize(deserializer).map(|nanos| Self(nanos.0))
+            }
+        }
+    };
+}
+
+impl_nanos_serde!(Timestamp);
+impl_nanos_serde!(Duration);
+
+impl From<Duration> for std::time::Duration {
+    fn from(duration: Duration) -> Self {
+        std::time::Duration::from_nanos(duration.0)
+    }
+}
+
+/// Fails if the duration doesn't fit into `u64` nanoseconds.
+impl TryFrom<std::time::Duration> for Duration {
+    type Error = TryFromIntError;
+
+    fn try_from(duration: std::time::Duration) -> Result<Self, Self::Error> {
+        u64::try_from(duration.as_nanos()).map(Self)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    fn from(timestamp: Timestamp) -> Self {
+        // `DateTime` reaches much further than the year 2554 of `u64::MAX` nanoseconds.
+        chrono::DateTime::from_timestamp(
+            timestamp.as_secs() as i64,
+            (timestamp.0 % NANOS_PER_SEC) as u32,
+        )
+        .unwrap_or_else(|| unreachable!())
+    }
+}
+
+/// Fails for the times before the Unix epoch or after the year 2554.
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for Timestamp {
+    type Error = TryFromIntError;
+
+    fn try_from(time: chrono::DateTime<chrono::Utc>) -> Result<Self, Self::Error> {
+        let nanos = i128::from(time.timestamp()) * i128::from(NANOS_PER_SEC)
+            + i128::from(time.timestamp_subsec_nanos());
+        u64::try_from(nanos).map(Self)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Duration> for chrono::Duration {
+    fn from(duration: Duration) -> Self {
+        chrono::Duration::seconds(duration.as_secs() as i64)
+            + chrono::Duration::nanoseconds((duration.0 % NANOS_PER_SEC) as i64)
+    }
+}
+
+/// Fails for the negative durations.
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::Duration> for Duration {
+    type Error = TryFromIntError;
+
+    fn try_from(duration: chrono::Duration) -> Result<Self, Self::Error> {
+        let nanos = i128::from(duration.num_seconds()) * i128::from(NANOS_PER_SEC)
+            + i128::from(duration.subsec_nanos());
+        u64::try_from(nanos).map(Self)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        assert_eq!(Duration::DAY, Duration::from_secs(86_400).unwrap());
+        assert_eq!(Duration::from_days(2).unwrap().as_millis(), 172_800_000);
+        assert_eq!(
+            Duration::SECOND * 90,
+            Duration::MINUTE.checked_add(Duration::SECOND * 30).unwrap()
+        );
+        assert_eq!(Timestamp::from_millis(1_500).unwrap().as_nanos(), 1_500_000_000);
+        assert_eq!(Timestamp::from_nanos(2_999_999_999).as_secs(), 2);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let start = Timestamp::from_secs(100).unwrap();
+        let end = start.checked_add(Duration::MINUTE).unwrap();
+        assert_eq!(end.checked_duration_since(start), Some(Duration::MINUTE));
+        assert_eq!(start.checked_duration_since(end), None);
+        assert_eq!(end.checked_sub(Duration::MINUTE), Some(start));
+        assert_eq!(start.checked_sub(Duration::HOUR), None);
+        assert_eq!(Timestamp::from_nanos(u64::MAX).checked_add(Duration::NANOSECOND), None);
+        assert_eq!(Duration::from_days(u64::MAX / 1000), None);
+        assert_eq!(Timestamp::from_secs(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_now() {
+        crate::test_utils::test_env::setup();
+        assert_eq!(Timestamp::now().as_nanos(), env::block_timestamp());
+        assert_eq!(env::block_timestamp_ms(), Timestamp::now().as_millis());
+    }
+
+    #[test]
+    fn test_std_duration() {
+        let duration = std::time::Duration::from_millis(1_500);
+        assert_eq!(Duration::try_from(duration), Ok(Duration::from_millis(1_500).unwrap()));
+        assert_eq!(std::time::Duration::from(Duration::MINUTE), std::time::Duration::from_secs(60));
+        assert!(Duration::try_from(std::time::Duration::from_secs(u64::MAX)).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        use chrono::{DateTime, TimeZone, Utc};
+        let timestamp = Timestamp::from_nanos(1_600_000_000_123_456_789);
+        let time = DateTime::<Utc>::from(timestamp);
+        assert_eq!(time.to_rfc3339(), "2020-09-13T12:26:40.123456789+00:00");
+        assert_eq!(Timestamp::try_from(time), Ok(timestamp));
+        let latest = Timestamp::from_nanos(u64::MAX);
+        assert_eq!(Timestamp::try_from(DateTime::<Utc>::from(latest)), Ok(latest));
+        assert!(Timestamp::try_from(Utc.with_ymd_and_hms(1969, 12, 31, 0, 0, 0).unwrap()).is_err());
+        let duration = chrono::Duration::from(Duration::from_nanos(u64::MAX));
+        assert_eq!(Duration::try_from(duration), Ok(Duration::from_nanos(u64::MAX)));
+        assert!(Duration::try_from(chrono::Duration::seconds(-1)).is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let timestamp = Timestamp::from_nanos(1_600_000_000_000_000_000);
+        let json = serde_json::to_string(&timestamp).unwrap();
+        assert_eq!(json, "\"1600000000000000000\"");
+        assert_eq!(serde_json::from_str::<Timestamp>(&json).unwrap(), timestamp);
+        assert_eq!(serde_json::to_string(&Duration::SECOND).unwrap(), "\"1000000000\"");
+    }
+}
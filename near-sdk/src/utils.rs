@@ -0,0 +1,70 @@
+//! Checks shared by the contracts.
+use crate::{diagnostics, env, Balance, ContractError, Promise};
+
+/// Panics unless exactly 1 yoctoNEAR is attached. Requiring the deposit forces the caller to sign
+/// the transaction with a full access key, so the transfers of the FT and NFT standards require it.
+/// `#[one_yocto]` on a method calls it before the method.
+pub fn assert_one_yocto() {
+    if env::attached_deposit() != 1 {
+        env::panic_err_with(
+            ContractError::INVALID_DEPOSIT,
+            "Requires attached deposit of exactly 1 yoctoNEAR",
+            diagnostics::call_context,
+        );
+    }
+}
+
+/// Panics unless at least `amount` yoctoNEAR is attached.
+pub fn assert_at_least_attached(amount: Balance) {
+    if env::attached_deposit() < amount {
+        env::panic_err_with(
+            ContractError::INVALID_DEPOSIT,
+            &format!("Requires attached deposit of at least {} yoctoNEAR", amount),
+            diagnostics::call_context,
+        );
+    }
+}
+
+/// Panics unless at least `required` yoctoNEAR is attached, and transfers the rest of the deposit
+/// back to the predecessor. Returns the refunded amount.
+pub fn refund_excess_deposit(required: Balance) -> Balance {
+    assert_at_least_attached(required);
+    let refund = env::attached_deposit() - required;
+    if refund > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(refund);
+    }
+    refund
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_assert_one_yocto() {
+        test_env::setup();
+        assert_one_yocto();
+    }
+
+    #[test]
+    fn test_refund_excess_deposit() {
+        test_env::setup_with_deposit(100);
+        assert_at_least_attached(100);
+        assert_eq!(refund_excess_deposit(100), 0);
+        assert!(env::created_receipts().is_empty());
+        assert_eq!(refund_excess_deposit(30), 70);
+        assert_eq!(env::created_receipts().len(), 1);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of at least 101 yoctoNEAR")]
+    fn test_refund_excess_deposit_insufficient() {
+        test_env::setup_with_deposit(100);
+        refund_excess_deposit(101);
+    }
+}
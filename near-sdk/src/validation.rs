@@ -0,0 +1,217 @@
+//! Checks of the arguments that `#[validate(...)]` generates in the method wrappers:
+//!
+//! ```ignore
+//! pub fn set_status(
+//!     &mut self,
+//!     #[validate(len(min = 1, max = 64), regex = "^[a-z ]+$")] message: String,
+//!     #[validate(range(min = 1, max = 10))] priority: u8,
+//! ) { ... }
+//! ```
+//!
+//! The checks run after the arguments are deserialized and panic with `INVALID_INPUT` and the name
+//! of the failing argument. `regex` requires the `validate-regex` feature, which adds the `regex`
+//! crate to the contract.
+use crate::json_types::{I128, I64, U128, U64};
+use crate::{env, ContractError};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+
+/// The length checked by `len(...)`: the number of the characters of a string, or the number of
+/// the elements of a collection.
+pub trait Length {
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for HashMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for HashSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for BTreeSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// A value checked by `range(...)`. The bounds are written in the underlying integer type, so
+/// `range(min = 1)` also works on the JSON wrappers like `U128`.
+pub trait Range {
+    type Bound: PartialOrd + Display;
+
+    fn range_value(&self) -> Self::Bound;
+}
+
+macro_rules! impl_range {
+    ($($ty: ty),*) => {
+        $(impl Range for $ty {
+            type Bound = $ty;
+
+            fn range_value(&self) -> $ty {
+                *self
+            }
+        })*
+    };
+}
+
+impl_range!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+macro_rules! impl_range_json {
+    ($($ty: ty => $bound: ty),*) => {
+        $(impl Range for $ty {
+            type Bound = $bound;
+
+            fn range_value(&self) -> $bound {
+                self.0
+            }
+        })*
+    };
+}
+
+impl_range_json!(U64 => u64, U128 => u128, I64 => i64, I128 => i128);
+
+fn invalid_argument(name: &str, reason: &str) -> ! {
+    env::panic_err(
+        ContractError::INVALID_INPUT,
+        &format!("Invalid argument `{}`: {}", name, reason),
+    )
+}
+
+/// Panics unless `min <= value <= max`.
+pub fn check_range<T: Range + ?Sized>(
+    name: &str,
+    value: &T,
+    min: Option<T::Bound>,
+    max: Option<T::Bound>,
+) {
+    let value = value.range_value();
+    if let Some(min) = min {
+        if value < min {
+            invalid_argument(name, &format!("{} is less than {}", value, min));
+        }
+    }
+    if let Some(max) = max {
+        if value > max {
+            invalid_argument(name, &format!("{} is greater than {}", value, max));
+        }
+    }
+}
+
+/// Panics unless the length of the value is within `min..=max`.
+pub fn check_len<T: Length + ?Sized>(
+    name: &str,
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>,
+) {
+    let length = value.length();
+    if let Some(min) = min {
+        if length < min {
+            invalid_argument(name, &format!("the length {} is less than {}", length, min));
+        }
+    }
+    if let Some(max) = max {
+        if length > max {
+            invalid_argument(name, &format!("the length {} is greater than {}", length, max));
+        }
+    }
+}
+
+/// Panics unless the string matches the regular expression.
+#[cfg(feature = "validate-regex")]
+pub fn check_regex<T: AsRef<str> + ?Sized>(name: &str, value: &T, regex: &str) {
+    let regex = regex::Regex::new(regex).unwrap_or_else(|err| {
+        env::panic(format!("Invalid regex of `{}`: {}", name, err).as_bytes())
+    });
+    if !regex.is_match(value.as_ref()) {
+        invalid_argument(name, &format!("doesn't match `{}`", regex));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_valid() {
+        test_env::setup();
+        check_range("amount", &5u64, Some(1), Some(5));
+        check_range("delta", &-1i32, Some(-1), None);
+        check_range("deposit", &U128(10u128.pow(24)), Some(1), Some(10u128.pow(24)));
+        check_len("memo", "ab", Some(1), Some(2));
+        check_len("memo", &"ключ".to_string(), None, Some(4));
+        check_len("ids", &vec![1, 2], Some(2), None);
+        #[cfg(feature = "validate-regex")]
+        check_regex("name", "alice", "^[a-z]+$");
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Invalid argument `amount`: 0 is less than 1")]
+    fn test_range_min() {
+        test_env::setup();
+        check_range("amount", &0u128, Some(1), None);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Invalid argument `deposit`: 0 is less than 1")]
+    fn test_range_json_integer() {
+        test_env::setup();
+        check_range("deposit", &U128(0), Some(1), None);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Invalid argument `memo`: the length 3 is greater than 2")]
+    fn test_len_max() {
+        test_env::setup();
+        check_len("memo", "abc", None, Some(2));
+    }
+
+    #[cfg(all(feature = "validate-regex", not(feature = "short-panics")))]
+    #[test]
+    #[should_panic(expected = "Invalid argument `name`")]
+    fn test_regex() {
+        test_env::setup();
+        check_regex("name", "Alice", "^[a-z]+$");
+    }
+}
@@ -0,0 +1,712 @@
+//! The parts of the wrappers that `#[near_bindgen]` generates for the methods of a contract. Every
+//! method of the contract has its own wrapper, so the shared steps live here and are compiled once,
+//! instead of being expanded into each wrapper. The generic functions keep the non-generic part,
+//! like reading the input and the promise results, in separate functions.
+//!
+//! With the `detailed-input-errors` feature the wrappers deserialize the JSON input with
+//! `input_json_detailed`, which names the missing or invalid argument in the panic message.
+//!
+//! The wrappers fail with `env::panic_err`: the panic message is the JSON of a `ContractError`, with
+//! the code of the failure class and the message below. With the `short-panics` feature the
+//! wrappers and the state access of `env` panic with short codes instead, and the panics of the
+//! contract itself are reported as `E0`. The contract then doesn't format the errors, which leaves
+//! `core::fmt` out of the small contracts:
+//!
+//! | Code  | Class                 | Message                                           |
+//! |-------|-----------------------|---------------------------------------------------|
+//! | `E0`  |                       | Any other panic of the contract                   |
+//! | `E1`  | `INVALID_DEPOSIT`     | Method doesn't accept deposit                     |
+//! | `E2`  | `INVALID_INPUT`       | Expected input since method has arguments.        |
+//! | `E3`  | `INVALID_INPUT`       | Failed to deserialize input from JSON.            |
+//! | `E4`  | `INVALID_INPUT`       | Failed to deserialize input from Borsh.           |
+//! | `E5`  | `CALLBACK_FAILED`     | Callback computation was not successful           |
+//! | `E6`  | `DESERIALIZATION`     | Failed to deserialize callback using JSON         |
+//! | `E7`  | `DESERIALIZATION`     | Failed to deserialize callback using Borsh        |
+//! | `E8`  | `SERIALIZATION`       | Failed to serialize the return value using JSON.  |
+//! | `E9`  | `SERIALIZATION`       | Failed to serialize the return value using Borsh. |
+//! | `E10` | `DESERIALIZATION`     | Cannot deserialize the contract state.            |
+//! | `E11` | `SERIALIZATION`       | Cannot serialize the contract state.              |
+//! | `E12` | `INVALID_INPUT`       | Input is too large.                               |
+//! | `E13` | `ALREADY_INITIALIZED` | The contract has already been initialized.        |
+//! | `E14` | `INVALID_INPUT`       | Unknown method.                                   |
+//! | `E15` | `SERIALIZATION`       | Failed to serialize the continuation arguments.   |
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "json")]
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::RefCell;
+use std::fmt::Debug;
+
+use crate::migration::ContractState;
+use crate::{
+    diagnostics, env, BlockchainInterface, ContractError, Gas, Promise, PromiseError, PromiseResult,
+};
+
+#[cfg(feature = "light-json")]
+use crate::light_json::{from_slice as json_from_slice, to_vec as json_to_vec};
+#[cfg(all(feature = "json", not(feature = "light-json")))]
+use serde_json::{from_slice as json_from_slice, to_vec as json_to_vec};
+
+/// The class of the failure with the short code, the code of its `ContractError`.
+#[cfg(not(feature = "short-panics"))]
+fn error_class(code: &str) -> &'static str {
+    match code {
+        "E1" => ContractError::INVALID_DEPOSIT,
+        "E2" | "E3" | "E4" | "E12" | "E14" => ContractError::INVALID_INPUT,
+        "E5" => ContractError::CALLBACK_FAILED,
+        "E8" | "E9" | "E11" | "E15" => ContractError::SERIALIZATION,
+        "E13" => ContractError::ALREADY_INITIALIZED,
+        _ => ContractError::DESERIALIZATION,
+    }
+}
+
+/// Panics with the `ContractError` of the class of the code and the message, or with the code with
+/// the `short-panics` feature.
+#[cfg(not(feature = "short-panics"))]
+pub(crate) fn fail(code: &str, message: &str) -> ! {
+    env::panic_err(error_class(code), message)
+}
+
+/// Panics with the `ContractError` of the class of the code and the message, or with the code with
+/// the `short-panics` feature.
+#[cfg(feature = "short-panics")]
+pub(crate) fn fail(code: &str, _message: &str) -> ! {
+    env::panic(code.as_bytes())
+}
+
+/// Panics like `fail`, with the context of the failure with the `debug-diagnostics` feature.
+#[cfg(not(feature = "short-panics"))]
+pub(crate) fn fail_with(code: &str, message: &str, context: impl FnOnce() -> String) -> ! {
+    env::panic_err_with(error_class(code), message, context)
+}
+
+/// Panics like `fail`, with the context of the failure with the `debug-diagnostics` feature.
+#[cfg(feature = "short-panics")]
+pub(crate) fn fail_with(code: &str, _message: &str, _context: impl FnOnce() -> String) -> ! {
+    env::panic(code.as_bytes())
+}
+
+/// Unwraps the result, or panics with the message and the error. With the `short-panics` feature
+/// it panics with the code and drops the error.
+#[cfg(not(feature = "short-panics"))]
+pub(crate) fn unwrap_or_fail<T, E: Debug>(result: Result<T, E>, code: &str, message: &str) -> T {
+    result.unwrap_or_else(|err| fail(code, &format!("{}: {:?}", message, err)))
+}
+
+/// Unwraps the result like `unwrap_or_fail`, with the context of the failure with the
+/// `debug-diagnostics` feature.
+#[cfg(not(feature = "short-panics"))]
+pub(crate) fn unwrap_or_fail_with<T, E: Debug>(
+    result: Result<T, E>,
+    code: &str,
+    message: &str,
+    context: impl FnOnce() -> String,
+) -> T {
+    result.unwrap_or_else(|err| fail_with(code, &format!("{}: {:?}", message, err), context))
+}
+
+/// Unwraps the result like `unwrap_or_fail`, with the context of the failure with the
+/// `debug-diagnostics` feature.
+#[cfg(feature = "short-panics")]
+pub(crate) fn unwrap_or_fail_with<T, E: Debug>(
+    result: Result<T, E>,
+    code: &str,
+    _message: &str,
+    _context: impl FnOnce() -> String,
+) -> T {
+    result.unwrap_or_else(|_| env::panic(code.as_bytes()))
+}
+
+/// Unwraps the result, or panics with the message and the error. With the `short-panics` feature
+/// it panics with the code and drops the error.
+#[cfg(feature = "short-panics")]
+pub(crate) fn unwrap_or_fail<T, E: Debug>(result: Result<T, E>, code: &str, _message: &str) -> T {
+    result.unwrap_or_else(|_| env::panic(code.as_bytes()))
+}
+
+/// Sets up the panic hook and the blockchain interface at the start of the method.
+pub fn setup(blockchain_interface: Box<dyn BlockchainInterface>) {
+    #[cfg(not(feature = "short-panics"))]
+    env::setup_panic_hook();
+    #[cfg(feature = "short-panics")]
+    std::panic::set_hook(Box::new(|_| env::panic(b"E0")));
+    env::set_blockchain_interface(blockchain_interface);
+}
+
+/// Panics if the method that is not payable receives a deposit.
+pub fn assert_no_deposit() {
+    if env::attached_deposit() != 0 {
+        fail_with("E1", "Method doesn't accept deposit", diagnostics::call_context);
+    }
+}
+
+/// Panics if the `#[init]` method is called when the contract state already exists, so the state
+/// is not silently replaced. `#[init(ignore_state)]` skips the check, e.g. for the migrations.
+pub fn assert_not_initialized() {
+    if env::state_exists() {
+        fail_with("E13", "The contract has already been initialized", diagnostics::call_context);
+    }
+}
+
+/// Reads the contract state with the `ContractState` of the contract struct, which checks the
+/// schema of the state with `#[near_bindgen(state_schema)]` on the struct.
+pub fn state_read<T: ContractState + BorshDeserialize>() -> Option<T> {
+    T::state_read()
+}
+
+/// Writes the contract state with the `ContractState` of the contract struct.
+pub fn state_write<T: ContractState + BorshSerialize>(state: &T) {
+    state.state_write()
+}
+
+/// Panics with the error of the `#[init]` method that returns `Result<Self, E>`, so the state is
+/// not written. The error is converted into `ContractError` for its code and message.
+pub fn init_error<E: Into<ContractError>>(err: E) -> ! {
+    let error = err.into();
+    env::panic_err(&error.code, &error.message)
+}
+
+/// Panics if the input is longer than `max_len` bytes, before it's read into the memory of the
+/// contract. `#[near_bindgen(max_input_len = ...)]` on an impl section checks it before the
+/// arguments of its methods are deserialized.
+pub fn assert_input_len(max_len: u64) {
+    if env::input_len().unwrap_or(0) > max_len {
+        fail_with("E12", "Input is too large", || format!("{{\"max_input_len\":{}}}", max_len));
+    }
+}
+
+/// The gas used at the entry of a `#[measure_gas]` method, see `measure_gas_entry`.
+#[derive(Debug, Clone, Copy)]
+pub struct GasMeter {
+    method: &'static str,
+    entry_gas: Gas,
+}
+
+/// Logs `measure_gas <method>: entry <used gas>` at the entry of a `#[measure_gas]` method, with
+/// the `measure-gas` feature. Without the feature the attribute logs nothing.
+pub fn measure_gas_entry(method: &'static str) -> GasMeter {
+    if !cfg!(feature = "measure-gas") {
+        return GasMeter { method, entry_gas: 0 };
+    }
+    let entry_gas = env::used_gas();
+    env::log(format!("measure_gas {}: entry {}", method, entry_gas).as_bytes());
+    GasMeter { method, entry_gas }
+}
+
+/// Logs `measure_gas <method>: exit <used gas>, method <gas used since the entry>` at the exit of
+/// a `#[measure_gas]` method, with the `measure-gas` feature.
+pub fn measure_gas_exit(meter: GasMeter) {
+    if !cfg!(feature = "measure-gas") {
+        return;
+    }
+    let exit_gas = env::used_gas();
+    env::log(
+        format!(
+            "measure_gas {}: exit {}, method {}",
+            meter.method,
+            exit_gas,
+            exit_gas.saturating_sub(meter.entry_gas)
+        )
+        .as_bytes(),
+    );
+}
+
+thread_local! {
+    /// The arguments of the method called through the entry point of
+    /// `#[near_bindgen(dispatch = "...")]`, which the wrapper reads instead of the whole input.
+    static DISPATCHED_ARGS: RefCell<Option<Option<Vec<u8>>>> = const { RefCell::new(None) };
+}
+
+/// Forgets the arguments of the dispatched method when the blockchain interface is replaced.
+pub(crate) fn clear_dispatched_args() {
+    DISPATCHED_ARGS.with(|dispatched| *dispatched.borrow_mut() = None);
+}
+
+fn input() -> Vec<u8> {
+    DISPATCHED_ARGS
+        .with(|args| args.borrow().clone())
+        .unwrap_or_else(env::input)
+        .unwrap_or_else(|| fail("E2", "Expected input since method has arguments."))
+}
+
+/// Reads the name of the method from the input of the entry point of
+/// `#[near_bindgen(dispatch = "...")]`. The input is the name, then a zero byte and the arguments
+/// of the method, e.g. `set_status\0{"message":"hello"}`, or only the name if the method takes no
+/// arguments.
+pub fn dispatched_method() -> Vec<u8> {
+    let mut method = env::input().unwrap_or_default();
+    let args = match method.iter().position(|&byte| byte == 0) {
+        Some(position) => {
+            let args = method.split_off(position + 1);
+            method.pop();
+            Some(args)
+        }
+        None => None,
+    };
+    DISPATCHED_ARGS.with(|dispatched| *dispatched.borrow_mut() = Some(args));
+    method
+}
+
+/// Panics if the entry point of `#[near_bindgen(dispatch = "...")]` has no such method.
+pub fn unknown_method(method: &[u8]) -> ! {
+    fail_with("E14", "Unknown method", || diagnostics::data_context(method))
+}
+
+/// Deserializes the arguments of the method from JSON.
+#[cfg(feature = "json")]
+pub fn input_json<T: DeserializeOwned>() -> T {
+    let input = input();
+    unwrap_or_fail_with(
+        json_from_slice(&input),
+        "E3",
+        "Failed to deserialize input from JSON.",
+        || diagnostics::data_context(&input),
+    )
+}
+
+/// Checks that the JSON value of an argument deserializes into its type.
+#[cfg(feature = "detailed-input-errors")]
+pub type JsonArgCheck = fn(serde_json::Value) -> Result<(), serde_json::Error>;
+
+/// The `JsonArgCheck` of the argument type.
+#[cfg(feature = "detailed-input-errors")]
+pub fn check_json_arg<T: DeserializeOwned>(
+    value: serde_json::Value,
+) -> Result<(), serde_json::Error> {
+    serde_json::from_value::<T>(value).map(|_| ())
+}
+
+/// Deserializes the arguments of the method from JSON like `input_json`. If they can't be
+/// deserialized, checks the arguments one by one and panics with the name of the first one that
+/// fails and the reason, instead of the position in the input.
+#[cfg(feature = "detailed-input-errors")]
+pub fn input_json_detailed<T: DeserializeOwned>(args: &[(&str, JsonArgCheck)]) -> T {
+    let input = input();
+    match json_from_slice(&input) {
+        Ok(value) => value,
+        Err(err) => fail_with("E3", &detailed_input_error(&input, args, err), || {
+            diagnostics::data_context(&input)
+        }),
+    }
+}
+
+#[cfg(feature = "detailed-input-errors")]
+fn detailed_input_error<E: Debug>(input: &[u8], args: &[(&str, JsonArgCheck)], err: E) -> String {
+    let message = "Failed to deserialize input from JSON.";
+    let mut object: serde_json::Map<String, serde_json::Value> = match serde_json::from_slice(input)
+    {
+        Ok(object) => object,
+        Err(err) => return format!("{} The input is not a JSON object: {}", message, err),
+    };
+    for (name, check) in args {
+        let value = object.remove(*name);
+        let is_missing = value.is_none();
+        if let Err(err) = check(value.unwrap_or(serde_json::Value::Null)) {
+            return if is_missing {
+                format!("{} Missing argument `{}`", message, name)
+            } else {
+                format!("{} Invalid argument `{}`: {}", message, name, err)
+            };
+        }
+    }
+    format!("{}: {:?}", message, err)
+}
+
+/// Deserializes the arguments of the method from Borsh.
+pub fn input_borsh<T: BorshDeserialize>() -> T {
+    let input = input();
+    unwrap_or_fail_with(
+        T::try_from_slice(&input),
+        "E4",
+        "Failed to deserialize input from Borsh.",
+        || diagnostics::data_context(&input),
+    )
+}
+
+fn callback_data(index: u64) -> Vec<u8> {
+    match env::promise_result(index) {
+        PromiseResult::Successful(data) => data,
+        #[cfg(not(feature = "short-panics"))]
+        _ => fail_with("E5", &format!("Callback computation {} was not successful", index), || {
+            format!("promise results: {}", env::promise_results_count())
+        }),
+        #[cfg(feature = "short-panics")]
+        _ => env::panic(b"E5"),
+    }
+}
+
+/// Deserializes the result of the promise with the given index from JSON.
+#[cfg(feature = "json")]
+pub fn callback_json<T: DeserializeOwned>(index: u64) -> T {
+    let data = callback_data(index);
+    unwrap_or_fail_with(
+        json_from_slice(&data),
+        "E6",
+        "Failed to deserialize callback using JSON",
+        || diagnostics::data_context(&data),
+    )
+}
+
+/// Deserializes the result of the promise with the given index from Borsh.
+pub fn callback_borsh<T: BorshDeserialize>(index: u64) -> T {
+    let data = callback_data(index);
+    unwrap_or_fail_with(
+        T::try_from_slice(&data),
+        "E7",
+        "Failed to deserialize callback using Borsh",
+        || diagnostics::data_context(&data),
+    )
+}
+
+/// Deserializes the results of all promises from JSON.
+#[cfg(feature = "json")]
+pub fn callback_vec_json<T: DeserializeOwned>() -> Vec<T> {
+    (0..env::promise_results_count()).map(callback_json).collect()
+}
+
+/// Deserializes the results of all promises from Borsh.
+pub fn callback_vec_borsh<T: BorshDeserialize>() -> Vec<T> {
+    (0..env::promise_results_count()).map(callback_borsh).collect()
+}
+
+fn callback_result_data(index: u64) -> Result<Vec<u8>, PromiseError> {
+    match env::promise_result(index) {
+        PromiseResult::Successful(data) => Ok(data),
+        _ => Err(PromiseError::Failed),
+    }
+}
+
+/// Reads the result of the promise with the given index from JSON for `#[callback_result]`. Unlike
+/// `callback_json`, it doesn't panic when the promise failed or its result can't be deserialized.
+#[cfg(feature = "json")]
+pub fn callback_result_json<T: DeserializeOwned>(index: u64) -> Result<T, PromiseError> {
+    callback_result_data(index)
+        .and_then(|data| json_from_slice(&data).map_err(|_| PromiseError::InvalidResult))
+}
+
+/// Reads the result of the promise with the given index from Borsh for `#[callback_result]`.
+pub fn callback_result_borsh<T: BorshDeserialize>(index: u64) -> Result<T, PromiseError> {
+    callback_result_data(index)
+        .and_then(|data| T::try_from_slice(&data).map_err(|_| PromiseError::InvalidResult))
+}
+
+/// The gas of the callback that runs the next segment of an `async` method, unless the method
+/// sets it with `#[continuation_gas(...)]`.
+pub const CONTINUATION_GAS: Gas = 20_000_000_000_000;
+
+/// Serializes the variables that the next segments of an `async` method use into the arguments of
+/// the callback.
+#[cfg(feature = "json")]
+pub fn continuation_args<T: Serialize>(args: &T) -> Vec<u8> {
+    unwrap_or_fail(json_to_vec(args), "E15", "Failed to serialize the continuation arguments.")
+}
+
+/// Chains the callback that runs the next segment of an `async` method to the awaited promise.
+/// The callback gets the variables that the next segments use.
+pub fn then_continue(promise: Promise, method: &str, args: Vec<u8>, gas: Gas) -> Promise {
+    promise.then(Promise::new(env::current_account_id()).function_call(
+        method.as_bytes().to_vec(),
+        args,
+        0,
+        gas,
+    ))
+}
+
+/// Panics if the private method, like a callback, is called by another account than the contract.
+pub fn assert_private(method: &str) {
+    if env::predecessor_account_id() != env::current_account_id() {
+        env::panic_err_with(
+            ContractError::PRIVATE_METHOD,
+            &format!("Method {} is private", method),
+            diagnostics::call_context,
+        );
+    }
+}
+
+/// Panics if the promise that the callback waits for failed, when its result is not used.
+pub fn assert_callback_success() {
+    callback_data(0);
+}
+
+/// Returns the result of the method serialized with JSON.
+#[cfg(feature = "json")]
+pub fn return_json<T: Serialize + ?Sized>(value: &T) {
+    let result = unwrap_or_fail(
+        json_to_vec(value),
+        "E8",
+        "Failed to serialize the return value using JSON.",
+    );
+    env::value_return(&result);
+}
+
+/// Returns the result of the method serialized with Borsh.
+pub fn return_borsh<T: BorshSerialize + ?Sized>(value: &T) {
+    let result = unwrap_or_fail(
+        value.try_to_vec(),
+        "E9",
+        "Failed to serialize the return value using Borsh.",
+    );
+    env::value_return(&result);
+}
+
+/// The name of the event logged by `return_error`.
+#[cfg(feature = "json")]
+pub const CONTRACT_ERROR_EVENT: &str = "contract_error";
+
+/// Returns the error of a method with `#[graceful]` as `{"error":{"code":...,"message":...}}`
+/// instead of panicking, and logs it as the `contract_error` event of the `near-sdk` standard. The
+/// transaction succeeds, so the changes made before the error are kept, including the contract
+/// struct that the wrapper writes like on `Ok`.
+#[cfg(feature = "json")]
+pub fn return_error<E: Into<ContractError>>(err: E) {
+    let error = err.into();
+    crate::events::emit_event(&error_event(&error));
+    env::value_return(format!("{{\"error\":{}}}", error.to_json()).as_bytes());
+}
+
+#[cfg(feature = "json")]
+fn error_event(error: &ContractError) -> crate::events::EventLog<'static> {
+    crate::events::EventLog {
+        standard: "near-sdk",
+        version: "1.0.0",
+        event: CONTRACT_ERROR_EVENT,
+        data: Some(crate::events::event_data(&[error])),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockedBlockchain, VMContext};
+
+    fn setup_with(input: Vec<u8>, promise_results: Vec<PromiseResult>) {
+        let context = VMContext {
+            current_account_id: "alice.near".to_string(),
+            signer_account_id: "bob.near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: "carol.near".to_string(),
+            input,
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 0,
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        };
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            promise_results,
+            Default::default(),
+            Default::default(),
+        )));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_input_json() {
+        setup_with(b"[1,2]".to_vec(), vec![]);
+        assert_eq!(input_json::<Vec<u8>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_input_borsh() {
+        setup_with(5u64.try_to_vec().unwrap(), vec![]);
+        assert_eq!(input_borsh::<u64>(), 5);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Failed to deserialize input from Borsh.")]
+    fn test_input_borsh_invalid() {
+        setup_with(vec![1], vec![]);
+        input_borsh::<u64>();
+    }
+
+    #[cfg(all(feature = "json", not(feature = "short-panics")))]
+    #[test]
+    #[should_panic(expected = "INVALID_INPUT")]
+    fn test_input_json_invalid() {
+        setup_with(b"[1,".to_vec(), vec![]);
+        input_json::<Vec<u8>>();
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "ALREADY_INITIALIZED")]
+    fn test_assert_not_initialized() {
+        setup_with(vec![], vec![]);
+        assert_not_initialized();
+        env::state_write(&1u8);
+        assert_not_initialized();
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "INVALID_CONFIG")]
+    fn test_init_error() {
+        setup_with(vec![], vec![]);
+        init_error(ContractError::new("INVALID_CONFIG", "The fee is too high"));
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Input is too large")]
+    fn test_assert_input_len() {
+        setup_with(b"[1,2]".to_vec(), vec![]);
+        assert_input_len(5);
+        assert_input_len(4);
+    }
+
+    #[test]
+    fn test_measure_gas() {
+        setup_with(vec![], vec![]);
+        let meter = measure_gas_entry("method");
+        assert_eq!(meter.method, "method");
+        measure_gas_exit(meter);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_return_error() {
+        setup_with(vec![], vec![]);
+        let error = ContractError::new(ContractError::INVALID_INPUT, "The amount is zero");
+        assert_eq!(
+            crate::events::event_log(&error_event(&error)),
+            r#"EVENT_JSON:{"standard":"near-sdk","version":"1.0.0","event":"contract_error","data":[{"code":"INVALID_INPUT","message":"The amount is zero"}]}"#
+        );
+        return_error(error);
+    }
+
+    #[cfg(feature = "detailed-input-errors")]
+    #[test]
+    fn test_detailed_input_error() {
+        let args: &[(&str, JsonArgCheck)] = &[
+            ("account_id", check_json_arg::<String>),
+            ("amount", check_json_arg::<u64>),
+            ("memo", check_json_arg::<Option<String>>),
+        ];
+        let error = |input: &str| detailed_input_error(input.as_bytes(), args, ());
+        assert_eq!(
+            error(r#"{"amount":1}"#),
+            "Failed to deserialize input from JSON. Missing argument `account_id`"
+        );
+        assert_eq!(
+            error(r#"{"account_id":"bob.near","amount":"1"}"#),
+            "Failed to deserialize input from JSON. Invalid argument `amount`: invalid type: string \"1\", expected u64"
+        );
+        assert_eq!(
+            error("[1]"),
+            "Failed to deserialize input from JSON. The input is not a JSON object: invalid type: sequence, expected a map at line 1 column 0"
+        );
+        assert_eq!(
+            error(r#"{"account_id":"bob.near","amount":1}"#),
+            "Failed to deserialize input from JSON.: ()"
+        );
+    }
+
+    #[cfg(all(feature = "detailed-input-errors", not(feature = "short-panics")))]
+    #[test]
+    #[should_panic(expected = "Invalid argument `k`")]
+    fn test_input_json_detailed() {
+        setup_with(br#"{"k":-1}"#.to_vec(), vec![]);
+        #[derive(serde::Deserialize)]
+        struct Input {
+            #[allow(dead_code)]
+            k: u64,
+        }
+        let _: Input = input_json_detailed(&[("k", check_json_arg::<u64>)]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_callbacks() {
+        setup_with(
+            vec![],
+            vec![
+                PromiseResult::Successful(b"1".to_vec()),
+                PromiseResult::Successful(b"2".to_vec()),
+            ],
+        );
+        assert_eq!(callback_json::<u8>(1), 2);
+        assert_eq!(callback_vec_json::<u8>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_callback_results() {
+        setup_with(
+            vec![],
+            vec![
+                PromiseResult::Successful(vec![1]),
+                PromiseResult::Successful(vec![]),
+                PromiseResult::Failed,
+            ],
+        );
+        assert_eq!(callback_result_borsh::<u8>(0), Ok(1));
+        assert_eq!(callback_result_borsh::<u8>(1), Err(PromiseError::InvalidResult));
+        assert_eq!(callback_result_borsh::<u8>(2), Err(PromiseError::Failed));
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Callback computation 0 was not successful")]
+    fn test_callback_failed() {
+        setup_with(vec![], vec![PromiseResult::Failed]);
+        callback_borsh::<u8>(0);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Callback computation 0 was not successful")]
+    fn test_assert_callback_success() {
+        setup_with(vec![], vec![PromiseResult::Successful(vec![])]);
+        assert_callback_success();
+        setup_with(vec![], vec![PromiseResult::Failed]);
+        assert_callback_success();
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_dispatched_method() {
+        setup_with(b"set\0[1,2]".to_vec(), vec![]);
+        assert_eq!(dispatched_method(), b"set".to_vec());
+        assert_eq!(input_json::<Vec<u8>>(), vec![1, 2]);
+        setup_with(b"get".to_vec(), vec![]);
+        assert_eq!(dispatched_method(), b"get".to_vec());
+        setup_with(b"set\0".to_vec(), vec![]);
+        assert_eq!(dispatched_method(), b"set".to_vec());
+        assert!(input().is_empty());
+        setup_with(b"[3]".to_vec(), vec![]);
+        assert_eq!(input_json::<Vec<u8>>(), vec![3]);
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Unknown method")]
+    fn test_unknown_method() {
+        setup_with(b"typo\0{}".to_vec(), vec![]);
+        unknown_method(&dispatched_method());
+    }
+
+    #[cfg(not(feature = "short-panics"))]
+    #[test]
+    #[should_panic(expected = "Method __transfer_then_1 is private")]
+    fn test_assert_private() {
+        setup_with(vec![], vec![]);
+        assert_private("__transfer_then_1");
+    }
+
+    #[cfg(feature = "short-panics")]
+    #[test]
+    #[should_panic(expected = "E7")]
+    fn test_short_panic() {
+        setup_with(vec![], vec![PromiseResult::Successful(vec![1])]);
+        callback_borsh::<u64>(0);
+    }
+}